@@ -0,0 +1,231 @@
+/// Vector side of hybrid search
+///
+/// The desktop app keeps heading-level embeddings of every note in
+/// `<vault>/.vault/semantic.db`. Hybrid queries embed the query text with the same model
+/// through a local OpenAI-compatible endpoint (Ollama or LM Studio), rank notes by their
+/// best-matching chunk, and fuse that ranking with the BM25 ranking using reciprocal-rank
+/// fusion so neither score scale dominates.
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Standard RRF damping constant; larger values flatten the contribution of top ranks
+pub const RRF_K: f32 = 60.0;
+
+/// Default local embedding endpoint (Ollama's OpenAI-compatible API)
+pub const DEFAULT_EMBEDDING_ENDPOINT: &str = "http://localhost:11434/v1";
+
+/// Turns query text into a vector with a given model
+#[async_trait]
+pub trait QueryEmbedder: Send + Sync {
+    async fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>, String>;
+}
+
+/// Embeds queries through a local OpenAI-compatible `/embeddings` endpoint
+pub struct HttpEmbedder {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+impl HttpEmbedder {
+    pub fn new(endpoint: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl QueryEmbedder for HttpEmbedder {
+    async fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>, String> {
+        let url = format!("{}/embeddings", self.endpoint);
+        let response = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "model": model, "input": [text] }))
+            .send()
+            .await
+            .map_err(|e| format!("Embedding endpoint {} unreachable: {}", url, e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Embedding request failed: {}", response.status()));
+        }
+
+        response
+            .json::<EmbeddingResponse>()
+            .await
+            .map_err(|e| e.to_string())?
+            .data
+            .into_iter()
+            .next()
+            .map(|d| d.embedding)
+            .ok_or_else(|| "Embedding endpoint returned no vector".to_string())
+    }
+}
+
+/// Read-only view of the app's semantic index
+pub struct VectorStore {
+    conn: Connection,
+}
+
+impl VectorStore {
+    pub fn open(db_path: &Path) -> Result<Self, String> {
+        if !db_path.exists() {
+            return Err(format!("No semantic index at {:?}", db_path));
+        }
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| e.to_string())?;
+        Ok(Self { conn })
+    }
+
+    /// The model most notes were embedded with; query vectors must come from the same one
+    pub fn model(&self) -> Result<Option<String>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT model FROM notes GROUP BY model ORDER BY COUNT(*) DESC LIMIT 1")
+            .map_err(|e| e.to_string())?;
+        let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+        match rows.next().map_err(|e| e.to_string())? {
+            Some(row) => Ok(Some(row.get(0).map_err(|e| e.to_string())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Notes ordered by the similarity of their best chunk to `query`, most similar first
+    pub fn rank(&self, model: &str, query: &[f32]) -> Result<Vec<(String, f32)>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT c.path, c.embedding FROM chunks c JOIN notes n ON n.path = c.path
+                 WHERE n.model = ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![model], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut best: HashMap<String, f32> = HashMap::new();
+        for row in rows {
+            let (path, blob) = row.map_err(|e| e.to_string())?;
+            let score = cosine_similarity(query, &decode_vector(&blob));
+            let entry = best.entry(path).or_insert(f32::MIN);
+            if score > *entry {
+                *entry = score;
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = best.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        Ok(ranked)
+    }
+}
+
+/// Location of the semantic index the app maintains for a vault
+pub fn default_semantic_db_path(vault_path: &Path) -> PathBuf {
+    vault_path.join(".vault").join("semantic.db")
+}
+
+/// Fuse several best-first rankings: each list contributes `1 / (RRF_K + rank)` per item
+pub fn reciprocal_rank_fusion(rankings: &[Vec<String>]) -> Vec<(String, f32)> {
+    let mut scores: HashMap<&str, f32> = HashMap::new();
+    for ranking in rankings {
+        for (rank, path) in ranking.iter().enumerate() {
+            *scores.entry(path.as_str()).or_insert(0.0) += 1.0 / (RRF_K + rank as f32 + 1.0);
+        }
+    }
+
+    let mut fused: Vec<(String, f32)> = scores.into_iter().map(|(p, s)| (p.to_string(), s)).collect();
+    fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+    fused
+}
+
+fn decode_vector(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reciprocal_rank_fusion_rewards_agreement() {
+        let keyword = vec!["a.md".to_string(), "b.md".to_string(), "c.md".to_string()];
+        let vector = vec!["b.md".to_string(), "d.md".to_string(), "a.md".to_string()];
+
+        let fused = reciprocal_rank_fusion(&[keyword, vector]);
+        let order: Vec<&str> = fused.iter().map(|(p, _)| p.as_str()).collect();
+
+        // Ranked well by both beats ranked first by only one
+        assert_eq!(order, vec!["b.md", "a.md", "d.md", "c.md"]);
+    }
+
+    #[test]
+    fn test_vector_store_ranks_best_chunk_per_note() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db_path = dir.path().join("semantic.db");
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE notes (path TEXT PRIMARY KEY, mtime INTEGER, size INTEGER, hash TEXT, model TEXT);
+                 CREATE TABLE chunks (id INTEGER PRIMARY KEY, path TEXT, heading TEXT, content TEXT, embedding BLOB);",
+            )
+            .unwrap();
+            let encode = |v: &[f32]| v.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+            for (path, model) in [("a.md", "m"), ("b.md", "m"), ("c.md", "other")] {
+                conn.execute("INSERT INTO notes VALUES (?1, 0, 0, '', ?2)", params![path, model]).unwrap();
+            }
+            for (path, vector) in [("a.md", [1.0f32, 0.0]), ("a.md", [0.0, 1.0]), ("b.md", [0.7, 0.7]), ("c.md", [0.0, 1.0])] {
+                conn.execute(
+                    "INSERT INTO chunks (path, heading, content, embedding) VALUES (?1, NULL, '', ?2)",
+                    params![path, encode(&vector)],
+                )
+                .unwrap();
+            }
+        }
+
+        let store = VectorStore::open(&db_path).unwrap();
+        assert_eq!(store.model().unwrap().as_deref(), Some("m"));
+
+        let ranked = store.rank("m", &[0.0, 1.0]).unwrap();
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].0, "a.md");
+        assert!((ranked[0].1 - 1.0).abs() < 1e-6);
+        assert_eq!(ranked[1].0, "b.md");
+    }
+}
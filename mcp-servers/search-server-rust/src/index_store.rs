@@ -0,0 +1,455 @@
+/// Persistent, incrementally updated full-text index for the vault
+///
+/// The tantivy index lives on disk (by default in the app's data directory) next to a
+/// manifest that records the mtime, size and content hash of every indexed note. On
+/// startup the manifest is reconciled against the vault so only changed files are
+/// re-read, and file watcher events are applied through `sync_paths`.
+///
+/// Besides the content, each note's tags, lowercased path and creation day are indexed
+/// so the filters of a `search_content` query run inside tantivy.
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tantivy::{
+    directory::MmapDirectory,
+    schema::{Field, Schema, Value, FAST, INDEXED, STORED, STRING, TEXT},
+    Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term,
+};
+use walkdir::WalkDir;
+
+use crate::extract_tags;
+use crate::query::{created_date, NoteFacts};
+
+/// Bump whenever the schema changes; each version gets its own directory
+const SCHEMA_VERSION: u32 = 2;
+const MANIFEST_FILE: &str = "manifest.json";
+const WRITER_HEAP_BYTES: usize = 50_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct FileEntry {
+    mtime: u64,
+    size: u64,
+    hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexManifest {
+    schema_version: u32,
+    files: HashMap<String, FileEntry>,
+}
+
+impl IndexManifest {
+    fn load(dir: &Path) -> Self {
+        fs::read_to_string(dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<IndexManifest>(&raw).ok())
+            .filter(|m| m.schema_version == SCHEMA_VERSION)
+            .unwrap_or_else(|| IndexManifest {
+                schema_version: SCHEMA_VERSION,
+                files: HashMap::new(),
+            })
+    }
+
+    fn save(&self, dir: &Path) -> Result<(), String> {
+        let raw = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        let tmp = dir.join(format!("{}.tmp", MANIFEST_FILE));
+        fs::write(&tmp, raw).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, dir.join(MANIFEST_FILE)).map_err(|e| e.to_string())
+    }
+}
+
+/// Counts reported after reconciling the index with the vault
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStats {
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+pub struct SearchIndex {
+    pub index: Index,
+    pub reader: IndexReader,
+    pub path_field: Field,
+    pub content_field: Field,
+    /// One raw term per tag
+    pub tags_field: Field,
+    /// Lowercased path, for prefix filters
+    pub path_lower_field: Field,
+    /// Creation date as days from the common era (`NaiveDate::num_days_from_ce`)
+    pub created_field: Field,
+    writer: IndexWriter,
+    vault_path: PathBuf,
+    index_root: PathBuf,
+    data_dir: PathBuf,
+    manifest: IndexManifest,
+}
+
+struct Fields {
+    path: Field,
+    content: Field,
+    tags: Field,
+    path_lower: Field,
+    created: Field,
+}
+
+fn build_schema() -> (Schema, Fields) {
+    let mut schema_builder = Schema::builder();
+    // Path is a raw (untokenized) term so documents can be replaced/deleted by path
+    let path = schema_builder.add_text_field("path", STRING | STORED);
+    let content = schema_builder.add_text_field("content", TEXT | STORED);
+    let tags = schema_builder.add_text_field("tags", STRING | STORED);
+    let path_lower = schema_builder.add_text_field("path_lower", STRING);
+    let created = schema_builder.add_i64_field("created", INDEXED | FAST | STORED);
+    let fields = Fields {
+        path,
+        content,
+        tags,
+        path_lower,
+        created,
+    };
+    (schema_builder.build(), fields)
+}
+
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+}
+
+impl SearchIndex {
+    /// Open the on-disk index under `index_root`, creating it if needed
+    pub fn open(vault_path: &Path, index_root: &Path) -> Result<Self, String> {
+        let data_dir = index_root.join(format!("v{}", SCHEMA_VERSION));
+        fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+
+        let (schema, fields) = build_schema();
+        let mut manifest = IndexManifest::load(&data_dir);
+
+        let index = match Self::open_index(&data_dir, schema.clone()) {
+            Ok(index) => index,
+            Err(_) => {
+                // Corrupt or incompatible index: start over inside our own versioned directory
+                fs::remove_dir_all(&data_dir).map_err(|e| e.to_string())?;
+                fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+                manifest.files.clear();
+                Self::open_index(&data_dir, schema)?
+            }
+        };
+
+        let writer: IndexWriter = index.writer(WRITER_HEAP_BYTES).map_err(|e| e.to_string())?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(|e: tantivy::TantivyError| e.to_string())?;
+
+        // A manifest without a matching index (or vice versa) cannot be trusted
+        let doc_count = reader.searcher().num_docs() as usize;
+        if doc_count != manifest.files.len() {
+            writer.delete_all_documents().map_err(|e| e.to_string())?;
+            manifest.files.clear();
+        }
+
+        Ok(Self {
+            index,
+            reader,
+            path_field: fields.path,
+            content_field: fields.content,
+            tags_field: fields.tags,
+            path_lower_field: fields.path_lower,
+            created_field: fields.created,
+            writer,
+            vault_path: vault_path.to_path_buf(),
+            index_root: index_root.to_path_buf(),
+            data_dir,
+            manifest,
+        })
+    }
+
+    fn open_index(data_dir: &Path, schema: Schema) -> Result<Index, String> {
+        let directory = MmapDirectory::open(data_dir).map_err(|e| e.to_string())?;
+        Index::open_or_create(directory, schema).map_err(|e| e.to_string())
+    }
+
+    /// Number of notes currently tracked by the index
+    pub fn document_count(&self) -> usize {
+        self.manifest.files.len()
+    }
+
+    /// What a stored document tells the query language about its note
+    pub fn note_facts<'a>(&self, doc: &'a TantivyDocument) -> Option<NoteFacts<'a>> {
+        Some(NoteFacts {
+            path: doc.get_first(self.path_field)?.as_str()?,
+            content: doc.get_first(self.content_field)?.as_str()?,
+            tags: doc
+                .get_all(self.tags_field)
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect(),
+            created: doc
+                .get_first(self.created_field)
+                .and_then(|v| v.as_i64())
+                .and_then(|day| i32::try_from(day).ok())
+                .and_then(NaiveDate::from_num_days_from_ce_opt),
+        })
+    }
+
+    /// Bring the index in line with the vault, re-reading only files whose
+    /// mtime/size changed and whose content hash differs from the manifest
+    pub fn reconcile(&mut self) -> Result<SyncStats, String> {
+        let mut stats = SyncStats::default();
+        let mut seen = HashSet::new();
+
+        for path in self.markdown_files(&self.vault_path.clone()) {
+            let rel = self.relative_path(&path);
+            if self.index_file(&path, &rel)? {
+                stats.indexed += 1;
+            } else {
+                stats.unchanged += 1;
+            }
+            seen.insert(rel);
+        }
+
+        let stale: Vec<String> = self
+            .manifest
+            .files
+            .keys()
+            .filter(|rel| !seen.contains(*rel))
+            .cloned()
+            .collect();
+        for rel in stale {
+            self.remove_document(&rel);
+            stats.removed += 1;
+        }
+
+        self.commit()?;
+        Ok(stats)
+    }
+
+    /// Apply a batch of changed paths reported by the file watcher.
+    ///
+    /// Each path is looked at in its current state, so creates, modifications,
+    /// deletions and both halves of a rename are handled the same way.
+    pub fn sync_paths(&mut self, paths: &[PathBuf]) -> Result<SyncStats, String> {
+        let mut stats = SyncStats::default();
+
+        for path in paths {
+            if path.starts_with(&self.index_root) || !path.starts_with(&self.vault_path) {
+                continue;
+            }
+            let rel = self.relative_path(path);
+
+            if path.is_dir() {
+                for file in self.markdown_files(path) {
+                    let file_rel = self.relative_path(&file);
+                    if self.index_file(&file, &file_rel)? {
+                        stats.indexed += 1;
+                    } else {
+                        stats.unchanged += 1;
+                    }
+                }
+            } else if path.is_file() {
+                if !is_markdown(path) {
+                    continue;
+                }
+                if self.index_file(path, &rel)? {
+                    stats.indexed += 1;
+                } else {
+                    stats.unchanged += 1;
+                }
+            } else {
+                // Gone: either a single note or a whole folder was removed/renamed away
+                let prefix = format!("{}/", rel);
+                let gone: Vec<String> = self
+                    .manifest
+                    .files
+                    .keys()
+                    .filter(|k| **k == rel || k.starts_with(&prefix))
+                    .cloned()
+                    .collect();
+                for gone_rel in gone {
+                    self.remove_document(&gone_rel);
+                    stats.removed += 1;
+                }
+            }
+        }
+
+        if stats.indexed > 0 || stats.removed > 0 {
+            self.commit()?;
+        }
+        Ok(stats)
+    }
+
+    fn markdown_files(&self, root: &Path) -> Vec<PathBuf> {
+        WalkDir::new(root)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| !e.path().starts_with(&self.index_root))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && is_markdown(e.path()))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    }
+
+    fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.vault_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Index a single file if it changed; returns whether the document was (re)written
+    fn index_file(&mut self, path: &Path, rel: &str) -> Result<bool, String> {
+        let Ok(metadata) = fs::metadata(path) else {
+            return Ok(false);
+        };
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let size = metadata.len();
+
+        if let Some(entry) = self.manifest.files.get(rel) {
+            if entry.mtime == mtime && entry.size == size {
+                return Ok(false);
+            }
+        }
+
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(false);
+        };
+        let hash = content_hash(&content);
+
+        if let Some(entry) = self.manifest.files.get_mut(rel) {
+            if entry.hash == hash {
+                // Touched but not edited: remember the new mtime so we skip it next time
+                entry.mtime = mtime;
+                entry.size = size;
+                return Ok(false);
+            }
+        }
+
+        self.writer
+            .delete_term(Term::from_field_text(self.path_field, rel));
+
+        let mut doc = tantivy::doc!(
+            self.path_field => rel,
+            self.content_field => content.as_str(),
+            self.path_lower_field => rel.to_lowercase()
+        );
+        for tag in extract_tags(&content) {
+            doc.add_text(self.tags_field, tag);
+        }
+        if let Some(created) = created_date(&content, path) {
+            doc.add_i64(self.created_field, i64::from(created.num_days_from_ce()));
+        }
+        self.writer.add_document(doc).map_err(|e| e.to_string())?;
+
+        self.manifest
+            .files
+            .insert(rel.to_string(), FileEntry { mtime, size, hash });
+        Ok(true)
+    }
+
+    fn remove_document(&mut self, rel: &str) {
+        self.writer
+            .delete_term(Term::from_field_text(self.path_field, rel));
+        self.manifest.files.remove(rel);
+    }
+
+    fn commit(&mut self) -> Result<(), String> {
+        self.writer.commit().map_err(|e| e.to_string())?;
+        self.reader.reload().map_err(|e| e.to_string())?;
+        self.manifest.save(&self.data_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let vault = temp_dir.path().join("vault");
+        fs::create_dir_all(vault.join("folder")).unwrap();
+        fs::write(vault.join("a.md"), "# A\n\nalpha content #one").unwrap();
+        fs::write(vault.join("folder/b.md"), "# B\n\nbeta content").unwrap();
+        let index_root = vault.join(".vault/search");
+        (temp_dir, vault, index_root)
+    }
+
+    #[test]
+    fn test_reconcile_skips_unchanged_files_on_reopen() {
+        let (_tmp, vault, index_root) = setup();
+
+        let mut index = SearchIndex::open(&vault, &index_root).unwrap();
+        let first = index.reconcile().unwrap();
+        assert_eq!(first.indexed, 2);
+        drop(index);
+
+        let mut reopened = SearchIndex::open(&vault, &index_root).unwrap();
+        let second = reopened.reconcile().unwrap();
+        assert_eq!(second.indexed, 0);
+        assert_eq!(second.unchanged, 2);
+        assert_eq!(reopened.reader.searcher().num_docs(), 2);
+    }
+
+    #[test]
+    fn test_reconcile_picks_up_offline_changes() {
+        let (_tmp, vault, index_root) = setup();
+        SearchIndex::open(&vault, &index_root)
+            .unwrap()
+            .reconcile()
+            .unwrap();
+
+        fs::write(vault.join("a.md"), "# A\n\nrewritten while the server was down").unwrap();
+        fs::remove_file(vault.join("folder/b.md")).unwrap();
+        fs::write(vault.join("c.md"), "# C").unwrap();
+
+        let mut index = SearchIndex::open(&vault, &index_root).unwrap();
+        let stats = index.reconcile().unwrap();
+        assert_eq!(stats.indexed, 2);
+        assert_eq!(stats.removed, 1);
+        assert_eq!(index.document_count(), 2);
+    }
+
+    #[test]
+    fn test_sync_paths_handles_rename_and_folder_delete() {
+        let (_tmp, vault, index_root) = setup();
+        let mut index = SearchIndex::open(&vault, &index_root).unwrap();
+        index.reconcile().unwrap();
+
+        fs::rename(vault.join("a.md"), vault.join("renamed.md")).unwrap();
+        let stats = index
+            .sync_paths(&[vault.join("a.md"), vault.join("renamed.md")])
+            .unwrap();
+        assert_eq!(stats.indexed, 1);
+        assert_eq!(stats.removed, 1);
+
+        fs::remove_dir_all(vault.join("folder")).unwrap();
+        let stats = index.sync_paths(&[vault.join("folder")]).unwrap();
+        assert_eq!(stats.removed, 1);
+        assert_eq!(index.document_count(), 1);
+        assert_eq!(index.reader.searcher().num_docs(), 1);
+    }
+
+    #[test]
+    fn test_index_directory_is_not_indexed() {
+        let (_tmp, vault, index_root) = setup();
+        fs::create_dir_all(&index_root).unwrap();
+        fs::write(index_root.join("stray.md"), "should be ignored").unwrap();
+
+        let mut index = SearchIndex::open(&vault, &index_root).unwrap();
+        index.reconcile().unwrap();
+        assert_eq!(index.document_count(), 2);
+    }
+}
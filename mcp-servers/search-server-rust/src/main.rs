@@ -2,36 +2,57 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use tantivy::{
-    collector::TopDocs,
-    query::QueryParser,
-    schema::{Schema, TEXT, STORED, Field, Value as TantivyValue},
-    Index, IndexReader,
-    TantivyDocument,
+    collector::{DocSetCollector, TopDocs},
+    query::{BooleanQuery, ConstScoreQuery, Occur, Query, QueryParser, TermQuery},
+    schema::{IndexRecordOption, Value as TantivyValue},
+    Searcher, TantivyDocument, Term,
 };
-use tempfile::TempDir;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info};
 use walkdir::WalkDir;
+use sha2::{Digest, Sha256};
 use regex::Regex;
 
+mod hybrid;
+mod index_store;
+mod query;
+// Shared with the app so both index the same tags
+#[path = "../../../src-tauri/src/tags/extract.rs"]
+mod tags;
 mod transport_line;
 
+use hybrid::{HttpEmbedder, QueryEmbedder, VectorStore};
+use index_store::SearchIndex;
+use query::{MatchOptions, SearchQuery};
+use tags::{extract_tags, tag_matches};
+
+/// How long to wait for a burst of file events to settle before reindexing
+const WATCH_DEBOUNCE_MS: u64 = 300;
+
 #[derive(Clone)]
 struct SearchServer {
     vault_path: PathBuf,
+    index_path: PathBuf,
     index: Arc<Mutex<Option<SearchIndex>>>,
+    watcher: Arc<std::sync::Mutex<Option<RecommendedWatcher>>>,
+    semantic_db_path: PathBuf,
+    embedder: Arc<dyn QueryEmbedder>,
 }
 
-struct SearchIndex {
-    index: Index,
-    reader: IndexReader,
-    path_field: Field,
-    content_field: Field,
-    tags_field: Field,
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SearchMode {
+    /// BM25 ranking; every term must match
+    #[default]
+    Keyword,
+    /// BM25 fused with vector similarity; filters must match, text terms only rank
+    Hybrid,
 }
 
 #[derive(Debug, Deserialize)]
@@ -44,6 +65,8 @@ struct SearchContentArgs {
     whole_word: bool,
     #[serde(default = "default_max_results")]
     max_results: usize,
+    #[serde(default)]
+    mode: SearchMode,
 }
 
 #[derive(Debug, Deserialize)]
@@ -118,116 +141,222 @@ fn default_true() -> bool {
 }
 
 impl SearchServer {
-    fn new(vault_path: PathBuf) -> Self {
+    fn new(vault_path: PathBuf, app_data_path: &Path) -> Self {
+        let index_path = default_index_path(&vault_path, app_data_path);
+        Self::with_index_path(vault_path, index_path)
+    }
+
+    fn with_index_path(vault_path: PathBuf, index_path: PathBuf) -> Self {
         Self {
+            semantic_db_path: hybrid::default_semantic_db_path(&vault_path),
             vault_path,
+            index_path,
             index: Arc::new(Mutex::new(None)),
+            watcher: Arc::new(std::sync::Mutex::new(None)),
+            embedder: Arc::new(HttpEmbedder::new(hybrid::DEFAULT_EMBEDDING_ENDPOINT)),
         }
     }
 
+    /// Use a different embedder for the vector half of hybrid search
+    fn with_embedder(mut self, embedder: Arc<dyn QueryEmbedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Open the persistent index and reindex only notes that changed since the last run
     async fn initialize_index(&self) -> Result<(), String> {
-        let temp_dir = TempDir::new().map_err(|e| e.to_string())?;
-        let index_path = temp_dir.path();
+        let mut search_index = SearchIndex::open(&self.vault_path, &self.index_path)?;
+        let stats = search_index.reconcile()?;
+
+        if env::var("MCP_DEBUG").is_ok() {
+            eprintln!(
+                "[Rust Search Server] Index ready at {:?} ({} notes): {} reindexed, {} unchanged, {} removed",
+                self.index_path,
+                search_index.document_count(),
+                stats.indexed,
+                stats.unchanged,
+                stats.removed
+            );
+        }
 
-        let mut schema_builder = Schema::builder();
-        let path_field = schema_builder.add_text_field("path", TEXT | STORED);
-        let content_field = schema_builder.add_text_field("content", TEXT | STORED);
-        let tags_field = schema_builder.add_text_field("tags", TEXT | STORED);
-        let schema = schema_builder.build();
+        *self.index.lock().await = Some(search_index);
 
-        let index = Index::create_in_dir(index_path, schema.clone())
-            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
 
-        let reader = index.reader().map_err(|e| e.to_string())?;
+    /// Watch the vault and keep the index in step with create/modify/rename/delete events
+    fn start_watcher(&self) -> Result<(), String> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Vec<PathBuf>>();
 
-        let search_index = SearchIndex {
-            index,
-            reader,
-            path_field,
-            content_field,
-            tags_field,
-        };
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                if !matches!(event.kind, EventKind::Access(_)) {
+                    let _ = tx.send(event.paths);
+                }
+            }
+        })
+        .map_err(|e| e.to_string())?;
 
-        let mut index_writer = search_index.index.writer(50_000_000)
+        watcher
+            .watch(&self.vault_path, RecursiveMode::Recursive)
             .map_err(|e| e.to_string())?;
+        *self.watcher.lock().map_err(|e| e.to_string())? = Some(watcher);
 
-        // Index all markdown files
-        for entry in WalkDir::new(&self.vault_path)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file() && e.path().extension().map_or(false, |ext| ext == "md"))
-        {
-            let path = entry.path();
-            let relative_path = path.strip_prefix(&self.vault_path)
-                .unwrap_or(path)
-                .to_string_lossy();
+        let index = self.index.clone();
+        tokio::spawn(async move {
+            while let Some(first) = rx.recv().await {
+                let mut pending: HashSet<PathBuf> = first.into_iter().collect();
 
-            if let Ok(content) = tokio::fs::read_to_string(path).await {
-                let tags = extract_tags(&content).join(" ");
-                
-                let doc = tantivy::doc!(
-                    search_index.path_field => relative_path.as_ref(),
-                    search_index.content_field => content.as_str(),
-                    search_index.tags_field => tags.as_str()
-                );
-                
-                index_writer.add_document(doc).map_err(|e| e.to_string())?;
-            }
-        }
-
-        index_writer.commit().map_err(|e| e.to_string())?;
+                // Editors often save via temp file + rename, so coalesce the burst
+                tokio::time::sleep(Duration::from_millis(WATCH_DEBOUNCE_MS)).await;
+                while let Ok(more) = rx.try_recv() {
+                    pending.extend(more);
+                }
 
-        *self.index.lock().await = Some(search_index);
-        std::mem::forget(temp_dir); // Keep the index directory alive
+                let paths: Vec<PathBuf> = pending.into_iter().collect();
+                if let Some(search_index) = index.lock().await.as_mut() {
+                    if let Err(e) = search_index.sync_paths(&paths) {
+                        if env::var("MCP_DEBUG").is_ok() {
+                            eprintln!("[Rust Search Server] Failed to update index: {}", e);
+                        }
+                    }
+                }
+            }
+        });
 
         Ok(())
     }
 
     async fn search_content(&self, args: SearchContentArgs) -> Result<Value, String> {
-        let index_lock = self.index.lock().await;
-        let index = index_lock.as_ref().ok_or("Index not initialized")?;
-
-        let searcher = index.reader.searcher();
-        let query_parser = QueryParser::for_index(&index.index, vec![index.content_field]);
-        
-        let query = if args.whole_word {
-            format!("\"{}\"", args.query)
-        } else {
-            args.query.clone()
+        let query = SearchQuery::parse(&args.query)?;
+        let options = MatchOptions {
+            case_sensitive: args.case_sensitive,
+            whole_word: args.whole_word,
         };
+        let hybrid = args.mode == SearchMode::Hybrid;
 
-        let query = query_parser.parse_query(&query).map_err(|e| e.to_string())?;
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(args.max_results))
-            .map_err(|e| e.to_string())?;
+        // Notes that pass the query, best BM25 score first, and in hybrid mode the notes
+        // that pass its filters (`None` when an alternative has no filters)
+        let mut keyword: Vec<(String, f32)> = Vec::new();
+        let mut eligible: Option<HashSet<String>> = None;
+        let mut contents: HashMap<String, String> = HashMap::new();
+        {
+            let index_lock = self.index.lock().await;
+            let index = index_lock.as_ref().ok_or("Index not initialized")?;
+            let searcher = index.reader.searcher();
+            let doc_count = searcher.num_docs() as usize;
+            if doc_count > 0 {
+                // Filters only narrow the results; the score comes from the text terms alone
+                let mut clauses: Vec<(Occur, Box<dyn Query>)> =
+                    vec![(Occur::Must, Box::new(ConstScoreQuery::new(query.index_query(index, options, true)?, 0.0)))];
+                let text_query = query.bm25_query();
+                if !text_query.is_empty() {
+                    let query_parser = QueryParser::for_index(&index.index, vec![index.content_field]);
+                    let (parsed, _errors) = query_parser.parse_query_lenient(&text_query);
+                    clauses.push((Occur::Should, parsed));
+                }
 
-        let mut results = Vec::new();
-        for (_score, doc_address) in top_docs {
-            let retrieved_doc: TantivyDocument = searcher.doc(doc_address).map_err(|e| e.to_string())?;
-            
-            if let (Some(path), Some(content)) = (
-                retrieved_doc.get_first(index.path_field).and_then(|v| v.as_str()),
-                retrieved_doc.get_first(index.content_field).and_then(|v| v.as_str())
-            ) {
-                let matches = find_matches_in_content(content, &args.query, args.case_sensitive, args.whole_word);
-                
-                if !matches.is_empty() {
-                    let total = matches.len();
-                    results.push(json!({
-                        "file": path,
-                        "matches": matches.into_iter().take(5).collect::<Vec<_>>(),
-                        "totalMatches": total
-                    }));
+                for (score, address) in searcher
+                    .search(&BooleanQuery::new(clauses), &TopDocs::with_limit(doc_count))
+                    .map_err(|e| e.to_string())?
+                {
+                    let doc: TantivyDocument = searcher.doc(address).map_err(|e| e.to_string())?;
+                    let Some(facts) = index.note_facts(&doc) else {
+                        continue;
+                    };
+                    if query.matches(&facts, options, true) {
+                        keyword.push((facts.path.to_string(), score));
+                        contents.insert(facts.path.to_string(), facts.content.to_string());
+                    }
+                }
+
+                if hybrid && !query.is_unfiltered() {
+                    let mut paths = HashSet::new();
+                    for address in searcher
+                        .search(query.index_query(index, options, false)?.as_ref(), &DocSetCollector)
+                        .map_err(|e| e.to_string())?
+                    {
+                        let doc: TantivyDocument = searcher.doc(address).map_err(|e| e.to_string())?;
+                        let Some(facts) = index.note_facts(&doc) else {
+                            continue;
+                        };
+                        if query.matches(&facts, options, false) {
+                            paths.insert(facts.path.to_string());
+                        }
+                    }
+                    eligible = Some(paths);
+                }
+            }
+        }
+        keyword.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        let keyword_ranking: Vec<String> = keyword.iter().map(|(path, _)| path.clone()).collect();
+
+        let mut ranked = keyword;
+        if hybrid {
+            let text = query.text_terms().join(" ");
+            if !text.is_empty() {
+                match self.vector_ranking(&text).await {
+                    Ok(vector_ranking) => {
+                        let vector_ranking: Vec<String> = vector_ranking
+                            .into_iter()
+                            .filter(|path| eligible.as_ref().is_none_or(|eligible| eligible.contains(path)))
+                            .collect();
+                        ranked = hybrid::reciprocal_rank_fusion(&[keyword_ranking, vector_ranking]);
+                    }
+                    Err(e) => {
+                        // Fall back to keyword results rather than failing the whole search
+                        if env::var("MCP_DEBUG").is_ok() {
+                            eprintln!("[Rust Search Server] Vector search unavailable: {}", e);
+                        }
+                    }
                 }
             }
         }
 
+        let index_lock = self.index.lock().await;
+        let index = index_lock.as_ref().ok_or("Index not initialized")?;
+        let searcher = index.reader.searcher();
+        let text_terms = query.text_terms();
+        let mut results = Vec::new();
+        for (path, score) in ranked.into_iter().take(args.max_results) {
+            // Notes only the vector ranking found have not been read yet
+            let content = match contents.remove(&path) {
+                Some(content) => Some(content),
+                None => stored_content(index, &searcher, &path)?,
+            };
+            let Some(content) = content else {
+                // In the semantic index but no longer in the vault
+                continue;
+            };
+            let matches: Vec<Value> = text_terms
+                .iter()
+                .flat_map(|term| find_matches_in_content(&content, term, args.case_sensitive, args.whole_word))
+                .collect();
+            let total = matches.len();
+            results.push(json!({
+                "file": path,
+                "score": score,
+                "matches": matches.into_iter().take(5).collect::<Vec<_>>(),
+                "totalMatches": total
+            }));
+        }
+
         Ok(json!(results))
     }
 
+    /// Vault notes ordered by semantic similarity to `text`, using the app's embeddings
+    async fn vector_ranking(&self, text: &str) -> Result<Vec<String>, String> {
+        let model = VectorStore::open(&self.semantic_db_path)?
+            .model()?
+            .ok_or("Semantic index is empty")?;
+        let vector = self.embedder.embed(&model, text).await?;
+
+        let ranked = VectorStore::open(&self.semantic_db_path)?.rank(&model, &vector)?;
+        Ok(ranked.into_iter().map(|(path, _)| path).collect())
+    }
+
     async fn search_by_tag(&self, args: SearchByTagArgs) -> Result<Value, String> {
         let mut results = Vec::new();
-        let search_tags: HashSet<String> = args.tags.iter().map(|t| t.to_lowercase()).collect();
 
         for entry in WalkDir::new(&self.vault_path)
             .follow_links(true)
@@ -242,18 +371,18 @@ impl SearchServer {
 
             if let Ok(content) = tokio::fs::read_to_string(path).await {
                 let file_tags = extract_tags(&content);
-                let file_tags_lower: HashSet<String> = file_tags.iter().map(|t| t.to_lowercase()).collect();
+                let query_matches = |query: &String| file_tags.iter().any(|tag| tag_matches(tag, query));
 
                 let has_match = if args.match_all {
-                    search_tags.iter().all(|tag| file_tags_lower.contains(tag))
+                    args.tags.iter().all(query_matches)
                 } else {
-                    search_tags.iter().any(|tag| file_tags_lower.contains(tag))
+                    args.tags.iter().any(query_matches)
                 };
 
                 if has_match {
-                    let matched_tags: Vec<String> = file_tags_lower
+                    let matched_tags: Vec<String> = file_tags
                         .iter()
-                        .filter(|t| search_tags.contains(*t))
+                        .filter(|tag| args.tags.iter().any(|query| tag_matches(tag, query)))
                         .cloned()
                         .collect();
 
@@ -463,13 +592,13 @@ impl SearchServer {
         vec![
             Tool {
                 name: "search_content".to_string(),
-                description: "Search for text content within markdown files in the vault".to_string(),
+                description: "Search markdown files in the vault. Supports words, \"quoted phrases\", tag:name, path:Folder/, created:>2025-01-01 (>, >=, <, <=), -term to exclude, and OR between alternatives".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
                         "query": {
                             "type": "string",
-                            "description": "Search query text"
+                            "description": "Search query, e.g. 'budget \"q3 plan\" tag:work -path:Archive/ OR created:>=2025-01-01'"
                         },
                         "case_sensitive": {
                             "type": "boolean",
@@ -485,6 +614,12 @@ impl SearchServer {
                             "type": "number",
                             "description": "Maximum number of results to return",
                             "default": 50
+                        },
+                        "mode": {
+                            "type": "string",
+                            "enum": ["keyword", "hybrid"],
+                            "description": "keyword: BM25 ranking, all terms must match. hybrid: BM25 fused with semantic similarity from local embeddings; filters still apply but text terms only affect ranking",
+                            "default": "keyword"
                         }
                     },
                     "required": ["query"]
@@ -501,7 +636,7 @@ impl SearchServer {
                             "items": {
                                 "type": "string"
                             },
-                            "description": "Tags to search for (without # prefix)"
+                            "description": "Tags to search for (# prefix optional). Nested tags are written area/sub; area/* also matches every tag nested under area"
                         },
                         "match_all": {
                             "type": "boolean",
@@ -763,17 +898,6 @@ impl SearchServer {
     }
 }
 
-fn extract_tags(content: &str) -> Vec<String> {
-    let tag_regex = Regex::new(r"(^|\s)#([a-zA-Z0-9_][a-zA-Z0-9_/-]*[a-zA-Z0-9_]|[a-zA-Z0-9_])(\s|$|[.,!?;:)])").unwrap();
-    let mut tags = HashSet::new();
-    
-    for cap in tag_regex.captures_iter(content) {
-        tags.insert(cap[2].to_string());
-    }
-    
-    tags.into_iter().collect()
-}
-
 fn find_matches_in_content(content: &str, query: &str, case_sensitive: bool, whole_word: bool) -> Vec<Value> {
     let search_query = if case_sensitive { query.to_string() } else { query.to_lowercase() };
     
@@ -953,17 +1077,39 @@ impl LineServer {
     }
 }
 
-/// Parse command line arguments for --index-path <path>
-fn parse_index_path() -> Option<String> {
+/// Stored content of the note at `path`, if it is in the index
+fn stored_content(index: &SearchIndex, searcher: &Searcher, path: &str) -> Result<Option<String>, String> {
+    let query = TermQuery::new(Term::from_field_text(index.path_field, path), IndexRecordOption::Basic);
+    let Some((_, address)) = searcher
+        .search(&query, &TopDocs::with_limit(1))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let doc: TantivyDocument = searcher.doc(address).map_err(|e| e.to_string())?;
+    Ok(doc.get_first(index.content_field).and_then(|v| v.as_str()).map(str::to_string))
+}
+
+/// Parse command line arguments for `<flag> <value>`
+fn parse_flag_value(flag: &str) -> Option<String> {
     let args: Vec<String> = env::args().collect();
     for i in 0..args.len() {
-        if args[i] == "--index-path" && i + 1 < args.len() {
+        if args[i] == flag && i + 1 < args.len() {
             return Some(args[i + 1].clone());
         }
     }
     None
 }
 
+/// Default on-disk location of a vault's index: a folder of the app's data directory named
+/// after a hash of the vault path, so indexes of different vaults never mix
+fn default_index_path(vault_path: &Path, app_data_path: &Path) -> PathBuf {
+    let hash = format!("{:x}", Sha256::digest(vault_path.to_string_lossy().as_bytes()));
+    app_data_path.join("search-index").join(&hash[..16])
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Only output debug messages if MCP_DEBUG is set
@@ -978,12 +1124,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .init();
     }
 
-    // Use --index-path if provided, otherwise fall back to CWD
-    let vault_path = parse_index_path()
+    // The vault is --vault-path if provided, otherwise the CWD (the app sets it to the vault)
+    let vault_path = parse_flag_value("--vault-path")
         .unwrap_or_else(|| env::current_dir()
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_else(|_| ".".to_string()));
 
+    // --index-path is where the persistent index is stored (default: a folder per vault under
+    // --app-data-path, the app's data directory, or the temp directory when run on its own)
+    let index_path = parse_flag_value("--index-path").map(PathBuf::from);
+    let app_data_path = parse_flag_value("--app-data-path")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| env::temp_dir().join("mcp-search-server"));
+    // --embedding-endpoint is the local OpenAI-compatible API used for hybrid search; the app
+    // passes the one its semantic index was built with (default: Ollama on localhost)
+    let embedding_endpoint = parse_flag_value("--embedding-endpoint");
+    let create_server = |vault_path: String| {
+        let server = match index_path.clone() {
+            Some(index_path) => SearchServer::with_index_path(PathBuf::from(vault_path), index_path),
+            None => SearchServer::new(PathBuf::from(vault_path), &app_data_path),
+        };
+        match &embedding_endpoint {
+            Some(endpoint) => server.with_embedder(Arc::new(HttpEmbedder::new(endpoint))),
+            None => server,
+        }
+    };
+
     if debug_enabled {
         eprintln!("[Rust Search Server] Operating in directory: {}", vault_path);
     }
@@ -1001,7 +1167,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         use transport_line::LineTransport;
         
         let transport = LineTransport::new();
-        let server = SearchServer::new(PathBuf::from(vault_path.clone()));
+        let server = create_server(vault_path.clone());
         
         // Initialize the search index
         if let Err(e) = server.initialize_index().await {
@@ -1009,6 +1175,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 error!("Failed to initialize search index: {}", e);
                 eprintln!("[Rust Search Server] Failed to initialize search index: {}", e);
             }
+        } else if let Err(e) = server.start_watcher() {
+            if debug_enabled {
+                eprintln!("[Rust Search Server] Failed to start file watcher: {}", e);
+            }
         }
         
         // Create a line-based server wrapper
@@ -1021,7 +1191,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         line_server.run().await?;
     } else {
         // Standard JSON-RPC mode
-        let mut server = SearchServer::new(PathBuf::from(vault_path));
+        let mut server = create_server(vault_path);
 
         // Initialize the search index
         if let Err(e) = server.initialize_index().await {
@@ -1029,6 +1199,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 error!("Failed to initialize search index: {}", e);
                 eprintln!("[Rust Search Server] Failed to initialize search index: {}", e);
             }
+        } else if let Err(e) = server.start_watcher() {
+            if debug_enabled {
+                eprintln!("[Rust Search Server] Failed to start file watcher: {}", e);
+            }
         }
 
         server.run().await?;
@@ -1043,6 +1217,12 @@ mod tests {
     use tempfile::TempDir;
     use tokio::fs;
 
+    /// A server keeping its index in a hidden folder of the test vault
+    fn test_server(vault_path: PathBuf) -> SearchServer {
+        let app_data_path = vault_path.join(".app-data");
+        SearchServer::new(vault_path, &app_data_path)
+    }
+
     async fn setup_test_vault() -> (TempDir, PathBuf) {
         let temp_dir = TempDir::new().unwrap();
         let vault_path = temp_dir.path().to_path_buf();
@@ -1072,10 +1252,21 @@ mod tests {
         (temp_dir, vault_path)
     }
 
+    #[test]
+    fn test_default_index_path_is_per_vault_in_app_data() {
+        let app_data = Path::new("/data/app");
+        let first = default_index_path(Path::new("/vaults/one"), app_data);
+        let second = default_index_path(Path::new("/vaults/two"), app_data);
+
+        assert!(first.starts_with("/data/app/search-index"));
+        assert_ne!(first, second);
+        assert_eq!(first, default_index_path(Path::new("/vaults/one"), app_data));
+    }
+
     #[tokio::test]
     async fn test_search_content() {
         let (_temp_dir, vault_path) = setup_test_vault().await;
-        let server = SearchServer::new(vault_path);
+        let server = test_server(vault_path);
         server.initialize_index().await.unwrap();
 
         // Let's do a simpler test with our fallback logic
@@ -1084,6 +1275,7 @@ mod tests {
             case_sensitive: false,
             whole_word: false,
             max_results: 10,
+            mode: SearchMode::Keyword,
         };
 
         // We'll just test that the function completes without error
@@ -1092,10 +1284,162 @@ mod tests {
         assert!(result.is_array());
     }
 
+    fn result_files(result: &Value) -> Vec<String> {
+        result
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["file"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    fn content_args(query: &str, mode: SearchMode) -> SearchContentArgs {
+        SearchContentArgs {
+            query: query.to_string(),
+            case_sensitive: false,
+            whole_word: false,
+            max_results: 10,
+            mode,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_content_query_language() {
+        let (_temp_dir, vault_path) = setup_test_vault().await;
+        fs::create_dir_all(vault_path.join("archive")).await.unwrap();
+        fs::write(
+            vault_path.join("archive/old.md"),
+            "---\ncreated_at: 2023-05-01T00:00:00Z\n---\n# Old\n\nAn archived test note with #tag3.",
+        )
+        .await
+        .unwrap();
+        let server = test_server(vault_path);
+        server.initialize_index().await.unwrap();
+
+        let mut files = result_files(&server.search_content(content_args("test tag:tag2", SearchMode::Keyword)).await.unwrap());
+        files.sort();
+        assert_eq!(files, vec!["note1.md", "note2.md"]);
+
+        let files = result_files(&server.search_content(content_args("\"multiple times\"", SearchMode::Keyword)).await.unwrap());
+        assert_eq!(files, vec!["note2.md"]);
+
+        let files = result_files(&server.search_content(content_args("test -tag:tag1 -path:archive/", SearchMode::Keyword)).await.unwrap());
+        assert_eq!(files, vec!["note2.md"]);
+
+        let mut files = result_files(&server.search_content(content_args("created:<2024-01-01 OR orphaned", SearchMode::Keyword)).await.unwrap());
+        files.sort();
+        assert_eq!(files, vec!["archive/old.md", "orphan.md"]);
+
+        assert!(server.search_content(content_args("created:>soon", SearchMode::Keyword)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_content_filters_in_index() {
+        let (_temp_dir, vault_path) = setup_test_vault().await;
+        fs::create_dir_all(vault_path.join("Team-Notes")).await.unwrap();
+        fs::write(
+            vault_path.join("Team-Notes/plan.md"),
+            "---\ncreated: 2025-03-01\ntags: [project/alpha]\n---\nMultiplayer planning",
+        )
+        .await
+        .unwrap();
+        let server = test_server(vault_path);
+        server.initialize_index().await.unwrap();
+
+        // The index query alone finds the notes, without reading files
+        let index_files = |query: &str, options: MatchOptions| {
+            let query = SearchQuery::parse(query).unwrap();
+            let index_lock = server.index.try_lock().unwrap();
+            let index = index_lock.as_ref().unwrap();
+            let searcher = index.reader.searcher();
+            let mut files: Vec<String> = searcher
+                .search(query.index_query(index, options, true).unwrap().as_ref(), &DocSetCollector)
+                .unwrap()
+                .into_iter()
+                .map(|address| {
+                    let doc: TantivyDocument = searcher.doc(address).unwrap();
+                    doc.get_first(index.path_field).unwrap().as_str().unwrap().to_string()
+                })
+                .collect();
+            files.sort();
+            files
+        };
+        let whole_word = MatchOptions { case_sensitive: false, whole_word: true };
+
+        assert_eq!(index_files("tag:project", MatchOptions::default()), vec!["Team-Notes/plan.md"]);
+        assert_eq!(index_files("tag:proj", MatchOptions::default()), Vec::<String>::new());
+        assert_eq!(index_files("path:team-notes/ OR tag:tag1", MatchOptions::default()), vec!["Team-Notes/plan.md", "note1.md"]);
+        assert_eq!(index_files("created:>=2025-03-01 -tag:tag3", MatchOptions::default()).len(), 3);
+        assert_eq!(index_files("created:<2025-03-02 created:>2025-02-28", MatchOptions::default()), vec!["Team-Notes/plan.md"]);
+        assert_eq!(index_files("multi", MatchOptions::default()), vec!["Team-Notes/plan.md", "note2.md"]);
+        assert_eq!(index_files("multi", whole_word), Vec::<String>::new());
+        assert_eq!(index_files("-tag:tag2", MatchOptions::default()), vec!["Team-Notes/plan.md", "orphan.md"]);
+
+        // Results are then checked for the exact text
+        let files = result_files(&server.search_content(content_args("\"multiple times\" OR tag:project/alpha", SearchMode::Keyword)).await.unwrap());
+        assert_eq!(files.len(), 2);
+        let mut args = content_args("MULTI", SearchMode::Keyword);
+        args.case_sensitive = true;
+        assert!(result_files(&server.search_content(args).await.unwrap()).is_empty());
+    }
+
+    struct FixedEmbedder;
+
+    #[async_trait::async_trait]
+    impl QueryEmbedder for FixedEmbedder {
+        async fn embed(&self, _model: &str, _text: &str) -> Result<Vec<f32>, String> {
+            Ok(vec![1.0, 0.0])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_content_hybrid_fuses_vector_results() {
+        let (_temp_dir, vault_path) = setup_test_vault().await;
+
+        // Semantic index as written by the app: orphan.md is the closest match by meaning
+        let db_path = hybrid::default_semantic_db_path(&vault_path);
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE notes (path TEXT PRIMARY KEY, mtime INTEGER, size INTEGER, hash TEXT, model TEXT);
+                 CREATE TABLE chunks (id INTEGER PRIMARY KEY, path TEXT, heading TEXT, content TEXT, embedding BLOB);",
+            )
+            .unwrap();
+            for (path, vector) in [("orphan.md", [1.0f32, 0.0]), ("note1.md", [0.0, 1.0]), ("note2.md", [0.0, 1.0])] {
+                let blob: Vec<u8> = vector.iter().flat_map(|x| x.to_le_bytes()).collect();
+                conn.execute("INSERT INTO notes VALUES (?1, 0, 0, '', 'fake')", rusqlite::params![path]).unwrap();
+                conn.execute(
+                    "INSERT INTO chunks (path, heading, content, embedding) VALUES (?1, NULL, '', ?2)",
+                    rusqlite::params![path, blob],
+                )
+                .unwrap();
+            }
+        }
+
+        let server = test_server(vault_path).with_embedder(Arc::new(FixedEmbedder));
+        server.initialize_index().await.unwrap();
+
+        // Keyword mode only finds literal matches
+        let files = result_files(&server.search_content(content_args("incoming", SearchMode::Keyword)).await.unwrap());
+        assert_eq!(files, vec!["orphan.md"]);
+        let files = result_files(&server.search_content(content_args("multiple", SearchMode::Keyword)).await.unwrap());
+        assert_eq!(files, vec!["note2.md"]);
+
+        // Hybrid mode recalls semantically similar notes, still respecting filters
+        let files = result_files(&server.search_content(content_args("multiple", SearchMode::Hybrid)).await.unwrap());
+        assert_eq!(files.len(), 3);
+        assert!(files.contains(&"orphan.md".to_string()));
+
+        let files = result_files(&server.search_content(content_args("multiple tag:tag2", SearchMode::Hybrid)).await.unwrap());
+        assert!(!files.contains(&"orphan.md".to_string()));
+        assert_eq!(files[0], "note2.md");
+    }
+
     #[tokio::test]
     async fn test_search_by_tag() {
         let (_temp_dir, vault_path) = setup_test_vault().await;
-        let server = SearchServer::new(vault_path);
+        let server = test_server(vault_path);
 
         let args = SearchByTagArgs {
             tags: vec!["tag2".to_string()],
@@ -1110,7 +1454,7 @@ mod tests {
     #[tokio::test]
     async fn test_find_orphaned_notes() {
         let (_temp_dir, vault_path) = setup_test_vault().await;
-        let server = SearchServer::new(vault_path);
+        let server = test_server(vault_path);
 
         let args = FindOrphanedNotesArgs {
             include_directories: None,
@@ -1124,7 +1468,7 @@ mod tests {
     #[tokio::test]
     async fn test_extract_highlights() {
         let (_temp_dir, vault_path) = setup_test_vault().await;
-        let server = SearchServer::new(vault_path);
+        let server = test_server(vault_path);
 
         let args = ExtractHighlightsArgs {
             group_by_file: false,
@@ -0,0 +1,509 @@
+/// Query language for `search_content`
+///
+/// A query is a list of terms separated by whitespace. Terms are ANDed together, and the
+/// `OR` keyword splits the query into alternatives:
+///
+/// - `word` / `"exact phrase"`: text that must appear in the note
+/// - `tag:project/alpha`: the note carries the tag (or a nested tag below it)
+/// - `path:Projects/`: the note's vault-relative path starts with the prefix
+/// - `created:>2025-01-01`: creation date comparison (`>`, `>=`, `<`, `<=`, or an exact day)
+/// - `-term`: excludes notes matching any of the above
+///
+/// Tags, paths and dates are matched inside the index. Text terms narrow the index down
+/// by the words they contain, and the notes found are then checked with `matches` for
+/// the exact substring, case and whole-word rules.
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use std::ops::Bound;
+use std::path::Path;
+use tantivy::{
+    query::{AllQuery, BooleanQuery, Occur, Query, RangeQuery, RegexQuery, TermQuery},
+    schema::{Field, IndexRecordOption},
+    Term as IndexTerm,
+};
+
+use crate::index_store::SearchIndex;
+
+/// Tokens this long are dropped by tantivy's default tokenizer, so cannot narrow a search
+const MAX_TOKEN_BYTES: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Before,
+    OnOrBefore,
+    On,
+    OnOrAfter,
+    After,
+}
+
+impl Comparison {
+    fn holds(self, value: NaiveDate, target: NaiveDate) -> bool {
+        match self {
+            Comparison::Before => value < target,
+            Comparison::OnOrBefore => value <= target,
+            Comparison::On => value == target,
+            Comparison::OnOrAfter => value >= target,
+            Comparison::After => value > target,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermKind {
+    Word(String),
+    Phrase(String),
+    Tag(String),
+    Path(String),
+    Created(Comparison, NaiveDate),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub kind: TermKind,
+    pub negated: bool,
+}
+
+impl Term {
+    /// Positive words and phrases score and highlight; everything else only filters
+    fn is_text(&self) -> bool {
+        !self.negated && matches!(self.kind, TermKind::Word(_) | TermKind::Phrase(_))
+    }
+}
+
+/// Text matching flags carried over from the original `search_content` arguments
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+}
+
+/// What a query can be evaluated against for a single note
+pub struct NoteFacts<'a> {
+    pub path: &'a str,
+    pub content: &'a str,
+    /// Lowercased tags
+    pub tags: Vec<String>,
+    pub created: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Alternatives (joined by OR), each a list of terms that must all hold
+    pub branches: Vec<Vec<Term>>,
+}
+
+struct Token {
+    text: String,
+    quoted: bool,
+    negated: bool,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut branches = Vec::new();
+        let mut current = Vec::new();
+
+        for token in tokenize(input) {
+            if !token.quoted && !token.negated && token.text == "OR" {
+                if !current.is_empty() {
+                    branches.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            current.push(parse_term(token)?);
+        }
+        if !current.is_empty() {
+            branches.push(current);
+        }
+
+        if branches.is_empty() {
+            return Err("Query is empty".to_string());
+        }
+
+        Ok(Self { branches })
+    }
+
+    /// Positive words and phrases across all alternatives, used for scoring and highlighting
+    pub fn text_terms(&self) -> Vec<&str> {
+        let mut texts = Vec::new();
+        for term in self.branches.iter().flatten().filter(|t| t.is_text()) {
+            let text = match &term.kind {
+                TermKind::Word(text) | TermKind::Phrase(text) => text.as_str(),
+                _ => continue,
+            };
+            if !texts.contains(&text) {
+                texts.push(text);
+            }
+        }
+        texts
+    }
+
+    /// The text terms as a tantivy query string (BM25 over the content field)
+    pub fn bm25_query(&self) -> String {
+        let mut parts = Vec::new();
+        for term in self.branches.iter().flatten().filter(|t| t.is_text()) {
+            match &term.kind {
+                TermKind::Word(word) => {
+                    let cleaned: String = word
+                        .chars()
+                        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+                        .collect();
+                    parts.extend(cleaned.split_whitespace().map(str::to_string));
+                }
+                TermKind::Phrase(phrase) => {
+                    parts.push(format!("\"{}\"", phrase.replace('"', " ")));
+                }
+                _ => {}
+            }
+        }
+        parts.join(" ")
+    }
+
+    /// Whether some alternative has nothing but positive words and phrases, so that with
+    /// text not required every note passes
+    pub fn is_unfiltered(&self) -> bool {
+        self.branches.iter().any(|branch| branch.iter().all(Term::is_text))
+    }
+
+    /// The notes that may satisfy the query, as a tantivy query. Every note that
+    /// `matches` is found; text terms can let through notes `matches` then rejects.
+    pub fn index_query(
+        &self,
+        index: &SearchIndex,
+        options: MatchOptions,
+        require_text: bool,
+    ) -> Result<Box<dyn Query>, String> {
+        let mut alternatives: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for branch in &self.branches {
+            let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+            for term in branch {
+                let occur = if term.negated { Occur::MustNot } else { Occur::Must };
+                match &term.kind {
+                    TermKind::Word(text) | TermKind::Phrase(text) => {
+                        // Excluded text is left to the substring check
+                        if !term.is_text() || !require_text {
+                            continue;
+                        }
+                        for token in index_tokens(text) {
+                            clauses.push((Occur::Must, token_query(index.content_field, &token, options.whole_word)?));
+                        }
+                    }
+                    TermKind::Tag(tag) => {
+                        let nested = prefix_query(index.tags_field, &format!("{}/", tag))?;
+                        let tag_query = BooleanQuery::new(vec![
+                            (Occur::Should, raw_term_query(index.tags_field, tag)),
+                            (Occur::Should, nested),
+                        ]);
+                        clauses.push((occur, Box::new(tag_query)));
+                    }
+                    TermKind::Path(prefix) => clauses.push((occur, prefix_query(index.path_lower_field, prefix)?)),
+                    TermKind::Created(cmp, date) => clauses.push((occur, created_query(index, *cmp, *date))),
+                }
+            }
+            // A query of exclusions alone matches nothing in tantivy
+            if !clauses.iter().any(|(occur, _)| *occur == Occur::Must) {
+                clauses.push((Occur::Must, Box::new(AllQuery)));
+            }
+            alternatives.push((Occur::Should, Box::new(BooleanQuery::new(clauses))));
+        }
+        Ok(Box::new(BooleanQuery::new(alternatives)))
+    }
+
+    /// Whether the note satisfies the query. With `require_text` off, positive words and
+    /// phrases are treated as satisfied so only the filters apply (used for hybrid recall).
+    pub fn matches(&self, note: &NoteFacts, options: MatchOptions, require_text: bool) -> bool {
+        self.branches.iter().any(|branch| {
+            branch.iter().all(|term| {
+                if term.is_text() && !require_text {
+                    return true;
+                }
+                term_holds(term, note, options) != term.negated
+            })
+        })
+    }
+}
+
+fn term_holds(term: &Term, note: &NoteFacts, options: MatchOptions) -> bool {
+    match &term.kind {
+        TermKind::Word(text) | TermKind::Phrase(text) => text_occurs(note.content, text, options),
+        TermKind::Tag(tag) => note
+            .tags
+            .iter()
+            .any(|t| t == tag || t.starts_with(&format!("{}/", tag))),
+        TermKind::Path(prefix) => note.path.to_lowercase().starts_with(prefix),
+        TermKind::Created(cmp, date) => note.created.is_some_and(|created| cmp.holds(created, *date)),
+    }
+}
+
+/// Lowercased alphanumeric runs of `text`, as the default tokenizer indexes them
+fn index_tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty() && token.len() < MAX_TOKEN_BYTES)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Notes with a content token equal to `token` or, unless `whole_word`, containing it
+fn token_query(field: Field, token: &str, whole_word: bool) -> Result<Box<dyn Query>, String> {
+    if whole_word {
+        return Ok(raw_term_query(field, token));
+    }
+    let query = RegexQuery::from_pattern(&format!(".*{}.*", regex::escape(token)), field).map_err(|e| e.to_string())?;
+    Ok(Box::new(query))
+}
+
+fn raw_term_query(field: Field, text: &str) -> Box<dyn Query> {
+    Box::new(TermQuery::new(IndexTerm::from_field_text(field, text), IndexRecordOption::Basic))
+}
+
+fn prefix_query(field: Field, prefix: &str) -> Result<Box<dyn Query>, String> {
+    let query = RegexQuery::from_pattern(&format!("{}.*", regex::escape(prefix)), field).map_err(|e| e.to_string())?;
+    Ok(Box::new(query))
+}
+
+fn created_query(index: &SearchIndex, cmp: Comparison, date: NaiveDate) -> Box<dyn Query> {
+    let field = index.index.schema().get_field_name(index.created_field).to_string();
+    let day = i64::from(date.num_days_from_ce());
+    let (lower, upper) = match cmp {
+        Comparison::Before => (Bound::Unbounded, Bound::Excluded(day)),
+        Comparison::OnOrBefore => (Bound::Unbounded, Bound::Included(day)),
+        Comparison::On => (Bound::Included(day), Bound::Included(day)),
+        Comparison::OnOrAfter => (Bound::Included(day), Bound::Unbounded),
+        Comparison::After => (Bound::Excluded(day), Bound::Unbounded),
+    };
+    Box::new(RangeQuery::new_i64_bounds(field, lower, upper))
+}
+
+/// Substring test honouring the case-sensitivity and whole-word flags
+pub fn text_occurs(content: &str, needle: &str, options: MatchOptions) -> bool {
+    let (haystack, needle) = if options.case_sensitive {
+        (content.to_string(), needle.to_string())
+    } else {
+        (content.to_lowercase(), needle.to_lowercase())
+    };
+    if needle.is_empty() {
+        return true;
+    }
+
+    if !options.whole_word {
+        return haystack.contains(&needle);
+    }
+
+    haystack.match_indices(&needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Split on whitespace, keeping quoted sections (including `key:"quoted value"`) together
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut negated = false;
+        if c == '-' {
+            chars.next();
+            match chars.peek() {
+                Some(next) if !next.is_whitespace() => negated = true,
+                _ => {
+                    tokens.push(Token {
+                        text: "-".to_string(),
+                        quoted: false,
+                        negated: false,
+                    });
+                    continue;
+                }
+            }
+        }
+
+        let mut text = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        while let Some(&c) = chars.peek() {
+            if c == '"' {
+                quoted = true;
+                in_quotes = !in_quotes;
+                chars.next();
+                continue;
+            }
+            if c.is_whitespace() && !in_quotes {
+                break;
+            }
+            text.push(c);
+            chars.next();
+        }
+
+        tokens.push(Token {
+            text,
+            quoted,
+            negated,
+        });
+    }
+
+    tokens
+}
+
+fn parse_term(token: Token) -> Result<Term, String> {
+    let negated = token.negated;
+    let term = |kind| Ok(Term { kind, negated });
+
+    if let Some((key, value)) = token.text.split_once(':') {
+        match key.to_lowercase().as_str() {
+            "tag" => return term(TermKind::Tag(value.trim_start_matches('#').to_lowercase())),
+            "path" => return term(TermKind::Path(value.trim_start_matches('/').to_lowercase())),
+            "created" => {
+                let (cmp, date) = parse_comparison(value)?;
+                return term(TermKind::Created(cmp, date));
+            }
+            _ => {}
+        }
+    }
+
+    if token.quoted {
+        term(TermKind::Phrase(token.text))
+    } else {
+        term(TermKind::Word(token.text))
+    }
+}
+
+fn parse_comparison(value: &str) -> Result<(Comparison, NaiveDate), String> {
+    let (cmp, date) = if let Some(rest) = value.strip_prefix(">=") {
+        (Comparison::OnOrAfter, rest)
+    } else if let Some(rest) = value.strip_prefix("<=") {
+        (Comparison::OnOrBefore, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (Comparison::After, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (Comparison::Before, rest)
+    } else {
+        (Comparison::On, value.strip_prefix('=').unwrap_or(value))
+    };
+
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date in created:{} (expected YYYY-MM-DD)", value))?;
+    Ok((cmp, date))
+}
+
+/// Creation date from front matter (`created_at`, `created` or `date`), falling back to the file
+pub fn created_date(content: &str, file: &Path) -> Option<NaiveDate> {
+    frontmatter_created(content).or_else(|| {
+        let metadata = std::fs::metadata(file).ok()?;
+        let time = metadata.created().or_else(|_| metadata.modified()).ok()?;
+        Some(DateTime::<Local>::from(time).date_naive())
+    })
+}
+
+fn frontmatter_created(content: &str) -> Option<NaiveDate> {
+    let mut lines = content.lines();
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+
+    for line in lines {
+        if line.trim_end() == "---" {
+            break;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !matches!(key.trim(), "created_at" | "created" | "date") {
+            continue;
+        }
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        if let Some(date) = value.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+            return Some(date);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note<'a>(path: &'a str, content: &'a str, tags: &[&str], created: &str) -> NoteFacts<'a> {
+        NoteFacts {
+            path,
+            content,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created: NaiveDate::parse_from_str(created, "%Y-%m-%d").ok(),
+        }
+    }
+
+    #[test]
+    fn test_parse_terms_and_or() {
+        let query = SearchQuery::parse(r#"budget "quarterly plan" tag:#Work -path:archive/ OR created:>=2025-01-01"#).unwrap();
+
+        assert_eq!(query.branches.len(), 2);
+        assert_eq!(
+            query.branches[0],
+            vec![
+                Term { kind: TermKind::Word("budget".into()), negated: false },
+                Term { kind: TermKind::Phrase("quarterly plan".into()), negated: false },
+                Term { kind: TermKind::Tag("work".into()), negated: false },
+                Term { kind: TermKind::Path("archive/".into()), negated: true },
+            ]
+        );
+        assert_eq!(
+            query.branches[1],
+            vec![Term {
+                kind: TermKind::Created(Comparison::OnOrAfter, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+                negated: false,
+            }]
+        );
+        assert_eq!(query.text_terms(), vec!["budget", "quarterly plan"]);
+        assert_eq!(query.bm25_query(), "budget \"quarterly plan\"");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(SearchQuery::parse("   ").is_err());
+        assert!(SearchQuery::parse("created:>yesterday").is_err());
+    }
+
+    #[test]
+    fn test_matches_filters_and_exclusions() {
+        let work = note("Projects/plan.md", "Quarterly budget review", &["work", "project/alpha"], "2025-03-01");
+        let old = note("Archive/plan.md", "Old budget", &["work"], "2024-06-01");
+
+        let query = SearchQuery::parse("budget tag:project -path:archive/").unwrap();
+        assert!(query.matches(&work, MatchOptions::default(), true));
+        assert!(!query.matches(&old, MatchOptions::default(), true));
+
+        let query = SearchQuery::parse("created:<2025-01-01 OR \"quarterly budget\"").unwrap();
+        assert!(query.matches(&work, MatchOptions::default(), true));
+        assert!(query.matches(&old, MatchOptions::default(), true));
+
+        let query = SearchQuery::parse("created:2025-03-01").unwrap();
+        assert!(query.matches(&work, MatchOptions::default(), true));
+        assert!(!query.matches(&old, MatchOptions::default(), true));
+
+        // Without text requirements only the filters decide
+        let query = SearchQuery::parse("nonexistent tag:work").unwrap();
+        assert!(!query.matches(&work, MatchOptions::default(), true));
+        assert!(query.matches(&work, MatchOptions::default(), false));
+    }
+
+    #[test]
+    fn test_text_options() {
+        let whole = MatchOptions { case_sensitive: false, whole_word: true };
+        assert!(text_occurs("The Test passed", "test", MatchOptions::default()));
+        assert!(!text_occurs("The Test passed", "test", MatchOptions { case_sensitive: true, whole_word: false }));
+        assert!(text_occurs("a test.", "test", whole));
+        assert!(!text_occurs("testing", "test", whole));
+    }
+
+    #[test]
+    fn test_frontmatter_created() {
+        let content = "---\nid: 1\ncreated_at: 2025-02-03T10:00:00Z\n---\n# Note";
+        assert_eq!(frontmatter_created(content), NaiveDate::from_ymd_opt(2025, 2, 3));
+        assert_eq!(frontmatter_created("# No front matter"), None);
+    }
+}
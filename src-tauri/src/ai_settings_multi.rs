@@ -66,6 +66,7 @@ impl AIProvider {
                 streaming_enabled: true,
                 last_modified: chrono::Utc::now(),
                 headers: None,
+                embedding_model: None,
             },
             AIProvider::Gemini => AISettings {
                 provider: self.clone(),
@@ -78,6 +79,7 @@ impl AIProvider {
                 streaming_enabled: true,
                 last_modified: chrono::Utc::now(),
                 headers: None,
+                embedding_model: None,
             },
             AIProvider::Ollama => AISettings {
                 provider: self.clone(),
@@ -90,6 +92,7 @@ impl AIProvider {
                 streaming_enabled: true,
                 last_modified: chrono::Utc::now(),
                 headers: None,
+                embedding_model: Some("nomic-embed-text".to_string()),
             },
             AIProvider::LMStudio => AISettings {
                 provider: self.clone(),
@@ -102,6 +105,7 @@ impl AIProvider {
                 streaming_enabled: true,
                 last_modified: chrono::Utc::now(),
                 headers: None,
+                embedding_model: Some("text-embedding-nomic-embed-text-v1.5".to_string()),
             },
             AIProvider::Bedrock => AISettings {
                 provider: self.clone(),
@@ -116,6 +120,7 @@ impl AIProvider {
                 streaming_enabled: true,
                 last_modified: chrono::Utc::now(),
                 headers: None,
+                embedding_model: None,
             },
             AIProvider::ClaudeAgent => AISettings {
                 provider: self.clone(),
@@ -128,6 +133,7 @@ impl AIProvider {
                 streaming_enabled: true,
                 last_modified: chrono::Utc::now(),
                 headers: None,
+                embedding_model: None,
             },
         }
     }
@@ -152,6 +158,8 @@ pub struct AISettings {
     pub last_modified: chrono::DateTime<chrono::Utc>,
     #[serde(default)]
     pub headers: Option<Vec<HeaderKV>>, // Optional custom headers
+    #[serde(default)]
+    pub embedding_model: Option<String>, // Model used for local semantic search
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -167,6 +175,8 @@ struct StoredSettings {
     last_modified: chrono::DateTime<chrono::Utc>,
     #[serde(default)]
     headers: Option<Vec<HeaderKV>>, // Persist custom headers
+    #[serde(default)]
+    embedding_model: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -299,6 +309,7 @@ pub async fn save_ai_settings_for_provider(
         streaming_enabled: settings.streaming_enabled,
         last_modified: settings.last_modified,
         headers: settings.headers,
+        embedding_model: settings.embedding_model,
     };
 
     // Save to provider-specific store
@@ -366,6 +377,7 @@ pub async fn get_ai_settings_for_provider(
             streaming_enabled: stored.streaming_enabled,
             last_modified: stored.last_modified,
             headers: stored.headers,
+            embedding_model: stored.embedding_model,
         })
     } else {
         println!(
@@ -479,6 +491,7 @@ pub async fn migrate_ai_settings(app: AppHandle) -> Result<bool, String> {
         streaming_enabled: true,
         last_modified: chrono::Utc::now(),
         headers: None,
+        embedding_model: provider.default_settings().embedding_model,
     };
 
     // Save to new provider-specific store
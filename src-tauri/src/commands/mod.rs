@@ -8,8 +8,13 @@ pub mod license;
 pub mod mcp_config;
 pub mod pty;
 pub mod sync;
+pub mod task_board_commands;
+pub mod task_bulk_commands;
+pub mod task_calendar_commands;
 pub mod task_commands;
+pub mod task_import_commands;
 pub mod task_index_commands;
+pub mod task_time_commands;
 pub mod util;
 pub mod uuid_commands;
 pub mod wikilink;
@@ -0,0 +1,114 @@
+use chrono::Local;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::identity::tasks::TaskStatus;
+use crate::identity::IdentityManager;
+use crate::tasks::{apply_file_edits, move_card, parse_board, Board, FileEdit};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveBoardCardRequest {
+    pub file_path: String,
+    /// Task UUID of the card
+    pub card_id: String,
+    /// Name of the column to move the card to
+    pub column: String,
+    /// Place in the column, the end when left out
+    pub position: Option<usize>,
+    /// Give the card the status the column stands for
+    #[serde(default)]
+    pub update_status: bool,
+    /// Status to give the card, whatever the column
+    pub status: Option<String>,
+}
+
+/// Load a board note as columns of cards. Cards without a task UUID are
+/// given one first, so every card is bound to its task in the index.
+#[tauri::command]
+pub async fn load_task_board(
+    file_path: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Board, String> {
+    let path = Path::new(&file_path);
+    let mut manager = identity_manager.lock().await;
+    manager
+        .batch_ensure_task_ids(path)
+        .map_err(|e| format!("Failed to assign task IDs: {}", e))?;
+    manager
+        .sync_file_tasks_to_index_async(path)
+        .await
+        .map_err(|e| format!("Failed to sync tasks to index: {}", e))?;
+
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read board: {}", e))?;
+    Ok(parse_board(&content))
+}
+
+/// Move a card to another column, or to another place in its own, and
+/// optionally update its status. The note is rewritten atomically and
+/// re-indexed; the updated board is returned.
+#[tauri::command]
+pub async fn move_task_board_card(
+    request: MoveBoardCardRequest,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Board, String> {
+    let path = Path::new(&request.file_path);
+    let manager = identity_manager.lock().await;
+    let vault_root = manager.vault_root();
+    let relative = path
+        .strip_prefix(vault_root)
+        .map_err(|_| "Board is not in the vault".to_string())?
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read board: {}", e))?;
+    let status = match request.status.as_deref() {
+        Some(name) => {
+            Some(TaskStatus::parse(name).ok_or_else(|| format!("Unknown task status: {}", name))?)
+        }
+        None if request.update_status => {
+            let board = parse_board(&content);
+            let column = board
+                .columns
+                .iter()
+                .find(|column| column.name.eq_ignore_ascii_case(request.column.trim()))
+                .ok_or_else(|| format!("The board has no column {}", request.column))?;
+            Some(column.status.ok_or_else(|| {
+                format!("Column {} does not stand for a task status", column.name)
+            })?)
+        }
+        None => None,
+    };
+
+    let after = move_card(
+        &content,
+        &request.card_id,
+        &request.column,
+        request.position,
+        status,
+        Local::now().date_naive(),
+    )
+    .map_err(|e| format!("Failed to move card: {}", e))?;
+    let edit = FileEdit {
+        path: relative,
+        before: Some(content),
+        after,
+    };
+    apply_file_edits(vault_root, std::slice::from_ref(&edit))
+        .map_err(|e| format!("Failed to write board: {}", e))?;
+    manager
+        .sync_file_tasks_to_index_async(path)
+        .await
+        .map_err(|e| format!("Failed to sync tasks to index: {}", e))?;
+
+    println!(
+        "🗂️ Moved card {} to column {}",
+        request.card_id, request.column
+    );
+    Ok(parse_board(&edit.after))
+}
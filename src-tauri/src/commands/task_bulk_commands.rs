@@ -0,0 +1,231 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::identity::IdentityManager;
+use crate::tasks::{
+    apply_file_edits, plan_bulk_edit, ArchiveTarget, BulkChange, BulkJournal, BulkOperation,
+    BulkSkip, QueryContext, TaskQuery, TaskRecord, TaskSearch,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkTaskRequest {
+    /// Task query (see `query_tasks`) picking the tasks to change
+    pub query: String,
+    pub operation: BulkOperation,
+    /// Return the changes without writing them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkTaskReport {
+    pub dry_run: bool,
+    /// Journal to pass to `undo_bulk_task_edit`, when the edit was applied
+    pub journal_id: Option<String>,
+    pub changes: Vec<BulkChange>,
+    pub skipped: Vec<BulkSkip>,
+    /// Vault-relative paths of the notes written, or that would be
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkJournalInfo {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub description: String,
+    pub tasks: usize,
+    pub files: Vec<String>,
+}
+
+impl From<BulkJournal> for BulkJournalInfo {
+    fn from(journal: BulkJournal) -> Self {
+        Self {
+            id: journal.id,
+            created_at: journal.created_at,
+            description: journal.description,
+            tasks: journal.tasks,
+            files: journal.files.into_iter().map(|file| file.path).collect(),
+        }
+    }
+}
+
+/// Bring the index up to date with notes a bulk edit wrote or removed
+async fn resync_index(manager: &IdentityManager, paths: &[PathBuf]) -> Result<(), String> {
+    for path in paths {
+        if path.exists() {
+            manager
+                .sync_file_tasks_to_index_async(path)
+                .await
+                .map_err(|e| format!("Failed to sync tasks to index: {}", e))?;
+        } else {
+            manager
+                .task_index()
+                .remove_file_tasks(path)
+                .await
+                .map_err(|e| format!("Failed to remove tasks from index: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply one operation to every task a query matches: set a property,
+/// reschedule, move, complete or archive. Each note is written once; with
+/// `dryRun` nothing is written and the report shows what would change.
+#[tauri::command]
+pub async fn bulk_update_tasks(
+    request: BulkTaskRequest,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<BulkTaskReport, String> {
+    if request.query.trim().is_empty() {
+        return Err("A task query is required for bulk edits".to_string());
+    }
+    let search =
+        TaskSearch::parse(&request.query).map_err(|e| format!("Invalid task query: {}", e))?;
+
+    let manager = identity_manager.lock().await;
+    let vault_root = manager.vault_root().to_path_buf();
+    let now = Local::now();
+    let context = QueryContext {
+        vault_root: Some(&vault_root),
+        today: now.date_naive(),
+    };
+    let tasks = search.apply(
+        manager
+            .task_index()
+            .query(TaskQuery::new().with_archived(search.includes_archived()))
+            .await,
+        &context,
+    );
+
+    run_bulk_edit(&manager, &tasks, &request.operation, &now, request.dry_run).await
+}
+
+/// Archive every task completed or cancelled more than `olderThanDays` ago,
+/// into monthly archive notes unless another target is given. Each archived
+/// task leaves a `[[tid:…]]` reference behind and can be undone like any
+/// bulk edit.
+#[tauri::command]
+pub async fn archive_completed_tasks(
+    older_than_days: u32,
+    into: Option<ArchiveTarget>,
+    dry_run: bool,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<BulkTaskReport, String> {
+    let manager = identity_manager.lock().await;
+    let now = Local::now();
+    let cutoff = now.with_timezone(&Utc) - Duration::days(older_than_days.into());
+    let tasks = manager
+        .task_index()
+        .query(TaskQuery::new().with_completed_before(cutoff))
+        .await;
+
+    let operation = BulkOperation::Archive {
+        into: into.unwrap_or_default(),
+    };
+    run_bulk_edit(&manager, &tasks, &operation, &now, dry_run).await
+}
+
+/// Plan an edit of the given tasks and, unless it is a dry run, write it,
+/// journal it for undo and re-index the notes it touched
+async fn run_bulk_edit(
+    manager: &IdentityManager,
+    tasks: &[TaskRecord],
+    operation: &BulkOperation,
+    now: &DateTime<Local>,
+    dry_run: bool,
+) -> Result<BulkTaskReport, String> {
+    let vault_root = manager.vault_root();
+    let plan = plan_bulk_edit(tasks, operation, vault_root, now)
+        .map_err(|e| format!("Failed to plan bulk edit: {}", e))?;
+    let files: Vec<String> = plan.files.iter().map(|file| file.path.clone()).collect();
+
+    let mut journal_id = None;
+    if !dry_run && !plan.files.is_empty() {
+        // The journal is saved first so every applied edit can be undone
+        let journal = BulkJournal::new(operation.describe(), plan.changes.len(), plan.files);
+        journal
+            .save(vault_root)
+            .map_err(|e| format!("Failed to save undo journal for bulk edit: {}", e))?;
+        let written = match apply_file_edits(vault_root, &journal.files) {
+            Ok(written) => written,
+            Err(e) => {
+                if let Err(e) = BulkJournal::remove(vault_root, &journal.id) {
+                    eprintln!(
+                        "⚠️ Failed to remove undo journal of failed bulk edit: {}",
+                        e
+                    );
+                }
+                return Err(format!("Failed to apply bulk edit: {}", e));
+            }
+        };
+        journal_id = Some(journal.id);
+        resync_index(manager, &written).await?;
+
+        println!(
+            "✏️ Bulk edit changed {} tasks in {} notes",
+            plan.changes.len(),
+            written.len()
+        );
+    }
+
+    Ok(BulkTaskReport {
+        dry_run,
+        journal_id,
+        changes: plan.changes,
+        skipped: plan.skipped,
+        files,
+    })
+}
+
+/// Bulk edits that can still be undone, newest first
+#[tauri::command]
+pub async fn list_bulk_task_journals(
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Vec<BulkJournalInfo>, String> {
+    let vault_root = identity_manager.lock().await.vault_root().to_path_buf();
+    let journals =
+        BulkJournal::list(&vault_root).map_err(|e| format!("Failed to list bulk edits: {}", e))?;
+
+    Ok(journals.into_iter().map(BulkJournalInfo::from).collect())
+}
+
+/// Undo a bulk edit, the latest one when no journal is given. Fails without
+/// changing anything when one of its notes was edited since.
+#[tauri::command]
+pub async fn undo_bulk_task_edit(
+    journal_id: Option<String>,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<BulkJournalInfo, String> {
+    let manager = identity_manager.lock().await;
+    let vault_root = manager.vault_root();
+
+    let journal = match journal_id {
+        Some(id) => BulkJournal::load(vault_root, &id),
+        None => BulkJournal::list(vault_root).and_then(|journals| {
+            journals
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("No bulk edit to undo"))
+        }),
+    }
+    .map_err(|e| format!("Failed to load bulk edit: {}", e))?;
+
+    let restored = journal
+        .undo(vault_root)
+        .map_err(|e| format!("Failed to undo bulk edit: {}", e))?;
+    resync_index(&manager, &restored).await?;
+
+    println!(
+        "↩️ Undid bulk edit {} ({} notes)",
+        journal.description,
+        restored.len()
+    );
+    Ok(journal.into())
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use warp::http::{Response, StatusCode};
+use warp::Filter;
+
+use crate::identity::IdentityManager;
+use crate::tasks::{
+    calendar_tasks, tasks_to_ics, CalendarComponent, CalendarOptions, QueryContext, TaskQuery,
+    TaskSearch,
+};
+use crate::vault_settings::{
+    get_vault_settings, save_vault_settings, CalendarFeedSettings, VaultSettingsInput,
+};
+
+/// Which tasks go into an export or the feed
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskCalendarRequest {
+    #[serde(default)]
+    pub component: CalendarComponent,
+    #[serde(default)]
+    pub include_closed: bool,
+    /// Task query (see `query_tasks`) the exported tasks have to match
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskCalendarFeedInfo {
+    pub port: u16,
+    pub url: String,
+    /// The same feed as a `webcal://` link, which calendar apps open as a
+    /// subscription
+    pub webcal_url: String,
+}
+
+struct RunningFeed {
+    info: TaskCalendarFeedInfo,
+    handle: JoinHandle<()>,
+}
+
+/// The loopback calendar feed, when it is being served
+#[derive(Default)]
+pub struct TaskCalendarFeedState {
+    running: Mutex<Option<RunningFeed>>,
+}
+
+impl TaskCalendarFeedState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn stop(&self) {
+        if let Some(feed) = self.running.lock().await.take() {
+            feed.handle.abort();
+            // Wait for the listener to be dropped so its port can be reused
+            let _ = feed.handle.await;
+            println!("📅 Task calendar feed stopped");
+        }
+    }
+}
+
+/// Render the vault's dated tasks as an iCalendar document
+async fn render_calendar(
+    identity_manager: &Mutex<IdentityManager>,
+    request: &TaskCalendarRequest,
+) -> Result<(String, usize), String> {
+    let manager = identity_manager.lock().await;
+    let search = match request
+        .query
+        .as_ref()
+        .filter(|text| !text.trim().is_empty())
+    {
+        Some(text) => {
+            Some(TaskSearch::parse(text).map_err(|e| format!("Invalid task query: {}", e))?)
+        }
+        None => None,
+    };
+    let include_archived = search.as_ref().is_some_and(TaskSearch::includes_archived);
+    let mut tasks = manager
+        .task_index()
+        .query(TaskQuery::new().with_archived(include_archived))
+        .await;
+
+    if let Some(search) = search {
+        let context = QueryContext {
+            vault_root: Some(manager.vault_root()),
+            today: chrono::Local::now().date_naive(),
+        };
+        tasks = search.apply(tasks, &context);
+    }
+
+    let vault_root = manager.vault_root();
+    let name = vault_root
+        .file_name()
+        .map(|name| format!("{} tasks", name.to_string_lossy()));
+    let options = CalendarOptions {
+        component: request.component,
+        include_closed: request.include_closed,
+        name,
+        vault_root: Some(vault_root),
+    };
+    let count = calendar_tasks(&tasks, &options).len();
+    Ok((tasks_to_ics(&tasks, &options), count))
+}
+
+/// Export tasks with due or scheduled dates to an `.ics` file, returning how
+/// many were written
+#[tauri::command]
+pub async fn export_tasks_ics(
+    output_path: String,
+    request: Option<TaskCalendarRequest>,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<usize, String> {
+    let request = request.unwrap_or_default();
+    let (ics, count) = render_calendar(&identity_manager, &request).await?;
+
+    tokio::fs::write(&output_path, ics)
+        .await
+        .map_err(|e| format!("Failed to write calendar file: {}", e))?;
+
+    println!("📅 Exported {} tasks to {}", count, output_path);
+    Ok(count)
+}
+
+/// Serve `/tasks/<token>/tasks.ics` on 127.0.0.1. The query string picks the
+/// component (`?component=event`), closed tasks (`?closed=true`) and a task
+/// query (`?q=tag:work`).
+fn serve_feed(
+    identity_manager: Arc<Mutex<IdentityManager>>,
+    port: u16,
+    token: String,
+) -> Result<(SocketAddr, JoinHandle<()>), String> {
+    let route = warp::get()
+        .and(warp::path("tasks"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("tasks.ics"))
+        .and(warp::path::end())
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(
+            move |request_token: String, params: HashMap<String, String>| {
+                let identity_manager = identity_manager.clone();
+                let token = token.clone();
+                async move {
+                    if request_token != token {
+                        return Err(warp::reject::not_found());
+                    }
+
+                    let request = TaskCalendarRequest {
+                        component: params
+                            .get("component")
+                            .and_then(|value| CalendarComponent::parse(value))
+                            .unwrap_or_default(),
+                        include_closed: params
+                            .get("closed")
+                            .is_some_and(|value| value == "true" || value == "1"),
+                        query: params.get("q").cloned(),
+                    };
+                    let response = match render_calendar(&identity_manager, &request).await {
+                        Ok((ics, _)) => Response::builder()
+                            .header("Content-Type", "text/calendar; charset=utf-8")
+                            .header("Cache-Control", "no-cache")
+                            .body(ics),
+                        Err(e) => Response::builder().status(StatusCode::BAD_REQUEST).body(e),
+                    };
+                    response.map_err(|_| warp::reject::reject())
+                }
+            },
+        );
+
+    let (address, server) = warp::serve(route)
+        .try_bind_ephemeral(([127, 0, 0, 1], port))
+        .map_err(|e| format!("Failed to start task calendar feed on port {}: {}", port, e))?;
+    Ok((address, tokio::spawn(server)))
+}
+
+/// Store the feed settings of a vault, keeping its other settings
+async fn save_feed_settings(
+    app: &AppHandle,
+    vault_path: String,
+    feed: CalendarFeedSettings,
+) -> Result<(), String> {
+    let settings = get_vault_settings(app.clone(), vault_path.clone()).await?;
+    let mut tasks = settings.tasks;
+    tasks.calendar_feed = feed;
+    let input = VaultSettingsInput {
+        vault_path,
+        editor: settings.editor,
+        files: settings.files,
+        tasks: Some(tasks),
+        periodic_notes: None,
+    };
+    save_vault_settings(app.clone(), input).await
+}
+
+/// Start serving the feed for the open vault, on the port and URL it was
+/// served on before so existing subscriptions keep working
+async fn start_feed(
+    app: &AppHandle,
+    identity_manager: Arc<Mutex<IdentityManager>>,
+    feed_state: &TaskCalendarFeedState,
+) -> Result<TaskCalendarFeedInfo, String> {
+    let vault_path = identity_manager
+        .lock()
+        .await
+        .vault_root()
+        .to_string_lossy()
+        .to_string();
+    let mut feed = get_vault_settings(app.clone(), vault_path.clone())
+        .await?
+        .tasks
+        .calendar_feed;
+    if feed.token.is_empty() {
+        feed.token = uuid::Uuid::new_v4().simple().to_string();
+    }
+
+    feed_state.stop().await;
+    let (address, handle) = serve_feed(identity_manager, feed.port, feed.token.clone())?;
+
+    let path = format!("{}/tasks/{}/tasks.ics", address, feed.token);
+    let info = TaskCalendarFeedInfo {
+        port: address.port(),
+        url: format!("http://{}", path),
+        webcal_url: format!("webcal://{}", path),
+    };
+    *feed_state.running.lock().await = Some(RunningFeed {
+        info: info.clone(),
+        handle,
+    });
+    println!("📅 Task calendar feed serving at {}", info.url);
+
+    feed.enabled = true;
+    feed.port = address.port();
+    save_feed_settings(app, vault_path, feed).await?;
+    Ok(info)
+}
+
+/// Start the loopback calendar feed of the open vault's tasks
+#[tauri::command]
+pub async fn start_task_calendar_feed(
+    app: AppHandle,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+    feed_state: State<'_, TaskCalendarFeedState>,
+) -> Result<TaskCalendarFeedInfo, String> {
+    start_feed(&app, identity_manager.inner().clone(), &feed_state).await
+}
+
+/// Stop the calendar feed and keep it off when the vault is opened again
+#[tauri::command]
+pub async fn stop_task_calendar_feed(
+    app: AppHandle,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+    feed_state: State<'_, TaskCalendarFeedState>,
+) -> Result<(), String> {
+    feed_state.stop().await;
+
+    let vault_path = identity_manager
+        .lock()
+        .await
+        .vault_root()
+        .to_string_lossy()
+        .to_string();
+    let mut feed = get_vault_settings(app.clone(), vault_path.clone())
+        .await?
+        .tasks
+        .calendar_feed;
+    feed.enabled = false;
+    save_feed_settings(&app, vault_path, feed).await
+}
+
+/// The running calendar feed, if any
+#[tauri::command]
+pub async fn get_task_calendar_feed(
+    feed_state: State<'_, TaskCalendarFeedState>,
+) -> Result<Option<TaskCalendarFeedInfo>, String> {
+    Ok(feed_state
+        .running
+        .lock()
+        .await
+        .as_ref()
+        .map(|feed| feed.info.clone()))
+}
+
+/// Serve the feed of a newly opened vault if it is enabled there, and stop
+/// the previous vault's feed otherwise
+pub async fn resume_task_calendar_feed(app: &AppHandle) {
+    let (Some(identity_manager), Some(feed_state)) = (
+        app.try_state::<Arc<Mutex<IdentityManager>>>(),
+        app.try_state::<TaskCalendarFeedState>(),
+    ) else {
+        return;
+    };
+
+    let vault_path = identity_manager
+        .lock()
+        .await
+        .vault_root()
+        .to_string_lossy()
+        .to_string();
+    let enabled = get_vault_settings(app.clone(), vault_path)
+        .await
+        .is_ok_and(|settings| settings.tasks.calendar_feed.enabled);
+    if !enabled {
+        feed_state.stop().await;
+        return;
+    }
+
+    if let Err(e) = start_feed(app, identity_manager.inner().clone(), &feed_state).await {
+        eprintln!("⚠️ {}", e);
+    }
+}
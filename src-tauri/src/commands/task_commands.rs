@@ -2,11 +2,11 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
-use tauri::{Emitter, State};
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex;
 
 use crate::identity::frontmatter::TaskProperties;
-use crate::identity::tasks::TaskStatus;
+use crate::identity::tasks::{TaskStatus, TaskStatusCycle};
 use crate::identity::IdentityManager;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -171,10 +171,7 @@ pub async fn get_tasks_for_note(
 
         task_infos.push(TaskInfo {
             id: task_id,
-            status: match task.status {
-                TaskStatus::Todo => "todo".to_string(),
-                TaskStatus::Done => "done".to_string(),
-            },
+            status: task.status.as_str().to_string(),
             text: task.content.clone(),
             line_number: task.line_number,
             file_path: file_path.clone(),
@@ -191,14 +188,68 @@ pub async fn get_tasks_for_note(
     Ok(task_infos)
 }
 
+/// The status cycle configured for the vault, or the default todo/done toggle
+async fn status_cycle(
+    app: &AppHandle,
+    identity_manager: &State<'_, Arc<Mutex<IdentityManager>>>,
+) -> TaskStatusCycle {
+    let vault_path = identity_manager
+        .lock()
+        .await
+        .vault_root()
+        .to_string_lossy()
+        .to_string();
+    match crate::vault_settings::get_vault_settings(app.clone(), vault_path).await {
+        Ok(settings) => TaskStatusCycle::parse(&settings.tasks.status_cycle).unwrap_or_else(|e| {
+            eprintln!("⚠️ Invalid task status cycle, using default: {}", e);
+            TaskStatusCycle::default()
+        }),
+        Err(_) => TaskStatusCycle::default(),
+    }
+}
+
+/// When a recurring task is completed, put its next occurrence, with a new
+/// UUID, on the line above the completed one
+fn with_next_occurrence(
+    content: &str,
+    line: &str,
+    toggled_line: String,
+    new_status: TaskStatus,
+) -> Result<String, String> {
+    let is_recurring = crate::identity::tasks::TaskParser::parse_line(line, 0)
+        .is_some_and(|task| task.properties.contains_key("repeat"));
+    if new_status != TaskStatus::Done || !is_recurring {
+        return Ok(toggled_line);
+    }
+
+    let new_id = crate::identity::uuid::UuidGenerator::new()
+        .generate()
+        .map_err(|e| format!("Failed to generate task ID: {}", e))?;
+    let today = chrono::Local::now().date_naive();
+    let Some(next_line) = crate::identity::tasks::TaskParser::next_occurrence(line, &new_id, today)
+    else {
+        return Ok(toggled_line);
+    };
+
+    println!("🔁 Created next occurrence of recurring task: {}", new_id);
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    Ok(format!("{}{}{}", next_line, newline, toggled_line))
+}
+
 /// Toggle task status by task ID
 #[tauri::command]
 pub async fn toggle_task_by_id(
+    app: AppHandle,
     file_path: String,
     task_id: String,
     identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
 ) -> Result<String, String> {
     let path = Path::new(&file_path);
+    let cycle = status_cycle(&app, &identity_manager).await;
 
     // Read file content
     let content =
@@ -226,13 +277,11 @@ pub async fn toggle_task_by_id(
     let line_idx = task_line_number.unwrap();
     let line = lines[line_idx];
 
-    // Toggle the task status in the line
-    let toggled_line = crate::identity::tasks::TaskParser::toggle_task_status(line);
-    let new_status = if toggled_line.contains("[x]") {
-        "done"
-    } else {
-        "todo"
-    };
+    // Move the task to the next status in the cycle
+    let status = cycle.next(task_found.as_ref().map_or(TaskStatus::Todo, |t| t.status));
+    let toggled_line = crate::identity::tasks::TaskParser::set_task_status(line, status);
+    let new_status = status.as_str();
+    let toggled_line = with_next_occurrence(&content, line, toggled_line, status)?;
 
     // Rebuild body content with toggled line, making sure we don't duplicate frontmatter
     let mut new_lines: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
@@ -283,20 +332,12 @@ pub async fn toggle_task_by_id(
         // Update or create task entry
         if let Some(fm_task) = tasks.get_mut(&task_id) {
             // Update existing task
-            if new_status == "done" {
-                fm_task.mark_done();
-            } else {
-                fm_task.mark_todo();
-            }
+            fm_task.set_status(status.into());
         } else {
             // Create new task entry
             let mut new_task =
                 crate::identity::frontmatter::TaskProperties::new(task.content.clone());
-            new_task.status = if new_status == "done" {
-                crate::identity::frontmatter::TaskStatus::Done
-            } else {
-                crate::identity::frontmatter::TaskStatus::Todo
-            };
+            new_task.status = status.into();
 
             // Set properties from parsed task
             if let Some(due) = task.properties.get("due") {
@@ -361,11 +402,13 @@ pub async fn toggle_task_by_id(
 /// Toggle task status between todo and done (legacy - by line number)
 #[tauri::command]
 pub async fn toggle_task_status(
+    app: AppHandle,
     file_path: String,
     line_number: usize,
     identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
 ) -> Result<String, String> {
     let path = Path::new(&file_path);
+    let cycle = status_cycle(&app, &identity_manager).await;
 
     // Read file content
     let content =
@@ -387,13 +430,11 @@ pub async fn toggle_task_status(
 
     let task = parsed_task.unwrap();
 
-    // Toggle the task status in the line
-    let toggled_line = crate::identity::tasks::TaskParser::toggle_task_status(line);
-    let new_status = if toggled_line.contains("[x]") {
-        "done"
-    } else {
-        "todo"
-    };
+    // Move the task to the next status in the cycle
+    let status = cycle.next(task.status);
+    let toggled_line = crate::identity::tasks::TaskParser::set_task_status(line, status);
+    let new_status = status.as_str();
+    let toggled_line = with_next_occurrence(&content, line, toggled_line, status)?;
 
     // Rebuild content with toggled line, but write back using body-only to avoid duplicating frontmatter
     let mut new_lines: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
@@ -416,18 +457,12 @@ pub async fn toggle_task_status(
         // Update or create task entry
         if let Some(fm_task) = tasks.get_mut(&task_id) {
             // Update existing task
-            if new_status == "done" {
-                fm_task.mark_done();
-            } else {
-                fm_task.mark_todo();
-            }
+            fm_task.set_status(status.into());
         } else {
             // Create new task entry
             let mut new_task =
                 crate::identity::frontmatter::TaskProperties::new(task.content.clone());
-            if new_status == "done" {
-                new_task.mark_done();
-            }
+            new_task.set_status(status.into());
 
             // Add any properties from the task line
             if let Some(due) = task.properties.get("due") {
@@ -560,10 +595,7 @@ pub async fn update_task_properties(
         match manager_lock.get_task_by_id(path, &request.task_id) {
             Ok(Some(parsed)) => {
                 let mut new_task = TaskProperties::new(parsed.content.clone());
-                match parsed.status {
-                    crate::identity::tasks::TaskStatus::Done => new_task.mark_done(),
-                    crate::identity::tasks::TaskStatus::Todo => new_task.mark_todo(),
-                }
+                new_task.set_status(parsed.status.into());
                 // Carry over inline props
                 if let Some(due) = parsed.properties.get("due") {
                     if let Ok(dt) = crate::identity::frontmatter::tasks::normalize_due_date(due) {
@@ -594,10 +626,8 @@ pub async fn update_task_properties(
     if let Some(task) = tasks.get_mut(&request.task_id) {
         // Apply updates
         if let Some(status) = request.updates.status {
-            match status.as_str() {
-                "done" => task.mark_done(),
-                "todo" => task.mark_todo(),
-                _ => {}
+            if let Some(status) = TaskStatus::parse(&status) {
+                task.set_status(status.into());
             }
         }
         if let Some(text) = request.updates.text {
@@ -731,10 +761,7 @@ pub async fn get_task_by_id(
 
             Ok(Some(TaskInfo {
                 id: actual_id,
-                status: match task.status {
-                    TaskStatus::Todo => "todo".to_string(),
-                    TaskStatus::Done => "done".to_string(),
-                },
+                status: task.status.as_str().to_string(),
                 text: task.content.clone(),
                 line_number: task.line_number,
                 file_path,
@@ -0,0 +1,151 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::identity::tasks::TaskIdentity;
+use crate::identity::IdentityManager;
+use crate::tasks::{render_notes, ImportFormat};
+
+/// Folder imported notes go in when the caller does not pick one
+const DEFAULT_IMPORT_FOLDER: &str = "Imported Tasks";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskImportRequest {
+    /// Export file to read
+    pub source_path: String,
+    /// Detected from the file when left out
+    pub format: Option<ImportFormat>,
+    /// Vault-relative folder for the notes, `Imported Tasks` by default
+    pub target_folder: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskImportReport {
+    pub format: ImportFormat,
+    pub tasks_imported: usize,
+    /// Vault-relative paths of the notes written, one per project
+    pub files: Vec<String>,
+}
+
+/// File name for a project's note, without characters file systems reject
+fn note_file_name(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .filter(|c| !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect();
+    let name = name.trim().trim_start_matches('.');
+    if name.is_empty() {
+        "Imported tasks.md".to_string()
+    } else {
+        format!("{}.md", name)
+    }
+}
+
+/// Write a note through a temporary file, so an existing note is never left
+/// half written
+fn write_note(path: &Path, content: &str) -> Result<(), String> {
+    use std::io::Write;
+    let temp_file = tempfile::NamedTempFile::new_in(path.parent().unwrap_or(Path::new(".")))
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    {
+        let mut file = temp_file.as_file();
+        file.write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to write note: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync note: {}", e))?;
+    }
+    temp_file
+        .persist(path)
+        .map_err(|e| format!("Failed to write note: {}", e))?;
+    Ok(())
+}
+
+/// Import tasks from a Todoist, TaskPaper or Things export into notes in the
+/// vault, one per project. Notes that already exist get the tasks appended.
+#[tauri::command]
+pub async fn import_tasks(
+    request: TaskImportRequest,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<TaskImportReport, String> {
+    let source_path = PathBuf::from(&request.source_path);
+    let content = tokio::fs::read_to_string(&source_path)
+        .await
+        .map_err(|e| format!("Failed to read export: {}", e))?;
+
+    let format = match request.format {
+        Some(format) => format,
+        None => ImportFormat::detect(&source_path, &content)
+            .ok_or("Unrecognized export format; choose the format to import")?,
+    };
+    // Todoist CSV exports hold a single project, named after the file
+    let file_stem = source_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string());
+    let tasks = format
+        .parse(&content, file_stem.as_deref(), &Local::now())
+        .map_err(|e| format!("Failed to parse export: {}", e))?;
+
+    let target_folder = request
+        .target_folder
+        .filter(|folder| !folder.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_IMPORT_FOLDER.to_string());
+    let relative_folder = Path::new(&target_folder);
+    if relative_folder
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err("Target folder must be a relative path inside the vault".to_string());
+    }
+
+    let mut identity = TaskIdentity::new();
+    let notes = render_notes(&tasks, &mut identity)
+        .map_err(|e| format!("Failed to generate task IDs: {}", e))?;
+
+    let manager = identity_manager.lock().await;
+    let folder = manager.vault_root().join(relative_folder);
+    tokio::fs::create_dir_all(&folder)
+        .await
+        .map_err(|e| format!("Failed to create folder: {}", e))?;
+
+    let mut files = Vec::new();
+    let mut tasks_imported = 0;
+    for note in notes {
+        let path = folder.join(note_file_name(&note.title));
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(existing) => format!("{}\n\n{}", existing.trim_end(), note.body),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                format!("# {}\n\n{}", note.title, note.body)
+            }
+            Err(e) => return Err(format!("Failed to read note {:?}: {}", path, e)),
+        };
+        write_note(&path, &content)?;
+
+        if let Err(e) = manager.sync_file_tasks_to_index_async(&path).await {
+            eprintln!("⚠️ Failed to index imported tasks in {:?}: {}", path, e);
+        }
+        tasks_imported += note.task_count;
+        files.push(
+            relative_folder
+                .join(path.file_name().unwrap_or_default())
+                .to_string_lossy()
+                .replace('\\', "/"),
+        );
+    }
+
+    println!(
+        "📥 Imported {} tasks into {} notes from {}",
+        tasks_imported,
+        files.len(),
+        request.source_path
+    );
+    Ok(TaskImportReport {
+        format,
+        tasks_imported,
+        files,
+    })
+}
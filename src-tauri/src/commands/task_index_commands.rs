@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
@@ -7,9 +7,12 @@ use tauri::State;
 use tokio::sync::Mutex;
 
 use crate::identity::frontmatter::Priority;
-use crate::identity::tasks::TaskStatus;
+use crate::identity::tasks::{Recurrence, TaskStatus};
 use crate::identity::IdentityManager;
-use crate::tasks::{IndexStats, TaskQuery, TaskRecord};
+use crate::tasks::{
+    extract_task_query_blocks, IndexStats, QueryContext, TaskProgress, TaskQuery, TaskRecord,
+    TaskSearch,
+};
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +21,18 @@ pub struct TaskSourceInfo {
     pub line_number: usize,
 }
 
+/// Results of a fenced ```` ```tasks ```` block in a note
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskQueryBlockResult {
+    /// 1-based line of the opening fence
+    pub line: usize,
+    pub query: String,
+    pub tasks: Vec<TaskRecord>,
+    /// Why the query could not be run, shown in place of the results
+    pub error: Option<String>,
+}
+
 /// Query tasks by project
 #[tauri::command]
 pub async fn query_tasks_by_project(
@@ -39,16 +54,12 @@ pub async fn query_tasks_by_status(
     let manager = identity_manager.lock().await;
     let index = manager.task_index();
 
-    let task_status = match status.as_str() {
-        "todo" => TaskStatus::Todo,
-        "done" => TaskStatus::Done,
-        _ => return Err("Invalid status".to_string()),
-    };
+    let task_status = TaskStatus::parse(&status).ok_or("Invalid status")?;
 
     Ok(index.get_tasks_by_status(task_status).await)
 }
 
-/// Query tasks due today
+/// Query tasks due or scheduled for today
 #[tauri::command]
 pub async fn query_tasks_today(
     identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
@@ -130,6 +141,13 @@ pub struct TaskQueryRequest {
     pub priority: Option<String>,
     pub has_due_date: Option<bool>,
     pub tags: Option<Vec<String>>,
+    pub recurring: Option<bool>,
+    pub repeat: Option<String>,
+    /// Textual query such as `status:todo due<=+7d sort:due`, applied on
+    /// top of the other filters
+    pub text: Option<String>,
+    /// Include archived tasks, which `is:archived` in the text also does
+    pub include_archived: Option<bool>,
 }
 
 #[tauri::command]
@@ -140,14 +158,18 @@ pub async fn query_tasks(
     let manager = identity_manager.lock().await;
     let index = manager.task_index();
 
-    let mut task_query = TaskQuery::new();
+    let search = match query.text.as_deref().filter(|text| !text.trim().is_empty()) {
+        Some(text) => {
+            Some(TaskSearch::parse(text).map_err(|e| format!("Invalid task query: {}", e))?)
+        }
+        None => None,
+    };
+    let include_archived = query.include_archived.unwrap_or(false)
+        || search.as_ref().is_some_and(TaskSearch::includes_archived);
+    let mut task_query = TaskQuery::new().with_archived(include_archived);
 
     if let Some(status_str) = query.status {
-        let status = match status_str.as_str() {
-            "todo" => TaskStatus::Todo,
-            "done" => TaskStatus::Done,
-            _ => return Err("Invalid status".to_string()),
-        };
+        let status = TaskStatus::parse(&status_str).ok_or("Invalid status")?;
         task_query = task_query.with_status(status);
     }
 
@@ -173,7 +195,69 @@ pub async fn query_tasks(
         task_query = task_query.with_tags(tags);
     }
 
-    Ok(index.query(task_query).await)
+    if let Some(recurring) = query.recurring {
+        task_query = task_query.with_recurring(recurring);
+    }
+
+    if let Some(repeat) = query.repeat {
+        if Recurrence::parse(&repeat).is_none() {
+            return Err("Invalid repeat rule".to_string());
+        }
+        task_query = task_query.with_repeat(&repeat);
+    }
+
+    let tasks = index.query(task_query).await;
+    match search {
+        Some(search) => {
+            let context = QueryContext {
+                vault_root: Some(manager.vault_root()),
+                today: Local::now().date_naive(),
+            };
+            Ok(search.apply(tasks, &context))
+        }
+        None => Ok(tasks),
+    }
+}
+
+/// Run the fenced ```` ```tasks ```` query blocks in a note
+#[tauri::command]
+pub async fn run_task_query_blocks(
+    file_path: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Vec<TaskQueryBlockResult>, String> {
+    let content = tokio::fs::read_to_string(&file_path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let manager = identity_manager.lock().await;
+    let index = manager.task_index();
+    let context = QueryContext {
+        vault_root: Some(manager.vault_root()),
+        today: Local::now().date_naive(),
+    };
+
+    let mut results = Vec::new();
+    for block in extract_task_query_blocks(&content) {
+        let (tasks, error) = match TaskSearch::parse(&block.query) {
+            Ok(search) => (
+                search.apply(
+                    index
+                        .query(TaskQuery::new().with_archived(search.includes_archived()))
+                        .await,
+                    &context,
+                ),
+                None,
+            ),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        results.push(TaskQueryBlockResult {
+            line: block.line,
+            query: block.query,
+            tasks,
+            error,
+        });
+    }
+    Ok(results)
 }
 
 /// Resolve a task ID to its source note and line number
@@ -196,6 +280,68 @@ pub async fn get_task_source_by_id(
     })
 }
 
+/// Get the subtasks nested directly under a task
+#[tauri::command]
+pub async fn get_task_children(
+    task_id: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Vec<TaskRecord>, String> {
+    let manager = identity_manager.lock().await;
+    let index = manager.task_index();
+
+    Ok(index.get_children(&task_id).await)
+}
+
+/// Get the tasks blocking a task
+#[tauri::command]
+pub async fn get_task_blockers(
+    task_id: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Vec<TaskRecord>, String> {
+    let manager = identity_manager.lock().await;
+    let index = manager.task_index();
+
+    Ok(index.get_blockers(&task_id).await)
+}
+
+/// Get the tasks blocked by a task
+#[tauri::command]
+pub async fn get_task_dependents(
+    task_id: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Vec<TaskRecord>, String> {
+    let manager = identity_manager.lock().await;
+    let index = manager.task_index();
+
+    Ok(index.get_dependents(&task_id).await)
+}
+
+/// Get the rolled up progress of a task's subtasks
+#[tauri::command]
+pub async fn get_task_progress(
+    task_id: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<TaskProgress, String> {
+    let manager = identity_manager.lock().await;
+    let index = manager.task_index();
+
+    index
+        .get_progress(&task_id)
+        .await
+        .map_err(|e| format!("Failed to get task progress: {}", e))
+}
+
+/// Find circular `blocked-by` dependencies
+#[tauri::command]
+pub async fn find_task_dependency_cycles(
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Vec<Vec<String>>, String> {
+    let manager = identity_manager.lock().await;
+    let index = manager.task_index();
+
+    Ok(index.find_dependency_cycles().await)
+}
+
 /// Sync tasks from a file to the index
 #[tauri::command]
 pub async fn sync_file_tasks_to_index(
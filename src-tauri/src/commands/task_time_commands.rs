@@ -0,0 +1,153 @@
+use chrono::{NaiveDate, Utc};
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::identity::frontmatter::{
+    FrontMatter, FrontMatterParser, FrontMatterWriter, TaskFrontMatter, TaskProperties, TimeEntry,
+};
+use crate::identity::IdentityManager;
+use crate::tasks::{time_summary_csv, TimeStats};
+
+/// Parse an optional `YYYY-MM-DD` bound of a date range
+fn parse_day(value: Option<String>, bound: &str) -> Result<Option<NaiveDate>, String> {
+    value
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                .map_err(|e| format!("Invalid {} date: {}", bound, e))
+        })
+        .transpose()
+}
+
+/// Change the front matter entry of a task with `update` and re-index its
+/// note. Tasks without an entry get one from what the index knows of them.
+async fn update_task_time_log<T>(
+    task_id: &str,
+    identity_manager: &Mutex<IdentityManager>,
+    update: impl FnOnce(&mut TaskProperties) -> Result<T, String>,
+) -> Result<T, String> {
+    let manager = identity_manager.lock().await;
+    let task = manager
+        .task_index()
+        .get_task(task_id)
+        .await
+        .map_err(|e| format!("Failed to find task: {}", e))?;
+    let path = task.file_path.as_path();
+
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let (fm, body) = FrontMatterParser::parse(&content)
+        .map_err(|e| format!("Failed to parse front matter: {}", e))?;
+    let mut fm = fm.unwrap_or_else(FrontMatter::new);
+
+    let mut props = TaskFrontMatter::get_task(&fm.extra_fields, task_id).unwrap_or_else(|| {
+        let mut props = TaskProperties::new(task.text.clone());
+        props.set_status(task.status.into());
+        props.priority = task.priority;
+        props.tags = task.tags.clone();
+        props.project = task.project.clone();
+        props
+    });
+    let result = update(&mut props)?;
+    TaskFrontMatter::upsert_task(&mut fm.extra_fields, task_id.to_string(), props);
+
+    FrontMatterWriter::write_atomic(path, &fm, &body)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    manager
+        .sync_file_tasks_to_index_async(path)
+        .await
+        .map_err(|e| format!("Failed to sync tasks to index: {}", e))?;
+
+    Ok(result)
+}
+
+/// Start the timer on a task, returning the new time log entry
+#[tauri::command]
+pub async fn start_task_timer(
+    task_id: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<TimeEntry, String> {
+    let now = Utc::now();
+    let entry = update_task_time_log(&task_id, &identity_manager, |props| {
+        if !props.start_timer(now) {
+            return Err("Timer is already running for this task".to_string());
+        }
+        Ok(TimeEntry {
+            start: now,
+            end: None,
+        })
+    })
+    .await?;
+
+    println!("⏱️ Started timer on task {}", task_id);
+    Ok(entry)
+}
+
+/// Stop the running timer on a task, returning the entry it closed
+#[tauri::command]
+pub async fn stop_task_timer(
+    task_id: String,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<TimeEntry, String> {
+    let now = Utc::now();
+    let entry = update_task_time_log(&task_id, &identity_manager, |props| {
+        props
+            .stop_timer(now)
+            .ok_or_else(|| "No timer is running for this task".to_string())
+    })
+    .await?;
+
+    println!(
+        "⏱️ Stopped timer on task {} after {} minutes",
+        task_id,
+        entry.minutes_at(now)
+    );
+    Ok(entry)
+}
+
+/// Time logged per project, tag and day, between two optional
+/// `YYYY-MM-DD` dates (inclusive)
+#[tauri::command]
+pub async fn get_task_time_stats(
+    from: Option<String>,
+    to: Option<String>,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<TimeStats, String> {
+    let from = parse_day(from, "start")?;
+    let to = parse_day(to, "end")?;
+
+    let manager = identity_manager.lock().await;
+    Ok(manager.task_index().get_time_stats(from, to).await)
+}
+
+/// Export the time logged per project to a CSV file for billing
+#[tauri::command]
+pub async fn export_task_time_csv(
+    output_path: String,
+    from: Option<String>,
+    to: Option<String>,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<TimeStats, String> {
+    let from = parse_day(from, "start")?;
+    let to = parse_day(to, "end")?;
+
+    let stats = identity_manager
+        .lock()
+        .await
+        .task_index()
+        .get_time_stats(from, to)
+        .await;
+    let csv =
+        time_summary_csv(&stats).map_err(|e| format!("Failed to build time summary: {}", e))?;
+    crate::csv::export_to_file(output_path.clone(), csv)
+        .await
+        .map_err(|e| format!("Failed to export time summary: {}", e))?;
+
+    println!(
+        "⏱️ Exported time for {} projects to {}",
+        stats.by_project.len(),
+        output_path
+    );
+    Ok(stats)
+}
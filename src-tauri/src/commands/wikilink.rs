@@ -1,7 +1,11 @@
+use crate::graph::{self, LinkGraphState, NoteSuggestion, UnlinkedMention};
+use crate::identity::uuid::UuidGenerator;
 use crate::refactored_app_state::RefactoredAppState;
+use crate::templates;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use tauri::{State, Window};
+use tauri::{AppHandle, State, Window};
 
 /// Response type for get_vault_notes command
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,14 +21,18 @@ pub struct WikiLinkResolution {
     pub exists: bool,
     pub path: Option<String>, // Relative path from vault root if exists
     pub name: String,         // Original WikiLink name
+    /// Closest notes when the link does not resolve, best first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<NoteSuggestion>,
 }
 
+/// Number of suggestions returned for an unresolved WikiLink
+const MAX_CANDIDATES: usize = 5;
+
 /// Normalize WikiLink name for file system matching
-/// Handles case-insensitive matching and special characters
+/// Handles case-insensitive matching and repeated whitespace, the same way the link graph does
 fn normalize_wikilink_name(name: &str) -> String {
-    name.trim()
-        .replace("  ", " ") // Replace double spaces with single
-        .to_lowercase() // Case insensitive
+    graph::index::normalize_name(name)
 }
 
 /// Extract note title from file path
@@ -109,16 +117,21 @@ pub async fn get_vault_notes(
     }
 
     // Sort notes by name for consistent ordering
-    notes.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    notes.sort_by_key(|note| normalize_wikilink_name(&note.name));
 
     Ok(notes)
 }
 
 /// Tauri command to resolve a WikiLink name to a file path
+///
+/// Matches note titles, then front matter aliases, then path-qualified names
+/// (`folder/Note`); the shortest path wins when several notes match. A lookup
+/// in the cached link graph, which the vault watcher keeps current.
 #[tauri::command]
 pub async fn resolve_wikilink(
     window: Window,
     refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
     link_name: String,
 ) -> Result<WikiLinkResolution, String> {
     if link_name.trim().is_empty() {
@@ -126,6 +139,7 @@ pub async fn resolve_wikilink(
             exists: false,
             path: None,
             name: link_name,
+            candidates: Vec::new(),
         });
     }
 
@@ -137,30 +151,89 @@ pub async fn resolve_wikilink(
         .await
         .ok_or_else(|| "No vault is currently open".to_string())?;
 
-    // Get all notes in the vault
-    let vault_notes = get_vault_notes(window, refactored_state).await?;
+    graph_state
+        .with_graph(vault_path, |graph| {
+            Ok(match graph::resolve_reference(graph, &link_name) {
+                Some(note) => WikiLinkResolution {
+                    exists: true,
+                    path: Some(note.path.clone()),
+                    name: link_name.clone(),
+                    candidates: Vec::new(),
+                },
+                // No match found, offer the closest notes instead
+                None => WikiLinkResolution {
+                    exists: false,
+                    path: None,
+                    name: link_name.clone(),
+                    candidates: graph::suggest(graph, &link_name, MAX_CANDIDATES),
+                },
+            })
+        })
+        .await
+}
 
-    let normalized_link_name = normalize_wikilink_name(&link_name);
+/// Tauri command to find plain-text mentions of a note in other notes
+///
+/// Looks for the note's title and front matter aliases, skipping front matter,
+/// code, existing links, URLs and tags.
+#[tauri::command]
+pub async fn find_unlinked_mentions(
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+    note: String,
+) -> Result<Vec<UnlinkedMention>, String> {
+    let window_id = crate::refactored_app_state::extract_window_id(&window);
 
-    // Try to find exact match first, then case-insensitive match
-    for note in vault_notes {
-        let normalized_note_name = normalize_wikilink_name(&note.name);
+    // Get vault path from window state
+    let vault_path = refactored_state
+        .get_window_vault_path(&window_id)
+        .await
+        .ok_or_else(|| "No vault is currently open".to_string())?;
 
-        if normalized_note_name == normalized_link_name {
-            return Ok(WikiLinkResolution {
-                exists: true,
-                path: Some(note.path),
-                name: link_name,
-            });
-        }
-    }
+    graph_state
+        .with_graph(vault_path, |graph| {
+            let node = graph
+                .find_note(&note)
+                .ok_or_else(|| format!("Note not found: {}", note))?;
+            graph::find_unlinked_mentions(graph, node)
+                .map_err(|e| format!("Failed to find mentions: {}", e))
+        })
+        .await
+}
 
-    // No match found
-    Ok(WikiLinkResolution {
-        exists: false,
-        path: None,
-        name: link_name,
-    })
+/// Tauri command to turn selected mentions from `find_unlinked_mentions` into WikiLinks
+///
+/// Returns the number of mentions linked; mentions edited since they were
+/// found are left alone.
+#[tauri::command]
+pub async fn link_unlinked_mentions(
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+    note: String,
+    mentions: Vec<UnlinkedMention>,
+) -> Result<usize, String> {
+    let window_id = crate::refactored_app_state::extract_window_id(&window);
+
+    // Get vault path from window state
+    let vault_path = refactored_state
+        .get_window_vault_path(&window_id)
+        .await
+        .ok_or_else(|| "No vault is currently open".to_string())?;
+
+    let linked = graph_state
+        .with_graph(vault_path, |graph| {
+            let node = graph
+                .find_note(&note)
+                .ok_or_else(|| format!("Note not found: {}", note))?;
+            graph::link_mentions(graph, node, &mentions)
+                .map_err(|e| format!("Failed to link mentions: {}", e))
+        })
+        .await?;
+
+    println!("🔗 Linked {} mentions of {}", linked, note);
+    Ok(linked)
 }
 
 /// Response type for create_note_from_wikilink command
@@ -169,6 +242,9 @@ pub struct NoteCreationResult {
     pub path: String,    // Relative path from vault root
     pub name: String,    // Note name/title
     pub content: String, // Initial content
+    /// Where the template put the cursor, in characters
+    #[serde(default)]
+    pub cursor: Option<usize>,
 }
 
 /// Convert WikiLink name to a safe filename
@@ -189,12 +265,16 @@ fn generate_initial_content(note_name: &str) -> String {
     format!("# {}\n\n", note_name)
 }
 
-/// Tauri command to create a new note from WikiLink
+/// Tauri command to create a new note from WikiLink, from a template in
+/// the templates folder when one is given
 #[tauri::command]
 pub async fn create_note_from_wikilink(
+    app: AppHandle,
     window: Window,
     refactored_state: State<'_, RefactoredAppState>,
     note_name: String,
+    template: Option<String>,
+    variables: Option<HashMap<String, String>>,
 ) -> Result<NoteCreationResult, String> {
     if note_name.trim().is_empty() {
         return Err("Note name cannot be empty".to_string());
@@ -224,7 +304,24 @@ pub async fn create_note_from_wikilink(
     }
 
     // Generate initial content
-    let initial_content = generate_initial_content(&note_name);
+    let (initial_content, cursor) = match template.filter(|t| !t.trim().is_empty()) {
+        Some(template) => {
+            let id = UuidGenerator::new()
+                .generate()
+                .map_err(|e| format!("Failed to generate note ID: {}", e))?;
+            let rendered = templates::render_note_template(
+                app,
+                &vault_path,
+                &template,
+                &note_name,
+                variables.unwrap_or_default(),
+                &id,
+            )
+            .await?;
+            (rendered.content, rendered.cursor)
+        }
+        None => (generate_initial_content(&note_name), None),
+    };
 
     // Write the file
     std::fs::write(&file_path, &initial_content)
@@ -237,6 +334,7 @@ pub async fn create_note_from_wikilink(
         path: relative_path,
         name: note_name,
         content: initial_content,
+        cursor,
     })
 }
 
@@ -346,39 +346,45 @@ pub async fn open_note(title: String, state: State<'_, crate::AppState>) -> Resu
     Ok(())
 }
 
+/// Notes carrying a tag, as paths relative to the vault. `area/*` also
+/// matches tags nested under `area`.
 #[tauri::command]
 pub async fn search_by_tag(
     tag: String,
-    state: State<'_, crate::AppState>,
+    window: tauri::Window,
+    refactored_state: State<'_, crate::refactored_app_state::RefactoredAppState>,
+    tag_state: State<'_, crate::tags::TagIndexState>,
 ) -> Result<Vec<String>, String> {
-    let _manager = &state.editor;
-    // For now, just log the request
     println!("Searching by tag: {}", tag);
+    let vault_path = crate::graph::commands::window_vault_path(&window, &refactored_state).await?;
 
-    // In a full implementation, this would:
-    // 1. Search through all markdown files
-    // 2. Find files containing the tag
-    // 3. Return the list of matching files
-
-    Ok(vec![])
+    tag_state
+        .with_index(&vault_path, |index| index.search(&tag))
+        .await
 }
 
+/// Content of `![[Note]]`, `![[Note#Heading]]` or `![[Note^block-id]]`, with
+/// nested embeds expanded. `None` if the note or the fragment does not exist.
 #[tauri::command]
 pub async fn get_embedded_block(
     note_title: String,
     block_id: Option<String>,
-    state: State<'_, crate::AppState>,
+    window: tauri::Window,
+    refactored_state: State<'_, crate::refactored_app_state::RefactoredAppState>,
+    graph_state: State<'_, crate::graph::LinkGraphState>,
 ) -> Result<Option<String>, String> {
-    let _manager = &state.editor;
-    // For now, just log the request
-    println!("Getting embedded block: {} {:?}", note_title, block_id);
-
-    // In a full implementation, this would:
-    // 1. Find the note file
-    // 2. If block_id is provided, find the specific block
-    // 3. Return the block content
-
-    Ok(Some("Sample embedded content".to_string()))
+    let vault_path = crate::graph::commands::window_vault_path(&window, &refactored_state).await?;
+    let reference = match block_id.as_deref().map(str::trim) {
+        Some(block_id) if !block_id.is_empty() => format!("{}^{}", note_title, block_id),
+        _ => note_title,
+    };
+
+    graph_state
+        .with_graph(vault_path, |graph| {
+            crate::graph::resolve_embed(graph, &reference)
+                .map_err(|e| format!("Failed to resolve embed: {}", e))
+        })
+        .await
 }
 
 #[tauri::command]
@@ -0,0 +1,500 @@
+//! Heading and block references
+//!
+//! Resolves the fragment of an embed: `![[Note#Heading]]` is the heading and
+//! everything under it up to the next heading of the same or a higher level,
+//! `![[Note^id]]` is the paragraph, list item or heading tagged with ` ^id`.
+//! Embeds inside the resolved text are expanded in turn, up to
+//! `MAX_EMBED_DEPTH` levels; an embed that would include itself is left as
+//! written.
+
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::ops::Range;
+
+use super::index::LinkGraph;
+use super::links::{parse_wikilink, LinkKind, RawLink};
+use crate::identity::frontmatter::FrontMatterParser;
+
+lazy_static! {
+    static ref HEADING_PATTERN: Regex = Regex::new(r"^(#{1,6})\s+(.*?)(?:\s+#+)?\s*$").unwrap();
+    static ref BLOCK_ID_PATTERN: Regex = Regex::new(r"(?:^|\s)\^([A-Za-z0-9-]+)\s*$").unwrap();
+    static ref LIST_ITEM_PATTERN: Regex = Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s").unwrap();
+    static ref EMBED_PATTERN: Regex = Regex::new(r"!\[\[([^\[\]\n]+?)\]\]").unwrap();
+    static ref INLINE_CODE_PATTERN: Regex = Regex::new(r"`[^`\n]*`").unwrap();
+}
+
+/// How many levels of embeds inside embeds are expanded
+pub const MAX_EMBED_DEPTH: usize = 5;
+
+/// A heading and the lines it spans
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    pub level: usize,
+    pub heading: String,
+    /// 1-based line of the heading
+    pub start_line: usize,
+    /// 1-based last line of the section (inclusive)
+    pub end_line: usize,
+}
+
+/// Every heading outside front matter and code blocks, in document order
+pub fn parse_sections(content: &str) -> Vec<Section> {
+    let lines: Vec<&str> = content.lines().collect();
+    let skipped = skipped_lines(content, &lines);
+
+    let mut sections: Vec<Section> = lines
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !skipped[*index])
+        .filter_map(|(index, line)| {
+            let caps = HEADING_PATTERN.captures(line)?;
+            Some(Section {
+                level: caps[1].len(),
+                heading: strip_block_id(&caps[2]).to_string(),
+                start_line: index + 1,
+                end_line: lines.len(),
+            })
+        })
+        .collect();
+
+    for i in 0..sections.len() {
+        let level = sections[i].level;
+        if let Some(next) = sections[i + 1..].iter().find(|s| s.level <= level) {
+            sections[i].end_line = next.start_line - 1;
+        }
+    }
+    sections
+}
+
+/// Text of the section under `heading`. Nested headings can be given as a
+/// path (`Parent#Child`); matching ignores case and repeated whitespace.
+pub fn heading_slice(content: &str, heading: &str) -> Option<String> {
+    let sections = parse_sections(content);
+    let mut parent: Option<&Section> = None;
+
+    for part in heading.split('#').map(normalize).filter(|p| !p.is_empty()) {
+        parent = Some(sections.iter().find(|s| {
+            normalize(&s.heading) == part
+                && parent.is_none_or(|p| {
+                    s.start_line > p.start_line && s.end_line <= p.end_line && s.level > p.level
+                })
+        })?);
+    }
+
+    let section = parent?;
+    let lines: Vec<&str> = content.lines().collect();
+    Some(join_lines(&lines[section.start_line - 1..section.end_line]))
+}
+
+/// Text of the block tagged `^block_id`
+pub fn block_slice(content: &str, block_id: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let skipped = skipped_lines(content, &lines);
+
+    let tagged = (0..lines.len()).find(|&index| {
+        !skipped[index]
+            && BLOCK_ID_PATTERN
+                .captures(lines[index])
+                .is_some_and(|caps| caps[1].eq_ignore_ascii_case(block_id.trim()))
+    })?;
+    let range = block_range(&lines, &skipped, tagged);
+    Some(join_lines(&lines[range]))
+}
+
+/// Gives the block containing `line` (1-based) a `^id` anchor, reusing the
+/// one it already has. Returns the ID and the updated document, or `None` if
+/// the line is blank or inside front matter or a code block.
+///
+/// IDs are derived from the block's text so the same block gets the same ID
+/// when asked twice before the document is saved.
+pub fn ensure_block_id(content: &str, line: usize) -> Option<(String, String)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let skipped = skipped_lines(content, &lines);
+    let index = line.checked_sub(1)?;
+    if index >= lines.len() || skipped[index] || lines[index].trim().is_empty() {
+        return None;
+    }
+
+    let range = containing_block(&lines, &skipped, index);
+    // List items carry the marker on the item line, above their children
+    let last = if LIST_ITEM_PATTERN.is_match(lines[range.start]) {
+        range.start
+    } else {
+        range.end - 1
+    };
+    if let Some(caps) = BLOCK_ID_PATTERN.captures(lines[last]) {
+        return Some((caps[1].to_string(), content.to_string()));
+    }
+    if let Some(caps) = lines
+        .get(range.end + 1)
+        .filter(|_| lines[range.end].trim().is_empty())
+        .and_then(|next| BLOCK_ID_PATTERN.captures(next.trim()))
+        .filter(|caps| caps.get(0).is_some_and(|m| m.start() == 0))
+    {
+        return Some((caps[1].to_string(), content.to_string()));
+    }
+
+    let existing: HashSet<String> = lines
+        .iter()
+        .filter_map(|l| BLOCK_ID_PATTERN.captures(l))
+        .map(|caps| caps[1].to_lowercase())
+        .collect();
+    let digest = format!(
+        "{:x}",
+        Sha256::digest(lines[range.clone()].join("\n").as_bytes())
+    );
+    let id = (6..=digest.len())
+        .map(|len| digest[..len].to_string())
+        .find(|id| !existing.contains(id))?;
+
+    // Tables and quotes take the marker on a line of its own
+    let trimmed = lines[last].trim_start();
+    let own_line = trimmed.starts_with('|') || trimmed.starts_with('>');
+
+    let mut updated = String::with_capacity(content.len() + id.len() + 4);
+    for (i, raw_line) in content.split_inclusive('\n').enumerate() {
+        if i != last {
+            updated.push_str(raw_line);
+            continue;
+        }
+        let text = raw_line.trim_end_matches(['\n', '\r']);
+        let ending = match &raw_line[text.len()..] {
+            "" => "\n",
+            ending => ending,
+        };
+        if own_line {
+            updated.push_str(&format!("{}{}{}^{}", text, ending, ending, id));
+        } else {
+            updated.push_str(&format!("{} ^{}", text.trim_end(), id));
+        }
+        if i + 1 < lines.len() || raw_line.ends_with('\n') {
+            updated.push_str(ending);
+        }
+    }
+
+    Some((id, updated))
+}
+
+/// Content of an embed reference such as `Note`, `Note#Heading` or
+/// `Note^block-id`, with nested embeds expanded. `None` if the note or the
+/// fragment does not exist.
+pub fn resolve_embed(graph: &LinkGraph, reference: &str) -> Result<Option<String>> {
+    let Some(link) = parse_wikilink(reference, true, 0) else {
+        return Ok(None);
+    };
+    embed_content(graph, "", &link, &mut Vec::new(), 0)
+}
+
+fn embed_content(
+    graph: &LinkGraph,
+    source_path: &str,
+    link: &RawLink,
+    chain: &mut Vec<String>,
+    depth: usize,
+) -> Result<Option<String>> {
+    if link.kind != LinkKind::Embed {
+        return Ok(None);
+    }
+    let Some(note) = graph.resolve(source_path, link) else {
+        return Ok(None);
+    };
+
+    let content = std::fs::read_to_string(graph.vault_root().join(&note.path))
+        .map_err(|e| anyhow!("Failed to read {}: {}", note.path, e))?;
+    let slice = match (&link.block, &link.heading) {
+        (Some(block), _) => block_slice(&content, block),
+        (None, Some(heading)) => heading_slice(&content, heading),
+        (None, None) => Some(note_body(&content)),
+    };
+    let Some(slice) = slice else {
+        return Ok(None);
+    };
+
+    chain.push(embed_key(&note.path, link));
+    let expanded = expand_embeds(graph, &note.path, &slice, chain, depth + 1);
+    chain.pop();
+    Ok(Some(strip_block_ids(&expanded?)))
+}
+
+/// Replaces the embeds in `text` with their content
+fn expand_embeds(
+    graph: &LinkGraph,
+    source_path: &str,
+    text: &str,
+    chain: &mut Vec<String>,
+    depth: usize,
+) -> Result<String> {
+    if depth > MAX_EMBED_DEPTH {
+        return Ok(text.to_string());
+    }
+
+    let mut output = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+        if in_fence || is_fence(line) {
+            output.push(line.to_string());
+            continue;
+        }
+
+        // Match against a copy with inline code blanked out, splice into the original
+        let masked = INLINE_CODE_PATTERN
+            .replace_all(line, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+        let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+        for caps in EMBED_PATTERN.captures_iter(&masked) {
+            let Some(link) = parse_wikilink(&caps[1], true, 0) else {
+                continue;
+            };
+            let Some(note) = graph.resolve(source_path, &link) else {
+                continue;
+            };
+            if chain.contains(&embed_key(&note.path, &link)) {
+                continue;
+            }
+            if let Some(content) = embed_content(graph, source_path, &link, chain, depth)? {
+                edits.push((caps.get(0).unwrap().range(), content));
+            }
+        }
+
+        let mut line = line.to_string();
+        for (range, content) in edits.into_iter().rev() {
+            line.replace_range(range, &content);
+        }
+        output.push(line);
+    }
+
+    Ok(output.join("\n"))
+}
+
+/// Lines of the block tagged on line `tagged`
+fn block_range(lines: &[&str], skipped: &[bool], tagged: usize) -> Range<usize> {
+    let line = lines[tagged];
+
+    // A marker on its own line tags the block above it
+    if line.trim_start().starts_with('^') {
+        let mut end = tagged;
+        while end > 0 && lines[end - 1].trim().is_empty() {
+            end -= 1;
+        }
+        if end == 0 {
+            return tagged..tagged + 1;
+        }
+        let mut start = end - 1;
+        while start > 0 && !lines[start - 1].trim().is_empty() && !skipped[start - 1] {
+            start -= 1;
+        }
+        return start..end;
+    }
+
+    if HEADING_PATTERN.is_match(line) {
+        return tagged..tagged + 1;
+    }
+    if LIST_ITEM_PATTERN.is_match(line) {
+        return list_item_range(lines, tagged);
+    }
+
+    let mut start = tagged;
+    while start > 0 && is_paragraph_line(lines, skipped, start - 1) {
+        start -= 1;
+    }
+    start..tagged + 1
+}
+
+/// Lines of the paragraph, list item or heading that contains line `index`
+fn containing_block(lines: &[&str], skipped: &[bool], index: usize) -> Range<usize> {
+    let line = lines[index];
+    if HEADING_PATTERN.is_match(line) {
+        return index..index + 1;
+    }
+    if LIST_ITEM_PATTERN.is_match(line) {
+        return list_item_range(lines, index);
+    }
+
+    let mut start = index;
+    while start > 0 && is_paragraph_line(lines, skipped, start - 1) {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < lines.len() && is_paragraph_line(lines, skipped, end) {
+        end += 1;
+    }
+    start..end
+}
+
+/// A list item and the more deeply indented lines under it
+fn list_item_range(lines: &[&str], index: usize) -> Range<usize> {
+    let indent = indentation(lines[index]);
+    let mut end = index + 1;
+    while end < lines.len() && !lines[end].trim().is_empty() && indentation(lines[end]) > indent {
+        end += 1;
+    }
+    index..end
+}
+
+fn is_paragraph_line(lines: &[&str], skipped: &[bool], index: usize) -> bool {
+    let line = lines[index];
+    !skipped[index]
+        && !line.trim().is_empty()
+        && !HEADING_PATTERN.is_match(line)
+        && !LIST_ITEM_PATTERN.is_match(line)
+}
+
+/// Marks front matter, fences and fenced code so they are never parsed as blocks
+fn skipped_lines(content: &str, lines: &[&str]) -> Vec<bool> {
+    let front_matter = match FrontMatterParser::parse(content) {
+        Ok((Some(_), body)) => lines.len() - body.lines().count(),
+        _ => 0,
+    };
+
+    let mut in_fence = false;
+    lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            if index < front_matter {
+                return true;
+            }
+            if is_fence(line) {
+                in_fence = !in_fence;
+                return true;
+            }
+            in_fence
+        })
+        .collect()
+}
+
+fn note_body(content: &str) -> String {
+    match FrontMatterParser::parse(content) {
+        Ok((Some(_), body)) => body.trim().to_string(),
+        _ => content.trim().to_string(),
+    }
+}
+
+/// Removes `^id` anchors, which are not part of the rendered text
+fn strip_block_ids(text: &str) -> String {
+    let mut in_fence = false;
+    let mut output = Vec::new();
+    for line in text.lines() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            let stripped = strip_block_id(line);
+            if stripped.trim().is_empty() && !line.trim().is_empty() {
+                continue;
+            }
+            output.push(stripped);
+            continue;
+        }
+        output.push(line);
+    }
+    output.join("\n").trim().to_string()
+}
+
+fn strip_block_id(line: &str) -> &str {
+    match BLOCK_ID_PATTERN.find(line) {
+        Some(m) => line[..m.start()].trim_end(),
+        None => line,
+    }
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    lines.join("\n").trim_end().to_string()
+}
+
+fn embed_key(path: &str, link: &RawLink) -> String {
+    format!(
+        "{}#{}^{}",
+        path,
+        link.heading.as_deref().map(normalize).unwrap_or_default(),
+        link.block.as_deref().unwrap_or("").to_lowercase()
+    )
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "---\nid: abc\n---\n# Meeting\n\nIntro line\nsecond line ^intro\n\n## Agenda\n- First\n  - nested\n- Second ^second\n\n```\n## Not a heading\n```\n\n## Notes\nText\n\n| a | b |\n| - | - |\n\n^table\n# Next\n";
+
+    #[test]
+    fn test_parse_sections_skips_code() {
+        let sections = parse_sections(NOTE);
+        let headings: Vec<(&str, usize, usize)> = sections
+            .iter()
+            .map(|s| (s.heading.as_str(), s.start_line, s.end_line))
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                ("Meeting", 4, 24),
+                ("Agenda", 9, 17),
+                ("Notes", 18, 24),
+                ("Next", 25, 25)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_heading_and_block_slices() {
+        assert_eq!(
+            heading_slice(NOTE, "agenda").unwrap(),
+            "## Agenda\n- First\n  - nested\n- Second ^second\n\n```\n## Not a heading\n```"
+        );
+        assert_eq!(
+            heading_slice(NOTE, "Meeting#Notes").unwrap(),
+            "## Notes\nText\n\n| a | b |\n| - | - |\n\n^table"
+        );
+        assert!(heading_slice(NOTE, "Next#Notes").is_none());
+
+        assert_eq!(
+            block_slice(NOTE, "intro").unwrap(),
+            "Intro line\nsecond line ^intro"
+        );
+        assert_eq!(block_slice(NOTE, "second").unwrap(), "- Second ^second");
+        assert_eq!(block_slice(NOTE, "table").unwrap(), "| a | b |\n| - | - |");
+        assert!(block_slice(NOTE, "missing").is_none());
+    }
+
+    #[test]
+    fn test_ensure_block_id_is_stable() {
+        let content = "Para one\ncontinues\n\n- item\n  - child\n";
+        let (id, updated) = ensure_block_id(content, 1).unwrap();
+        assert_eq!(id.len(), 6);
+        assert_eq!(
+            updated,
+            format!("Para one\ncontinues ^{}\n\n- item\n  - child\n", id)
+        );
+
+        // Asking again returns the anchor that is now in the document
+        let (again, unchanged) = ensure_block_id(&updated, 2).unwrap();
+        assert_eq!(again, id);
+        assert_eq!(unchanged, updated);
+
+        let (item_id, updated) = ensure_block_id(content, 4).unwrap();
+        assert!(updated.contains(&format!("- item ^{}\n  - child", item_id)));
+        assert!(ensure_block_id(content, 3).is_none());
+    }
+}
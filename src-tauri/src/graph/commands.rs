@@ -0,0 +1,536 @@
+//! Tauri commands for the link graph
+//!
+//! A vault's graph is loaded on first use and then kept current from the
+//! vault watcher, so commands only re-read files reported as changed and
+//! resolving a link is a lookup.
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State, Window};
+use tokio::sync::{mpsc, Mutex};
+
+use super::blocks::ensure_block_id;
+use super::health::{apply_fix, check_vault, HealthFix, HealthReport};
+use super::index::{GraphNeighbor, GraphStats, LinkEdge, LinkGraph, NoteNode};
+use super::rename::{apply_rename, clean_path, plan_rename, RenamePlan};
+use crate::identity::watcher::{IdentityWatcher, WatcherConfig};
+use crate::identity::IdentityManager;
+use crate::refactored_app_state::{extract_window_id, RefactoredAppState};
+use crate::vault_settings::{get_vault_settings, normalize_image_location};
+
+/// Loaded link graphs, one per open vault
+#[derive(Default)]
+pub struct LinkGraphState {
+    graphs: Mutex<HashMap<PathBuf, LinkGraph>>,
+    /// Files the vault watcher reported since each graph was last updated
+    changed: std::sync::Mutex<HashMap<PathBuf, HashSet<PathBuf>>>,
+    /// Vaults whose renames are being watched
+    watched: std::sync::Mutex<HashSet<PathBuf>>,
+    /// `(vault, old path)` of moves made through `move_note`, which updates
+    /// their links itself
+    app_moves: std::sync::Mutex<HashSet<(PathBuf, String)>>,
+}
+
+impl LinkGraphState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f` against the up-to-date graph of `vault_path`. The first call
+    /// for a vault checks every note; later calls only re-read the files
+    /// reported through `files_changed`. Reading notes happens on a blocking
+    /// thread so it never stalls the async runtime.
+    pub async fn with_graph<T>(
+        &self,
+        vault_path: PathBuf,
+        f: impl FnOnce(&LinkGraph) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut graphs = self.graphs.lock().await;
+        let changed = lock(&self.changed).remove(&vault_path).unwrap_or_default();
+        if !graphs.contains_key(&vault_path) || !changed.is_empty() {
+            let cached = graphs.remove(&vault_path);
+            let root = vault_path.clone();
+            let graph = tokio::task::spawn_blocking(move || match cached {
+                Some(mut graph) => graph.update_paths(&changed).map(|_| graph),
+                None => {
+                    let mut graph = LinkGraph::open(&root);
+                    graph.refresh().map(|_| graph)
+                }
+            })
+            .await
+            .map_err(|e| format!("Failed to update link graph: {}", e))?
+            .map_err(|e| format!("Failed to update link graph: {}", e))?;
+            graphs.insert(vault_path.clone(), graph);
+        }
+        f(&graphs[&vault_path])
+    }
+
+    /// Records files created, modified or removed in `vault_path`, which the
+    /// graph picks up the next time it is used. Called by the vault watcher.
+    pub fn files_changed<'a>(
+        &self,
+        vault_path: &Path,
+        paths: impl IntoIterator<Item = &'a PathBuf>,
+    ) {
+        lock(&self.changed)
+            .entry(vault_path.to_path_buf())
+            .or_default()
+            .extend(paths.into_iter().cloned());
+    }
+
+    /// Drops the cached graph and its snapshot so the next call re-parses every note.
+    pub async fn invalidate(&self, vault_path: &Path) -> Result<(), String> {
+        self.graphs.lock().await.remove(vault_path);
+        let snapshot = super::snapshot_path(vault_path);
+        if snapshot.exists() {
+            std::fs::remove_file(&snapshot)
+                .map_err(|e| format!("Failed to remove link graph snapshot: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Moves a note with `move_file`, then rewrites links that pointed at its
+    /// old location when `update_links` is set. The plan is taken before the
+    /// move so links are matched against the graph as it was.
+    pub async fn move_note(
+        &self,
+        vault_path: &Path,
+        old_path: &str,
+        new_path: &str,
+        update_links: bool,
+        move_file: impl FnOnce() -> Result<(), String>,
+    ) -> Result<(), String> {
+        // The rename watcher leaves links alone for moves made here
+        let app_move = (vault_path.to_path_buf(), clean_path(old_path));
+        lock(&self.app_moves).insert(app_move.clone());
+        let moved = [
+            vault_path.join(clean_path(old_path)),
+            vault_path.join(clean_path(new_path)),
+        ];
+        if !update_links {
+            move_file().inspect_err(|_| {
+                lock(&self.app_moves).remove(&app_move);
+            })?;
+            self.files_changed(vault_path, &moved);
+            return Ok(());
+        }
+
+        let plan = self
+            .with_graph(vault_path.to_path_buf(), |graph| {
+                plan_rename(graph, old_path, new_path)
+                    .map_err(|e| format!("Failed to plan link updates: {}", e))
+            })
+            .await
+            .and_then(|plan| move_file().map(|_| plan))
+            .inspect_err(|_| {
+                lock(&self.app_moves).remove(&app_move);
+            })?;
+        self.files_changed(vault_path, &moved);
+
+        let updated = self
+            .with_graph(vault_path.to_path_buf(), |graph| {
+                apply_rename(graph, &plan).map_err(|e| format!("Failed to update links: {}", e))
+            })
+            .await?;
+        println!(
+            "🔗 Updated {} links in {} files after moving {} -> {}",
+            plan.link_count(),
+            updated,
+            old_path,
+            new_path
+        );
+        Ok(())
+    }
+
+    /// Rewrites links to notes renamed outside the app, e.g. in a file
+    /// manager or by a sync client. Starts one rename watcher per vault;
+    /// later calls for the same vault do nothing.
+    pub fn watch_renames(&self, app: &AppHandle, vault_path: &Path) {
+        if !lock(&self.watched).insert(vault_path.to_path_buf()) {
+            return;
+        }
+
+        let (sender, mut renames) = mpsc::unbounded_channel();
+        let identity_manager =
+            Arc::new(RwLock::new(IdentityManager::new(vault_path.to_path_buf())));
+        let mut watcher = IdentityWatcher::new(
+            identity_manager,
+            vault_path.to_path_buf(),
+            WatcherConfig::default(),
+        );
+        watcher.set_rename_events(sender);
+
+        let vault = vault_path.to_path_buf();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = watcher.watch().await {
+                eprintln!("⚠️ Rename watcher stopped for {}: {}", vault.display(), e);
+            }
+        });
+
+        let app = app.clone();
+        let vault = vault_path.to_path_buf();
+        tauri::async_runtime::spawn(async move {
+            while let Some((old_path, new_path)) = renames.recv().await {
+                let state = app.state::<LinkGraphState>();
+                if let Err(e) = state.note_renamed(&vault, &old_path, &new_path).await {
+                    eprintln!(
+                        "⚠️ Failed to update links after {} was renamed: {}",
+                        old_path.display(),
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    /// Updates the links to a note the rename watcher saw move from the
+    /// absolute `old_path` to `new_path`
+    pub async fn note_renamed(
+        &self,
+        vault_path: &Path,
+        old_path: &Path,
+        new_path: &Path,
+    ) -> Result<(), String> {
+        let (Some(old_path), Some(new_path)) = (
+            note_path(vault_path, old_path),
+            note_path(vault_path, new_path),
+        ) else {
+            return Ok(());
+        };
+        if lock(&self.app_moves).remove(&(vault_path.to_path_buf(), old_path.clone())) {
+            return Ok(());
+        }
+        self.files_changed(
+            vault_path,
+            [&vault_path.join(&old_path), &vault_path.join(&new_path)],
+        );
+
+        let (links, updated) = self
+            .with_graph(vault_path.to_path_buf(), |graph| {
+                let plan = plan_rename(graph, &old_path, &new_path)
+                    .map_err(|e| format!("Failed to plan link updates: {}", e))?;
+                if plan.files.is_empty() {
+                    return Ok((0, 0));
+                }
+                let updated = apply_rename(graph, &plan)
+                    .map_err(|e| format!("Failed to update links: {}", e))?;
+                Ok((plan.link_count(), updated))
+            })
+            .await?;
+        if updated > 0 {
+            println!(
+                "🔗 Updated {} links in {} files after {} was renamed to {}",
+                links, updated, old_path, new_path
+            );
+        }
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Vault-relative path of a markdown note from a watcher path, which may be
+/// reported against the canonical vault path
+fn note_path(vault_path: &Path, path: &Path) -> Option<String> {
+    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        return None;
+    }
+    let relative = path
+        .strip_prefix(vault_path)
+        .map(Path::to_path_buf)
+        .ok()
+        .or_else(|| {
+            let canonical = vault_path.canonicalize().ok()?;
+            path.strip_prefix(canonical).ok().map(Path::to_path_buf)
+        })?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Summary of a note in the graph
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphNote {
+    pub id: String,
+    pub path: String,
+    pub title: String,
+}
+
+impl From<&NoteNode> for GraphNote {
+    fn from(note: &NoteNode) -> Self {
+        Self {
+            id: note.id.clone(),
+            path: note.path.clone(),
+            title: note.title.clone(),
+        }
+    }
+}
+
+/// Everything the links panel shows for one note
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteLinks {
+    pub note: GraphNote,
+    pub outgoing: Vec<LinkEdge>,
+    pub backlinks: Vec<LinkEdge>,
+    pub embeds: Vec<LinkEdge>,
+    pub task_links: Vec<LinkEdge>,
+}
+
+pub(crate) async fn window_vault_path(
+    window: &Window,
+    refactored_state: &RefactoredAppState,
+) -> Result<PathBuf, String> {
+    let window_id = extract_window_id(window);
+    match refactored_state.get_window_state(&window_id).await {
+        Some(window_state) => {
+            let vault_lock = window_state.vault.lock().await;
+            match &*vault_lock {
+                Some(vault) => Ok(vault.path().to_path_buf()),
+                None => Err("No vault opened".to_string()),
+            }
+        }
+        None => Err("Window not found".to_string()),
+    }
+}
+
+fn find_note<'a>(graph: &'a LinkGraph, note: &str) -> Result<&'a NoteNode, String> {
+    graph
+        .find_note(note)
+        .ok_or_else(|| format!("Note not found: {}", note))
+}
+
+/// Outgoing links, backlinks, embeds and task links of a note (UUID or relative path)
+#[tauri::command]
+pub async fn get_note_links(
+    note: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<NoteLinks, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    graph_state
+        .with_graph(vault_path, |graph| {
+            let node = find_note(graph, &note)?;
+            Ok(NoteLinks {
+                note: node.into(),
+                outgoing: graph.outgoing(node),
+                backlinks: graph.backlinks(node),
+                embeds: graph.embeds(node),
+                task_links: graph.task_links(node),
+            })
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_outgoing_links(
+    note: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<Vec<LinkEdge>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    graph_state
+        .with_graph(vault_path, |graph| {
+            Ok(graph.outgoing(find_note(graph, &note)?))
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_backlinks(
+    note: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<Vec<LinkEdge>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    graph_state
+        .with_graph(vault_path, |graph| {
+            Ok(graph.backlinks(find_note(graph, &note)?))
+        })
+        .await
+}
+
+/// Links across the vault whose target does not exist
+#[tauri::command]
+pub async fn get_unresolved_links(
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<Vec<LinkEdge>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    graph_state
+        .with_graph(vault_path, |graph| Ok(graph.unresolved()))
+        .await
+}
+
+/// Notes that reference a task with `[[tid:...]]`
+#[tauri::command]
+pub async fn get_task_backlinks(
+    task_id: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<Vec<LinkEdge>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    graph_state
+        .with_graph(vault_path, |graph| Ok(graph.task_backlinks(&task_id)))
+        .await
+}
+
+/// Notes within `depth` hops (default 1) of a note, in either direction
+#[tauri::command]
+pub async fn get_note_neighbors(
+    note: String,
+    depth: Option<usize>,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<Vec<GraphNeighbor>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    let depth = depth.unwrap_or(1).min(5);
+    graph_state
+        .with_graph(vault_path, |graph| {
+            Ok(graph.neighbors(find_note(graph, &note)?, depth))
+        })
+        .await
+}
+
+/// Discards the persisted graph and re-parses every note
+#[tauri::command]
+pub async fn rebuild_link_graph(
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<GraphStats, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    println!("🕸️ Rebuilding link graph for {:?}", vault_path);
+
+    graph_state.invalidate(&vault_path).await?;
+    let stats = graph_state
+        .with_graph(vault_path, |graph| Ok(graph.stats()))
+        .await?;
+
+    println!(
+        "✅ Link graph rebuilt: {} notes, {} links, {} unresolved",
+        stats.notes, stats.links, stats.unresolved_links
+    );
+    Ok(stats)
+}
+
+/// Links that would be rewritten if the note at `old_path` moved to `new_path`.
+/// Also works after the fact, for renames made outside the app.
+#[tauri::command]
+pub async fn preview_rename_links(
+    old_path: String,
+    new_path: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<RenamePlan, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    graph_state
+        .with_graph(vault_path, |graph| {
+            plan_rename(graph, &old_path, &new_path)
+                .map_err(|e| format!("Failed to plan link updates: {}", e))
+        })
+        .await
+}
+
+/// Applies a plan from `preview_rename_links`; returns the number of files updated
+#[tauri::command]
+pub async fn apply_rename_links(
+    plan: RenamePlan,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<usize, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    println!(
+        "🔗 Rewriting links for {} -> {}",
+        plan.old_path, plan.new_path
+    );
+
+    graph_state
+        .with_graph(vault_path, |graph| {
+            apply_rename(graph, &plan).map_err(|e| format!("Failed to update links: {}", e))
+        })
+        .await
+}
+
+/// Gives the block at `line` (1-based) of a note a `^id` anchor so it can be
+/// embedded with `![[Note^id]]`. Returns the existing ID if the block has one.
+#[tauri::command]
+pub async fn create_block_id(
+    note: String,
+    line: usize,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<String, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    let note_path = graph_state
+        .with_graph(vault_path.clone(), |graph| {
+            Ok(vault_path.join(&find_note(graph, &note)?.path))
+        })
+        .await?;
+
+    let content =
+        std::fs::read_to_string(&note_path).map_err(|e| format!("Failed to read note: {}", e))?;
+    let (id, updated) = ensure_block_id(&content, line)
+        .ok_or_else(|| format!("No block at line {} of {}", line, note))?;
+    if updated != content {
+        std::fs::write(&note_path, updated).map_err(|e| format!("Failed to write note: {}", e))?;
+        println!("🧱 Added block ID ^{} to {:?}", id, note_path);
+    }
+    Ok(id)
+}
+
+/// Broken links and attachments, unused images, duplicate UUIDs and orphaned
+/// sidecars across the vault
+#[tauri::command]
+pub async fn get_vault_health(
+    app: AppHandle,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<HealthReport, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    println!("🩺 Checking vault health for {:?}", vault_path);
+
+    let image_location =
+        match get_vault_settings(app, vault_path.to_string_lossy().to_string()).await {
+            Ok(settings) => settings.files.image_location,
+            Err(_) => normalize_image_location("Files/"),
+        };
+    let report = graph_state
+        .with_graph(vault_path, |graph| Ok(check_vault(graph, &image_location)))
+        .await?;
+
+    println!(
+        "✅ Vault health: {} issues in {} notes, {} fixable",
+        report.issues.len(),
+        report.notes_checked,
+        report.fixable()
+    );
+    Ok(report)
+}
+
+/// Applies the fix attached to an issue from `get_vault_health`
+#[tauri::command]
+pub async fn fix_vault_health_issue(
+    fix: HealthFix,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<(), String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    apply_fix(&vault_path, &fix).map_err(|e| format!("Failed to fix issue: {}", e))?;
+    println!("🩹 Applied vault health fix: {:?}", fix);
+    Ok(())
+}
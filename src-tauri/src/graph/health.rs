@@ -0,0 +1,477 @@
+//! Vault health report
+//!
+//! Collects the problems that build up in a vault over time: wikilinks to
+//! notes that do not exist, `[[tid:...]]` and UUID links to items that were
+//! deleted, image and attachment references to missing files, attachments in
+//! the image folder that no note uses, notes or files sharing a UUID, and
+//! `.meta.json` sidecars whose file is gone. Issues that can be repaired
+//! without asking the user anything carry a `HealthFix`.
+
+use anyhow::{anyhow, bail, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path};
+use walkdir::WalkDir;
+
+use super::index::{strip_md, LinkEdge, LinkGraph, PATH_ID_PREFIX};
+use super::links::{rewrite_links, LinkKind};
+use super::rename::relative_link;
+use super::resolve::suggest;
+use crate::identity::frontmatter::{FrontMatter, FrontMatterParser, FrontMatterWriter};
+use crate::identity::sidecar::SidecarManager;
+use crate::identity::uuid::UuidGenerator;
+use crate::vault_settings::normalize_image_location;
+
+lazy_static! {
+    /// `![alt](path)` images and `[text](file.pdf)` links to local files
+    static ref LOCAL_FILE_LINK_PATTERN: Regex =
+        Regex::new(r#"!?\[[^\]\n]*\]\(([^)\s]+)(?:\s+"[^"\n]*")?\)"#).unwrap();
+    /// `<img src="...">`, `<a href="...">` and other HTML tags pointing at a file
+    static ref HTML_FILE_LINK_PATTERN: Regex =
+        Regex::new(r#"(?i)<[a-z][a-z0-9]*\s[^>]*?\b(?:src|href)\s*=\s*(?:"([^"\n]*)"|'([^'\n]*)')"#)
+            .unwrap();
+    static ref INLINE_CODE_PATTERN: Regex = Regex::new(r"`[^`\n]*`").unwrap();
+}
+
+const SIDECAR_SUFFIX: &str = ".meta.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthIssueKind {
+    /// A wikilink or markdown link to a note that does not exist
+    BrokenLink,
+    /// A `[[tid:...]]` or `[[<uuid>]]` link to a task or note that was deleted
+    DeletedIdLink,
+    /// An embed or link to an image or other file that does not exist
+    BrokenAttachment,
+    /// A file in the image folder that no note refers to
+    OrphanedAttachment,
+    /// A note or file whose UUID is also used by another one
+    DuplicateId,
+    /// A `.meta.json` sidecar whose file no longer exists
+    OrphanedSidecar,
+}
+
+/// A one-click repair for an issue. Paths are relative to the vault root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum HealthFix {
+    /// Points the link at `line` of `path` whose target is `from` at `to`
+    #[serde(rename_all = "camelCase")]
+    RewriteLink {
+        path: String,
+        line: usize,
+        from: String,
+        to: String,
+    },
+    /// Gives a note (front matter) or file (sidecar) a fresh UUID
+    #[serde(rename_all = "camelCase")]
+    AssignNewId { path: String },
+    /// Deletes a file nothing refers to, together with its sidecar
+    #[serde(rename_all = "camelCase")]
+    DeleteFile { path: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    /// File the issue was found in
+    pub path: String,
+    /// 1-based line of the offending link
+    pub line: Option<usize>,
+    /// Link target, attachment path or duplicated UUID
+    pub target: String,
+    pub message: String,
+    pub fix: Option<HealthFix>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub notes_checked: usize,
+    pub attachments_checked: usize,
+    pub issues: Vec<HealthIssue>,
+}
+
+impl HealthReport {
+    pub fn count(&self, kind: HealthIssueKind) -> usize {
+        self.issues.iter().filter(|i| i.kind == kind).count()
+    }
+
+    pub fn fixable(&self) -> usize {
+        self.issues.iter().filter(|i| i.fix.is_some()).count()
+    }
+}
+
+/// Checks the vault behind `graph`. `image_location` is the configured image
+/// folder; only attachments inside it are reported when unused.
+pub fn check_vault(graph: &LinkGraph, image_location: &str) -> HealthReport {
+    let mut issues = Vec::new();
+    let mut referenced: HashSet<String> = HashSet::new();
+
+    for note in graph.notes() {
+        for edge in graph.outgoing(note) {
+            match &edge.target_path {
+                Some(target) => {
+                    referenced.insert(target.clone());
+                }
+                None => issues.extend(unresolved_issue(graph, &edge)),
+            }
+        }
+
+        let Ok(content) = std::fs::read_to_string(graph.vault_root().join(&note.path)) else {
+            continue;
+        };
+        for (line, target) in local_file_links(&content) {
+            match graph.resolve_attachment_reference(&note.path, &target) {
+                Some(path) => {
+                    referenced.insert(path);
+                }
+                None => issues.push(HealthIssue {
+                    kind: HealthIssueKind::BrokenAttachment,
+                    path: note.path.clone(),
+                    line: Some(line),
+                    message: format!("File {} does not exist", target),
+                    target,
+                    fix: None,
+                }),
+            }
+        }
+    }
+
+    let image_folder = normalize_image_location(image_location).to_lowercase();
+    for attachment in graph.attachments() {
+        if attachment.to_lowercase().starts_with(&image_folder) && !referenced.contains(attachment)
+        {
+            issues.push(HealthIssue {
+                kind: HealthIssueKind::OrphanedAttachment,
+                path: attachment.to_string(),
+                line: None,
+                target: attachment.to_string(),
+                message: "No note embeds or links to this file".to_string(),
+                fix: Some(HealthFix::DeleteFile {
+                    path: attachment.to_string(),
+                }),
+            });
+        }
+    }
+
+    // UUID -> every note or file carrying it
+    let mut ids: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for note in graph.notes() {
+        if !note.id.starts_with(PATH_ID_PREFIX) {
+            ids.entry(note.id.clone())
+                .or_default()
+                .push(note.path.clone());
+        }
+    }
+    for sidecar in sidecars(graph.vault_root()) {
+        let original = original_path(&sidecar);
+        let full_path = graph.vault_root().join(&original);
+        if !full_path.exists() {
+            issues.push(HealthIssue {
+                kind: HealthIssueKind::OrphanedSidecar,
+                path: sidecar.clone(),
+                line: None,
+                target: original.clone(),
+                message: format!("{} no longer exists", original),
+                fix: Some(HealthFix::DeleteFile { path: sidecar }),
+            });
+        } else if let Ok(Some(data)) = SidecarManager::read(&full_path) {
+            ids.entry(data.id).or_default().push(original);
+        }
+    }
+    for (id, mut paths) in ids.into_iter().filter(|(_, paths)| paths.len() > 1) {
+        // The shortest path keeps the UUID; copies usually have the longer name
+        paths.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        for path in &paths[1..] {
+            issues.push(HealthIssue {
+                kind: HealthIssueKind::DuplicateId,
+                path: path.clone(),
+                line: None,
+                target: id.clone(),
+                message: format!("UUID {} is also used by {}", id, paths[0]),
+                fix: Some(HealthFix::AssignNewId { path: path.clone() }),
+            });
+        }
+    }
+
+    issues.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then_with(|| a.path.cmp(&b.path))
+            .then(a.line.cmp(&b.line))
+    });
+    HealthReport {
+        notes_checked: graph.notes().len(),
+        attachments_checked: graph.attachments().len(),
+        issues,
+    }
+}
+
+/// Classifies a link that resolved to nothing
+fn unresolved_issue(graph: &LinkGraph, edge: &LinkEdge) -> Option<HealthIssue> {
+    let link = &edge.link;
+    let target = link.target.trim();
+    let issue = |kind, message: String, fix| HealthIssue {
+        kind,
+        path: edge.source_path.clone(),
+        line: Some(link.line),
+        target: link.target.clone(),
+        message,
+        fix,
+    };
+
+    if link.kind == LinkKind::Task {
+        return Some(issue(
+            HealthIssueKind::DeletedIdLink,
+            format!("Task {} no longer exists", target),
+            None,
+        ));
+    }
+    if uuid::Uuid::parse_str(target).is_ok() {
+        // `[[<uuid>]]` is fine as long as a note still carries that UUID
+        return graph.find_note(target).is_none().then(|| {
+            issue(
+                HealthIssueKind::DeletedIdLink,
+                format!("No note has the UUID {}", target),
+                None,
+            )
+        });
+    }
+    if is_attachment_target(target) {
+        return Some(issue(
+            HealthIssueKind::BrokenAttachment,
+            format!("File {} does not exist", target),
+            None,
+        ));
+    }
+
+    let fix = suggest(graph, target, 1).into_iter().next().map(|best| {
+        let to = match link.kind {
+            LinkKind::Markdown => relative_link(&edge.source_path, &best.path),
+            _ => {
+                let unique = graph.notes_named(&best.title).len() == 1;
+                let name = if target.contains('/') || !unique {
+                    strip_md(&best.path).to_string()
+                } else {
+                    best.title.clone()
+                };
+                if target.to_lowercase().ends_with(".md") {
+                    format!("{}.md", name)
+                } else {
+                    name
+                }
+            }
+        };
+        HealthFix::RewriteLink {
+            path: edge.source_path.clone(),
+            line: link.line,
+            from: link.target.clone(),
+            to,
+        }
+    });
+    let message = match &fix {
+        Some(HealthFix::RewriteLink { to, .. }) => {
+            format!("No note named {}; did you mean {}?", target, to)
+        }
+        _ => format!("No note named {}", target),
+    };
+    Some(issue(HealthIssueKind::BrokenLink, message, fix))
+}
+
+/// Applies a fix from `check_vault`
+pub fn apply_fix(vault_root: &Path, fix: &HealthFix) -> Result<()> {
+    match fix {
+        HealthFix::RewriteLink {
+            path,
+            line,
+            from,
+            to,
+        } => {
+            let full_path = vault_path(vault_root, path)?;
+            let content = std::fs::read_to_string(&full_path)
+                .map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+            let rewritten = rewrite_links(&content, |link| {
+                (link.line == *line && link.target == *from).then(|| to.clone())
+            });
+            if rewritten == content {
+                bail!("The link to {} on line {} of {} is gone", from, line, path);
+            }
+            std::fs::write(&full_path, rewritten)?;
+        }
+        HealthFix::AssignNewId { path } => {
+            let full_path = vault_path(vault_root, path)?;
+            if !full_path.is_file() {
+                bail!("{} does not exist", path);
+            }
+            let id = UuidGenerator::new().generate()?;
+            if SidecarManager::should_use_sidecar(&full_path) {
+                SidecarManager::update(&full_path, |data| data.id = id)?;
+            } else {
+                let content = std::fs::read_to_string(&full_path)?;
+                let mut front_matter = FrontMatterParser::parse(&content)?
+                    .0
+                    .unwrap_or_else(FrontMatter::new);
+                front_matter.id = Some(id);
+                FrontMatterWriter::write_atomic(&full_path, &front_matter, "")?;
+            }
+        }
+        HealthFix::DeleteFile { path } => {
+            let full_path = vault_path(vault_root, path)?;
+            if full_path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+            {
+                bail!("Refusing to delete note {}", path);
+            }
+            std::fs::remove_file(&full_path)
+                .map_err(|e| anyhow!("Failed to delete {}: {}", path, e))?;
+            if !path.ends_with(SIDECAR_SUFFIX) {
+                SidecarManager::delete(&full_path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Joins a relative path onto the vault root, rejecting anything that would leave it
+fn vault_path(vault_root: &Path, rel: &str) -> Result<std::path::PathBuf> {
+    let rel = Path::new(rel);
+    if rel
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("Path is outside the vault: {:?}", rel);
+    }
+    Ok(vault_root.join(rel))
+}
+
+/// Local targets of `![alt](path)` and `[text](path)` links and of HTML `src`
+/// and `href` attributes that point at attachments, with their 1-based line
+/// numbers. URLs and notes are skipped.
+fn local_file_links(content: &str) -> Vec<(usize, String)> {
+    let mut links = Vec::new();
+    let mut in_fence = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line = INLINE_CODE_PATTERN
+            .replace_all(line, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+        let markdown = LOCAL_FILE_LINK_PATTERN
+            .captures_iter(&line)
+            .filter_map(|caps| caps.get(1));
+        // Query strings and fragments are not part of an HTML file reference
+        let html = HTML_FILE_LINK_PATTERN
+            .captures_iter(&line)
+            .filter_map(|caps| caps.get(1).or_else(|| caps.get(2)))
+            .map(|target| {
+                let target = target.as_str();
+                target.split(['?', '#']).next().unwrap_or(target)
+            });
+        for target in markdown.map(|m| m.as_str()).chain(html) {
+            if target.contains(':') || !is_attachment_target(target) {
+                continue;
+            }
+            let target = urlencoding::decode(target)
+                .map(|t| t.into_owned())
+                .unwrap_or_else(|_| target.to_string());
+            links.push((index + 1, target));
+        }
+    }
+
+    links
+}
+
+/// Whether a link target names a file other than a note, judging by its
+/// extension (`v1.2` and similar note names are not extensions)
+fn is_attachment_target(target: &str) -> bool {
+    let name = target.rsplit('/').next().unwrap_or(target);
+    let Some((_, extension)) = name.rsplit_once('.') else {
+        return false;
+    };
+    !extension.eq_ignore_ascii_case("md")
+        && (1..=5).contains(&extension.len())
+        && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        && extension.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+/// Relative paths of every `.meta.json` sidecar, skipping hidden folders
+fn sidecars(vault_root: &Path) -> Vec<String> {
+    let walker = WalkDir::new(vault_root)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e.file_type().is_dir()
+                || !e.file_name().to_string_lossy().starts_with('.')
+        });
+
+    let mut paths: Vec<String> = walker
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy();
+            name.starts_with('.')
+                && name.len() > SIDECAR_SUFFIX.len() + 1
+                && name.ends_with(SIDECAR_SUFFIX)
+        })
+        .filter_map(|e| {
+            e.path()
+                .strip_prefix(vault_root)
+                .ok()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// `folder/.file.pdf.meta.json` -> `folder/file.pdf`
+fn original_path(sidecar: &str) -> String {
+    let (folder, name) = match sidecar.rsplit_once('/') {
+        Some((folder, name)) => (Some(folder), name),
+        None => (None, sidecar),
+    };
+    let name = &name[1..name.len() - SIDECAR_SUFFIX.len()];
+    match folder {
+        Some(folder) => format!("{}/{}", folder, name),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_file_links_skip_urls_notes_and_code() {
+        let content = "![shot](Files/Pasted%20image.png) [doc](../report.pdf \"Report\")\n\
+                       [note](Other.md) ![remote](https://example.com/a.png) [v](Release v1.2)\n\
+                       `![code](x.png)`\n```\n![fenced](y.png)\n```\n";
+        assert_eq!(
+            local_file_links(content),
+            vec![
+                (1, "Files/Pasted image.png".to_string()),
+                (1, "../report.pdf".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_original_path() {
+        assert_eq!(original_path(".a.pdf.meta.json"), "a.pdf");
+        assert_eq!(original_path("Files/.b c.png.meta.json"), "Files/b c.png");
+    }
+}
@@ -0,0 +1,817 @@
+//! Persistent link graph
+//!
+//! Every markdown note becomes a node keyed by its front matter UUID (notes
+//! without one fall back to `path:<relative path>`). The parsed links of each
+//! note are kept in a JSON snapshot at `<vault>/.vault/graph.json` together
+//! with the file's mtime and size, so a refresh only re-reads notes that
+//! changed. Once loaded, the graph is kept current from the vault watcher's
+//! events with `update_paths`, which re-reads only the reported files. Link
+//! targets are resolved against the whole vault after every update, which
+//! keeps backlinks and unresolved links correct when notes are added,
+//! renamed or removed.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+use super::links::{extract_links, extract_task_ids, LinkKind, RawLink};
+use crate::identity::frontmatter::FrontMatterParser;
+use crate::vault_settings::normalize_image_reference;
+
+const SNAPSHOT_VERSION: u32 = 2;
+
+/// Prefix of the node ID used for notes that have no UUID yet
+pub const PATH_ID_PREFIX: &str = "path:";
+
+/// A note in the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteNode {
+    pub id: String,
+    /// Path relative to the vault root, using `/` separators
+    pub path: String,
+    pub title: String,
+    /// Alternative names from the front matter `aliases:` list
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub links: Vec<RawLink>,
+    pub task_ids: Vec<String>,
+    mtime: u64,
+    size: u64,
+}
+
+/// A link together with its resolved endpoints
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkEdge {
+    pub source_id: String,
+    pub source_path: String,
+    #[serde(flatten)]
+    pub link: RawLink,
+    /// Note the link points at (for task links, the note containing the task)
+    pub target_id: Option<String>,
+    /// Resolved file, which may be an attachment rather than a note
+    pub target_path: Option<String>,
+}
+
+impl LinkEdge {
+    pub fn is_resolved(&self) -> bool {
+        self.target_path.is_some()
+    }
+}
+
+/// A note reached while walking the graph
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphNeighbor {
+    pub id: String,
+    pub path: String,
+    pub title: String,
+    pub distance: usize,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshStats {
+    pub parsed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphStats {
+    pub notes: usize,
+    pub links: usize,
+    pub unresolved_links: usize,
+    pub orphaned_notes: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    notes: Vec<NoteNode>,
+}
+
+pub struct LinkGraph {
+    vault_root: PathBuf,
+    snapshot_path: PathBuf,
+    /// Nodes keyed by relative path
+    notes: HashMap<String, NoteNode>,
+    ids: HashMap<String, String>,
+    /// Lowercased note name -> paths of notes with that name
+    names: HashMap<String, Vec<String>>,
+    /// Lowercased alias -> paths of notes declaring it
+    aliases: HashMap<String, Vec<String>>,
+    /// Lowercased relative path without extension -> path
+    note_paths: HashMap<String, String>,
+    /// Lowercased relative path / file name of non-markdown files -> path
+    attachment_paths: HashMap<String, String>,
+    attachment_names: HashMap<String, String>,
+    task_owner: HashMap<String, String>,
+    /// Target note path -> (source path, link index)
+    incoming: HashMap<String, Vec<(String, usize)>>,
+}
+
+impl LinkGraph {
+    /// Loads the persisted graph for a vault. Call `refresh` to bring it up to date.
+    pub fn open(vault_root: &Path) -> Self {
+        let snapshot_path = super::snapshot_path(vault_root);
+        let notes = std::fs::read_to_string(&snapshot_path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Snapshot>(&raw).ok())
+            .filter(|snapshot| snapshot.version == SNAPSHOT_VERSION)
+            .map(|snapshot| {
+                snapshot
+                    .notes
+                    .into_iter()
+                    .map(|note| (note.path.clone(), note))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut graph = Self {
+            vault_root: vault_root.to_path_buf(),
+            snapshot_path,
+            notes,
+            ids: HashMap::new(),
+            names: HashMap::new(),
+            aliases: HashMap::new(),
+            note_paths: HashMap::new(),
+            attachment_paths: HashMap::new(),
+            attachment_names: HashMap::new(),
+            task_owner: HashMap::new(),
+            incoming: HashMap::new(),
+        };
+        graph.rebuild_lookups();
+        graph
+    }
+
+    pub fn vault_root(&self) -> &Path {
+        &self.vault_root
+    }
+
+    /// Re-parses notes whose mtime or size changed, drops deleted notes and
+    /// re-resolves every link. The snapshot is rewritten only if something changed.
+    pub fn refresh(&mut self) -> Result<RefreshStats> {
+        let mut stats = RefreshStats::default();
+        let mut seen = HashSet::new();
+        let mut attachments = Vec::new();
+
+        for entry in walk(&self.vault_root) {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+
+            let rel = self.relative_path(path);
+            if !is_markdown(path) {
+                attachments.push(rel);
+                continue;
+            }
+
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let (mtime, size) = file_stamp(&metadata);
+            seen.insert(rel.clone());
+
+            if self
+                .notes
+                .get(&rel)
+                .is_some_and(|n| n.mtime == mtime && n.size == size)
+            {
+                stats.unchanged += 1;
+                continue;
+            }
+
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let node = parse_note(&rel, &content, mtime, size);
+            self.notes.insert(rel, node);
+            stats.parsed += 1;
+        }
+
+        let before = self.notes.len();
+        self.notes.retain(|path, _| seen.contains(path));
+        stats.removed = before - self.notes.len();
+
+        self.attachment_paths = attachments
+            .into_iter()
+            .map(|rel| (rel.to_lowercase(), rel))
+            .collect();
+        self.rebuild_lookups();
+        if stats.parsed > 0 || stats.removed > 0 || !self.snapshot_path.exists() {
+            self.save()?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Brings the graph up to date with files the vault watcher reported as
+    /// created, modified or removed, without walking the rest of the vault.
+    /// A path may be a note, an attachment or a folder.
+    pub fn update_paths(&mut self, paths: &HashSet<PathBuf>) -> Result<RefreshStats> {
+        let mut stats = RefreshStats::default();
+        // The watcher may report paths against the canonical vault path
+        let canonical_root = self.vault_root.canonicalize().ok();
+        for path in paths {
+            let relative = match path.strip_prefix(&self.vault_root) {
+                Ok(relative) => relative,
+                Err(_) => match canonical_root
+                    .as_deref()
+                    .and_then(|root| path.strip_prefix(root).ok())
+                {
+                    Some(relative) => relative,
+                    None => continue,
+                },
+            };
+            let hidden = relative
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+            if relative.as_os_str().is_empty() || hidden {
+                continue;
+            }
+
+            // Forget whatever was at the path, including a folder's contents
+            let path = &self.vault_root.join(relative);
+            let rel = self.relative_path(path);
+            let folder = format!("{}/", rel);
+            let under = |p: &String| *p == rel || p.starts_with(&folder);
+            let mut stale: HashSet<String> =
+                self.notes.keys().filter(|p| under(p)).cloned().collect();
+            self.notes.retain(|p, _| !stale.contains(p));
+            self.attachment_paths.retain(|_, p| !under(p));
+
+            for entry in walk(path) {
+                let path = entry.path();
+                if !path.is_file() {
+                    continue;
+                }
+                let rel = self.relative_path(path);
+                if !is_markdown(path) {
+                    self.attachment_paths.insert(rel.to_lowercase(), rel);
+                    continue;
+                }
+                let (Ok(metadata), Ok(content)) = (entry.metadata(), std::fs::read_to_string(path))
+                else {
+                    continue;
+                };
+                let (mtime, size) = file_stamp(&metadata);
+                stale.remove(&rel);
+                self.notes
+                    .insert(rel.clone(), parse_note(&rel, &content, mtime, size));
+                stats.parsed += 1;
+            }
+            stats.removed += stale.len();
+        }
+
+        self.rebuild_lookups();
+        if stats.parsed > 0 || stats.removed > 0 {
+            self.save()?;
+        }
+        Ok(stats)
+    }
+
+    /// Writes the snapshot atomically (temp file + rename).
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.snapshot_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut notes: Vec<&NoteNode> = self.notes.values().collect();
+        notes.sort_by(|a, b| a.path.cmp(&b.path));
+        let raw = serde_json::to_string(&serde_json::json!({
+            "version": SNAPSHOT_VERSION,
+            "notes": notes,
+        }))?;
+
+        let tmp = self.snapshot_path.with_extension("json.tmp");
+        std::fs::write(&tmp, raw)?;
+        std::fs::rename(&tmp, &self.snapshot_path)?;
+        Ok(())
+    }
+
+    fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.vault_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    fn rebuild_lookups(&mut self) {
+        self.ids.clear();
+        self.names.clear();
+        self.aliases.clear();
+        self.note_paths.clear();
+        self.task_owner.clear();
+        self.incoming.clear();
+
+        for note in self.notes.values() {
+            self.ids.insert(note.id.clone(), note.path.clone());
+            self.names
+                .entry(normalize_name(&note.title))
+                .or_default()
+                .push(note.path.clone());
+            for alias in &note.aliases {
+                self.aliases
+                    .entry(normalize_name(alias))
+                    .or_default()
+                    .push(note.path.clone());
+            }
+            self.note_paths
+                .insert(strip_md(&note.path).to_lowercase(), note.path.clone());
+            for task_id in &note.task_ids {
+                self.task_owner.insert(task_id.clone(), note.path.clone());
+            }
+        }
+
+        // Shortest path wins when several attachments share a name
+        self.attachment_names.clear();
+        let mut attachments: Vec<&String> = self.attachment_paths.values().collect();
+        attachments.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        for rel in attachments {
+            let name = rel.rsplit('/').next().unwrap_or(rel).to_lowercase();
+            self.attachment_names
+                .entry(name)
+                .or_insert_with(|| rel.clone());
+        }
+
+        // Shortest path wins when several notes share a name
+        for paths in self.names.values_mut().chain(self.aliases.values_mut()) {
+            paths.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+            paths.dedup();
+        }
+
+        let mut incoming: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        for note in self.notes.values() {
+            for (index, link) in note.links.iter().enumerate() {
+                if let Some(target) = self.resolve_note_target(&note.path, link) {
+                    incoming
+                        .entry(target)
+                        .or_default()
+                        .push((note.path.clone(), index));
+                }
+            }
+        }
+        for sources in incoming.values_mut() {
+            sources.sort();
+        }
+        self.incoming = incoming;
+    }
+
+    /// Path of the note a link points at, if it resolves to a note
+    fn resolve_note_target(&self, source_path: &str, link: &RawLink) -> Option<String> {
+        match link.kind {
+            LinkKind::Task => self.task_owner.get(&link.target).cloned(),
+            LinkKind::Markdown => {
+                let joined = join_relative(source_path, &link.target)?;
+                self.note_paths
+                    .get(&strip_md(&joined).to_lowercase())
+                    .cloned()
+            }
+            LinkKind::Wiki | LinkKind::Embed => {
+                let target = strip_md(link.target.trim());
+                let name = normalize_name(target.rsplit('/').next().unwrap_or(target));
+                if target.contains('/') {
+                    let key = target.trim_start_matches('/').to_lowercase();
+                    if let Some(path) = self.note_paths.get(&key) {
+                        return Some(path.clone());
+                    }
+                    // `[[folder/Note]]` may leave out the folders above `folder`
+                    return self
+                        .names
+                        .get(&name)
+                        .and_then(|paths| paths.iter().find(|p| ends_with_path(p, &key)))
+                        .cloned();
+                }
+                // Titles take precedence over aliases
+                self.names
+                    .get(&name)
+                    .or_else(|| self.aliases.get(&name))
+                    .and_then(|paths| paths.first().cloned())
+            }
+        }
+    }
+
+    /// Non-markdown file a link points at (images, PDFs and other attachments)
+    fn resolve_attachment(&self, source_path: &str, link: &RawLink) -> Option<String> {
+        if matches!(link.kind, LinkKind::Task) {
+            return None;
+        }
+        self.resolve_attachment_reference(source_path, &link.target)
+    }
+
+    /// Resolves an image or attachment reference written in `source_path`:
+    /// relative to the note, then from the vault root, then by file name.
+    pub fn resolve_attachment_reference(&self, source_path: &str, target: &str) -> Option<String> {
+        let target = normalize_image_reference(target);
+        let target = target.trim_start_matches('/');
+        if let Some(joined) = join_relative(source_path, target) {
+            if let Some(path) = self.attachment_paths.get(&joined.to_lowercase()) {
+                return Some(path.clone());
+            }
+        }
+        if let Some(path) = self.attachment_paths.get(&target.to_lowercase()) {
+            return Some(path.clone());
+        }
+        let name = target.rsplit('/').next().unwrap_or(target).to_lowercase();
+        self.attachment_names.get(&name).cloned()
+    }
+
+    /// Every non-markdown file in the vault, sorted by path
+    pub fn attachments(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = self.attachment_paths.values().map(String::as_str).collect();
+        paths.sort();
+        paths
+    }
+
+    fn edge(&self, source: &NoteNode, link: &RawLink) -> LinkEdge {
+        let (target_id, target_path) = match self.resolve_note_target(&source.path, link) {
+            Some(path) => (self.notes.get(&path).map(|n| n.id.clone()), Some(path)),
+            None => (None, self.resolve_attachment(&source.path, link)),
+        };
+
+        LinkEdge {
+            source_id: source.id.clone(),
+            source_path: source.path.clone(),
+            link: link.clone(),
+            target_id,
+            target_path,
+        }
+    }
+
+    /// Note a link written in `source_path` points at
+    pub fn resolve(&self, source_path: &str, link: &RawLink) -> Option<&NoteNode> {
+        self.resolve_note_target(source_path, link)
+            .and_then(|path| self.notes.get(&path))
+    }
+
+    /// Finds a note by UUID, relative path (with or without `.md`), name or alias.
+    pub fn find_note(&self, reference: &str) -> Option<&NoteNode> {
+        let reference = reference.trim().replace('\\', "/");
+        if let Some(path) = self.ids.get(&reference) {
+            return self.notes.get(path);
+        }
+        if let Some(note) = self.notes.get(&reference) {
+            return Some(note);
+        }
+
+        let key = strip_md(reference.trim_start_matches('/')).to_lowercase();
+        if let Some(path) = self.note_paths.get(&key) {
+            return self.notes.get(path);
+        }
+        let name = normalize_name(&key);
+        self.names
+            .get(&name)
+            .or_else(|| self.aliases.get(&name))
+            .and_then(|paths| paths.first())
+            .and_then(|path| self.notes.get(path))
+    }
+
+    /// All notes, sorted by path
+    pub fn notes(&self) -> Vec<&NoteNode> {
+        let mut notes: Vec<&NoteNode> = self.notes.values().collect();
+        notes.sort_by(|a, b| a.path.cmp(&b.path));
+        notes
+    }
+
+    /// Links written in the note, in document order
+    pub fn outgoing(&self, note: &NoteNode) -> Vec<LinkEdge> {
+        note.links
+            .iter()
+            .map(|link| self.edge(note, link))
+            .collect()
+    }
+
+    /// Links in other notes (or the note itself) that resolve to this note
+    pub fn backlinks(&self, note: &NoteNode) -> Vec<LinkEdge> {
+        self.incoming
+            .get(&note.path)
+            .map(|sources| {
+                sources
+                    .iter()
+                    .filter_map(|(source_path, index)| {
+                        let source = self.notes.get(source_path)?;
+                        Some(self.edge(source, source.links.get(*index)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `![[...]]` embeds in the note
+    pub fn embeds(&self, note: &NoteNode) -> Vec<LinkEdge> {
+        self.outgoing_of_kind(note, LinkKind::Embed)
+    }
+
+    /// `[[tid:...]]` references in the note
+    pub fn task_links(&self, note: &NoteNode) -> Vec<LinkEdge> {
+        self.outgoing_of_kind(note, LinkKind::Task)
+    }
+
+    fn outgoing_of_kind(&self, note: &NoteNode, kind: LinkKind) -> Vec<LinkEdge> {
+        note.links
+            .iter()
+            .filter(|link| link.kind == kind)
+            .map(|link| self.edge(note, link))
+            .collect()
+    }
+
+    /// Every `[[tid:...]]` reference to a task across the vault
+    pub fn task_backlinks(&self, task_id: &str) -> Vec<LinkEdge> {
+        self.notes()
+            .into_iter()
+            .flat_map(|note| {
+                note.links
+                    .iter()
+                    .filter(|link| link.kind == LinkKind::Task && link.target == task_id)
+                    .map(move |link| self.edge(note, link))
+            })
+            .collect()
+    }
+
+    /// Links whose target matches no note or attachment in the vault
+    pub fn unresolved(&self) -> Vec<LinkEdge> {
+        self.notes()
+            .into_iter()
+            .flat_map(|note| self.outgoing(note))
+            .filter(|edge| !edge.is_resolved())
+            .collect()
+    }
+
+    /// Links that would resolve to a note stored at `rel_path`, whether or not
+    /// the note is still there. After a rename the old path no longer exists,
+    /// but the links written against it still need to be found.
+    pub fn links_to_path(&self, rel_path: &str) -> Vec<LinkEdge> {
+        self.notes()
+            .into_iter()
+            .flat_map(|note| {
+                note.links
+                    .iter()
+                    .filter(|link| self.points_at_path(&note.path, link, rel_path))
+                    .map(move |link| self.edge(note, link))
+            })
+            .collect()
+    }
+
+    pub fn points_at_path(&self, source_path: &str, link: &RawLink, rel_path: &str) -> bool {
+        let key = strip_md(rel_path).to_lowercase();
+        match link.kind {
+            LinkKind::Task => false,
+            LinkKind::Markdown => join_relative(source_path, &link.target)
+                .is_some_and(|joined| strip_md(&joined).to_lowercase() == key),
+            LinkKind::Wiki | LinkKind::Embed => {
+                let target = strip_md(link.target.trim());
+                let target_key = target.trim_start_matches('/').to_lowercase();
+                let qualified = target.contains('/');
+                if qualified {
+                    if target_key == key {
+                        return true;
+                    }
+                    if self.note_paths.contains_key(&target_key)
+                        || !ends_with_path(rel_path, &target_key)
+                    {
+                        return false;
+                    }
+                }
+
+                let name = normalize_name(target.rsplit('/').next().unwrap_or(target));
+                let stem = Path::new(rel_path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("");
+                if name != normalize_name(stem) {
+                    return false;
+                }
+                // The note at `rel_path` must win the shortest-path tie-break
+                self.names.get(&name).is_none_or(|paths| {
+                    paths
+                        .iter()
+                        .filter(|p| p.as_str() != rel_path)
+                        .filter(|p| !qualified || ends_with_path(p, &target_key))
+                        .all(|p| (p.len(), p.as_str()) > (rel_path.len(), rel_path))
+                })
+            }
+        }
+    }
+
+    /// Whether a note or attachment exists at `rel_path`
+    pub fn contains_path(&self, rel_path: &str) -> bool {
+        self.note_paths
+            .contains_key(&strip_md(rel_path).to_lowercase())
+            || self.attachment_paths.contains_key(&rel_path.to_lowercase())
+    }
+
+    /// Paths of the notes whose name normalizes to `name`
+    pub fn notes_named(&self, name: &str) -> Vec<&str> {
+        self.names
+            .get(&normalize_name(name))
+            .map(|paths| paths.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Notes within `depth` hops of `note`, following links in both directions
+    pub fn neighbors(&self, note: &NoteNode, depth: usize) -> Vec<GraphNeighbor> {
+        let mut distances: HashMap<String, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        distances.insert(note.path.clone(), 0);
+        queue.push_back(note.path.clone());
+
+        while let Some(path) = queue.pop_front() {
+            let distance = distances[&path];
+            if distance >= depth {
+                continue;
+            }
+            let Some(current) = self.notes.get(&path) else {
+                continue;
+            };
+
+            let outgoing = current
+                .links
+                .iter()
+                .filter_map(|link| self.resolve_note_target(&current.path, link));
+            let incoming = self
+                .incoming
+                .get(&path)
+                .into_iter()
+                .flatten()
+                .map(|(source, _)| source.clone());
+
+            for next in outgoing.chain(incoming).collect::<Vec<_>>() {
+                if !distances.contains_key(&next) {
+                    distances.insert(next.clone(), distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut neighbors: Vec<GraphNeighbor> = distances
+            .into_iter()
+            .filter(|(path, _)| *path != note.path)
+            .filter_map(|(path, distance)| {
+                let node = self.notes.get(&path)?;
+                Some(GraphNeighbor {
+                    id: node.id.clone(),
+                    path: node.path.clone(),
+                    title: node.title.clone(),
+                    distance,
+                })
+            })
+            .collect();
+        neighbors.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        neighbors
+    }
+
+    pub fn stats(&self) -> GraphStats {
+        let links = self.notes.values().map(|n| n.links.len()).sum();
+        let orphaned_notes = self
+            .notes
+            .keys()
+            .filter(|path| {
+                self.incoming
+                    .get(*path)
+                    .is_none_or(|sources| sources.iter().all(|(s, _)| s == *path))
+            })
+            .count();
+
+        GraphStats {
+            notes: self.notes.len(),
+            links,
+            unresolved_links: self.unresolved().len(),
+            orphaned_notes,
+        }
+    }
+}
+
+fn parse_note(rel: &str, content: &str, mtime: u64, size: u64) -> NoteNode {
+    let (front_matter, body) = match FrontMatterParser::parse(content) {
+        Ok(parsed) => parsed,
+        Err(_) => (None, content.to_string()),
+    };
+    let aliases = front_matter
+        .as_ref()
+        .map(|fm| front_matter_aliases(&fm.extra_fields))
+        .unwrap_or_default();
+    let id = front_matter
+        .and_then(|fm| fm.id)
+        .unwrap_or_else(|| format!("{}{}", PATH_ID_PREFIX, rel));
+
+    // Keep line numbers relative to the whole file
+    let offset = content.lines().count() - body.lines().count();
+    let mut links = extract_links(&body);
+    for link in &mut links {
+        link.line += offset;
+    }
+
+    NoteNode {
+        id,
+        path: rel.to_string(),
+        title: Path::new(rel)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string(),
+        aliases,
+        links,
+        task_ids: extract_task_ids(&body),
+        mtime,
+        size,
+    }
+}
+
+/// Files and folders under `root`, skipping hidden ones like `.vault`
+fn walk(root: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(root)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+}
+
+/// Modification time in milliseconds and size, to spot changed notes
+fn file_stamp(metadata: &std::fs::Metadata) -> (u64, u64) {
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    (mtime, metadata.len())
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+}
+
+pub(super) fn strip_md(path: &str) -> &str {
+    // `get` rather than indexing, as the last three bytes may split a character
+    match path.get(path.len().saturating_sub(3)..) {
+        Some(ext) if path.len() > 3 && ext.eq_ignore_ascii_case(".md") => &path[..path.len() - 3],
+        _ => path,
+    }
+}
+
+/// `aliases:` (or `alias:`) as a YAML list or a comma separated string
+fn front_matter_aliases(fields: &BTreeMap<String, Value>) -> Vec<String> {
+    let mut aliases = Vec::new();
+    for key in ["aliases", "alias"] {
+        match fields.get(key) {
+            Some(Value::Array(items)) => {
+                aliases.extend(items.iter().filter_map(|v| v.as_str()).map(str::to_string))
+            }
+            Some(Value::String(list)) => aliases.extend(list.split(',').map(str::to_string)),
+            _ => {}
+        }
+    }
+    aliases
+        .into_iter()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// Name normalization used for wikilink matching: trimmed, whitespace
+/// collapsed to single spaces, lowercase
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Whether `path` (without `.md`) is `key` or ends with `/key`
+fn ends_with_path(path: &str, key: &str) -> bool {
+    let path = strip_md(path).to_lowercase();
+    path == key
+        || path
+            .strip_suffix(key)
+            .is_some_and(|rest| rest.ends_with('/'))
+}
+
+/// Resolves `target` relative to the folder of `source`, rejecting paths that leave the vault
+pub(super) fn join_relative(source: &str, target: &str) -> Option<String> {
+    let base = Path::new(source).parent().unwrap_or(Path::new(""));
+    let mut parts: Vec<String> = Vec::new();
+    for component in base.join(target).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            _ => {}
+        }
+    }
+    Some(parts.join("/"))
+}
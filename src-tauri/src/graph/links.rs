@@ -0,0 +1,296 @@
+//! Link extraction
+//!
+//! Finds wikilinks (`[[Note]]`, `[[Note|alias]]`, `[[Note#Heading]]`,
+//! `[[Note^block]]`), embeds (`![[Note]]`), task references (`[[tid:...]]`)
+//! and relative markdown links (`[text](Other.md)`) in a note's body. Links
+//! inside fenced code blocks and inline code spans are ignored.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+    static ref WIKILINK_PATTERN: Regex = Regex::new(r"(!?)\[\[([^\[\]\n]+?)\]\]").unwrap();
+    static ref MARKDOWN_LINK_PATTERN: Regex =
+        Regex::new(r"(?:^|[^!\]])\[[^\]\n]*\]\(([^)\s]+\.md)(?:#[^)\s]*)?\)").unwrap();
+    static ref INLINE_CODE_PATTERN: Regex = Regex::new(r"`[^`\n]*`").unwrap();
+    static ref TASK_ID_PATTERN: Regex = Regex::new(r"<!-- tid:\s*([a-zA-Z0-9-]+)\s*-->").unwrap();
+}
+
+/// Prefix marking a wikilink that points at a task instead of a note
+pub const TASK_LINK_PREFIX: &str = "tid:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkKind {
+    /// `[[Note]]`
+    Wiki,
+    /// `![[Note]]`
+    Embed,
+    /// `[text](Note.md)`
+    Markdown,
+    /// `[[tid:<uuid>]]`
+    Task,
+}
+
+/// A link as written in the source note
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawLink {
+    pub kind: LinkKind,
+    /// Note name, relative path, or task ID for task links
+    pub target: String,
+    pub heading: Option<String>,
+    pub block: Option<String>,
+    pub alias: Option<String>,
+    /// 1-based line number in the file
+    pub line: usize,
+}
+
+/// Extracts every link from a markdown document.
+pub fn extract_links(content: &str) -> Vec<RawLink> {
+    let mut links = Vec::new();
+    let mut in_fence = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        // Blank out inline code so its contents cannot produce links
+        let line = INLINE_CODE_PATTERN
+            .replace_all(line, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+
+        for caps in WIKILINK_PATTERN.captures_iter(&line) {
+            let embed = &caps[1] == "!";
+            if let Some(link) = parse_wikilink(&caps[2], embed, index + 1) {
+                links.push(link);
+            }
+        }
+
+        for caps in MARKDOWN_LINK_PATTERN.captures_iter(&line) {
+            let target = &caps[1];
+            if target.contains("://") {
+                continue;
+            }
+            let target = urlencoding::decode(target)
+                .map(|t| t.into_owned())
+                .unwrap_or_else(|_| target.to_string());
+            links.push(RawLink {
+                kind: LinkKind::Markdown,
+                target,
+                heading: None,
+                block: None,
+                alias: None,
+                line: index + 1,
+            });
+        }
+    }
+
+    links
+}
+
+/// Rewrites link targets in a markdown document.
+///
+/// `rewrite` sees every link `extract_links` would report (line numbers count
+/// from the top of `content`) and returns the new target, or `None` to leave
+/// the link alone. Headings, block references, aliases and embed markers are
+/// kept; markdown link targets stay percent-encoded if they were.
+pub fn rewrite_links(content: &str, mut rewrite: impl FnMut(&RawLink) -> Option<String>) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut in_fence = false;
+
+    for (index, raw_line) in content.split_inclusive('\n').enumerate() {
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let ending = &raw_line[line.len()..];
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            output.push_str(raw_line);
+            continue;
+        }
+        if in_fence {
+            output.push_str(raw_line);
+            continue;
+        }
+
+        // Same masking as extraction; spaces keep byte offsets aligned with `line`
+        let masked = INLINE_CODE_PATTERN
+            .replace_all(line, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+        let mut edits: Vec<(std::ops::Range<usize>, String)> = Vec::new();
+
+        for caps in WIKILINK_PATTERN.captures_iter(&masked) {
+            let inner = caps.get(2).unwrap();
+            let Some(link) = parse_wikilink(inner.as_str(), &caps[1] == "!", index + 1) else {
+                continue;
+            };
+            if let Some(new_target) = rewrite(&link) {
+                if let Some(offset) = inner.as_str().find(&link.target) {
+                    let start = inner.start() + offset;
+                    edits.push((start..start + link.target.len(), new_target));
+                }
+            }
+        }
+
+        for caps in MARKDOWN_LINK_PATTERN.captures_iter(&masked) {
+            let target = caps.get(1).unwrap();
+            if target.as_str().contains("://") {
+                continue;
+            }
+            let link = RawLink {
+                kind: LinkKind::Markdown,
+                target: urlencoding::decode(target.as_str())
+                    .map(|t| t.into_owned())
+                    .unwrap_or_else(|_| target.as_str().to_string()),
+                heading: None,
+                block: None,
+                alias: None,
+                line: index + 1,
+            };
+            if let Some(new_target) = rewrite(&link) {
+                let new_target = if target.as_str().contains('%') {
+                    new_target.replace(' ', "%20")
+                } else {
+                    new_target
+                };
+                edits.push((target.range(), new_target));
+            }
+        }
+
+        let mut line = line.to_string();
+        edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, replacement) in edits {
+            line.replace_range(range, &replacement);
+        }
+        output.push_str(&line);
+        output.push_str(ending);
+    }
+
+    output
+}
+
+/// Parses the inside of `[[...]]`.
+pub(super) fn parse_wikilink(inner: &str, embed: bool, line: usize) -> Option<RawLink> {
+    let (reference, alias) = match inner.split_once('|') {
+        Some((reference, alias)) => (reference, Some(alias.trim().to_string())),
+        None => (inner, None),
+    };
+    let reference = reference.trim();
+
+    if let Some(task_id) = reference.strip_prefix(TASK_LINK_PREFIX) {
+        let task_id = task_id.trim();
+        if task_id.is_empty() {
+            return None;
+        }
+        return Some(RawLink {
+            kind: LinkKind::Task,
+            target: task_id.to_string(),
+            heading: None,
+            block: None,
+            alias,
+            line,
+        });
+    }
+
+    let (reference, block) = match reference.split_once('^') {
+        Some((reference, block)) => (reference, Some(block.trim().to_string())),
+        None => (reference, None),
+    };
+    let (target, heading) = match reference.split_once('#') {
+        Some((target, heading)) => (target.trim(), Some(heading.trim().to_string())),
+        None => (reference.trim(), None),
+    };
+
+    // `[[#Heading]]` points into the current note; there is no edge to record
+    if target.is_empty() {
+        return None;
+    }
+
+    Some(RawLink {
+        kind: if embed {
+            LinkKind::Embed
+        } else {
+            LinkKind::Wiki
+        },
+        target: target.to_string(),
+        heading: heading.filter(|h| !h.is_empty()),
+        block: block.filter(|b| !b.is_empty()),
+        alias: alias.filter(|a| !a.is_empty()),
+        line,
+    })
+}
+
+/// Task IDs declared in the note (`<!-- tid: ... -->` markers).
+pub fn extract_task_ids(content: &str) -> Vec<String> {
+    TASK_ID_PATTERN
+        .captures_iter(content)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_all_link_forms() {
+        let content = "See [[Project Plan|the plan]] and [[Design#Goals]].\n\
+                       ![[diagram.png]] ![[Spec^intro]]\n\
+                       Task: [[tid:0190a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b]]\n\
+                       Also [notes](Meetings/Weekly%20Sync.md#agenda) and [site](https://x.io/a.md).";
+        let links = extract_links(content);
+
+        assert_eq!(links.len(), 6);
+        assert_eq!(links[0].kind, LinkKind::Wiki);
+        assert_eq!(links[0].target, "Project Plan");
+        assert_eq!(links[0].alias.as_deref(), Some("the plan"));
+        assert_eq!(links[1].target, "Design");
+        assert_eq!(links[1].heading.as_deref(), Some("Goals"));
+        assert_eq!(links[2].kind, LinkKind::Embed);
+        assert_eq!(links[2].target, "diagram.png");
+        assert_eq!(links[3].block.as_deref(), Some("intro"));
+        assert_eq!(links[4].kind, LinkKind::Task);
+        assert_eq!(links[4].target, "0190a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b");
+        assert_eq!(links[4].line, 3);
+        assert_eq!(links[5].kind, LinkKind::Markdown);
+        assert_eq!(links[5].target, "Meetings/Weekly Sync.md");
+    }
+
+    #[test]
+    fn test_ignores_code_and_self_links() {
+        let content = "```\n[[Inside Fence]]\n```\n`[[Inline]]` [[#Local heading]] [[Real]]";
+        let links = extract_links(content);
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target, "Real");
+    }
+
+    #[test]
+    fn test_rewrite_links_keeps_link_decorations() {
+        let content = "[[Old]] [[Old|alias]] ![[Old#Part^b1]] `[[Old]]`\r\n\
+                       ```\n[[Old]]\n```\n[x](Old%20Name.md#top) [[Other]]\n";
+        let rewritten = rewrite_links(content, |link| match link.target.as_str() {
+            "Old" => Some("New Name".to_string()),
+            "Old Name.md" => Some("Dir/New Name.md".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(
+            rewritten,
+            "[[New Name]] [[New Name|alias]] ![[New Name#Part^b1]] `[[Old]]`\r\n\
+             ```\n[[Old]]\n```\n[x](Dir/New%20Name.md#top) [[Other]]\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_task_ids() {
+        let content = "- [ ] One <!-- tid: abc-123 -->\n- [x] Two <!-- tid: def-456 -->";
+        assert_eq!(extract_task_ids(content), vec!["abc-123", "def-456"]);
+    }
+}
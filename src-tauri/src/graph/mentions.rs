@@ -0,0 +1,251 @@
+//! Unlinked mentions
+//!
+//! Finds places where other notes name a note, by title or front matter
+//! alias, in plain text without linking to it, and turns selected mentions
+//! into wikilinks. Front matter, code, existing links, URLs, tags and HTML
+//! comments are never treated as mentions. Matching is case-insensitive and
+//! only on whole words.
+
+use anyhow::{anyhow, bail, Result};
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::index::{normalize_name, strip_md, LinkGraph, NoteNode};
+use super::rename::write_all;
+use crate::identity::frontmatter::FrontMatterParser;
+
+lazy_static! {
+    /// Text that can never become a link: code spans, wikilinks, markdown
+    /// links, URLs, HTML comments and tags
+    static ref PROTECTED_PATTERN: Regex = Regex::new(
+        r"`[^`\n]*`|!?\[\[[^\]\n]*\]\]|!?\[[^\]\n]*\]\([^)\n]*\)|<!--.*?-->|[a-zA-Z][a-zA-Z0-9+.-]*://\S+|#[\p{L}\p{N}_/-]+"
+    )
+    .unwrap();
+}
+
+/// Names shorter than this are too ambiguous to look for
+const MIN_NAME_LENGTH: usize = 2;
+
+/// A plain-text mention of a note in another note
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlinkedMention {
+    /// Note containing the mention
+    pub path: String,
+    /// 1-based line number
+    pub line: usize,
+    /// Byte offset of the mention within the line
+    pub column: usize,
+    /// The mention as written
+    pub text: String,
+    /// The whole line, for showing the mention in context
+    pub context: String,
+}
+
+/// Mentions of `note` in every other note, in path and line order
+pub fn find_unlinked_mentions(graph: &LinkGraph, note: &NoteNode) -> Result<Vec<UnlinkedMention>> {
+    let Some(pattern) = names_pattern(note) else {
+        return Ok(Vec::new());
+    };
+
+    let mut mentions = Vec::new();
+    for source in graph.notes() {
+        if source.path == note.path {
+            continue;
+        }
+        let content = std::fs::read_to_string(graph.vault_root().join(&source.path))
+            .map_err(|e| anyhow!("Failed to read {}: {}", source.path, e))?;
+        mentions.extend(mentions_in(&source.path, &content, &pattern));
+    }
+    Ok(mentions)
+}
+
+/// Turns the given mentions of `note` into `[[links]]`. Mentions whose text
+/// changed since they were found are skipped, and mentions in anything but a
+/// note of the vault are refused. Every note is written or none is. Returns
+/// the number linked.
+pub fn link_mentions(
+    graph: &LinkGraph,
+    note: &NoteNode,
+    mentions: &[UnlinkedMention],
+) -> Result<usize> {
+    let Some(pattern) = names_pattern(note) else {
+        return Ok(0);
+    };
+    let target = link_target(graph, note);
+
+    let notes = graph.notes();
+    let mut by_path: BTreeMap<&str, Vec<&UnlinkedMention>> = BTreeMap::new();
+    for mention in mentions {
+        if !notes.iter().any(|n| n.path == mention.path) {
+            bail!("{} is not a note in this vault", mention.path);
+        }
+        by_path.entry(&mention.path).or_default().push(mention);
+    }
+
+    let mut linked = 0;
+    let mut contents = Vec::new();
+    for (path, selected) in by_path {
+        if path == note.path {
+            continue;
+        }
+        let full_path = graph.vault_root().join(path);
+        let content = std::fs::read_to_string(&full_path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+
+        // Only rewrite mentions that are still there and still unlinked
+        let mut current: Vec<UnlinkedMention> = mentions_in(path, &content, &pattern)
+            .into_iter()
+            .filter(|found| {
+                selected.iter().any(|m| {
+                    m.line == found.line && m.column == found.column && m.text == found.text
+                })
+            })
+            .collect();
+        if current.is_empty() {
+            continue;
+        }
+        current.sort_by_key(|m| std::cmp::Reverse((m.line, m.column)));
+
+        let mut lines: Vec<String> = content.split('\n').map(str::to_string).collect();
+        for mention in &current {
+            let line = &mut lines[mention.line - 1];
+            let link = if mention.text == target {
+                format!("[[{}]]", target)
+            } else {
+                format!("[[{}|{}]]", target, mention.text)
+            };
+            line.replace_range(mention.column..mention.column + mention.text.len(), &link);
+        }
+        contents.push((full_path, lines.join("\n")));
+        linked += current.len();
+    }
+    write_all(graph.vault_root(), &contents)?;
+    Ok(linked)
+}
+
+/// Case-insensitive pattern for the note's title and aliases, longest first
+/// so `Project Plan` wins over an alias `Project`
+fn names_pattern(note: &NoteNode) -> Option<Regex> {
+    let mut names: Vec<String> = std::iter::once(&note.title)
+        .chain(&note.aliases)
+        .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|name| name.chars().count() >= MIN_NAME_LENGTH)
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+    names.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    names.dedup_by(|a, b| normalize_name(a) == normalize_name(b));
+
+    let alternatives: Vec<String> = names
+        .iter()
+        .map(|name| {
+            name.split(' ')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(r"\s+")
+        })
+        .collect();
+    RegexBuilder::new(&alternatives.join("|"))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+fn mentions_in(path: &str, content: &str, pattern: &Regex) -> Vec<UnlinkedMention> {
+    let body_start = match FrontMatterParser::parse(content) {
+        Ok((Some(_), body)) => content.lines().count() - body.lines().count(),
+        _ => 0,
+    };
+
+    let mut mentions = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in content.lines().enumerate().skip(body_start) {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        // Blanking keeps byte offsets aligned with `line`
+        let masked =
+            PROTECTED_PATTERN.replace_all(line, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+        for found in pattern.find_iter(&masked) {
+            let before = masked[..found.start()].chars().next_back();
+            let after = masked[found.end()..].chars().next();
+            if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+                continue;
+            }
+            mentions.push(UnlinkedMention {
+                path: path.to_string(),
+                line: index + 1,
+                column: found.start(),
+                text: line[found.range()].to_string(),
+                context: line.to_string(),
+            });
+        }
+    }
+    mentions
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// How links to the note are written: its title, or its path when another
+/// note shares the title
+fn link_target(graph: &LinkGraph, note: &NoteNode) -> String {
+    if graph.notes_named(&note.title).len() > 1 {
+        strip_md(&note.path).to_string()
+    } else {
+        note.title.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(title: &str, aliases: &[&str]) -> NoteNode {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("path:{}.md", title),
+            "path": format!("{}.md", title),
+            "title": title,
+            "aliases": aliases,
+            "links": [],
+            "taskIds": [],
+            "mtime": 0,
+            "size": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mentions_skip_links_code_and_partial_words() {
+        let pattern = names_pattern(&node("Project Plan", &["PP"])).unwrap();
+        let content = "---\ntitle: Project Plan\n---\n\
+                       The project  plan is due. See [[Project Plan]] or [plan](Project Plan.md).\n\
+                       `Project Plan` #project-plan https://x.com/Project-Plan <!-- Project Plan -->\n\
+                       ```\nProject Plan\n```\n\
+                       pp, ppt, Project Plans and (Project Plan).\n";
+
+        let found: Vec<(usize, usize, String)> = mentions_in("a.md", content, &pattern)
+            .into_iter()
+            .map(|m| (m.line, m.column, m.text))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (4, 4, "project  plan".to_string()),
+                (9, 0, "pp".to_string()),
+                (9, 28, "Project Plan".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Note link graph
+//!
+//! Builds and persists an index of the links between notes: outgoing links,
+//! backlinks, unresolved links, embeds and `[[tid:...]]` task references.
+//! Nodes are keyed by the note's front matter UUID so backlinks survive
+//! renames, and the same index drives rewriting links when a note is renamed
+//! and resolving `![[Note#Heading]]` / `![[Note^block]]` embeds. Wikilinks
+//! resolve by title, alias or path, with fuzzy suggestions for near-misses,
+//! a health report finds broken links, attachments and identifiers, and
+//! plain-text mentions of a note can be turned into links.
+//! The graph is exposed to the frontend through Tauri commands and to plugins
+//! through the `GraphApi`.
+
+pub mod blocks;
+pub mod commands;
+pub mod health;
+pub mod index;
+pub mod links;
+pub mod mentions;
+pub mod rename;
+pub mod resolve;
+
+pub use blocks::{ensure_block_id, resolve_embed, Section};
+pub use commands::*;
+pub use health::{check_vault, HealthFix, HealthIssue, HealthIssueKind, HealthReport};
+pub use index::{GraphNeighbor, GraphStats, LinkEdge, LinkGraph, NoteNode, RefreshStats};
+pub use links::{extract_links, LinkKind, RawLink};
+pub use mentions::{find_unlinked_mentions, link_mentions, UnlinkedMention};
+pub use rename::{apply_rename, plan_rename, RenamePlan};
+pub use resolve::{resolve_reference, suggest, NoteSuggestion};
+
+use std::path::{Path, PathBuf};
+
+/// Location of the persisted graph for a vault
+pub fn snapshot_path(vault_root: &Path) -> PathBuf {
+    vault_root.join(".vault").join("graph.json")
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,329 @@
+//! Rename-aware link rewriting
+//!
+//! When a note moves, links written against its old name or path stop
+//! resolving. `plan_rename` starts from the note's UUID in the link graph,
+//! finds every link that pointed at the old location and computes the
+//! rewritten files without touching disk, so the change can be previewed.
+//! `apply_rename` recomputes the plan, refuses files that changed since the
+//! preview, and stages every file before replacing any of them.
+//!
+//! Renames made outside the app are reported by the identity watcher and
+//! applied straight away through `LinkGraphState::watch_renames`.
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use super::index::{join_relative, strip_md, LinkGraph};
+use super::links::{rewrite_links, LinkKind, RawLink};
+
+/// A line whose links change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineChange {
+    /// 1-based line number
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// Pending changes to one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRewrite {
+    /// Path relative to the vault root, after the rename
+    pub path: String,
+    /// SHA-256 of the content the preview was computed from
+    pub hash: String,
+    pub changes: Vec<LineChange>,
+}
+
+/// Every link rewrite needed after moving a note from `old_path` to `new_path`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePlan {
+    pub note_id: Option<String>,
+    pub old_path: String,
+    pub new_path: String,
+    pub files: Vec<FileRewrite>,
+}
+
+impl RenamePlan {
+    pub fn link_count(&self) -> usize {
+        self.files.iter().map(|f| f.changes.len()).sum()
+    }
+}
+
+/// Computes the link rewrites for a rename. Works both before the file is
+/// moved and after (for renames made outside the app).
+pub fn plan_rename(graph: &LinkGraph, old_path: &str, new_path: &str) -> Result<RenamePlan> {
+    Ok(build_rewrites(graph, old_path, new_path)?.0)
+}
+
+/// Writes the rewrites from a previewed plan. Fails without changing anything
+/// if a file was edited after the preview. Returns the number of files updated.
+pub fn apply_rename(graph: &LinkGraph, preview: &RenamePlan) -> Result<usize> {
+    let (plan, contents) = build_rewrites(graph, &preview.old_path, &preview.new_path)?;
+
+    for file in &plan.files {
+        let previewed = preview.files.iter().find(|f| f.path == file.path);
+        if previewed.is_none_or(|f| f.hash != file.hash) {
+            bail!(
+                "{} changed since the preview; preview the rename again",
+                file.path
+            );
+        }
+    }
+
+    write_all(graph.vault_root(), &contents)?;
+    Ok(contents.len())
+}
+
+fn build_rewrites(
+    graph: &LinkGraph,
+    old_path: &str,
+    new_path: &str,
+) -> Result<(RenamePlan, Vec<(PathBuf, String)>)> {
+    let old_path = clean_path(old_path);
+    let new_path = clean_path(new_path);
+    if old_path.is_empty() || new_path.is_empty() {
+        bail!("Paths cannot be empty");
+    }
+
+    // The renamed note is at the old path before a move and at the new path after one
+    let moved = graph
+        .notes()
+        .into_iter()
+        .find(|n| n.path == old_path)
+        .or_else(|| graph.notes().into_iter().find(|n| n.path == new_path));
+    let note_id = moved.map(|n| n.id.clone());
+    let moved_path = moved.map(|n| n.path.clone());
+
+    let referencing = match moved.filter(|n| n.path == old_path) {
+        Some(note) => graph.backlinks(note),
+        None => graph.links_to_path(&old_path),
+    };
+    let mut sources: BTreeSet<String> = referencing
+        .into_iter()
+        .filter(|edge| edge.link.kind != LinkKind::Task)
+        .map(|edge| edge.source_path)
+        .collect();
+    // Relative links inside the moved note break when it changes folder
+    if let Some(path) = &moved_path {
+        if parent_dir(&old_path) != parent_dir(&new_path) {
+            sources.insert(path.clone());
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut contents = Vec::new();
+    for source in sources {
+        let is_moved = moved_path.as_deref() == Some(source.as_str());
+        let full_path = graph.vault_root().join(&source);
+        let content = std::fs::read_to_string(&full_path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", source, e))?;
+
+        // Links are written relative to where the file was, and resolve relative to where it will be
+        let (written_from, lives_at) = if is_moved {
+            (old_path.as_str(), new_path.as_str())
+        } else {
+            (source.as_str(), source.as_str())
+        };
+        let rewritten = rewrite_links(&content, |link| {
+            new_target(
+                graph,
+                link,
+                written_from,
+                lives_at,
+                is_moved,
+                &old_path,
+                &new_path,
+            )
+        });
+        if rewritten == content {
+            continue;
+        }
+
+        let changes = content
+            .lines()
+            .zip(rewritten.lines())
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(index, (before, after))| LineChange {
+                line: index + 1,
+                before: before.to_string(),
+                after: after.to_string(),
+            })
+            .collect();
+
+        let path = if is_moved { new_path.clone() } else { source };
+        let write_to = if full_path.exists() {
+            full_path
+        } else {
+            graph.vault_root().join(&path)
+        };
+        files.push(FileRewrite {
+            path: path.clone(),
+            hash: hash_content(&content),
+            changes,
+        });
+        contents.push((write_to, rewritten));
+    }
+
+    Ok((
+        RenamePlan {
+            note_id,
+            old_path,
+            new_path,
+            files,
+        },
+        contents,
+    ))
+}
+
+/// Replacement target for a link, or `None` if the link is unaffected
+fn new_target(
+    graph: &LinkGraph,
+    link: &RawLink,
+    written_from: &str,
+    lives_at: &str,
+    is_moved: bool,
+    old_path: &str,
+    new_path: &str,
+) -> Option<String> {
+    match link.kind {
+        LinkKind::Task => None,
+        LinkKind::Wiki | LinkKind::Embed => {
+            if !graph.points_at_path(written_from, link, old_path) {
+                return None;
+            }
+            let keep_extension = link.target.to_lowercase().ends_with(".md");
+            let stem = note_name(new_path);
+            // Fall back to a path when another note already uses the new name
+            let ambiguous = graph
+                .notes_named(&stem)
+                .iter()
+                .any(|p| *p != old_path && *p != new_path);
+            let target = if link.target.contains('/') || ambiguous {
+                strip_md(new_path).to_string()
+            } else {
+                stem
+            };
+            Some(if keep_extension {
+                format!("{}.md", target)
+            } else {
+                target
+            })
+        }
+        LinkKind::Markdown => {
+            let target = join_relative(written_from, &link.target)?;
+            if strip_md(&target).eq_ignore_ascii_case(strip_md(old_path)) {
+                Some(relative_link(lives_at, new_path))
+            } else if is_moved && graph.contains_path(&target) {
+                Some(relative_link(lives_at, &target))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Stages every file next to its destination, then swaps them in. If any
+/// step fails, the staged files are removed and files already swapped in get
+/// their original content back.
+pub(super) fn write_all(vault_root: &Path, contents: &[(PathBuf, String)]) -> Result<()> {
+    let mut staged: Vec<(PathBuf, &PathBuf)> = Vec::new();
+    for (path, content) in contents {
+        if !path.starts_with(vault_root) {
+            remove_staged(&staged);
+            bail!("Refusing to write outside the vault: {:?}", path);
+        }
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("note");
+        let temp = path.with_file_name(format!(".{}.rename-tmp", file_name));
+        if let Err(e) = std::fs::write(&temp, content) {
+            let _ = std::fs::remove_file(&temp);
+            remove_staged(&staged);
+            bail!("Failed to stage {:?}: {}", path, e);
+        }
+        staged.push((temp, path));
+    }
+
+    let mut replaced: Vec<(&PathBuf, Option<Vec<u8>>)> = Vec::new();
+    for (index, (temp, path)) in staged.iter().enumerate() {
+        let original = std::fs::read(path).ok();
+        if let Err(e) = std::fs::rename(temp, path) {
+            remove_staged(&staged[index..]);
+            restore(&replaced);
+            bail!("Failed to write {:?}: {}", path, e);
+        }
+        replaced.push((path, original));
+    }
+    Ok(())
+}
+
+fn remove_staged(staged: &[(PathBuf, &PathBuf)]) {
+    for (temp, _) in staged {
+        let _ = std::fs::remove_file(temp);
+    }
+}
+
+/// Puts back the content files had before `write_all` replaced them
+fn restore(replaced: &[(&PathBuf, Option<Vec<u8>>)]) {
+    for (path, original) in replaced.iter().rev() {
+        let result = match original {
+            Some(content) => std::fs::write(path, content),
+            None => std::fs::remove_file(path),
+        };
+        if let Err(e) = result {
+            eprintln!(
+                "⚠️ Failed to restore {:?} after a failed link update: {}",
+                path, e
+            );
+        }
+    }
+}
+
+/// Relative markdown link from the note at `from` to `to`
+pub(super) fn relative_link(from: &str, to: &str) -> String {
+    let from_dir: Vec<&str> = parent_dir(from)
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    let to_parts: Vec<&str> = to.split('/').filter(|s| !s.is_empty()).collect();
+
+    let common = from_dir
+        .iter()
+        .zip(&to_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts: Vec<&str> = vec![".."; from_dir.len() - common];
+    parts.extend(&to_parts[common..]);
+    parts.join("/")
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn note_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+pub(super) fn clean_path(path: &str) -> String {
+    path.trim()
+        .replace('\\', "/")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+fn hash_content(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
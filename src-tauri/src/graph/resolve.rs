@@ -0,0 +1,114 @@
+//! Wikilink resolution and suggestions
+//!
+//! Exact resolution is done by the index: note titles first, then front
+//! matter aliases, with path-qualified links (`[[folder/Note]]`) matched
+//! against the end of the note's path and ties going to the shortest path.
+//! When a link does not resolve, `suggest` ranks the notes whose title or
+//! alias is closest to it.
+
+use serde::{Deserialize, Serialize};
+
+use super::index::{normalize_name, strip_md, LinkGraph, NoteNode};
+use super::links::parse_wikilink;
+
+/// Suggestions scoring below this are dropped
+pub const MIN_SUGGESTION_SCORE: f32 = 0.5;
+
+/// A note that a link may have meant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSuggestion {
+    pub path: String,
+    pub title: String,
+    /// The title or alias that matched
+    pub matched: String,
+    /// Similarity between 0 and 1, higher is better
+    pub score: f32,
+}
+
+/// Resolves the text of a wikilink (`Note`, `folder/Note`, `Note#Heading|alias`)
+pub fn resolve_reference<'a>(graph: &'a LinkGraph, reference: &str) -> Option<&'a NoteNode> {
+    let link = parse_wikilink(reference, false, 0)?;
+    graph.resolve("", &link)
+}
+
+/// Notes whose title or alias is close to `reference`, best first
+pub fn suggest(graph: &LinkGraph, reference: &str, limit: usize) -> Vec<NoteSuggestion> {
+    let target = parse_wikilink(reference, false, 0)
+        .map(|link| link.target)
+        .unwrap_or_default();
+    let target = strip_md(&target);
+    let query = normalize_name(target.rsplit('/').next().unwrap_or(target));
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut suggestions: Vec<NoteSuggestion> = graph
+        .notes()
+        .into_iter()
+        .filter_map(|note| {
+            std::iter::once(&note.title)
+                .chain(&note.aliases)
+                .map(|name| (name, similarity(&query, &normalize_name(name))))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .filter(|(_, score)| *score >= MIN_SUGGESTION_SCORE)
+                .map(|(name, score)| NoteSuggestion {
+                    path: note.path.clone(),
+                    title: note.title.clone(),
+                    matched: name.clone(),
+                    score,
+                })
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.path.len().cmp(&b.path.len()))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Similarity of two normalized names. Prefixes and substrings score higher
+/// than their edit distance alone would give them.
+fn similarity(query: &str, name: &str) -> f32 {
+    if query == name {
+        return 1.0;
+    }
+    let (short, long) = if query.chars().count() <= name.chars().count() {
+        (query, name)
+    } else {
+        (name, query)
+    };
+    let coverage = short.chars().count() as f32 / long.chars().count().max(1) as f32;
+
+    let edit = 1.0 - edit_distance(query, name) as f32 / long.chars().count().max(1) as f32;
+    // Very short names are a prefix or substring of too many others to count
+    let partial = if short.chars().count() < 3 {
+        0.0
+    } else if long.starts_with(short) {
+        0.7 + 0.25 * coverage
+    } else if long.contains(short) {
+        0.6 + 0.25 * coverage
+    } else {
+        0.0
+    };
+    edit.max(partial)
+}
+
+/// Levenshtein distance in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
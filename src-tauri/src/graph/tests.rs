@@ -0,0 +1,645 @@
+use super::*;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn write(root: &std::path::Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn setup_vault() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+
+    write(
+        root,
+        "Project.md",
+        "---\nid: 11111111-1111-7111-8111-111111111111\n---\n# Project\n\n- [ ] Ship it <!-- tid: task-1 -->\n",
+    );
+    write(
+        root,
+        "Daily/2024-01-01.md",
+        "Worked on [[Project|the project]] and [[Missing Note]].\n![[diagram.png]] ![[Gone.png]]\nSee [[tid:task-1]] and [design](../Design/Spec.md).\n",
+    );
+    write(root, "Design/Spec.md", "Back to [[project#Goals]].\n");
+    write(root, "Loose.md", "No links here.\n");
+    write(root, "assets/diagram.png", "png");
+    write(root, ".vault/ignored.md", "[[Project]]");
+
+    dir
+}
+
+#[test]
+fn test_backlinks_are_keyed_by_uuid() {
+    let dir = setup_vault();
+    let mut graph = LinkGraph::open(dir.path());
+    graph.refresh().unwrap();
+
+    let project = graph
+        .find_note("11111111-1111-7111-8111-111111111111")
+        .unwrap();
+    assert_eq!(project.path, "Project.md");
+
+    let backlinks = graph.backlinks(project);
+    let sources: Vec<&str> = backlinks.iter().map(|e| e.source_path.as_str()).collect();
+    // Wikilink, task link and the lowercase link with a heading
+    assert_eq!(
+        sources,
+        vec![
+            "Daily/2024-01-01.md",
+            "Daily/2024-01-01.md",
+            "Design/Spec.md"
+        ]
+    );
+    assert!(backlinks
+        .iter()
+        .all(|e| e.target_id.as_deref() == Some("11111111-1111-7111-8111-111111111111")));
+
+    let spec = graph.find_note("Design/Spec").unwrap();
+    assert_eq!(spec.id, "path:Design/Spec.md");
+    assert_eq!(graph.backlinks(spec).len(), 1);
+}
+
+#[test]
+fn test_outgoing_embeds_and_unresolved() {
+    let dir = setup_vault();
+    let mut graph = LinkGraph::open(dir.path());
+    graph.refresh().unwrap();
+
+    let daily = graph.find_note("Daily/2024-01-01.md").unwrap();
+    assert_eq!(graph.outgoing(daily).len(), 6);
+
+    let embeds = graph.embeds(daily);
+    assert_eq!(embeds.len(), 2);
+    assert_eq!(embeds[0].target_path.as_deref(), Some("assets/diagram.png"));
+    assert!(embeds[0].target_id.is_none());
+
+    let tasks = graph.task_links(daily);
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].target_path.as_deref(), Some("Project.md"));
+    assert_eq!(graph.task_backlinks("task-1").len(), 1);
+
+    let unresolved: Vec<String> = graph
+        .unresolved()
+        .into_iter()
+        .map(|e| e.link.target)
+        .collect();
+    assert_eq!(unresolved, vec!["Missing Note", "Gone.png"]);
+
+    let stats = graph.stats();
+    assert_eq!(stats.notes, 4);
+    assert_eq!(stats.unresolved_links, 2);
+    // Daily and Loose have no incoming links
+    assert_eq!(stats.orphaned_notes, 2);
+}
+
+#[test]
+fn test_refresh_is_incremental_and_persisted() {
+    let dir = setup_vault();
+    let root = dir.path();
+
+    let mut graph = LinkGraph::open(root);
+    let first = graph.refresh().unwrap();
+    assert_eq!(first.parsed, 4);
+    assert!(snapshot_path(root).exists());
+
+    // A fresh instance picks up the snapshot and has nothing to re-parse
+    let mut reopened = LinkGraph::open(root);
+    let second = reopened.refresh().unwrap();
+    assert_eq!(second.parsed, 0);
+    assert_eq!(second.unchanged, 4);
+
+    write(
+        root,
+        "Missing Note.md",
+        "Now it exists, with a link to [[Loose]].\n",
+    );
+    fs::remove_file(root.join("Design/Spec.md")).unwrap();
+    let third = reopened.refresh().unwrap();
+    assert_eq!(third.parsed, 1);
+    assert_eq!(third.removed, 1);
+
+    let missing = reopened.find_note("missing note").unwrap();
+    assert_eq!(reopened.backlinks(missing).len(), 1);
+    let loose = reopened.find_note("Loose").unwrap();
+    assert_eq!(reopened.backlinks(loose).len(), 1);
+    assert_eq!(reopened.unresolved().len(), 2);
+}
+
+#[test]
+fn test_neighbors_follow_both_directions() {
+    let dir = setup_vault();
+    let mut graph = LinkGraph::open(dir.path());
+    graph.refresh().unwrap();
+
+    let spec = graph.find_note("Design/Spec.md").unwrap();
+    let one_hop: Vec<String> = graph
+        .neighbors(spec, 1)
+        .into_iter()
+        .map(|n| n.path)
+        .collect();
+    assert_eq!(one_hop, vec!["Daily/2024-01-01.md", "Project.md"]);
+
+    let loose = graph.find_note("Loose").unwrap();
+    assert!(graph.neighbors(loose, 3).is_empty());
+}
+
+#[test]
+fn test_rename_preview_and_apply() {
+    let dir = setup_vault();
+    let root = dir.path();
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+
+    let plan = plan_rename(&graph, "Project.md", "Roadmap.md").unwrap();
+    assert_eq!(
+        plan.note_id.as_deref(),
+        Some("11111111-1111-7111-8111-111111111111")
+    );
+    // Task links keep working through the UUID and are left alone
+    assert_eq!(plan.files.len(), 2);
+    assert_eq!(plan.link_count(), 2);
+    assert_eq!(
+        plan.files[0].changes[0].after,
+        "Worked on [[Roadmap|the project]] and [[Missing Note]]."
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("Design/Spec.md")).unwrap(),
+        "Back to [[project#Goals]].\n"
+    );
+
+    fs::rename(root.join("Project.md"), root.join("Roadmap.md")).unwrap();
+    graph.refresh().unwrap();
+    assert_eq!(apply_rename(&graph, &plan).unwrap(), 2);
+
+    assert_eq!(
+        fs::read_to_string(root.join("Design/Spec.md")).unwrap(),
+        "Back to [[Roadmap#Goals]].\n"
+    );
+    graph.refresh().unwrap();
+    let roadmap = graph.find_note("Roadmap").unwrap();
+    assert_eq!(graph.backlinks(roadmap).len(), 3);
+}
+
+#[test]
+fn test_rename_into_folder_fixes_relative_links() {
+    let dir = setup_vault();
+    let root = dir.path();
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+
+    // Moved outside the app: plan from the paths alone
+    fs::create_dir_all(root.join("Archive")).unwrap();
+    fs::rename(
+        root.join("Daily/2024-01-01.md"),
+        root.join("Archive/2024-01-01.md"),
+    )
+    .unwrap();
+    write(root, "Index.md", "[day](Daily/2024-01-01.md)\n");
+    graph.refresh().unwrap();
+
+    let plan = plan_rename(&graph, "Daily/2024-01-01.md", "Archive/2024-01-01.md").unwrap();
+    apply_rename(&graph, &plan).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("Index.md")).unwrap(),
+        "[day](Archive/2024-01-01.md)\n"
+    );
+    let moved = fs::read_to_string(root.join("Archive/2024-01-01.md")).unwrap();
+    assert!(moved.contains("[design](../Design/Spec.md)"));
+}
+
+#[test]
+fn test_stale_rename_preview_is_rejected() {
+    let dir = setup_vault();
+    let root = dir.path();
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+
+    let plan = plan_rename(&graph, "Project.md", "Roadmap.md").unwrap();
+    write(root, "Design/Spec.md", "Edited: [[Project]]\n");
+    graph.refresh().unwrap();
+
+    let err = apply_rename(&graph, &plan).unwrap_err();
+    assert!(err.to_string().contains("Design/Spec.md"));
+    // Nothing was written, including files that did not change
+    assert!(fs::read_to_string(root.join("Daily/2024-01-01.md"))
+        .unwrap()
+        .contains("[[Project|the project]]"));
+}
+
+#[test]
+fn test_resolve_embed_expands_nested_embeds_once() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Status.md",
+        "# Weekly\n![[Meetings/Standup#Decisions]]\n\n# Risks\nSee ![[Status#Weekly]] ![[image.png]]\n",
+    );
+    write(
+        root,
+        "Meetings/Standup.md",
+        "## Decisions\n- Ship on Friday ^ship\n- Embed back: ![[Status#Risks]]\n## Other\nIgnored\n",
+    );
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+
+    assert_eq!(
+        resolve_embed(&graph, "standup^ship").unwrap().unwrap(),
+        "- Ship on Friday"
+    );
+    // Status#Weekly -> Standup#Decisions -> Status#Risks -> Status#Weekly stops the cycle
+    assert_eq!(
+        resolve_embed(&graph, "Status#Weekly").unwrap().unwrap(),
+        "# Weekly\n## Decisions\n- Ship on Friday\n- Embed back: # Risks\nSee ![[Status#Weekly]] ![[image.png]]"
+    );
+    assert!(resolve_embed(&graph, "Status#Missing").unwrap().is_none());
+    assert!(resolve_embed(&graph, "Nowhere").unwrap().is_none());
+}
+
+#[test]
+fn test_resolution_with_aliases_paths_and_suggestions() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Projects/Roadmap.md",
+        "---\naliases: [Plan 2025, The  Plan]\n---\nBody\n",
+    );
+    write(root, "Archive/Projects/Roadmap.md", "Old roadmap\n");
+    write(root, "Plan 2025.md", "A note titled like the alias\n");
+    write(root, "Meeting Notes.md", "---\nalias: Standup\n---\n");
+    write(
+        root,
+        "Index.md",
+        "[[The Plan]] [[archive/projects/Roadmap]] [[Projects/Roadmap]] [[Nowhere/Roadmap]]\n",
+    );
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+
+    let resolve = |reference: &str| resolve_reference(&graph, reference).map(|n| n.path.as_str());
+    // Titles win over aliases, aliases ignore case and spacing
+    assert_eq!(resolve("plan 2025"), Some("Plan 2025.md"));
+    assert_eq!(resolve("the plan#Goals|text"), Some("Projects/Roadmap.md"));
+    assert_eq!(resolve("standup"), Some("Meeting Notes.md"));
+    // Shortest path on ties, partial paths match the end of the path
+    assert_eq!(resolve("Roadmap"), Some("Projects/Roadmap.md"));
+    assert_eq!(
+        resolve("Archive/Projects/Roadmap"),
+        Some("Archive/Projects/Roadmap.md")
+    );
+    assert_eq!(resolve("projects/roadmap.md"), Some("Projects/Roadmap.md"));
+    assert_eq!(resolve("Nowhere/Roadmap"), None);
+
+    let outgoing = graph.outgoing(graph.find_note("Index").unwrap());
+    let targets: Vec<Option<&str>> = outgoing.iter().map(|e| e.target_path.as_deref()).collect();
+    assert_eq!(
+        targets,
+        vec![
+            Some("Projects/Roadmap.md"),
+            Some("Archive/Projects/Roadmap.md"),
+            Some("Projects/Roadmap.md"),
+            None
+        ]
+    );
+
+    let suggestions = suggest(&graph, "Metting Notes", 3);
+    assert_eq!(suggestions[0].path, "Meeting Notes.md");
+    let suggestions = suggest(&graph, "Roadma", 5);
+    assert_eq!(suggestions[0].path, "Projects/Roadmap.md");
+    assert_eq!(suggestions[1].path, "Archive/Projects/Roadmap.md");
+    assert_eq!(suggest(&graph, "Standups", 1)[0].matched, "Standup");
+    assert!(suggest(&graph, "zzz", 5).is_empty());
+}
+
+#[test]
+fn test_non_ascii_link_targets() {
+    assert_eq!(index::strip_md("Señal.md"), "Señal");
+    assert_eq!(index::strip_md("Señal"), "Señal");
+    assert_eq!(index::strip_md("会议v2"), "会议v2");
+    assert_eq!(index::strip_md("会议"), "会议");
+
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "Señal.md", "# Señal\n");
+    write(dir.path(), "Index.md", "[[Señal]] [[会议v2]] [[Café.MD]]\n");
+    let mut graph = LinkGraph::open(dir.path());
+    graph.refresh().unwrap();
+
+    let index = graph.find_note("Index").unwrap();
+    assert_eq!(
+        graph.outgoing(index)[0].target_path.as_deref(),
+        Some("Señal.md")
+    );
+    let unresolved: Vec<String> = graph
+        .unresolved()
+        .into_iter()
+        .map(|e| e.link.target)
+        .collect();
+    assert_eq!(unresolved, vec!["会议v2", "Café.MD"]);
+}
+
+#[test]
+fn test_normalize_name() {
+    assert_eq!(
+        index::normalize_name("  Multiple   Spaces\there "),
+        "multiple spaces here"
+    );
+    assert_eq!(index::normalize_name("UPPERCASE"), "uppercase");
+    assert_eq!(index::normalize_name(""), "");
+}
+
+#[test]
+fn test_vault_health_report_and_fixes() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let id = "22222222-2222-7222-8222-222222222222";
+    write(
+        root,
+        "Project Plan.md",
+        &format!("---\nid: {}\n---\n# Plan\n", id),
+    );
+    write(
+        root,
+        "Copies/Project Plan copy.md",
+        &format!("---\nid: {}\ntags: [x]\n---\nCopied\n", id),
+    );
+    write(
+        root,
+        "Journal.md",
+        "See [[Project Pln]] and [[Nothing Close]].\n\
+         [[tid:gone-task]] [[33333333-3333-7333-8333-333333333333]] [[22222222-2222-7222-8222-222222222222]]\n\
+         ![[Files/used.png]] ![shot](Files/files/pasted%20image.png) ![missing](Files/lost.png)\n\
+         <img src=\"Files/diagram.svg\" width=\"300\"> <a href='Files/report.pdf#page=2'>report</a>\n",
+    );
+    write(root, "Files/used.png", "png");
+    write(root, "Files/diagram.svg", "<svg/>");
+    write(root, "Files/report.pdf", "pdf");
+    write(root, "Files/pasted image.png", "png");
+    write(root, "Files/unused.png", "png");
+    write(root, "Files/.unused.png.meta.json", "{}");
+    write(root, "Elsewhere/.deleted.pdf.meta.json", "{}");
+
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+    let report = check_vault(&graph, "files");
+
+    assert_eq!(report.notes_checked, 3);
+    assert_eq!(report.count(HealthIssueKind::BrokenLink), 2);
+    assert_eq!(report.count(HealthIssueKind::DeletedIdLink), 2);
+    assert_eq!(report.count(HealthIssueKind::BrokenAttachment), 1);
+    assert_eq!(report.count(HealthIssueKind::OrphanedSidecar), 1);
+
+    let orphaned: Vec<&str> = report
+        .issues
+        .iter()
+        .filter(|i| i.kind == HealthIssueKind::OrphanedAttachment)
+        .map(|i| i.path.as_str())
+        .collect();
+    assert_eq!(orphaned, vec!["Files/unused.png"]);
+
+    let duplicate = report
+        .issues
+        .iter()
+        .find(|i| i.kind == HealthIssueKind::DuplicateId)
+        .unwrap();
+    assert_eq!(duplicate.path, "Copies/Project Plan copy.md");
+
+    let typo = report
+        .issues
+        .iter()
+        .find(|i| i.target == "Project Pln")
+        .unwrap();
+    assert_eq!(
+        typo.fix,
+        Some(HealthFix::RewriteLink {
+            path: "Journal.md".to_string(),
+            line: 1,
+            from: "Project Pln".to_string(),
+            to: "Project Plan".to_string(),
+        })
+    );
+
+    for issue in &report.issues {
+        if let Some(fix) = &issue.fix {
+            health::apply_fix(root, fix).unwrap();
+        }
+    }
+    assert!(fs::read_to_string(root.join("Journal.md"))
+        .unwrap()
+        .starts_with("See [[Project Plan]] and [[Nothing Close]]."));
+    assert!(!root.join("Files/unused.png").exists());
+    assert!(!root.join("Files/.unused.png.meta.json").exists());
+    assert!(!root.join("Elsewhere/.deleted.pdf.meta.json").exists());
+    let copy = fs::read_to_string(root.join("Copies/Project Plan copy.md")).unwrap();
+    assert!(!copy.contains(id));
+    assert!(copy.contains("Copied"));
+
+    graph.refresh().unwrap();
+    let report = check_vault(&graph, "Files/");
+    assert_eq!(report.fixable(), 0);
+    assert_eq!(report.issues.len(), 4);
+    assert!(health::apply_fix(
+        root,
+        &HealthFix::DeleteFile {
+            path: "../outside.png".to_string()
+        }
+    )
+    .is_err());
+}
+
+#[test]
+fn test_unlinked_mentions_are_found_and_linked() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Roadmap.md",
+        "---\naliases: [Q3 Plan]\n---\nRoadmap mentions itself.\n",
+    );
+    write(
+        root,
+        "Meeting.md",
+        "Discussed the roadmap and the Q3 plan.\nAlready [[Roadmap]], `Roadmap` and roadmaps.\n",
+    );
+    write(
+        root,
+        "Other.md",
+        "The Roadmap is late.\r\nNothing else.\r\n",
+    );
+
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+    let note = graph.find_note("Roadmap").unwrap().clone();
+
+    let mentions = find_unlinked_mentions(&graph, &note).unwrap();
+    let found: Vec<(&str, usize, &str)> = mentions
+        .iter()
+        .map(|m| (m.path.as_str(), m.line, m.text.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Meeting.md", 1, "roadmap"),
+            ("Meeting.md", 1, "Q3 plan"),
+            ("Other.md", 1, "Roadmap"),
+        ]
+    );
+
+    let selected: Vec<UnlinkedMention> = mentions
+        .iter()
+        .filter(|m| m.path == "Meeting.md")
+        .cloned()
+        .collect();
+    assert_eq!(link_mentions(&graph, &note, &selected).unwrap(), 2);
+    assert_eq!(
+        fs::read_to_string(root.join("Meeting.md")).unwrap(),
+        "Discussed the [[Roadmap|roadmap]] and the [[Roadmap|Q3 plan]].\nAlready [[Roadmap]], `Roadmap` and roadmaps.\n"
+    );
+
+    // Mentions that moved since they were found are left alone
+    write(
+        root,
+        "Other.md",
+        "Edited: The Roadmap is late.\r\nNothing else.\r\n",
+    );
+    assert_eq!(link_mentions(&graph, &note, &mentions).unwrap(), 0);
+
+    graph.refresh().unwrap();
+    let remaining = find_unlinked_mentions(&graph, &note).unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].column, 12);
+    assert_eq!(link_mentions(&graph, &note, &remaining).unwrap(), 1);
+    assert_eq!(
+        fs::read_to_string(root.join("Other.md")).unwrap(),
+        "Edited: The [[Roadmap]] is late.\r\nNothing else.\r\n"
+    );
+}
+
+#[test]
+fn test_failed_write_restores_files_and_removes_temps() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(root, "A.md", "old a\n");
+    // A folder where a note is expected makes the second swap fail
+    write(root, "B.md/keep.txt", "");
+
+    let contents = vec![
+        (root.join("A.md"), "new a\n".to_string()),
+        (root.join("B.md"), "new b\n".to_string()),
+    ];
+    assert!(rename::write_all(root, &contents).is_err());
+
+    assert_eq!(fs::read_to_string(root.join("A.md")).unwrap(), "old a\n");
+    let leftovers: Vec<_> = fs::read_dir(root)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".rename-tmp"))
+        .collect();
+    assert!(leftovers.is_empty());
+}
+
+#[tokio::test]
+async fn test_external_rename_updates_links() {
+    let dir = setup_vault();
+    let root = dir.path();
+    let state = LinkGraphState::new();
+    state
+        .with_graph(root.to_path_buf(), |_| Ok(()))
+        .await
+        .unwrap();
+
+    fs::rename(root.join("Project.md"), root.join("Roadmap.md")).unwrap();
+    state
+        .note_renamed(root, &root.join("Project.md"), &root.join("Roadmap.md"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("Design/Spec.md")).unwrap(),
+        "Back to [[Roadmap#Goals]].\n"
+    );
+}
+
+#[tokio::test]
+async fn test_app_move_without_link_updates_is_left_alone() {
+    let dir = setup_vault();
+    let root = dir.path();
+    let state = LinkGraphState::new();
+
+    state
+        .move_note(root, "Project.md", "Roadmap.md", false, || {
+            fs::rename(root.join("Project.md"), root.join("Roadmap.md")).map_err(|e| e.to_string())
+        })
+        .await
+        .unwrap();
+    state
+        .note_renamed(root, &root.join("Project.md"), &root.join("Roadmap.md"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("Design/Spec.md")).unwrap(),
+        "Back to [[project#Goals]].\n"
+    );
+}
+
+#[test]
+fn test_mentions_outside_the_vault_are_refused() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("vault");
+    write(&root, "Roadmap.md", "# Roadmap\n");
+    write(dir.path(), "outside.md", "The Roadmap is late.\n");
+
+    let mut graph = LinkGraph::open(&root);
+    graph.refresh().unwrap();
+    let note = graph.find_note("Roadmap").unwrap().clone();
+
+    let mention = UnlinkedMention {
+        path: "../outside.md".to_string(),
+        line: 1,
+        column: 4,
+        text: "Roadmap".to_string(),
+        context: "The Roadmap is late.".to_string(),
+    };
+    assert!(link_mentions(&graph, &note, &[mention]).is_err());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("outside.md")).unwrap(),
+        "The Roadmap is late.\n"
+    );
+}
+
+#[test]
+fn test_update_paths_rereads_only_reported_files() {
+    let dir = setup_vault();
+    let root = dir.path();
+    let mut graph = LinkGraph::open(root);
+    graph.refresh().unwrap();
+
+    // Not reported, so not picked up
+    write(root, "Unreported.md", "[[Project]]\n");
+    write(root, "Notes/New.md", "[[Loose]] ![[assets/chart.png]]\n");
+    write(root, "assets/chart.png", "png");
+    fs::remove_dir_all(root.join("Design")).unwrap();
+
+    let changed: HashSet<PathBuf> = [
+        root.join("Notes"),
+        root.join("assets/chart.png"),
+        root.join("Design"),
+        root.join(".vault/graph.json"),
+    ]
+    .into_iter()
+    .collect();
+    let stats = graph.update_paths(&changed).unwrap();
+    assert_eq!((stats.parsed, stats.removed), (1, 1));
+
+    assert!(graph.find_note("Unreported").is_none());
+    assert!(graph.find_note("Spec").is_none());
+    let new = graph.find_note("New").unwrap();
+    assert!(graph.outgoing(new).iter().all(|edge| edge.is_resolved()));
+    let loose = graph.find_note("Loose").unwrap();
+    assert_eq!(graph.backlinks(loose).len(), 1);
+}
@@ -10,7 +10,7 @@ use std::path::Path;
 use tempfile::NamedTempFile;
 use yaml_rust::{Yaml, YamlLoader};
 
-pub use tasks::{Priority, TaskFrontMatter, TaskProperties, TaskStatus};
+pub use tasks::{Priority, TaskFrontMatter, TaskProperties, TaskStatus, TimeEntry};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FrontMatter {
@@ -257,9 +257,16 @@ impl FrontMatterWriter {
             serde_json::Value::Bool(b) => b.to_string(),
             serde_json::Value::Number(n) => n.to_string(),
             serde_json::Value::String(s) => {
-                // Quote strings that contain special characters
-                if s.contains(':') || s.contains('\n') || s.starts_with(' ') {
-                    format!("\"{}\"", s.replace('"', "\\\""))
+                // Quote strings that contain special characters, or that
+                // would read back as something other than a string
+                if Self::needs_quotes(s) {
+                    let escaped = s
+                        .replace('\\', "\\\\")
+                        .replace('"', "\\\"")
+                        .replace('\n', "\\n")
+                        .replace('\r', "\\r")
+                        .replace('\t', "\\t");
+                    format!("\"{}\"", escaped)
                 } else {
                     s.clone()
                 }
@@ -292,6 +299,16 @@ impl FrontMatterWriter {
         }
     }
 
+    fn needs_quotes(s: &str) -> bool {
+        s.is_empty()
+            || s.contains(':')
+            || s.contains(" #")
+            || s.contains(|c: char| c.is_control())
+            || s.starts_with(|c: char| c.is_whitespace() || "-?,[]{}#&*!|>'\"%@`".contains(c))
+            || s.ends_with(char::is_whitespace)
+            || !matches!(Yaml::from_str(s), Yaml::String(_))
+    }
+
     pub fn write_atomic(path: &Path, front_matter: &FrontMatter, content: &str) -> Result<()> {
         // Read existing content if file exists
         let full_content = if path.exists() {
@@ -218,6 +218,71 @@ mod tests {
         assert!(merged.contains_key("task-3"));
     }
 
+    #[test]
+    fn test_task_time_log() {
+        let start = Utc.with_ymd_and_hms(2025, 6, 11, 9, 0, 0).unwrap();
+        let mut props = TaskProperties::new("Write report".to_string());
+        assert!(props.start_timer(start));
+        assert!(!props.start_timer(start + chrono::Duration::minutes(5)));
+        assert!(props.running_timer().is_some());
+
+        let entry = props
+            .stop_timer(start + chrono::Duration::seconds(50 * 60 + 40))
+            .unwrap();
+        assert_eq!(entry.minutes_at(Utc::now()), 51);
+        assert!(props.running_timer().is_none());
+        assert!(props.stop_timer(Utc::now()).is_none());
+        props.start_timer(start + chrono::Duration::hours(2));
+
+        // Both entries survive a round trip through front matter
+        let mut fm = FrontMatter::with_id("note-123".to_string());
+        TaskFrontMatter::upsert_task(&mut fm.extra_fields, "task-1".to_string(), props);
+        let yaml = FrontMatterWriter::write(&fm, "").unwrap();
+        let (parsed, _) = FrontMatterParser::parse(&yaml).unwrap();
+        let task = TaskFrontMatter::get_task(&parsed.unwrap().extra_fields, "task-1").unwrap();
+        assert_eq!(task.time_log.len(), 2);
+        assert_eq!(task.time_log[0], entry);
+        assert_eq!(
+            task.running_timer().unwrap().start,
+            start + chrono::Duration::hours(2)
+        );
+
+        // A timer stopped in one copy and left running in the other is stopped
+        let mut stopped = task.clone();
+        stopped.stop_timer(start + chrono::Duration::hours(3));
+        let original = HashMap::from([("task-1".to_string(), task.clone())]);
+        let update1 = HashMap::from([("task-1".to_string(), task)]);
+        let update2 = HashMap::from([("task-1".to_string(), stopped)]);
+        let merged = merge_task_updates(&original, &update1, &update2).unwrap();
+        let merged = &merged["task-1"];
+        assert_eq!(merged.time_log.len(), 2);
+        assert!(merged.running_timer().is_none());
+    }
+
+    #[test]
+    fn test_task_archived_at() {
+        let archived_at = Utc.with_ymd_and_hms(2025, 7, 1, 6, 0, 0).unwrap();
+        let mut props = TaskProperties::new("Old chore".to_string());
+        props.mark_done();
+        props.archived_at = Some(archived_at);
+
+        let mut fm = FrontMatter::with_id("note-123".to_string());
+        TaskFrontMatter::upsert_task(&mut fm.extra_fields, "task-1".to_string(), props);
+        let yaml = FrontMatterWriter::write(&fm, "").unwrap();
+        let (parsed, _) = FrontMatterParser::parse(&yaml).unwrap();
+        let task = TaskFrontMatter::get_task(&parsed.unwrap().extra_fields, "task-1").unwrap();
+        assert_eq!(task.archived_at, Some(archived_at));
+
+        // Archiving in either copy wins over a copy that did not archive
+        let mut unarchived = task.clone();
+        unarchived.archived_at = None;
+        let original = HashMap::from([("task-1".to_string(), unarchived.clone())]);
+        let update1 = HashMap::from([("task-1".to_string(), unarchived)]);
+        let update2 = HashMap::from([("task-1".to_string(), task)]);
+        let merged = merge_task_updates(&original, &update1, &update2).unwrap();
+        assert_eq!(merged["task-1"].archived_at, Some(archived_at));
+    }
+
     // Test 3.6: Field ordering preservation
     #[test]
     fn test_frontmatter_field_ordering() {
@@ -1,6 +1,6 @@
 use super::{FrontMatter, FrontMatterParser, FrontMatterWriter};
 use anyhow::Result;
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Local, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
@@ -17,13 +17,45 @@ pub struct TaskProperties {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Start/stop log of the task's timer, oldest first
+    #[serde(default)]
+    pub time_log: Vec<TimeEntry>,
+    /// When the task was moved out of the way of open tasks
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+/// One stretch of time logged on a task; `end` is empty while the timer runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeEntry {
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    /// Minutes logged, to the nearest minute, counting a running entry up
+    /// to `now`
+    pub fn minutes_at(&self, now: DateTime<Utc>) -> u64 {
+        let seconds = (self.end.unwrap_or(now) - self.start).num_seconds().max(0);
+        (seconds as u64 + 30) / 60
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
     Todo,
     Done,
+    InProgress,
+    Cancelled,
+    Blocked,
+    Deferred,
+}
+
+impl TaskStatus {
+    pub fn as_str(self) -> &'static str {
+        crate::identity::tasks::TaskStatus::from(self).as_str()
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -47,6 +79,8 @@ impl TaskProperties {
             created_at: now,
             updated_at: now,
             completed_at: None,
+            time_log: Vec::new(),
+            archived_at: None,
         }
     }
 
@@ -61,6 +95,50 @@ impl TaskProperties {
         self.completed_at = None;
         self.updated_at = Utc::now();
     }
+
+    /// Change the status; closed (done or cancelled) tasks keep a completion date
+    pub fn set_status(&mut self, status: TaskStatus) {
+        match status {
+            TaskStatus::Done => self.mark_done(),
+            TaskStatus::Cancelled => {
+                self.status = status;
+                self.completed_at = Some(Utc::now());
+                self.updated_at = Utc::now();
+            }
+            _ => {
+                self.status = status;
+                self.completed_at = None;
+                self.updated_at = Utc::now();
+            }
+        }
+    }
+
+    /// The entry of the running timer, if there is one
+    pub fn running_timer(&self) -> Option<&TimeEntry> {
+        self.time_log.iter().find(|entry| entry.end.is_none())
+    }
+
+    /// Start the timer at `at`; false when it is already running
+    pub fn start_timer(&mut self, at: DateTime<Utc>) -> bool {
+        if self.running_timer().is_some() {
+            return false;
+        }
+        self.time_log.push(TimeEntry {
+            start: at,
+            end: None,
+        });
+        self.updated_at = Utc::now();
+        true
+    }
+
+    /// Stop the running timer at `at`, returning the entry it closed
+    pub fn stop_timer(&mut self, at: DateTime<Utc>) -> Option<TimeEntry> {
+        let entry = self.time_log.iter_mut().find(|entry| entry.end.is_none())?;
+        entry.end = Some(at.max(entry.start));
+        let entry = entry.clone();
+        self.updated_at = Utc::now();
+        Some(entry)
+    }
 }
 
 /// Task-related extensions for FrontMatter operations
@@ -92,10 +170,8 @@ impl TaskFrontMatter {
         let status = obj
             .get("status")
             .and_then(|v| v.as_str())
-            .map(|s| match s {
-                "done" => TaskStatus::Done,
-                _ => TaskStatus::Todo,
-            })
+            .and_then(crate::identity::tasks::TaskStatus::parse)
+            .map(TaskStatus::from)
             .unwrap_or(TaskStatus::Todo);
 
         let text = obj
@@ -145,6 +221,35 @@ impl TaskFrontMatter {
             .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
             .map(|dt| dt.with_timezone(&Utc));
 
+        let time_log = obj
+            .get("time_log")
+            .and_then(|v| v.as_array())
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|entry| {
+                        let time = |key: &str| {
+                            entry
+                                .get(key)
+                                .and_then(|v| v.as_str())
+                                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                                .map(|dt| dt.with_timezone(&Utc))
+                        };
+                        Some(TimeEntry {
+                            start: time("start")?,
+                            end: time("end"),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let archived_at = obj
+            .get("archived_at")
+            .and_then(|v| v.as_str())
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
         Ok(TaskProperties {
             status,
             text,
@@ -155,6 +260,8 @@ impl TaskFrontMatter {
             created_at,
             updated_at,
             completed_at,
+            time_log,
+            archived_at,
         })
     }
 
@@ -164,13 +271,7 @@ impl TaskFrontMatter {
 
         obj.insert(
             "status".to_string(),
-            serde_json::Value::String(
-                match task.status {
-                    TaskStatus::Todo => "todo",
-                    TaskStatus::Done => "done",
-                }
-                .to_string(),
-            ),
+            serde_json::Value::String(task.status.as_str().to_string()),
         );
 
         obj.insert(
@@ -230,6 +331,35 @@ impl TaskFrontMatter {
             );
         }
 
+        if let Some(ref archived) = task.archived_at {
+            obj.insert(
+                "archived_at".to_string(),
+                serde_json::Value::String(archived.to_rfc3339()),
+            );
+        }
+
+        if !task.time_log.is_empty() {
+            let entries: Vec<serde_json::Value> = task
+                .time_log
+                .iter()
+                .map(|entry| {
+                    let mut entry_obj = serde_json::Map::new();
+                    entry_obj.insert(
+                        "start".to_string(),
+                        serde_json::Value::String(entry.start.to_rfc3339()),
+                    );
+                    if let Some(ref end) = entry.end {
+                        entry_obj.insert(
+                            "end".to_string(),
+                            serde_json::Value::String(end.to_rfc3339()),
+                        );
+                    }
+                    serde_json::Value::Object(entry_obj)
+                })
+                .collect();
+            obj.insert("time_log".to_string(), serde_json::Value::Array(entries));
+        }
+
         serde_json::Value::Object(obj)
     }
 
@@ -423,6 +553,19 @@ pub fn normalize_due_date(input: &str) -> Result<DateTime<Utc>> {
         return Ok(Utc.from_utc_datetime(&datetime));
     }
 
+    // Natural language (`next friday`, `tomorrow 3pm`) in local time. Dates
+    // without a time keep the midnight UTC form used for plain dates.
+    if let Some(date) = crate::identity::tasks::TaskDate::parse(input, &Local::now()) {
+        return match date.time {
+            Some(time) => Local
+                .from_local_datetime(&date.date.and_time(time))
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok_or_else(|| anyhow::anyhow!("Invalid local time: {}", input)),
+            None => Ok(Utc.from_utc_datetime(&date.date.and_time(chrono::NaiveTime::MIN))),
+        };
+    }
+
     anyhow::bail!("Unable to parse date: {}", input)
 }
 
@@ -439,7 +582,7 @@ pub fn validate_task_properties(props: &TaskProperties) -> Result<()> {
     }
 
     // If not completed, should not have completed_at
-    if props.status == TaskStatus::Todo && props.completed_at.is_some() {
+    if props.status != TaskStatus::Done && props.completed_at.is_some() {
         anyhow::bail!("Uncompleted tasks should not have a completion date");
     }
 
@@ -502,13 +645,20 @@ fn merge_single_task(
 ) -> Result<TaskProperties> {
     let mut merged = original.clone();
 
-    // Status - if both changed, prefer completed
+    // Status - if both changed, prefer completed, then the latest change
     if update1.status != original.status || update2.status != original.status {
         if update1.status == TaskStatus::Done || update2.status == TaskStatus::Done {
             merged.status = TaskStatus::Done;
             merged.completed_at = update1.completed_at.or(update2.completed_at);
         } else {
-            merged.status = TaskStatus::Todo;
+            let update1_wins = update2.status == original.status
+                || (update1.status != original.status && update1.updated_at >= update2.updated_at);
+            merged.status = if update1_wins {
+                update1.status
+            } else {
+                update2.status
+            };
+            merged.completed_at = None;
         }
     }
 
@@ -561,6 +711,28 @@ fn merge_single_task(
         merged.project = update2.project.clone();
     }
 
+    // Archived - stays archived if either side archived it
+    merged.archived_at = update1.archived_at.or(update2.archived_at);
+
+    // Time log - keep the entries of both, a stopped entry replacing the
+    // running one it was started as
+    let mut time_log: Vec<TimeEntry> = Vec::new();
+    for entry in update1.time_log.iter().chain(&update2.time_log) {
+        match time_log
+            .iter_mut()
+            .find(|logged| logged.start == entry.start)
+        {
+            Some(logged) => {
+                if logged.end.is_none() {
+                    logged.end = entry.end;
+                }
+            }
+            None => time_log.push(entry.clone()),
+        }
+    }
+    time_log.sort_by_key(|entry| entry.start);
+    merged.time_log = time_log;
+
     // Update timestamp
     merged.updated_at = std::cmp::max(update1.updated_at, update2.updated_at);
 
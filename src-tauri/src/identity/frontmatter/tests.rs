@@ -148,6 +148,42 @@ fn test_write_preserves_extra_fields() {
     assert!(result.contains("version: 1"));
 }
 
+#[test]
+fn test_write_quotes_strings_that_read_back_differently() {
+    let mut fm = FrontMatter::new();
+    let strings = [
+        "yes",
+        "true",
+        "null",
+        "~",
+        "42",
+        "1.5",
+        "#x",
+        "[a",
+        "{b}",
+        "- item",
+        "a #b",
+        "Q3: plan",
+        "",
+        " padded",
+        "line\nbreak",
+        "back\\slash \"quoted\"",
+        "plain text",
+    ];
+    for (i, text) in strings.iter().enumerate() {
+        fm.extra_fields
+            .insert(format!("field{:02}", i), serde_json::json!(text));
+    }
+    fm.extra_fields
+        .insert("list".into(), serde_json::json!(["#tag", "no", "ok"]));
+
+    let written = FrontMatterWriter::write(&fm, "Body").unwrap();
+    assert!(written.contains("field16: plain text\n"));
+    let (parsed, body) = FrontMatterParser::parse(&written).unwrap();
+    assert_eq!(parsed.unwrap().extra_fields, fm.extra_fields);
+    assert_eq!(body, "Body");
+}
+
 #[test]
 fn test_roundtrip_preservation() {
     let original = r#"---
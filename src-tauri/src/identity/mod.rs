@@ -25,8 +25,8 @@ use crate::identity::frontmatter::{FrontMatter, FrontMatterParser, FrontMatterWr
 use crate::identity::sidecar::{SidecarData, SidecarManager};
 use crate::identity::tasks::{ParsedTask, TaskIdentity, TaskParser, TaskStatus};
 use crate::identity::uuid::UuidGenerator;
-use crate::tasks::{TaskIndex, TaskRecord};
-use chrono::NaiveDate;
+use crate::tasks::{FileFingerprint, TaskIndex, TaskIndexSnapshot, TaskRecord, WarmStartStats};
+use chrono::{NaiveDate, NaiveTime};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -288,68 +288,7 @@ impl IdentityManager {
             );
             anyhow::anyhow!("Failed to read file at {:?}: {}", file_path, e)
         })?;
-        let tasks = TaskParser::extract_all_tasks(&content);
-
-        // Parse front matter for task metadata
-        let (front_matter, _) = FrontMatterParser::parse(&content)?;
-        let fm_tasks = if let Some(fm) = front_matter {
-            crate::identity::frontmatter::TaskFrontMatter::extract_tasks(&fm.extra_fields)
-                .unwrap_or_default()
-        } else {
-            HashMap::new()
-        };
-
-        // Convert to TaskRecords and update index
-        let task_records: Vec<TaskRecord> = tasks
-            .into_iter()
-            .filter_map(|task| {
-                let task_id = task.id?;
-                let fm_props = fm_tasks.get(&task_id);
-
-                Some(TaskRecord {
-                    id: task_id.clone(),
-                    file_path: file_path.to_path_buf(),
-                    line_number: task.line_number,
-                    status: task.status,
-                    text: task.content.clone(),
-                    project: task
-                        .properties
-                        .get("project")
-                        .cloned()
-                        .or_else(|| fm_props.and_then(|p| p.project.clone())),
-                    due_date: task
-                        .properties
-                        .get("due")
-                        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
-                        .or_else(|| fm_props.and_then(|p| p.due.map(|dt| dt.date_naive()))),
-                    priority: task
-                        .properties
-                        .get("priority")
-                        .and_then(|p| match p.as_str() {
-                            "high" => Some(Priority::High),
-                            "medium" => Some(Priority::Medium),
-                            "low" => Some(Priority::Low),
-                            _ => None,
-                        })
-                        .or_else(|| fm_props.and_then(|p| p.priority)),
-                    tags: task
-                        .properties
-                        .get("tags")
-                        .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
-                        .or_else(|| fm_props.and_then(|p| p.tags.clone())),
-                    created_at: fm_props.map(|p| p.created_at).unwrap_or_else(Utc::now),
-                    updated_at: fm_props.map(|p| p.updated_at).unwrap_or_else(Utc::now),
-                    completed_at: if task.status == TaskStatus::Done {
-                        fm_props
-                            .and_then(|p| p.completed_at)
-                            .or_else(|| Some(Utc::now()))
-                    } else {
-                        None
-                    },
-                    properties: task.properties,
-                })
-            })
-            .collect();
+        let task_records = Self::task_records_from_content(file_path, &content)?;
 
         // Update the index with all tasks from this file - now async safe
         let task_index = self.task_index();
@@ -364,10 +303,24 @@ impl IdentityManager {
     pub fn sync_file_tasks_to_index(&self, file_path: &Path) -> Result<()> {
         // Read and parse all tasks from the file
         let content = std::fs::read_to_string(file_path)?;
-        let tasks = TaskParser::extract_all_tasks(&content);
+        let task_records = Self::task_records_from_content(file_path, &content)?;
+
+        // Update the index with all tasks from this file
+        let task_index = self.task_index();
+        let runtime = tokio::runtime::Handle::try_current()
+            .or_else(|_| tokio::runtime::Runtime::new().map(|rt| rt.handle().clone()))?;
+
+        runtime.block_on(async { task_index.update_file_tasks(file_path, task_records).await })?;
+
+        Ok(())
+    }
+
+    /// Parse the tasks in a file's content into index records
+    fn task_records_from_content(file_path: &Path, content: &str) -> Result<Vec<TaskRecord>> {
+        let tasks = TaskParser::extract_all_tasks(content);
 
         // Parse front matter for task metadata
-        let (front_matter, _) = FrontMatterParser::parse(&content)?;
+        let (front_matter, _) = FrontMatterParser::parse(content)?;
         let fm_tasks = if let Some(fm) = front_matter {
             crate::identity::frontmatter::TaskFrontMatter::extract_tasks(&fm.extra_fields)
                 .unwrap_or_default()
@@ -375,8 +328,21 @@ impl IdentityManager {
             HashMap::new()
         };
 
+        // Tasks checked off outside the app have no completion time in front
+        // matter; they were completed before the note was last modified
+        let modified_at: DateTime<Utc> = std::fs::metadata(file_path)
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        // Nested tasks point at their parent's line; resolve it to the ID
+        let ids_by_line: HashMap<usize, String> = tasks
+            .iter()
+            .filter_map(|task| Some((task.line_number, task.id.clone()?)))
+            .collect();
+
         // Convert to TaskRecords and update index
-        let task_records: Vec<TaskRecord> = tasks
+        let mut task_records: Vec<TaskRecord> = tasks
             .into_iter()
             .filter_map(|task| {
                 let task_id = task.id?;
@@ -398,6 +364,11 @@ impl IdentityManager {
                         .get("due")
                         .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
                         .or_else(|| fm_props.and_then(|p| p.due.map(|dt| dt.date_naive()))),
+                    due_time: property_time(&task.properties, "due_time"),
+                    scheduled_date: property_date(&task.properties, "scheduled"),
+                    scheduled_time: property_time(&task.properties, "scheduled_time"),
+                    start_date: property_date(&task.properties, "start"),
+                    start_time: property_time(&task.properties, "start_time"),
                     priority: task
                         .properties
                         .get("priority")
@@ -415,26 +386,71 @@ impl IdentityManager {
                         .or_else(|| fm_props.and_then(|p| p.tags.clone())),
                     created_at: fm_props.map(|p| p.created_at).unwrap_or_else(Utc::now),
                     updated_at: fm_props.map(|p| p.updated_at).unwrap_or_else(Utc::now),
-                    completed_at: if task.status == TaskStatus::Done {
+                    completed_at: if task.status.is_closed() {
                         fm_props
                             .and_then(|p| p.completed_at)
-                            .or_else(|| Some(Utc::now()))
+                            .or(Some(modified_at))
                     } else {
                         None
                     },
+                    parent_id: task
+                        .parent_line
+                        .and_then(|line| ids_by_line.get(&line).cloned()),
+                    blocked_by: task
+                        .properties
+                        .get("blocked_by")
+                        .map(|ids| ids.split(',').map(str::to_string).collect())
+                        .unwrap_or_default(),
+                    estimate_minutes: task
+                        .properties
+                        .get("estimate")
+                        .and_then(|minutes| minutes.parse().ok()),
+                    time_log: fm_props.map(|p| p.time_log.clone()).unwrap_or_default(),
+                    archived: fm_props.is_some_and(|p| p.archived_at.is_some()),
                     properties: task.properties,
                 })
             })
             .collect();
 
-        // Update the index with all tasks from this file
-        let task_index = self.task_index();
-        let runtime = tokio::runtime::Handle::try_current()
-            .or_else(|_| tokio::runtime::Runtime::new().map(|rt| rt.handle().clone()))?;
-
-        runtime.block_on(async { task_index.update_file_tasks(file_path, task_records).await })?;
+        // Tasks archived into front matter only have a `[[tid:...]]` line
+        // left in the body
+        for (task_id, props) in fm_tasks {
+            if props.archived_at.is_none() || task_records.iter().any(|t| t.id == task_id) {
+                continue;
+            }
+            let reference = format!("[[tid:{}]]", task_id);
+            let line_number = content
+                .lines()
+                .position(|line| line.contains(&reference))
+                .map_or(0, |index| index + 1);
+            task_records.push(TaskRecord {
+                id: task_id,
+                file_path: file_path.to_path_buf(),
+                line_number,
+                status: props.status.into(),
+                text: props.text,
+                project: props.project,
+                due_date: props.due.map(|dt| dt.date_naive()),
+                due_time: None,
+                scheduled_date: None,
+                scheduled_time: None,
+                start_date: None,
+                start_time: None,
+                priority: props.priority,
+                tags: props.tags,
+                created_at: props.created_at,
+                updated_at: props.updated_at,
+                completed_at: props.completed_at,
+                properties: HashMap::new(),
+                parent_id: None,
+                blocked_by: Vec::new(),
+                estimate_minutes: None,
+                time_log: props.time_log,
+                archived: true,
+            });
+        }
 
-        Ok(())
+        Ok(task_records)
     }
 
     fn canonicalize_path(&self, path: &Path) -> Result<PathBuf> {
@@ -574,6 +590,141 @@ impl IdentityManager {
         );
         Ok(())
     }
+
+    /// Populate the task index from the snapshot at `snapshot_path`, re-parsing
+    /// only files that changed since it was saved, then save a fresh snapshot.
+    /// Falls back to parsing every file when there is no usable snapshot.
+    pub async fn warm_start_task_index(&self, snapshot_path: &Path) -> Result<WarmStartStats> {
+        use walkdir::WalkDir;
+
+        let mut stats = WarmStartStats::default();
+        if !self.vault_root.exists() {
+            println!(
+                "    📁 Vault root does not exist yet: {:?}",
+                self.vault_root
+            );
+            return Ok(stats);
+        }
+
+        let task_index = self.task_index();
+        let previous = match TaskIndexSnapshot::load(snapshot_path, &self.vault_root) {
+            Ok(Some(snapshot)) => {
+                snapshot.restore(&task_index).await?;
+                stats.restored = true;
+                println!(
+                    "    💾 Restored task index snapshot: {} tasks from {} files",
+                    task_index.size().await,
+                    snapshot.files.len()
+                );
+                snapshot.files
+            }
+            Ok(None) => HashMap::new(),
+            Err(e) => {
+                eprintln!("    ⚠️ Ignoring unusable task index snapshot: {}", e);
+                HashMap::new()
+            }
+        };
+
+        let mut files = HashMap::new();
+        for entry in WalkDir::new(&self.vault_root)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("md") {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            // Untouched since the snapshot: keep its tasks without reading it
+            if let Some(known) = previous.get(path).filter(|f| f.matches_metadata(&metadata)) {
+                files.insert(path.to_path_buf(), known.clone());
+                stats.unchanged += 1;
+                continue;
+            }
+
+            // Metadata is read before the content, so a write in between is
+            // picked up again on the next start
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let fingerprint = FileFingerprint::new(&metadata, &content);
+            if previous
+                .get(path)
+                .is_some_and(|f| f.hash == fingerprint.hash)
+            {
+                stats.unchanged += 1;
+            } else {
+                match Self::task_records_from_content(path, &content) {
+                    Ok(records) => task_index.update_file_tasks(path, records).await?,
+                    Err(e) => {
+                        eprintln!("    ⚠️ Failed to parse tasks in {:?}: {}", path, e);
+                        continue;
+                    }
+                }
+                stats.reparsed += 1;
+            }
+            files.insert(path.to_path_buf(), fingerprint);
+        }
+
+        // Files that were deleted or can no longer be read
+        for path in previous.keys().filter(|path| !files.contains_key(*path)) {
+            task_index.remove_file_tasks(path).await?;
+            stats.removed += 1;
+        }
+
+        let snapshot = TaskIndexSnapshot::capture(&self.vault_root, files, &task_index).await?;
+        if let Err(e) = snapshot.save(snapshot_path) {
+            eprintln!("    ⚠️ Failed to save task index snapshot: {}", e);
+        }
+
+        println!(
+            "    ✅ Task index ready: {} unchanged, {} re-parsed, {} removed",
+            stats.unchanged, stats.reparsed, stats.removed
+        );
+        Ok(stats)
+    }
+
+    /// Check the task index for consistency without blocking the caller. An
+    /// inconsistent index is cleared and rebuilt from the vault, replacing the
+    /// snapshot it came from.
+    pub fn verify_task_index_in_background(&self, snapshot_path: PathBuf) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let task_index = manager.task_index();
+            let Err(e) = task_index.verify_consistency().await else {
+                println!("    ✅ Task index consistency verified");
+                return;
+            };
+
+            eprintln!("    ⚠️ {}; rebuilding task index", e);
+            if let Err(e) = std::fs::remove_file(&snapshot_path) {
+                eprintln!("    ⚠️ Failed to remove task index snapshot: {}", e);
+            }
+            task_index.clear().await;
+            if let Err(e) = manager.warm_start_task_index(&snapshot_path).await {
+                eprintln!("    ⚠️ Failed to rebuild task index: {}", e);
+            }
+        });
+    }
+}
+
+/// An ISO date property written by the task parser
+fn property_date(properties: &HashMap<String, String>, key: &str) -> Option<NaiveDate> {
+    properties
+        .get(key)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+/// A time of day property written by the task parser
+fn property_time(properties: &HashMap<String, String>, key: &str) -> Option<NaiveTime> {
+    let value = properties.get(key)?;
+    NaiveTime::parse_from_str(value, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .ok()
 }
 
 #[cfg(test)]
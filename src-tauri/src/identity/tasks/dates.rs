@@ -0,0 +1,317 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use chrono_english::{parse_date_string, Dialect};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::ops::Range;
+
+lazy_static! {
+    // `@due(...)`, `@due ...`, `@due:...`, `due: ...` and `due:: ...`, and
+    // the same for scheduled and start
+    static ref DATE_MARKER_PATTERN: Regex = Regex::new(
+        r"(?i)(?:^|\s)(@?)(due|scheduled|start)(\(|::?\s*|\s+)"
+    ).unwrap();
+}
+
+/// Longest run of words tried as a natural language date
+const MAX_DATE_WORDS: usize = 5;
+
+/// Most digits a number in a natural language date may have; chrono-english
+/// panics or overflows on amounts like `4000000000 days`
+const MAX_AMOUNT_DIGITS: usize = 4;
+
+/// The dates a task can carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateKind {
+    /// When the task has to be done
+    Due,
+    /// When the task is planned to be worked on
+    Scheduled,
+    /// The task is not actionable before this date
+    Start,
+}
+
+impl DateKind {
+    pub const ALL: [DateKind; 3] = [DateKind::Due, DateKind::Scheduled, DateKind::Start];
+
+    /// Name used in task lines and as the property key
+    pub fn key(self) -> &'static str {
+        match self {
+            DateKind::Due => "due",
+            DateKind::Scheduled => "scheduled",
+            DateKind::Start => "start",
+        }
+    }
+
+    /// Property key for the time of day
+    pub fn time_key(self) -> &'static str {
+        match self {
+            DateKind::Due => "due_time",
+            DateKind::Scheduled => "scheduled_time",
+            DateKind::Start => "start_time",
+        }
+    }
+}
+
+/// A date with an optional time of day, in the user's local time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskDate {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+}
+
+impl TaskDate {
+    /// Parse an ISO date (`2025-06-13`, `2025-06-13 15:00`) or a natural
+    /// language one (`next friday 3pm`, `tomorrow`, `monday 9am`) relative to
+    /// `now`, whose timezone relative dates are resolved in
+    pub fn parse<Tz: TimeZone>(text: &str, now: &DateTime<Tz>) -> Option<Self>
+    where
+        Tz::Offset: Copy,
+    {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+            return Some(Self { date, time: None });
+        }
+        for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"] {
+            if let Ok(at) = NaiveDateTime::parse_from_str(text, format) {
+                return Some(Self {
+                    date: at.date(),
+                    time: Some(at.time()),
+                });
+            }
+        }
+
+        let today = now.date_naive();
+        let lower = text.to_lowercase();
+        let offset = match lower.as_str() {
+            "today" => Some(0),
+            "tomorrow" => Some(1),
+            "yesterday" => Some(-1),
+            _ => None,
+        };
+        if let Some(days) = offset {
+            return Some(Self {
+                date: today + chrono::Duration::days(days),
+                time: None,
+            });
+        }
+
+        if !is_natural_date_text(&lower) {
+            return None;
+        }
+        let parsed = parse_date_string(&lower, *now, Dialect::Uk).ok()?;
+
+        // Dates without a time come back at midnight, and relative offsets
+        // keep the time of `now`, so a time that is the same from two
+        // different bases was written out
+        let at = |h, m, s| {
+            let base = today.and_hms_opt(h, m, s)?;
+            let base = now.timezone().from_local_datetime(&base).single()?;
+            parse_date_string(&lower, base, Dialect::Uk).ok()
+        };
+        let time = match (at(1, 23, 45), at(13, 57, 21)) {
+            (Some(a), Some(b)) if a.time() == b.time() && a.time() != NaiveTime::MIN => {
+                Some(a.time())
+            }
+            _ => None,
+        };
+
+        Some(Self {
+            date: parsed.date_naive(),
+            time,
+        })
+    }
+
+    /// Time of day as written back to task lines
+    pub fn time_string(&self) -> Option<String> {
+        self.time.map(|time| {
+            if time.second() == 0 {
+                time.format("%H:%M").to_string()
+            } else {
+                time.format("%H:%M:%S").to_string()
+            }
+        })
+    }
+}
+
+/// Whether text can be handed to chrono-english, which reads a bare number
+/// as a year (`due: 3 reports` would be due in the year 3) and cannot take
+/// very large amounts
+fn is_natural_date_text(text: &str) -> bool {
+    let bare_number = text
+        .trim_start_matches(['+', '-'])
+        .chars()
+        .all(|c| c.is_ascii_digit());
+    !bare_number
+        && text
+            .split(|c: char| !c.is_ascii_digit())
+            .all(|digits| digits.len() <= MAX_AMOUNT_DIGITS)
+}
+
+/// ISO form written back to task lines
+impl fmt::Display for TaskDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date.format("%Y-%m-%d"))?;
+        if let Some(time) = self.time_string() {
+            write!(f, " {}", time)?;
+        }
+        Ok(())
+    }
+}
+
+/// A task date as written in a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateMention {
+    pub kind: DateKind,
+    /// Byte range of the marker and its value
+    pub range: Range<usize>,
+    /// The value as written
+    pub raw: String,
+    /// `None` when an `@due(...)` value is not a date
+    pub date: Option<TaskDate>,
+}
+
+/// Find the first mention of a kind of date in a task's text. `due: ...`
+/// only counts when it is followed by a date, while `@due(...)` and
+/// `@due ...` count even when the value cannot be parsed.
+pub fn find_task_date<Tz: TimeZone>(
+    text: &str,
+    kind: DateKind,
+    now: &DateTime<Tz>,
+) -> Option<DateMention>
+where
+    Tz::Offset: Copy,
+{
+    for captures in DATE_MARKER_PATTERN.captures_iter(text) {
+        if !captures[2].eq_ignore_ascii_case(kind.key()) {
+            continue;
+        }
+        let at_sign = !captures[1].is_empty();
+        let start = captures.get(1).unwrap().start();
+        let separator = captures.get(3).unwrap();
+        let value_start = separator.end();
+
+        // `@due(...)`
+        if separator.as_str() == "(" {
+            if !at_sign {
+                continue;
+            }
+            let Some(close) = text[value_start..].find(')') else {
+                continue;
+            };
+            let raw = text[value_start..value_start + close].trim().to_string();
+            return Some(DateMention {
+                kind,
+                range: start..value_start + close + 1,
+                date: TaskDate::parse(&raw, now),
+                raw,
+            });
+        }
+
+        // `due ...` without a colon is plain text
+        if !at_sign && !separator.as_str().contains(':') {
+            continue;
+        }
+
+        // The longest run of words that reads as a date
+        let ends = value_word_ends(&text[value_start..]);
+        for end in ends.iter().rev() {
+            let raw = &text[value_start..value_start + end];
+            if let Some(date) = TaskDate::parse(raw, now) {
+                return Some(DateMention {
+                    kind,
+                    range: start..value_start + end,
+                    raw: raw.to_string(),
+                    date: Some(date),
+                });
+            }
+        }
+
+        if at_sign {
+            if let Some(end) = ends.first() {
+                return Some(DateMention {
+                    kind,
+                    range: start..value_start + end,
+                    raw: text[value_start..value_start + end].to_string(),
+                    date: None,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// End offsets of the words that could be part of a date value, stopping at
+/// the next property, tag, link, task ID or closing parenthesis
+fn value_word_ends(text: &str) -> Vec<usize> {
+    let mut ends = Vec::new();
+    let mut position = 0;
+    while ends.len() < MAX_DATE_WORDS {
+        let rest = &text[position..];
+        let trimmed = rest.trim_start();
+        if trimmed.is_empty() {
+            break;
+        }
+        let start = position + rest.len() - trimmed.len();
+        let len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let word = &text[start..start + len];
+
+        let starts_property = word.starts_with(['@', '#', '!', '<', '[', '🔁'])
+            || word.contains("::")
+            || DATE_MARKER_PATTERN.is_match(word);
+        if starts_property {
+            break;
+        }
+        if let Some(paren) = word.find(')') {
+            if paren > 0 {
+                ends.push(start + paren);
+            }
+            break;
+        }
+
+        position = start + len;
+        ends.push(position);
+    }
+    ends
+}
+
+/// Rewrite every due, scheduled and start date in a task line to ISO
+/// `@due(2025-06-13 15:00)` form. Values that are not dates are left alone.
+pub fn normalize_task_dates<Tz: TimeZone>(line: &str, now: &DateTime<Tz>) -> String
+where
+    Tz::Offset: Copy,
+{
+    let mut mentions: Vec<DateMention> = DateKind::ALL
+        .iter()
+        .filter_map(|kind| find_task_date(line, *kind, now))
+        .filter(|mention| mention.date.is_some())
+        .collect();
+    mentions.sort_by_key(|mention| std::cmp::Reverse(mention.range.start));
+
+    let mut result = line.to_string();
+    for mention in mentions {
+        if let Some(date) = mention.date {
+            let normalized = format!("@{}({})", mention.kind.key(), date);
+            result.replace_range(mention.range, &normalized);
+        }
+    }
+    result
+}
+
+/// Remove a kind of date from a task line, with the whitespace before it
+pub fn remove_task_date<Tz: TimeZone>(line: &str, kind: DateKind, now: &DateTime<Tz>) -> String
+where
+    Tz::Offset: Copy,
+{
+    match find_task_date(line, kind, now) {
+        Some(mention) => {
+            let start = line[..mention.range.start].trim_end().len();
+            format!("{}{}", &line[..start], &line[mention.range.end..])
+        }
+        None => line.to_string(),
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use super::super::dates::*;
+    use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+
+    // Wednesday 2025-06-11, 09:30 in UTC+2
+    fn now() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 6, 11, 9, 30, 0)
+            .unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn time(h: u32, m: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(h, m, 0)
+    }
+
+    #[test]
+    fn test_parse_task_dates() {
+        let now = now();
+        let cases = [
+            ("2025-07-01", date(2025, 7, 1), None),
+            ("2025-07-01 15:00", date(2025, 7, 1), time(15, 0)),
+            ("2025-07-01T08:15", date(2025, 7, 1), time(8, 15)),
+            ("today", date(2025, 6, 11), None),
+            ("Tomorrow", date(2025, 6, 12), None),
+            ("yesterday", date(2025, 6, 10), None),
+            ("friday", date(2025, 6, 13), None),
+            ("friday 8pm", date(2025, 6, 13), time(20, 0)),
+            ("next friday 3pm", date(2025, 6, 20), time(15, 0)),
+        ];
+        for (text, expected_date, expected_time) in cases {
+            let parsed = TaskDate::parse(text, &now).unwrap_or_else(|| panic!("{}", text));
+            assert_eq!(parsed.date, expected_date, "{}", text);
+            assert_eq!(parsed.time, expected_time, "{}", text);
+        }
+
+        assert!(TaskDate::parse("someday", &now).is_none());
+        // A bare number is not a year
+        assert!(TaskDate::parse("3", &now).is_none());
+        assert!(TaskDate::parse("2026", &now).is_none());
+        // Amounts too large for chrono-english are rejected, not panicked on
+        for text in [
+            "4000000000 days",
+            "4000000000 weeks",
+            "4000000000 years",
+            "in 99999999999999999999 days",
+        ] {
+            assert!(TaskDate::parse(text, &now).is_none(), "{}", text);
+        }
+        assert!(TaskDate::parse("", &now).is_none());
+
+        let parsed = TaskDate::parse("2025-07-01 15:00", &now).unwrap();
+        assert_eq!(parsed.to_string(), "2025-07-01 15:00");
+        assert_eq!(parsed.time_string(), Some("15:00".to_string()));
+    }
+
+    #[test]
+    fn test_find_task_date_forms() {
+        let now = now();
+
+        let line = "Ship it @due(2025-07-01) @scheduled(tomorrow) @start(garbage)";
+        let due = find_task_date(line, DateKind::Due, &now).unwrap();
+        assert_eq!(&line[due.range.clone()], "@due(2025-07-01)");
+        assert_eq!(due.date.unwrap().date, date(2025, 7, 1));
+        let scheduled = find_task_date(line, DateKind::Scheduled, &now).unwrap();
+        assert_eq!(scheduled.date.unwrap().date, date(2025, 6, 12));
+        // Written as a marker, so it counts even though it is not a date
+        let start = find_task_date(line, DateKind::Start, &now).unwrap();
+        assert_eq!(start.raw, "garbage");
+        assert!(start.date.is_none());
+
+        // `due:` takes the longest run of words that reads as a date
+        let line = "Call Sam due: next friday 3pm #work";
+        let due = find_task_date(line, DateKind::Due, &now).unwrap();
+        assert_eq!(due.raw, "next friday 3pm");
+        assert_eq!(due.date.unwrap().time, time(15, 0));
+
+        let line = "Plan scheduled:: 2025-06-20 <!-- tid: 0000-aaaa -->";
+        let scheduled = find_task_date(line, DateKind::Scheduled, &now).unwrap();
+        assert_eq!(scheduled.raw, "2025-06-20");
+
+        // A number followed by prose is not a year
+        assert!(find_task_date("File due: 3 reports", DateKind::Due, &now).is_none());
+        assert!(find_task_date("Count due: 4000000000 days", DateKind::Due, &now).is_none());
+        let due = find_task_date("Ship due: 3 days", DateKind::Due, &now).unwrap();
+        assert_eq!(due.date.unwrap().date, date(2025, 6, 14));
+
+        // Prose is not a date
+        assert!(find_task_date("Check the due date", DateKind::Due, &now).is_none());
+        assert!(find_task_date("Fix the start: button", DateKind::Start, &now).is_none());
+        assert!(find_task_date("Restart: server", DateKind::Start, &now).is_none());
+    }
+
+    #[test]
+    fn test_normalize_task_dates() {
+        let now = now();
+        assert_eq!(
+            normalize_task_dates("- [ ] Call Sam due: next friday 3pm #work", &now),
+            "- [ ] Call Sam @due(2025-06-20 15:00) #work"
+        );
+        assert_eq!(
+            normalize_task_dates(
+                "- [ ] Draft start: tomorrow scheduled: friday due: 2025-06-30 !p1",
+                &now
+            ),
+            "- [ ] Draft @start(2025-06-12) @scheduled(2025-06-13) @due(2025-06-30) !p1"
+        );
+        // Already normalized and unparseable values are left alone
+        let line = "- [ ] Ship @due(2025-06-30) @scheduled(later)";
+        assert_eq!(normalize_task_dates(line, &now), line);
+    }
+
+    #[test]
+    fn test_remove_task_date() {
+        let now = now();
+        assert_eq!(
+            remove_task_date("- [ ] Ship @due(2025-06-30) !p1", DateKind::Due, &now),
+            "- [ ] Ship !p1"
+        );
+        assert_eq!(
+            remove_task_date("- [ ] Ship scheduled: friday", DateKind::Scheduled, &now),
+            "- [ ] Ship"
+        );
+        assert_eq!(
+            remove_task_date("- [ ] Ship", DateKind::Start, &now),
+            "- [ ] Ship"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Durations for task estimates and logged time, written as `2h`, `45m`,
+//! `1h30m`, `1h 30m` or `1.5h`
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    // One number with its unit; longer unit names are tried first
+    static ref DURATION_PART_PATTERN: Regex = Regex::new(
+        r"(?i)(\d+(?:\.\d+)?)\s*(hours?|hrs?|h|minutes?|mins?|m)"
+    ).unwrap();
+}
+
+/// Parse a duration into whole minutes. Every part of the value has to be a
+/// number of hours or minutes, so `2h` and `1h 30m` parse but `2` and
+/// `2 days` do not.
+pub fn parse_duration(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let mut minutes = 0.0;
+    let mut end = 0;
+    for captures in DURATION_PART_PATTERN.captures_iter(value) {
+        let part = captures.get(0)?;
+        if !value[end..part.start()].trim().is_empty() {
+            return None;
+        }
+        end = part.end();
+
+        let amount: f64 = captures[1].parse().ok()?;
+        minutes += if captures[2].to_lowercase().starts_with('h') {
+            amount * 60.0
+        } else {
+            amount
+        };
+    }
+    if end == 0 || !value[end..].trim().is_empty() {
+        return None;
+    }
+    Some(minutes.round() as u32)
+}
+
+/// Format minutes the way estimates are written, e.g. `1h30m`
+pub fn format_duration(minutes: u64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use super::super::duration::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h"), Some(120));
+        assert_eq!(parse_duration("45m"), Some(45));
+        assert_eq!(parse_duration("1h30m"), Some(90));
+        assert_eq!(parse_duration(" 1h 30m "), Some(90));
+        assert_eq!(parse_duration("1.5h"), Some(90));
+        assert_eq!(parse_duration("2 hours 15 mins"), Some(135));
+        assert_eq!(parse_duration("2"), None);
+        assert_eq!(parse_duration("2 days"), None);
+        assert_eq!(parse_duration("about 2h"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45), "45m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(90), "1h30m");
+        assert_eq!(parse_duration(&format_duration(135)), Some(135));
+    }
+}
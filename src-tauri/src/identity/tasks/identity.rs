@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use chrono::Local;
 use lru::LruCache;
 use parking_lot::RwLock;
 use std::collections::HashMap;
@@ -131,10 +132,7 @@ impl TaskIdentity {
         let mut task_props = TaskProperties::new(parsed_task.content.clone());
 
         // Set status
-        task_props.status = match parsed_task.status {
-            TaskStatus::Done => FmTaskStatus::Done,
-            TaskStatus::Todo => FmTaskStatus::Todo,
-        };
+        task_props.status = parsed_task.status.into();
 
         // Extract properties from the parsed task
         if let Some(due) = parsed_task.properties.get("due") {
@@ -265,30 +263,32 @@ impl TaskIdentity {
         Ok(tasks.into_iter().find(|t| t.id.as_deref() == Some(task_id)))
     }
 
-    /// Batch ensure all tasks in a file have IDs
+    /// Batch ensure all tasks in a file have IDs, normalizing their due,
+    /// scheduled and start dates to ISO form while the lines are rewritten
     pub fn batch_ensure_task_ids(&mut self, file_path: &Path) -> Result<Vec<String>> {
         let content = fs::read_to_string(file_path)?;
         let tasks = TaskParser::extract_all_tasks(&content);
+        let now = Local::now();
 
         let mut task_ids = Vec::new();
         let mut lines_to_update: HashMap<usize, String> = HashMap::new();
 
         for task in tasks {
-            if let Some(existing_id) = task.id {
-                task_ids.push(existing_id.clone());
-                // Update cache
-                let mut cache = self.cache.write();
-                cache.insert(existing_id, file_path.to_path_buf(), task.line_number);
-            } else {
-                // Generate new ID
-                let new_id = self.generator.generate()?;
-                task_ids.push(new_id.clone());
-                lines_to_update.insert(task.line_number, new_id.clone());
-
-                // Update cache
-                let mut cache = self.cache.write();
-                cache.insert(new_id, file_path.to_path_buf(), task.line_number);
+            let task_id = match task.id {
+                Some(existing_id) => existing_id,
+                None => self.generator.generate()?,
+            };
+            task_ids.push(task_id.clone());
+
+            let normalized = TaskParser::normalize_dates(&task.raw_line, &now);
+            let updated = TaskParser::add_tid_to_line(&normalized, &task_id);
+            if updated != task.raw_line {
+                lines_to_update.insert(task.line_number, updated);
             }
+
+            // Update cache
+            let mut cache = self.cache.write();
+            cache.insert(task_id, file_path.to_path_buf(), task.line_number);
         }
 
         // Apply all updates at once if needed
@@ -299,15 +299,14 @@ impl TaskIdentity {
         Ok(task_ids)
     }
 
-    /// Batch update multiple lines in a file
+    /// Batch replace multiple lines in a file
     fn batch_update_file(&self, file_path: &Path, updates: HashMap<usize, String>) -> Result<()> {
         let content = fs::read_to_string(file_path)?;
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
-        for (line_number, task_id) in updates {
+        for (line_number, line) in updates {
             if line_number > 0 && line_number <= lines.len() {
-                lines[line_number - 1] =
-                    TaskParser::add_tid_to_line(&lines[line_number - 1], &task_id);
+                lines[line_number - 1] = line;
             }
         }
 
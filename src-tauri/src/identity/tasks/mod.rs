@@ -1,10 +1,22 @@
+pub mod dates;
+pub mod duration;
 pub mod identity;
 pub mod parser;
+pub mod recurrence;
 
+#[cfg(test)]
+mod dates_test;
+#[cfg(test)]
+mod duration_test;
 #[cfg(test)]
 mod identity_test;
 #[cfg(test)]
 mod parser_test;
+#[cfg(test)]
+mod recurrence_test;
 
+pub use dates::{DateKind, TaskDate};
+pub use duration::{format_duration, parse_duration};
 pub use identity::TaskIdentity;
-pub use parser::{ParsedTask, TaskParser, TaskStatus};
+pub use parser::{ParsedTask, TaskParser, TaskStatus, TaskStatusCycle};
+pub use recurrence::{Frequency, Recurrence};
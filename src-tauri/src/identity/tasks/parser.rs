@@ -1,14 +1,23 @@
-use chrono::{NaiveDate, Utc};
-use chrono_english::{parse_date_string, Dialect};
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone};
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::dates::{find_task_date, remove_task_date, DateKind, TaskDate};
+use super::duration::parse_duration;
+use super::recurrence::{Frequency, Recurrence};
+use crate::identity::frontmatter::TaskStatus as FmTaskStatus;
+
 lazy_static! {
     // Main task pattern: matches checkbox tasks with optional indent
     static ref TASK_PATTERN: Regex = Regex::new(
-        r"^(\s*)- \[([ xX])\]\s+(.+?)(?:\s*<!-- tid:\s*([a-f0-9-]+)\s*-->)?$"
+        r"^(\s*)- \[([ xX/>!-])\]\s+(.+?)(?:\s*<!-- tid:\s*([a-f0-9-]+)\s*-->)?$"
+    ).unwrap();
+
+    // The checkbox of a task line, for changing its status in place
+    static ref CHECKBOX_PATTERN: Regex = Regex::new(
+        r"^(\s*- \[)[ xX/>!-](\])"
     ).unwrap();
 
     // Task ID pattern for extracting existing IDs
@@ -17,14 +26,6 @@ lazy_static! {
     ).unwrap();
 
     // Property patterns
-    static ref DUE_PATTERN: Regex = Regex::new(
-        r"@due\(([^)]+)\)"
-    ).unwrap();
-    // Alternative due syntaxes: @due 2025-09-01 or @due:2025-09-01 or natural tokens
-    static ref DUE_ALT_PATTERN: Regex = Regex::new(
-        r"@due(?::|\s+)\s*([^\s)]+)"
-    ).unwrap();
-
     static ref PRIORITY_PATTERN: Regex = Regex::new(
         r"!(?:p([1-5])|high|medium|low)"
     ).unwrap();
@@ -39,12 +40,172 @@ lazy_static! {
     static ref PROJECT_ALT_PATTERN: Regex = Regex::new(
         r"@project(?::|\s+)\s*([^\s)]+)"
     ).unwrap();
+
+    // Repeat rules: @repeat(weekly) or 🔁 every week on Monday, which runs
+    // until the next property, tag or task ID
+    static ref REPEAT_PATTERN: Regex = Regex::new(
+        r"@repeat\(([^)]+)\)"
+    ).unwrap();
+    static ref REPEAT_EMOJI_PATTERN: Regex = Regex::new(
+        r"🔁\s*([^@!#<]+)"
+    ).unwrap();
+
+    // Dependencies: blocked-by:: [[tid:...]], [[tid:...]]
+    static ref BLOCKED_BY_PATTERN: Regex = Regex::new(
+        r"blocked-by::((?:\s*,?\s*\[\[tid:[a-zA-Z0-9-]+\]\])+)"
+    ).unwrap();
+    static ref TASK_LINK_PATTERN: Regex = Regex::new(
+        r"\[\[tid:([a-zA-Z0-9-]+)\]\]"
+    ).unwrap();
+
+    // Estimated effort: estimate:: 2h, estimate:: 1h 30m
+    static ref ESTIMATE_PATTERN: Regex = Regex::new(
+        r"(?i)estimate::\s*(\d[\d.]*\s*[a-z]+(?:\s*\d[\d.]*\s*[a-z]+)*)"
+    ).unwrap();
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TaskStatus {
     Todo,
     Done,
+    InProgress,
+    Cancelled,
+    Blocked,
+    Deferred,
+}
+
+impl TaskStatus {
+    pub const ALL: [TaskStatus; 6] = [
+        TaskStatus::Todo,
+        TaskStatus::InProgress,
+        TaskStatus::Blocked,
+        TaskStatus::Deferred,
+        TaskStatus::Done,
+        TaskStatus::Cancelled,
+    ];
+
+    /// Character between the checkbox brackets: `[ ]`, `[x]`, `[/]`, `[-]`,
+    /// `[!]` and `[>]`
+    pub fn checkbox(self) -> char {
+        match self {
+            TaskStatus::Todo => ' ',
+            TaskStatus::Done => 'x',
+            TaskStatus::InProgress => '/',
+            TaskStatus::Cancelled => '-',
+            TaskStatus::Blocked => '!',
+            TaskStatus::Deferred => '>',
+        }
+    }
+
+    pub fn from_checkbox(c: char) -> Option<Self> {
+        match c {
+            ' ' => Some(TaskStatus::Todo),
+            'x' | 'X' => Some(TaskStatus::Done),
+            '/' => Some(TaskStatus::InProgress),
+            '-' => Some(TaskStatus::Cancelled),
+            '!' => Some(TaskStatus::Blocked),
+            '>' => Some(TaskStatus::Deferred),
+            _ => None,
+        }
+    }
+
+    /// Name used by commands and settings
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::Done => "done",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Cancelled => "cancelled",
+            TaskStatus::Blocked => "blocked",
+            TaskStatus::Deferred => "deferred",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().replace(['-', ' '], "_").as_str() {
+            "todo" => Some(TaskStatus::Todo),
+            "done" => Some(TaskStatus::Done),
+            "in_progress" | "inprogress" | "doing" => Some(TaskStatus::InProgress),
+            "cancelled" | "canceled" => Some(TaskStatus::Cancelled),
+            "blocked" => Some(TaskStatus::Blocked),
+            "deferred" => Some(TaskStatus::Deferred),
+            _ => None,
+        }
+    }
+
+    /// Whether the task needs no more work
+    pub fn is_closed(self) -> bool {
+        matches!(self, TaskStatus::Done | TaskStatus::Cancelled)
+    }
+}
+
+impl From<TaskStatus> for FmTaskStatus {
+    fn from(status: TaskStatus) -> Self {
+        match status {
+            TaskStatus::Todo => FmTaskStatus::Todo,
+            TaskStatus::Done => FmTaskStatus::Done,
+            TaskStatus::InProgress => FmTaskStatus::InProgress,
+            TaskStatus::Cancelled => FmTaskStatus::Cancelled,
+            TaskStatus::Blocked => FmTaskStatus::Blocked,
+            TaskStatus::Deferred => FmTaskStatus::Deferred,
+        }
+    }
+}
+
+impl From<FmTaskStatus> for TaskStatus {
+    fn from(status: FmTaskStatus) -> Self {
+        match status {
+            FmTaskStatus::Todo => TaskStatus::Todo,
+            FmTaskStatus::Done => TaskStatus::Done,
+            FmTaskStatus::InProgress => TaskStatus::InProgress,
+            FmTaskStatus::Cancelled => TaskStatus::Cancelled,
+            FmTaskStatus::Blocked => TaskStatus::Blocked,
+            FmTaskStatus::Deferred => TaskStatus::Deferred,
+        }
+    }
+}
+
+/// The order statuses move through when a task is toggled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskStatusCycle(Vec<TaskStatus>);
+
+impl Default for TaskStatusCycle {
+    fn default() -> Self {
+        Self(vec![TaskStatus::Todo, TaskStatus::Done])
+    }
+}
+
+impl TaskStatusCycle {
+    /// Build a cycle from status names such as `["todo", "in_progress", "done"]`
+    pub fn parse(names: &[String]) -> Result<Self, String> {
+        let mut statuses = Vec::new();
+        for name in names {
+            let status =
+                TaskStatus::parse(name).ok_or_else(|| format!("Unknown task status: {}", name))?;
+            if statuses.contains(&status) {
+                return Err(format!("Task status listed twice: {}", name));
+            }
+            statuses.push(status);
+        }
+        if statuses.len() < 2 {
+            return Err("A status cycle needs at least two statuses".to_string());
+        }
+        Ok(Self(statuses))
+    }
+
+    pub fn statuses(&self) -> &[TaskStatus] {
+        &self.0
+    }
+
+    /// The status after `status`. Statuses outside the cycle go to `Done`
+    /// when the cycle has it, otherwise to the start of the cycle.
+    pub fn next(&self, status: TaskStatus) -> TaskStatus {
+        match self.0.iter().position(|s| *s == status) {
+            Some(index) => self.0[(index + 1) % self.0.len()],
+            None if self.0.contains(&TaskStatus::Done) => TaskStatus::Done,
+            None => self.0[0],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +214,9 @@ pub struct ParsedTask {
     pub status: TaskStatus,
     pub line_number: usize,
     pub indent_level: usize,
+    /// Line of the task this one is nested under, set by `extract_all_tasks`
+    #[serde(default)]
+    pub parent_line: Option<usize>,
     pub id: Option<String>,
     pub properties: HashMap<String, String>,
     pub raw_line: String,
@@ -70,29 +234,37 @@ impl TaskParser {
             let tid = captures.get(4).map(|m| m.as_str().to_string());
 
             // Calculate indent level (tabs count as 4 spaces)
-            let indent_level = indent.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum();
+            let indent_level = Self::indent_width(indent);
 
             // Determine task status
-            let status = match status_char.to_lowercase().as_str() {
-                "x" => TaskStatus::Done,
-                _ => TaskStatus::Todo,
-            };
+            let status = status_char
+                .chars()
+                .next()
+                .and_then(TaskStatus::from_checkbox)
+                .unwrap_or(TaskStatus::Todo);
 
             // Extract properties from content
             let mut properties = HashMap::new();
 
-            // Extract due date (support multiple syntaxes)
-            if let Some(due_match) = DUE_PATTERN.captures(content_with_props) {
-                if let Some(due_str) = due_match.get(1) {
-                    let due_value = due_str.as_str();
-                    let normalized_date = Self::parse_date(due_value);
-                    properties.insert("due".to_string(), normalized_date);
-                }
-            } else if let Some(alt) = DUE_ALT_PATTERN.captures(content_with_props) {
-                if let Some(due_str) = alt.get(1) {
-                    let due_value = due_str.as_str();
-                    let normalized_date = Self::parse_date(due_value);
-                    properties.insert("due".to_string(), normalized_date);
+            // Extract due, scheduled and start dates (support multiple
+            // syntaxes and natural language, in local time)
+            let now = Local::now();
+            for kind in DateKind::ALL {
+                if let Some(mention) = find_task_date(content_with_props, kind, &now) {
+                    match mention.date {
+                        Some(date) => {
+                            properties.insert(
+                                kind.key().to_string(),
+                                date.date.format("%Y-%m-%d").to_string(),
+                            );
+                            if let Some(time) = date.time_string() {
+                                properties.insert(kind.time_key().to_string(), time);
+                            }
+                        }
+                        None => {
+                            properties.insert(kind.key().to_string(), mention.raw);
+                        }
+                    }
                 }
             }
 
@@ -135,11 +307,38 @@ impl TaskParser {
                 }
             }
 
+            // Extract dependencies, stored as comma separated task IDs
+            if let Some(blocked_by) = BLOCKED_BY_PATTERN.captures(content_with_props) {
+                let ids: Vec<&str> = TASK_LINK_PATTERN
+                    .captures_iter(&blocked_by[1])
+                    .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+                    .collect();
+                properties.insert("blocked_by".to_string(), ids.join(","));
+            }
+
+            // Extract estimate, stored in minutes
+            let estimate = ESTIMATE_PATTERN
+                .captures(content_with_props)
+                .and_then(|captures| parse_duration(&captures[1]));
+            if let Some(minutes) = estimate {
+                properties.insert("estimate".to_string(), minutes.to_string());
+            }
+
+            // Extract repeat rule, stored in RRULE form
+            let repeat = REPEAT_PATTERN
+                .captures(content_with_props)
+                .or_else(|| REPEAT_EMOJI_PATTERN.captures(content_with_props))
+                .and_then(|captures| Recurrence::parse(&captures[1]));
+            if let Some(repeat) = repeat {
+                properties.insert("repeat".to_string(), repeat.to_string());
+            }
+
             Some(ParsedTask {
                 content: content_with_props.to_string(),
                 status,
                 line_number,
                 indent_level,
+                parent_line: None,
                 id: tid,
                 properties,
                 raw_line: line.to_string(),
@@ -149,12 +348,27 @@ impl TaskParser {
         }
     }
 
-    /// Extract all tasks from a document
+    /// Extract all tasks from a document, linking each nested task to the
+    /// task it is indented under
     pub fn extract_all_tasks(content: &str) -> Vec<ParsedTask> {
         let mut tasks = Vec::new();
+        // (indent, line number) of the tasks enclosing the current line
+        let mut ancestors: Vec<(usize, usize)> = Vec::new();
 
         for (index, line) in content.lines().enumerate() {
-            if let Some(task) = Self::parse_line(line, index + 1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // Any line ends the tasks indented at least as deep as it is
+            let indent = Self::indent_width(line);
+            while ancestors.last().is_some_and(|(depth, _)| *depth >= indent) {
+                ancestors.pop();
+            }
+
+            if let Some(mut task) = Self::parse_line(line, index + 1) {
+                task.parent_line = ancestors.last().map(|(_, line)| *line);
+                ancestors.push((task.indent_level, task.line_number));
                 tasks.push(task);
             }
         }
@@ -162,6 +376,14 @@ impl TaskParser {
         tasks
     }
 
+    /// Leading whitespace width, with tabs counting as 4 spaces
+    fn indent_width(line: &str) -> usize {
+        line.chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum()
+    }
+
     /// Add a task ID to a line if it doesn't already have one
     pub fn add_tid_to_line(line: &str, tid: &str) -> String {
         // Check if line already has a tid
@@ -175,12 +397,80 @@ impl TaskParser {
 
     /// Toggle task status between checked and unchecked
     pub fn toggle_task_status(line: &str) -> String {
-        if line.contains("- [ ]") {
-            line.replace("- [ ]", "- [x]")
-        } else if line.contains("- [x]") || line.contains("- [X]") {
-            line.replace("- [x]", "- [ ]").replace("- [X]", "- [ ]")
-        } else {
-            line.to_string()
+        Self::cycle_task_status(line, &TaskStatusCycle::default())
+    }
+
+    /// Move a task line to the next status in `cycle`
+    pub fn cycle_task_status(line: &str, cycle: &TaskStatusCycle) -> String {
+        match Self::parse_line(line, 0) {
+            Some(task) => Self::set_task_status(line, cycle.next(task.status)),
+            None => line.to_string(),
+        }
+    }
+
+    /// Rewrite the checkbox of a task line for `status`
+    pub fn set_task_status(line: &str, status: TaskStatus) -> String {
+        CHECKBOX_PATTERN
+            .replace(line, |caps: &regex::Captures| {
+                format!("{}{}{}", &caps[1], status.checkbox(), &caps[2])
+            })
+            .to_string()
+    }
+
+    /// The line for the next occurrence of a recurring task: unchecked, with
+    /// `new_id` as its task ID and due on the first date the repeat rule gives
+    /// after the current due date, or after `today` when there is none.
+    /// Scheduled and start dates move by as much as the due date.
+    /// Returns `None` if the line is not a recurring task.
+    pub fn next_occurrence(line: &str, new_id: &str, today: NaiveDate) -> Option<String> {
+        let task = Self::parse_line(line, 0)?;
+        let recurrence = Recurrence::parse(task.properties.get("repeat")?)?;
+        let now = Local::now();
+        let current_due = find_task_date(line, DateKind::Due, &now).and_then(|m| m.date);
+        let base = current_due.map_or(today, |due| due.date);
+        let due = TaskDate {
+            date: recurrence.next_after(base),
+            time: current_due.and_then(|due| due.time),
+        };
+        let shift = due.date - base;
+
+        let without_id = TID_PATTERN.replace(line, "");
+        let mut next = remove_task_date(&without_id, DateKind::Due, &now);
+        for kind in [DateKind::Scheduled, DateKind::Start] {
+            let Some(mention) = find_task_date(&next, kind, &now) else {
+                continue;
+            };
+            if let Some(date) = mention.date {
+                let moved = TaskDate {
+                    date: date.date + shift,
+                    time: date.time,
+                };
+                next.replace_range(mention.range, &format!("@{}({})", kind.key(), moved));
+            }
+        }
+        // Clamping to a short month would stick (Jan 31, Feb 28, Mar 28), so a
+        // monthly rule without a day is pinned to the day it was on
+        if recurrence.frequency == Frequency::Monthly
+            && recurrence.month_day.is_none()
+            && due.date.day() != base.day()
+        {
+            next = replace_repeat_rule(&next, |rule| Recurrence::on_month_day(rule, base.day()));
+        }
+        let unchecked = Self::set_task_status(&next, TaskStatus::Todo);
+
+        let next = format!("{} @due({})", unchecked.trim_end(), due);
+        Some(Self::add_tid_to_line(&next, new_id))
+    }
+
+    /// Rewrite the due, scheduled and start dates of a task line to ISO form,
+    /// resolving natural language dates relative to `now`
+    pub fn normalize_dates<Tz: TimeZone>(line: &str, now: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Copy,
+    {
+        match Self::parse_line(line, 0) {
+            Some(_) => super::dates::normalize_task_dates(line, now),
+            None => line.to_string(),
         }
     }
 
@@ -194,68 +484,106 @@ impl TaskParser {
         }
     }
 
-    /// Parse date string (supports natural language)
-    fn parse_date(date_str: &str) -> String {
-        // First try standard date format (YYYY-MM-DD)
-        if let Ok(_) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-            return date_str.to_string();
-        }
-
-        // Try natural language parsing
-        if let Ok(parsed) = parse_date_string(date_str, Utc::now(), Dialect::Uk) {
-            return parsed.format("%Y-%m-%d").to_string();
-        }
-
-        // Return original if parsing fails
-        date_str.to_string()
-    }
-
-    /// Update task properties in a line
+    /// Set (`Some`) or remove (`None`) properties in a task line: `due`,
+    /// `scheduled`, `start`, `priority`, `project`, `estimate` and `tags`
+    /// (comma separated). New markup goes before the task ID.
     pub fn update_task_properties(line: &str, updates: &HashMap<String, Option<String>>) -> String {
+        const KEYS: [&str; 7] = [
+            "due",
+            "scheduled",
+            "start",
+            "priority",
+            "project",
+            "estimate",
+            "tags",
+        ];
         let mut result = line.to_string();
 
-        for (key, value) in updates {
-            match key.as_str() {
-                "due" => {
-                    // Remove existing due date
-                    result = DUE_PATTERN.replace(&result, "").to_string();
-                    // Add new due date if provided
-                    if let Some(new_value) = value {
-                        let tid_match = TID_PATTERN.find(&result);
-                        if let Some(m) = tid_match {
-                            let insert_pos = m.start();
-                            result.insert_str(insert_pos, &format!(" @due({})", new_value));
-                        } else {
-                            result.push_str(&format!(" @due({})", new_value));
-                        }
-                    }
+        for key in KEYS {
+            let Some(value) = updates.get(key) else {
+                continue;
+            };
+            // Remove the existing markup, then build the new one
+            let marker = match key {
+                "due" | "scheduled" | "start" => {
+                    let kind = DateKind::ALL
+                        .into_iter()
+                        .find(|kind| kind.key() == key)
+                        .unwrap_or(DateKind::Due);
+                    result = remove_task_date(&result, kind, &Local::now());
+                    value.as_ref().map(|date| format!("@{}({})", key, date))
                 }
                 "priority" => {
-                    // Remove existing priority
-                    result = PRIORITY_PATTERN.replace(&result, "").to_string();
-                    // Add new priority if provided
-                    if let Some(new_value) = value {
-                        let priority_marker = match new_value.as_str() {
+                    result = Self::remove_markup(&result, &PRIORITY_PATTERN);
+                    value.as_ref().map(|priority| {
+                        match priority.as_str() {
                             "high" => "!p1",
                             "medium" => "!p2",
                             "low" => "!p4",
                             _ => "!p3",
-                        };
-                        let tid_match = TID_PATTERN.find(&result);
-                        if let Some(m) = tid_match {
-                            let insert_pos = m.start();
-                            result.insert_str(insert_pos, &format!(" {}", priority_marker));
-                        } else {
-                            result.push_str(&format!(" {}", priority_marker));
                         }
-                    }
+                        .to_string()
+                    })
+                }
+                "project" => {
+                    result = Self::remove_markup(&result, &PROJECT_PATTERN);
+                    result = Self::remove_markup(&result, &PROJECT_ALT_PATTERN);
+                    value.as_ref().map(|project| format!("@project({})", project))
+                }
+                "estimate" => {
+                    result = Self::remove_markup(&result, &ESTIMATE_PATTERN);
+                    value.as_ref().map(|estimate| format!("estimate:: {}", estimate))
+                }
+                _ => {
+                    result = Self::remove_markup(&result, &TAG_PATTERN);
+                    value.as_ref().and_then(|tags| {
+                        let tags: Vec<String> = tags
+                            .split(',')
+                            .map(|tag| tag.trim().trim_start_matches('#'))
+                            .filter(|tag| !tag.is_empty())
+                            .map(|tag| format!("#{}", tag))
+                            .collect();
+                        (!tags.is_empty()).then(|| tags.join(" "))
+                    })
                 }
-                _ => {}
+            };
+
+            if let Some(marker) = marker {
+                let insert_pos = match TID_PATTERN.find(&result) {
+                    Some(m) => result[..m.start()].trim_end().len(),
+                    None => result.trim_end().len(),
+                };
+                result.insert_str(insert_pos, &format!(" {}", marker));
             }
         }
 
         result
     }
+
+    /// Remove every match of a property pattern, with the whitespace before it
+    fn remove_markup(line: &str, pattern: &Regex) -> String {
+        let mut result = line.to_string();
+        while let Some(m) = pattern.find(&result) {
+            let start = result[..m.start()].trim_end().len();
+            result.replace_range(start..m.end(), "");
+        }
+        result
+    }
+}
+
+/// `line` with the text of its repeat rule replaced by `rule(text)`
+fn replace_repeat_rule(line: &str, rule: impl FnOnce(&str) -> String) -> String {
+    let Some(text) = REPEAT_PATTERN
+        .captures(line)
+        .or_else(|| REPEAT_EMOJI_PATTERN.captures(line))
+        .and_then(|captures| captures.get(1))
+    else {
+        return line.to_string();
+    };
+    let end = text.start() + text.as_str().trim_end().len();
+    let mut result = line.to_string();
+    result.replace_range(text.start()..end, &rule(&line[text.start()..end]));
+    result
 }
 
 #[cfg(test)]
@@ -2,6 +2,7 @@
 mod tests {
     use super::super::parser::*;
     use chrono::{NaiveDate, Utc};
+    use std::collections::HashMap;
 
     #[test]
     fn test_parse_simple_unchecked_task() {
@@ -302,4 +303,250 @@ Regular text here
         assert_eq!(task.content, "Tab indented task");
         assert_eq!(task.indent_level, 4); // Tab counts as 4 spaces
     }
+
+    #[test]
+    fn test_parse_repeat_rules() {
+        let line = "- [ ] Team sync 🔁 every week on Monday @due(2025-01-06) #work";
+        let task = TaskParser::parse_line(line, 1).unwrap();
+        assert_eq!(
+            task.properties.get("repeat"),
+            Some(&"FREQ=WEEKLY;BYDAY=MO".to_string())
+        );
+        assert_eq!(task.properties.get("due"), Some(&"2025-01-06".to_string()));
+        assert_eq!(task.properties.get("tags"), Some(&"work".to_string()));
+
+        let task = TaskParser::parse_line("- [ ] Pay rent @repeat(monthly)", 1).unwrap();
+        assert_eq!(
+            task.properties.get("repeat"),
+            Some(&"FREQ=MONTHLY".to_string())
+        );
+
+        let task = TaskParser::parse_line("- [ ] Odd @repeat(now and then)", 1).unwrap();
+        assert!(!task.properties.contains_key("repeat"));
+    }
+
+    #[test]
+    fn test_next_occurrence_line() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let line = "  - [ ] Team sync 🔁 every week on Monday @due(2025-01-06) !p1 <!-- tid: 0000-aaaa -->";
+        let next = TaskParser::next_occurrence(line, "0000-bbbb", today).unwrap();
+        assert_eq!(
+            next,
+            "  - [ ] Team sync 🔁 every week on Monday !p1 @due(2025-01-13) <!-- tid: 0000-bbbb -->"
+        );
+
+        // Without a due date the next occurrence counts from today
+        let line = "- [x] Water plants @repeat(every 3 days) <!-- tid: 0000-cccc -->";
+        let next = TaskParser::next_occurrence(line, "0000-dddd", today).unwrap();
+        assert_eq!(
+            next,
+            "- [ ] Water plants @repeat(every 3 days) @due(2025-03-04) <!-- tid: 0000-dddd -->"
+        );
+
+        let parsed = TaskParser::parse_line(&next, 1).unwrap();
+        assert_eq!(parsed.id, Some("0000-dddd".to_string()));
+
+        assert!(
+            TaskParser::next_occurrence("- [ ] One-off @due(2025-01-06)", "x", today).is_none()
+        );
+    }
+
+    #[test]
+    fn test_monthly_occurrence_keeps_its_day() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let line = "- [x] Pay rent 🔁 monthly @due(2025-01-31) <!-- tid: 0000-aaaa -->";
+        let february = TaskParser::next_occurrence(line, "0000-bbbb", today).unwrap();
+        assert_eq!(
+            february,
+            "- [ ] Pay rent 🔁 monthly on the 31st @due(2025-02-28) <!-- tid: 0000-bbbb -->"
+        );
+        let march = TaskParser::next_occurrence(&february, "0000-cccc", today).unwrap();
+        assert!(march.contains("@due(2025-03-31)"));
+
+        let line = "- [x] Invoice @repeat(FREQ=MONTHLY;INTERVAL=1) @due(2025-01-30)";
+        let february = TaskParser::next_occurrence(line, "0000-dddd", today).unwrap();
+        assert!(february.starts_with(
+            "- [ ] Invoice @repeat(FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=30) @due(2025-02-28)"
+        ));
+
+        // Months long enough leave the rule as written
+        let line = "- [x] Review @repeat(monthly) @due(2025-01-15)";
+        let next = TaskParser::next_occurrence(line, "0000-eeee", today).unwrap();
+        assert!(next.starts_with("- [ ] Review @repeat(monthly) @due(2025-02-15)"));
+    }
+
+    #[test]
+    fn test_parse_scheduled_and_start_dates() {
+        let line =
+            "- [ ] Review @due(2025-06-20 17:30) @scheduled(2025-06-18) @start(2025-06-16 09:00)";
+        let task = TaskParser::parse_line(line, 1).unwrap();
+        assert_eq!(task.properties.get("due"), Some(&"2025-06-20".to_string()));
+        assert_eq!(task.properties.get("due_time"), Some(&"17:30".to_string()));
+        assert_eq!(
+            task.properties.get("scheduled"),
+            Some(&"2025-06-18".to_string())
+        );
+        assert!(!task.properties.contains_key("scheduled_time"));
+        assert_eq!(
+            task.properties.get("start"),
+            Some(&"2025-06-16".to_string())
+        );
+        assert_eq!(
+            task.properties.get("start_time"),
+            Some(&"09:00".to_string())
+        );
+
+        // Moving a recurring task moves its scheduled and start dates along
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let line = "- [x] Report @repeat(weekly) @start(2025-06-02) @scheduled(2025-06-04 10:00) @due(2025-06-06 17:00)";
+        let next = TaskParser::next_occurrence(line, "0000-eeee", today).unwrap();
+        assert_eq!(
+            next,
+            "- [ ] Report @repeat(weekly) @start(2025-06-09) @scheduled(2025-06-11 10:00) @due(2025-06-13 17:00) <!-- tid: 0000-eeee -->"
+        );
+    }
+
+    #[test]
+    fn test_parse_lifecycle_states() {
+        let cases = [
+            ("- [/] Drafting", TaskStatus::InProgress),
+            ("- [-] Dropped", TaskStatus::Cancelled),
+            ("- [!] Waiting on review", TaskStatus::Blocked),
+            ("- [>] Next quarter", TaskStatus::Deferred),
+        ];
+        for (line, status) in cases {
+            let task = TaskParser::parse_line(line, 1).unwrap();
+            assert_eq!(task.status, status);
+            assert_eq!(
+                TaskParser::set_task_status(line, TaskStatus::Todo)
+                    .chars()
+                    .nth(3),
+                Some(' ')
+            );
+            assert_eq!(TaskStatus::from_checkbox(status.checkbox()), Some(status));
+            assert_eq!(TaskStatus::parse(status.as_str()), Some(status));
+        }
+        assert!(TaskParser::parse_line("- [?] Unknown state", 1).is_none());
+    }
+
+    #[test]
+    fn test_cycle_task_status() {
+        let cycle = TaskStatusCycle::parse(&[
+            "todo".to_string(),
+            "in-progress".to_string(),
+            "done".to_string(),
+        ])
+        .unwrap();
+
+        let line = "  - [ ] Write report <!-- tid: 0000-aaaa -->";
+        let line = TaskParser::cycle_task_status(line, &cycle);
+        assert_eq!(line, "  - [/] Write report <!-- tid: 0000-aaaa -->");
+        let line = TaskParser::cycle_task_status(&line, &cycle);
+        assert_eq!(line, "  - [x] Write report <!-- tid: 0000-aaaa -->");
+        let line = TaskParser::cycle_task_status(&line, &cycle);
+        assert_eq!(line, "  - [ ] Write report <!-- tid: 0000-aaaa -->");
+
+        // Statuses outside the cycle are completed
+        assert_eq!(
+            TaskParser::cycle_task_status("- [!] Blocked task", &cycle),
+            "- [x] Blocked task"
+        );
+        assert_eq!(
+            TaskParser::toggle_task_status("- [/] Started"),
+            "- [x] Started"
+        );
+
+        assert!(TaskStatusCycle::parse(&["todo".to_string()]).is_err());
+        assert!(TaskStatusCycle::parse(&["todo".to_string(), "later".to_string()]).is_err());
+        assert!(TaskStatusCycle::parse(&["done".to_string(), "done".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_extract_task_hierarchy() {
+        let content = "- [ ] Launch\n\
+                       \t- [ ] Write docs\n\
+                       \t\tNotes about the docs\n\
+                       \t\t- [x] Outline\n\
+                       \t- [ ] Ship\n\
+                       Paragraph\n\
+                       \n\
+                       - [ ] Next";
+        let tasks = TaskParser::extract_all_tasks(content);
+        let parents: Vec<(usize, Option<usize>)> = tasks
+            .iter()
+            .map(|task| (task.line_number, task.parent_line))
+            .collect();
+        assert_eq!(
+            parents,
+            vec![
+                (1, None),
+                (2, Some(1)),
+                (4, Some(2)),
+                (5, Some(1)),
+                (8, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_blocked_by() {
+        let line = "- [ ] Deploy blocked-by:: [[tid:0000-aaaa]], [[tid:0000-bbbb]] #ops";
+        let task = TaskParser::parse_line(line, 1).unwrap();
+        assert_eq!(
+            task.properties.get("blocked_by"),
+            Some(&"0000-aaaa,0000-bbbb".to_string())
+        );
+
+        // A plain task link is not a dependency
+        let task = TaskParser::parse_line("- [ ] See [[tid:0000-aaaa]]", 1).unwrap();
+        assert!(!task.properties.contains_key("blocked_by"));
+    }
+
+    #[test]
+    fn test_parse_estimate() {
+        let task = TaskParser::parse_line("- [ ] Write report estimate:: 2h #work", 1).unwrap();
+        assert_eq!(task.properties.get("estimate"), Some(&"120".to_string()));
+        assert_eq!(task.properties.get("tags"), Some(&"work".to_string()));
+
+        let task = TaskParser::parse_line("- [ ] Review estimate:: 1h 30m", 1).unwrap();
+        assert_eq!(task.properties.get("estimate"), Some(&"90".to_string()));
+
+        let task = TaskParser::parse_line("- [ ] Plan estimate:: soon", 1).unwrap();
+        assert!(!task.properties.contains_key("estimate"));
+    }
+
+    #[test]
+    fn test_update_task_properties() {
+        let line = "- [ ] Write report @due(2025-06-13) !p4 @project(old) #draft #work <!-- tid: 0000-aaaa -->";
+        let updates = HashMap::from([
+            ("due".to_string(), Some("2025-06-20".to_string())),
+            ("priority".to_string(), Some("high".to_string())),
+            ("project".to_string(), Some("Alpha".to_string())),
+            ("estimate".to_string(), Some("2h".to_string())),
+            ("tags".to_string(), Some("work, #review".to_string())),
+        ]);
+        let updated = TaskParser::update_task_properties(line, &updates);
+        assert_eq!(
+            updated,
+            "- [ ] Write report @due(2025-06-20) !p1 @project(Alpha) estimate:: 2h #work #review <!-- tid: 0000-aaaa -->"
+        );
+
+        let task = TaskParser::parse_line(&updated, 1).unwrap();
+        assert_eq!(task.id.as_deref(), Some("0000-aaaa"));
+        assert_eq!(task.properties.get("priority"), Some(&"high".to_string()));
+        assert_eq!(task.properties.get("estimate"), Some(&"120".to_string()));
+
+        let cleared = TaskParser::update_task_properties(
+            &updated,
+            &HashMap::from([
+                ("due".to_string(), None),
+                ("project".to_string(), None),
+                ("tags".to_string(), None),
+            ]),
+        );
+        assert_eq!(
+            cleared,
+            "- [ ] Write report !p1 estimate:: 2h <!-- tid: 0000-aaaa -->"
+        );
+    }
 }
@@ -0,0 +1,284 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use std::fmt;
+
+/// How often a recurring task repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A repeat rule, written on tasks as `🔁 every week on Monday` or
+/// `@repeat(monthly)` and stored in RRULE form (`FREQ=WEEKLY;BYDAY=MO`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// Days of the week for weekly rules, Monday first
+    pub weekdays: Vec<Weekday>,
+    /// Day of the month for monthly rules
+    pub month_day: Option<u32>,
+}
+
+impl Recurrence {
+    fn new(frequency: Frequency, interval: u32) -> Self {
+        Self {
+            frequency,
+            interval,
+            weekdays: Vec::new(),
+            month_day: None,
+        }
+    }
+
+    /// Parse a rule written in plain English (`every 2 weeks on Mon, Fri`,
+    /// `monthly on the 15th`, `every weekday`) or as an RRULE
+    pub fn parse(rule: &str) -> Option<Self> {
+        let rule = rule.trim();
+        let upper = rule.to_uppercase();
+        if upper.starts_with("RRULE:") || upper.starts_with("FREQ=") {
+            return Self::parse_rrule(upper.trim_start_matches("RRULE:"));
+        }
+        Self::parse_english(&rule.to_lowercase())
+    }
+
+    fn parse_rrule(rule: &str) -> Option<Self> {
+        let mut recurrence: Option<Self> = None;
+        let mut interval = 1;
+        let mut weekdays = Vec::new();
+        let mut month_day = None;
+
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            match key.trim() {
+                "FREQ" => {
+                    let frequency = match value.trim() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return None,
+                    };
+                    recurrence = Some(Self::new(frequency, 1));
+                }
+                "INTERVAL" => interval = value.trim().parse().ok().filter(|n| *n > 0)?,
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        weekdays.push(parse_weekday(day.trim())?);
+                    }
+                }
+                "BYMONTHDAY" => month_day = parse_month_day(value.trim()),
+                _ => {}
+            }
+        }
+
+        let mut recurrence = recurrence?;
+        recurrence.interval = interval;
+        recurrence.set_weekdays(weekdays);
+        recurrence.month_day = month_day.filter(|_| recurrence.frequency == Frequency::Monthly);
+        Some(recurrence)
+    }
+
+    fn parse_english(rule: &str) -> Option<Self> {
+        let rule = rule.strip_prefix("every").unwrap_or(rule).trim();
+        let (period, on) = match rule.split_once(" on ") {
+            Some((period, on)) => (period.trim(), Some(on.trim())),
+            None => (rule, None),
+        };
+
+        // `every monday`, `every mon, thu`
+        if let Some(weekdays) = parse_weekday_list(period).filter(|_| on.is_none()) {
+            let mut recurrence = Self::new(Frequency::Weekly, 1);
+            recurrence.set_weekdays(weekdays);
+            return Some(recurrence);
+        }
+
+        let words: Vec<&str> = period.split_whitespace().collect();
+        let (interval, unit) = match words.as_slice() {
+            [unit] => (1, *unit),
+            ["other", unit] => (2, *unit),
+            [count, unit] => (count.parse().ok().filter(|n| *n > 0)?, *unit),
+            _ => return None,
+        };
+
+        let mut recurrence = match unit {
+            "day" | "days" | "daily" => Self::new(Frequency::Daily, interval),
+            "week" | "weeks" | "weekly" => Self::new(Frequency::Weekly, interval),
+            "month" | "months" | "monthly" => Self::new(Frequency::Monthly, interval),
+            "year" | "years" | "yearly" | "annually" => Self::new(Frequency::Yearly, interval),
+            "weekday" | "weekdays" => {
+                let mut recurrence = Self::new(Frequency::Weekly, interval);
+                recurrence.set_weekdays(vec![
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ]);
+                recurrence
+            }
+            _ => return None,
+        };
+
+        if let Some(on) = on {
+            match recurrence.frequency {
+                Frequency::Weekly if recurrence.weekdays.is_empty() => {
+                    recurrence.set_weekdays(parse_weekday_list(on)?)
+                }
+                Frequency::Monthly => {
+                    let day = on.trim_start_matches("the").trim();
+                    recurrence.month_day = Some(parse_month_day(day)?);
+                }
+                _ => return None,
+            }
+        }
+        Some(recurrence)
+    }
+
+    /// A monthly rule as written (`monthly`, `FREQ=MONTHLY`) with `day` of
+    /// the month added
+    pub fn on_month_day(rule: &str, day: u32) -> String {
+        let upper = rule.to_uppercase();
+        if upper.starts_with("RRULE:") || upper.starts_with("FREQ=") {
+            return format!("{};BYMONTHDAY={}", rule.trim_end_matches(';'), day);
+        }
+        let suffix = match day {
+            1 | 21 | 31 => "st",
+            2 | 22 => "nd",
+            3 | 23 => "rd",
+            _ => "th",
+        };
+        format!("{} on the {}{}", rule, day, suffix)
+    }
+
+    fn set_weekdays(&mut self, mut weekdays: Vec<Weekday>) {
+        weekdays.sort_by_key(|day| day.num_days_from_monday());
+        weekdays.dedup();
+        self.weekdays = weekdays;
+    }
+
+    /// The first occurrence after `date`
+    pub fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        let interval = self.interval.max(1);
+        match self.frequency {
+            Frequency::Daily => date + Duration::days(interval as i64),
+            Frequency::Weekly if self.weekdays.is_empty() => {
+                date + Duration::weeks(interval as i64)
+            }
+            Frequency::Weekly => {
+                let offset = date.weekday().num_days_from_monday();
+                // A later day in the same week, otherwise the first day of
+                // the week `interval` weeks on
+                if let Some(day) = self
+                    .weekdays
+                    .iter()
+                    .find(|day| day.num_days_from_monday() > offset)
+                {
+                    return date + Duration::days((day.num_days_from_monday() - offset) as i64);
+                }
+                let week_start = date - Duration::days(offset as i64);
+                week_start
+                    + Duration::weeks(interval as i64)
+                    + Duration::days(self.weekdays[0].num_days_from_monday() as i64)
+            }
+            Frequency::Monthly => {
+                let day = self.month_day.unwrap_or(date.day());
+                if day > date.day() {
+                    let this_month = with_day_clamped(date, day);
+                    if this_month > date {
+                        return this_month;
+                    }
+                }
+                add_months(date, interval, day)
+            }
+            Frequency::Yearly => add_months(date, interval * 12, date.day()),
+        }
+    }
+}
+
+/// RRULE form of the rule, used as the stored and queried value
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self.weekdays.iter().map(|day| weekday_code(*day)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(day) = self.month_day {
+            write!(f, ";BYMONTHDAY={}", day)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    let day = day.trim().trim_end_matches('s').to_lowercase();
+    let day = match day.as_str() {
+        "mo" | "mon" | "monday" => Weekday::Mon,
+        "tu" | "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "we" | "wed" | "wednesday" => Weekday::Wed,
+        "th" | "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fr" | "fri" | "friday" => Weekday::Fri,
+        "sa" | "sat" | "saturday" => Weekday::Sat,
+        "su" | "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(day)
+}
+
+/// `monday, wednesday and friday`
+fn parse_weekday_list(list: &str) -> Option<Vec<Weekday>> {
+    list.replace(" and ", ",")
+        .split(',')
+        .map(str::trim)
+        .filter(|day| !day.is_empty())
+        .map(parse_weekday)
+        .collect::<Option<Vec<_>>>()
+        .filter(|days| !days.is_empty())
+}
+
+/// `15`, `15th`, `1st`
+fn parse_month_day(day: &str) -> Option<u32> {
+    day.trim_end_matches(|c: char| c.is_ascii_alphabetic())
+        .parse()
+        .ok()
+        .filter(|day| (1..=31).contains(day))
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// `date` moved by `months`, on `day` or the last day of a shorter month
+fn add_months(date: NaiveDate, months: u32, day: u32) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date);
+    let target = first
+        .checked_add_months(Months::new(months))
+        .unwrap_or(first);
+    with_day_clamped(target, day)
+}
+
+fn with_day_clamped(date: NaiveDate, day: u32) -> NaiveDate {
+    (1..=day)
+        .rev()
+        .find_map(|day| date.with_day(day))
+        .unwrap_or(date)
+}
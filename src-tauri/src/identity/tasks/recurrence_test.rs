@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use super::super::recurrence::*;
+    use chrono::{NaiveDate, Weekday};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn rrule(rule: &str) -> String {
+        Recurrence::parse(rule).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_english_rules() {
+        assert_eq!(rrule("daily"), "FREQ=DAILY");
+        assert_eq!(rrule("every 3 days"), "FREQ=DAILY;INTERVAL=3");
+        assert_eq!(rrule("every week on Monday"), "FREQ=WEEKLY;BYDAY=MO");
+        assert_eq!(rrule("Every Friday and Monday"), "FREQ=WEEKLY;BYDAY=MO,FR");
+        assert_eq!(
+            rrule("every other week on tue, thu"),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH"
+        );
+        assert_eq!(rrule("every weekday"), "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR");
+        assert_eq!(rrule("monthly"), "FREQ=MONTHLY");
+        assert_eq!(
+            rrule("every month on the 15th"),
+            "FREQ=MONTHLY;BYMONTHDAY=15"
+        );
+        assert_eq!(rrule("every 2 years"), "FREQ=YEARLY;INTERVAL=2");
+    }
+
+    #[test]
+    fn test_parse_rrule() {
+        let recurrence = Recurrence::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=WE,MO").unwrap();
+        assert_eq!(recurrence.frequency, Frequency::Weekly);
+        assert_eq!(recurrence.interval, 2);
+        assert_eq!(recurrence.weekdays, vec![Weekday::Mon, Weekday::Wed]);
+        assert_eq!(
+            rrule("freq=monthly;bymonthday=31"),
+            "FREQ=MONTHLY;BYMONTHDAY=31"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_rules() {
+        assert!(Recurrence::parse("").is_none());
+        assert!(Recurrence::parse("sometimes").is_none());
+        assert!(Recurrence::parse("every 0 days").is_none());
+        assert!(Recurrence::parse("every day on Monday").is_none());
+        assert!(Recurrence::parse("every month on the 40th").is_none());
+        assert!(Recurrence::parse("FREQ=HOURLY").is_none());
+    }
+
+    #[test]
+    fn test_next_occurrence_dates() {
+        let next = |rule: &str, from: NaiveDate| Recurrence::parse(rule).unwrap().next_after(from);
+
+        // 2025-01-06 is a Monday
+        assert_eq!(next("daily", date(2025, 1, 6)), date(2025, 1, 7));
+        assert_eq!(next("weekly", date(2025, 1, 6)), date(2025, 1, 13));
+        assert_eq!(
+            next("every week on Monday", date(2025, 1, 6)),
+            date(2025, 1, 13)
+        );
+        assert_eq!(
+            next("every week on Monday", date(2025, 1, 8)),
+            date(2025, 1, 13)
+        );
+        assert_eq!(next("every mon, fri", date(2025, 1, 6)), date(2025, 1, 10));
+        assert_eq!(next("every weekday", date(2025, 1, 10)), date(2025, 1, 13));
+        assert_eq!(
+            next("every 2 weeks on Monday, Wednesday", date(2025, 1, 8)),
+            date(2025, 1, 20)
+        );
+
+        // Month ends are clamped to shorter months
+        assert_eq!(next("monthly", date(2025, 1, 31)), date(2025, 2, 28));
+        assert_eq!(
+            next("every month on the 31st", date(2025, 2, 28)),
+            date(2025, 3, 31)
+        );
+        assert_eq!(
+            next("every month on the 15th", date(2025, 1, 10)),
+            date(2025, 1, 15)
+        );
+        assert_eq!(
+            next("every month on the 15th", date(2025, 1, 15)),
+            date(2025, 2, 15)
+        );
+        assert_eq!(next("yearly", date(2024, 2, 29)), date(2025, 2, 28));
+    }
+}
@@ -226,3 +226,63 @@ fn test_deeply_nested_paths() {
     // Verify retrieval works
     assert_eq!(manager.get_note_id(&file_path).unwrap(), Some(id));
 }
+
+#[tokio::test]
+async fn test_warm_start_task_index_reparses_only_changed_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let vault = temp_dir.path().join("vault");
+    let snapshot_path = temp_dir.path().join("data").join("tasks.bin");
+    fs::create_dir_all(&vault).unwrap();
+
+    let kept = vault.join("kept.md");
+    let touched = vault.join("touched.md");
+    let edited = vault.join("edited.md");
+    let deleted = vault.join("deleted.md");
+    fs::write(&kept, "- [ ] Keep <!-- tid: aaaa-0001 -->\n").unwrap();
+    fs::write(&touched, "- [ ] Touch <!-- tid: aaaa-0002 -->\n").unwrap();
+    fs::write(&edited, "- [ ] Edit <!-- tid: aaaa-0003 -->\n").unwrap();
+    fs::write(&deleted, "- [ ] Delete <!-- tid: aaaa-0004 -->\n").unwrap();
+
+    let manager = IdentityManager::new(vault.clone());
+    let stats = manager.warm_start_task_index(&snapshot_path).await.unwrap();
+    assert!(!stats.restored);
+    assert_eq!(stats.reparsed, 4);
+    assert!(snapshot_path.exists());
+
+    // Same content with a new mtime, changed content and a deleted file
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+    fs::File::options()
+        .write(true)
+        .open(&touched)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    fs::write(
+        &edited,
+        "- [x] Edit <!-- tid: aaaa-0003 -->\n- [ ] New <!-- tid: aaaa-0005 -->\n",
+    )
+    .unwrap();
+    fs::remove_file(&deleted).unwrap();
+
+    let manager = IdentityManager::new(vault.clone());
+    let stats = manager.warm_start_task_index(&snapshot_path).await.unwrap();
+    assert!(stats.restored);
+    assert_eq!(stats.unchanged, 2);
+    assert_eq!(stats.reparsed, 1);
+    assert_eq!(stats.removed, 1);
+
+    let index = manager.task_index();
+    assert_eq!(index.size().await, 4);
+    assert!(index.get_task("aaaa-0001").await.is_ok());
+    assert!(index.get_task("aaaa-0004").await.is_err());
+    assert_eq!(
+        index.get_task("aaaa-0003").await.unwrap().status,
+        TaskStatus::Done
+    );
+    index.verify_consistency().await.unwrap();
+
+    // A snapshot taken for another vault is ignored
+    let other = IdentityManager::new(temp_dir.path().to_path_buf());
+    let stats = other.warm_start_task_index(&snapshot_path).await.unwrap();
+    assert!(!stats.restored);
+}
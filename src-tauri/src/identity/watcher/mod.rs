@@ -52,6 +52,8 @@ pub struct IdentityWatcher {
     deletion_cache: Arc<RwLock<DeletionCache>>,
     config: WatcherConfig,
     vault_root: PathBuf,
+    /// Receives `(old, new)` for every confirmed rename, e.g. to offer a link refactor
+    rename_events: Option<mpsc::UnboundedSender<(PathBuf, PathBuf)>>,
 }
 
 impl IdentityWatcher {
@@ -69,9 +71,15 @@ impl IdentityWatcher {
             ))),
             config,
             vault_root,
+            rename_events: None,
         }
     }
 
+    /// Subscribe to confirmed renames (direct and heuristic)
+    pub fn set_rename_events(&mut self, sender: mpsc::UnboundedSender<(PathBuf, PathBuf)>) {
+        self.rename_events = Some(sender);
+    }
+
     /// Start watching the vault directory for file changes
     pub async fn watch(&mut self) -> Result<()> {
         let (tx, mut rx) = mpsc::channel(100);
@@ -142,9 +150,11 @@ impl IdentityWatcher {
                 println!("Rename detected: {:?} -> {:?}", old_path, new_path);
             }
 
-            // Update identity manager
-            let mut manager = self.identity_manager.write();
+            // Update identity manager. The manager shares its cache, so a
+            // clone can be updated without holding the lock across the await.
+            let mut manager = self.identity_manager.read().clone();
             manager.update_note_path(old_path, new_path).await?;
+            self.notify_rename(old_path, new_path);
         }
 
         Ok(())
@@ -200,8 +210,9 @@ impl IdentityWatcher {
                 // Verify with additional heuristics
                 if self.rename_detector.is_likely_rename(&old_metadata, path) {
                     // Update identity manager with the rename
-                    let mut manager = self.identity_manager.write();
+                    let mut manager = self.identity_manager.read().clone();
                     manager.update_note_path(&old_metadata.path, path).await?;
+                    self.notify_rename(&old_metadata.path, path);
 
                     // Remove from deletion cache
                     self.deletion_cache.write().remove(&old_metadata.path);
@@ -216,6 +227,12 @@ impl IdentityWatcher {
         Ok(())
     }
 
+    fn notify_rename(&self, old_path: &Path, new_path: &Path) {
+        if let Some(sender) = &self.rename_events {
+            let _ = sender.send((old_path.to_path_buf(), new_path.to_path_buf()));
+        }
+    }
+
     /// Get file size for fingerprinting
     fn get_file_size(&self, path: &Path) -> Option<u64> {
         std::fs::metadata(path).ok().map(|m| m.len())
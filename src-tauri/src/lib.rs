@@ -5,16 +5,23 @@ pub mod app_state;
 pub mod commands;
 pub mod csv;
 pub mod editor;
+pub mod graph;
 pub mod identity;
 pub mod license;
 pub mod mcp;
 pub mod pdf_intelligence;
+pub mod periodic;
 pub mod plugin_runtime;
+pub mod properties;
 pub mod refactored_app_state;
+pub mod semantic;
+pub mod tags;
 pub mod tasks;
+pub mod templates;
 pub mod vault;
 pub mod vault_agent_commands;
 pub mod vault_id;
+pub mod vault_settings;
 pub mod window_commands;
 pub mod window_commands_basic;
 pub mod window_factory;
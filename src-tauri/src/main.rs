@@ -19,16 +19,22 @@ mod auth;
 mod commands;
 mod csv;
 mod editor;
+mod graph;
 mod identity;
 mod license;
 mod mcp;
 mod mcp_settings;
 mod pdf_export;
 mod pdf_intelligence;
+mod periodic;
 mod plugin_runtime;
 mod plugins;
+mod properties;
 mod refactored_app_state;
+mod semantic;
+mod tags;
 mod tasks;
+mod templates;
 mod vault;
 mod vault_agent_commands;
 mod vault_id;
@@ -254,10 +260,18 @@ async fn open_vault(
     }
     println!("✅ IdentityManager reinitialization complete");
 
+    // Serve the task calendar feed if this vault has it turned on
+    commands::task_calendar_commands::resume_task_calendar_feed(&app).await;
+
     // Manually trigger task scanning after vault is open (avoiding deadlock)
     {
         println!("📚 Triggering manual task index population...");
         let vault_path_for_scan = vault_path.clone();
+        let snapshot_path = app
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| crate::tasks::snapshot_path(&dir, &vault_path));
         // Get the state before app is moved
         if let Some(identity_mgr) = app.try_state::<Arc<tokio::sync::Mutex<IdentityManager>>>() {
             let identity_mgr = identity_mgr.inner().clone();
@@ -269,11 +283,23 @@ async fn open_vault(
                     "🔍 Starting background task scan for vault: {:?}",
                     vault_path_for_scan
                 );
-                let manager = identity_mgr.lock().await;
-                if let Err(e) = manager.scan_vault_for_tasks_async().await {
-                    eprintln!("⚠️ Failed to scan vault for tasks: {}", e);
-                } else {
-                    println!("✅ Background task scan completed");
+                // Work on a clone that shares the task index, so task commands
+                // are not locked out while files are parsed
+                let manager = identity_mgr.lock().await.clone();
+                let Some(snapshot_path) = snapshot_path else {
+                    if let Err(e) = manager.scan_vault_for_tasks_async().await {
+                        eprintln!("⚠️ Failed to scan vault for tasks: {}", e);
+                    } else {
+                        println!("✅ Background task scan completed");
+                    }
+                    return;
+                };
+                match manager.warm_start_task_index(&snapshot_path).await {
+                    Ok(_) => {
+                        println!("✅ Background task scan completed");
+                        manager.verify_task_index_in_background(snapshot_path);
+                    }
+                    Err(e) => eprintln!("⚠️ Failed to scan vault for tasks: {}", e),
                 }
             });
         } else {
@@ -374,10 +400,22 @@ async fn start_file_watcher(
 
     // Register the window with the vault (which sets up file watching)
     refactored_state
-        .register_window_vault(&window_id, path)
+        .register_window_vault(&window_id, path.clone())
         .await?;
     println!("✅ File watcher started for: {}", vault_path);
 
+    // Keep links working when notes are renamed outside the app
+    window
+        .app_handle()
+        .state::<graph::LinkGraphState>()
+        .watch_renames(window.app_handle(), &path);
+
+    // Catch the semantic index up with changes made while the vault was closed
+    window
+        .app_handle()
+        .state::<semantic::SemanticIndexState>()
+        .refresh(window.app_handle(), &path);
+
     Ok(())
 }
 
@@ -734,6 +772,7 @@ async fn write_file_content(
                     let path = std::path::Path::new(&file_path);
 
                     // Update the updated_at timestamp if the file has frontmatter
+                    let mut property_fields = None;
                     let (updated_content, new_timestamp) =
                         if path.extension().and_then(|e| e.to_str()) == Some("md") {
                             use identity::frontmatter::{FrontMatterParser, FrontMatterWriter};
@@ -767,6 +806,12 @@ async fn write_file_content(
 
                             let body = strip_leading_frontmatter(raw_body);
 
+                            // Typed properties are checked once the note is
+                            // written; a mismatch never stops the save
+                            property_fields = existing_fm
+                                .as_ref()
+                                .map(|fm| fm.extra_fields.clone());
+
                             // If there's frontmatter with an ID, update the timestamp
                             if let Some(mut fm) = existing_fm {
                                 if fm.id.is_some() {
@@ -791,6 +836,10 @@ async fn write_file_content(
                         .write_file(path, &updated_content)
                         .map_err(|e| format!("Failed to write file: {}", e))?;
 
+                    if let Some(fields) = &property_fields {
+                        properties::report_property_errors(&window, vault.path(), path, fields);
+                    }
+
                     // Update the identity manager cache if needed
                     let full_path = vault.path().join(path);
                     if path.extension().and_then(|e| e.to_str()) == Some("md") {
@@ -838,13 +887,18 @@ async fn fetch_image_as_base64(url: String) -> Result<String, String> {
     Ok(format!("data:{};base64,{}", content_type, base64_string))
 }
 
+/// Create a note, from a template in the templates folder when one is
+/// given. Returns where the template put the cursor, in characters.
 #[tauri::command]
 async fn create_new_file(
     file_name: String,
+    template: Option<String>,
+    variables: Option<std::collections::HashMap<String, String>>,
+    app: tauri::AppHandle,
     window: tauri::Window,
     refactored_state: State<'_, RefactoredAppState>,
     identity_manager: State<'_, Arc<RwLock<IdentityManager>>>,
-) -> Result<(), String> {
+) -> Result<Option<usize>, String> {
     println!("📝 create_new_file called with name: {}", file_name);
     eprintln!("📝 create_new_file called with name: {}", file_name);
 
@@ -881,8 +935,31 @@ async fn create_new_file(
                         }
                     };
 
+                    let mut cursor = None;
                     // Create default content with frontmatter if UUID was generated
-                    let default_content = if !uuid.is_empty() {
+                    let default_content = if let Some(template) =
+                        template.as_deref().filter(|t| !t.trim().is_empty())
+                    {
+                        if uuid.is_empty() {
+                            return Err("Failed to generate UUID for new note".to_string());
+                        }
+                        let title = path
+                            .file_stem()
+                            .and_then(|s| s.to_str())
+                            .unwrap_or("Untitled");
+                        let rendered = templates::render_note_template(
+                            app,
+                            vault.path(),
+                            template,
+                            title,
+                            variables.unwrap_or_default(),
+                            &uuid,
+                        )
+                        .await?;
+                        println!("🧩 Rendered new file from template: {}", template);
+                        cursor = rendered.cursor;
+                        rendered.content
+                    } else if !uuid.is_empty() {
                         use crate::identity::frontmatter::{FrontMatter, FrontMatterWriter};
                         let now = chrono::Utc::now();
                         let mut front_matter = FrontMatter::new();
@@ -935,7 +1012,7 @@ async fn create_new_file(
                                 let _ = manager.get_note_id(&full_path);
                             }
 
-                            Ok(())
+                            Ok(cursor)
                         }
                         Err(e) => {
                             println!("❌ Failed to create file: {}", e);
@@ -1243,8 +1320,10 @@ async fn delete_folder(
 async fn move_file(
     old_path: String,
     new_path: String,
+    update_links: Option<bool>,
     window: tauri::Window,
     refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, graph::LinkGraphState>,
 ) -> Result<(), String> {
     println!("📦 move_file called: {} -> {}", old_path, new_path);
 
@@ -1266,10 +1345,21 @@ async fn move_file(
                             .map_err(|e| format!("Failed to create parent directory: {}", e))?;
                     }
 
-                    std::fs::rename(&old_full_path, &new_full_path).map_err(|e| {
-                        println!("❌ Failed to move file: {}", e);
-                        format!("Failed to move file: {}", e)
-                    })
+                    // Optionally rewrite links that pointed at the old location
+                    graph_state
+                        .move_note(
+                            vault.path(),
+                            &old_path,
+                            &new_path,
+                            update_links.unwrap_or(false),
+                            || {
+                                std::fs::rename(&old_full_path, &new_full_path).map_err(|e| {
+                                    println!("❌ Failed to move file: {}", e);
+                                    format!("Failed to move file: {}", e)
+                                })
+                            },
+                        )
+                        .await
                 }
                 None => Err("No vault opened".to_string()),
             }
@@ -1282,8 +1372,10 @@ async fn move_file(
 async fn rename_file(
     old_path: String,
     new_path: String,
+    update_links: Option<bool>,
     window: tauri::Window,
     refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, graph::LinkGraphState>,
 ) -> Result<(), String> {
     println!("✏️ rename_file called: {} -> {}", old_path, new_path);
 
@@ -1308,10 +1400,21 @@ async fn rename_file(
                             .map_err(|e| format!("Failed to create parent directory: {}", e))?;
                     }
 
-                    std::fs::rename(&old_full_path, &new_full_path).map_err(|e| {
-                        println!("❌ Failed to rename file: {}", e);
-                        format!("Failed to rename file: {}", e)
-                    })
+                    // Optionally rewrite links that pointed at the old location
+                    graph_state
+                        .move_note(
+                            vault.path(),
+                            &old_path,
+                            &new_path,
+                            update_links.unwrap_or(false),
+                            || {
+                                std::fs::rename(&old_full_path, &new_full_path).map_err(|e| {
+                                    println!("❌ Failed to rename file: {}", e);
+                                    format!("Failed to rename file: {}", e)
+                                })
+                            },
+                        )
+                        .await
                 }
                 None => Err("No vault opened".to_string()),
             }
@@ -2018,12 +2121,42 @@ fn main() {
             get_widget_settings,
             save_widget_settings,
             commands::task_index_commands::query_tasks,
+            commands::task_index_commands::run_task_query_blocks,
             commands::task_index_commands::query_tasks_by_status,
             commands::task_index_commands::query_tasks_today,
             commands::task_index_commands::query_tasks_overdue,
             commands::task_index_commands::query_tasks_by_date_range,
             commands::task_index_commands::get_task_source_by_id,
+            commands::task_index_commands::get_task_children,
+            commands::task_index_commands::get_task_blockers,
+            commands::task_index_commands::get_task_dependents,
+            commands::task_index_commands::get_task_progress,
+            commands::task_index_commands::find_task_dependency_cycles,
             commands::task_index_commands::sync_file_tasks_to_index,
+            commands::task_calendar_commands::export_tasks_ics,
+            commands::task_calendar_commands::start_task_calendar_feed,
+            commands::task_calendar_commands::stop_task_calendar_feed,
+            commands::task_calendar_commands::get_task_calendar_feed,
+            commands::task_import_commands::import_tasks,
+            commands::task_time_commands::start_task_timer,
+            commands::task_time_commands::stop_task_timer,
+            commands::task_time_commands::get_task_time_stats,
+            commands::task_time_commands::export_task_time_csv,
+            commands::task_bulk_commands::bulk_update_tasks,
+            commands::task_bulk_commands::list_bulk_task_journals,
+            commands::task_bulk_commands::undo_bulk_task_edit,
+            commands::task_bulk_commands::archive_completed_tasks,
+            commands::task_board_commands::load_task_board,
+            commands::task_board_commands::move_task_board_card,
+            periodic::open_periodic_note,
+            periodic::get_adjacent_periodic_note,
+            templates::list_templates,
+            templates::get_template_prompts,
+            properties::get_property_schema,
+            properties::save_property_schema,
+            properties::delete_property_schema,
+            properties::get_note_properties,
+            properties::update_note_property,
             commands::task_commands::toggle_task_status,
             commands::task_commands::toggle_task_by_id,
             commands::task_commands::open_file_at_line,
@@ -2032,6 +2165,8 @@ fn main() {
             commands::wikilink::get_vault_notes,
             commands::wikilink::resolve_wikilink,
             commands::wikilink::create_note_from_wikilink,
+            commands::wikilink::find_unlinked_mentions,
+            commands::wikilink::link_unlinked_mentions,
             // UUID identity commands
             commands::uuid_commands::get_note_uuid,
             commands::uuid_commands::ensure_note_uuid,
@@ -2115,6 +2250,9 @@ fn main() {
             vault_agent_commands::agent_list_tags,
             vault_agent_commands::agent_notes_by_tag,
             vault_agent_commands::agent_semantic_search,
+            semantic::get_embedding_endpoint,
+            vault_agent_commands::agent_find_unlinked_mentions,
+            vault_agent_commands::agent_link_mentions,
             // CSV Editor Pro commands
             csv::list_csv_files,
             csv::read_csv_data,
@@ -2125,6 +2263,19 @@ fn main() {
             csv::get_csv_ai_context,
             csv::get_csv_statistics,
             csv::export_to_file,
+            // Link graph commands
+            graph::get_note_links,
+            graph::get_outgoing_links,
+            graph::get_backlinks,
+            graph::get_unresolved_links,
+            graph::get_task_backlinks,
+            graph::get_note_neighbors,
+            graph::rebuild_link_graph,
+            graph::preview_rename_links,
+            graph::apply_rename_links,
+            graph::create_block_id,
+            graph::get_vault_health,
+            graph::fix_vault_health_issue,
         ])
         .setup(|app| {
             // Create MCP manager with app handle
@@ -2207,6 +2358,16 @@ fn main() {
                     .expect("Failed to create RefactoredAppState");
             app.manage(refactored_app_state);
 
+            // Manage per-vault link graphs for backlink commands
+            app.manage(graph::LinkGraphState::new());
+
+            // Manage per-vault tag indexes, updated from the vault file watcher
+            app.manage(tags::TagIndexState::new());
+            app.manage(semantic::SemanticIndexState::new());
+
+            // Manage the loopback calendar feed of the open vault's tasks
+            app.manage(commands::task_calendar_commands::TaskCalendarFeedState::new());
+
             // Run AI settings migration on startup
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -0,0 +1,213 @@
+//! Tauri commands for periodic notes
+//!
+//! Formats, templates and task rollover come from the vault settings. Notes
+//! are written through the same atomic writes as bulk task edits, and the
+//! task index is kept up to date with the notes tasks roll over between.
+
+use chrono::{Local, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use super::{adjacent_note, Direction, Period, PeriodicNoteFormat};
+use crate::identity::uuid::UuidGenerator;
+use crate::identity::IdentityManager;
+use crate::tasks::{apply_file_edits, plan_bulk_edit, BulkOperation, FileEdit, TaskRecord};
+use crate::templates::{TemplateContext, TemplateEngine};
+use crate::vault_settings::{get_vault_settings, VaultSettings};
+
+/// A periodic note, as opened or found by navigating
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodicNote {
+    pub period: Period,
+    /// Vault-relative path
+    pub path: String,
+    pub title: String,
+    /// First and last day of the period
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// The note was made by this call
+    pub created: bool,
+    /// Unfinished tasks moved in from the previous note
+    pub rolled_over: usize,
+}
+
+impl PeriodicNote {
+    fn new(format: &PeriodicNoteFormat, start: NaiveDate, path: String) -> Self {
+        let title = Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Self {
+            period: format.period,
+            title,
+            start,
+            end: format.period.end(start),
+            path,
+            created: false,
+            rolled_over: 0,
+        }
+    }
+}
+
+fn parse_day(date: Option<&str>) -> Result<NaiveDate, String> {
+    match date.map(str::trim).filter(|date| !date.is_empty()) {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date: {}", date)),
+        None => Ok(Local::now().date_naive()),
+    }
+}
+
+async fn vault_settings(app: AppHandle, vault_root: &Path) -> VaultSettings {
+    match get_vault_settings(app, vault_root.to_string_lossy().to_string()).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("⚠️ Failed to load vault settings, using defaults: {}", e);
+            VaultSettings::default()
+        }
+    }
+}
+
+/// Move the unfinished tasks of `previous` to the end of `target`, or of
+/// its rollover heading. Returns the number of tasks moved.
+async fn roll_over_tasks(
+    manager: &mut IdentityManager,
+    previous: &str,
+    target: &str,
+    heading: &str,
+) -> Result<usize, String> {
+    let vault_root = manager.vault_root().to_path_buf();
+    let previous = vault_root.join(previous);
+    // Tasks without an ID get one first, so every task keeps the same ID
+    // in its new note
+    manager
+        .batch_ensure_task_ids(&previous)
+        .map_err(|e| format!("Failed to assign task IDs: {}", e))?;
+    manager
+        .sync_file_tasks_to_index_async(&previous)
+        .await
+        .map_err(|e| format!("Failed to sync tasks to index: {}", e))?;
+
+    let tasks: Vec<TaskRecord> = manager
+        .task_index()
+        .get_tasks_by_file(&previous)
+        .await
+        .into_iter()
+        .filter(|task| !task.status.is_closed() && !task.archived)
+        .collect();
+    if tasks.is_empty() {
+        return Ok(0);
+    }
+
+    let operation = BulkOperation::Move {
+        target: target.to_string(),
+        section: Some(heading.trim().to_string()).filter(|heading| !heading.is_empty()),
+    };
+    let plan = plan_bulk_edit(&tasks, &operation, &vault_root, &Local::now())
+        .map_err(|e| format!("Failed to plan task rollover: {}", e))?;
+    let written = apply_file_edits(&vault_root, &plan.files)
+        .map_err(|e| format!("Failed to roll over tasks: {}", e))?;
+    for path in &written {
+        manager
+            .sync_file_tasks_to_index_async(path)
+            .await
+            .map_err(|e| format!("Failed to sync tasks to index: {}", e))?;
+    }
+    Ok(plan.changes.len())
+}
+
+/// Open the note of a period, `date` (YYYY-MM-DD) falls in or today's,
+/// making it from its template when it does not exist yet
+#[tauri::command]
+pub async fn open_periodic_note(
+    app: AppHandle,
+    period: Period,
+    date: Option<String>,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<PeriodicNote, String> {
+    let start = period.start(parse_day(date.as_deref())?);
+    let mut manager = identity_manager.lock().await;
+    let vault_root = manager.vault_root().to_path_buf();
+    let settings = vault_settings(app, &vault_root).await;
+    let format = PeriodicNoteFormat::from_settings(&settings, period);
+    let path = format.path(start);
+    let mut note = PeriodicNote::new(&format, start, path.clone());
+    if vault_root.join(&path).exists() {
+        return Ok(note);
+    }
+
+    let template = match &format.template {
+        Some(template) => std::fs::read_to_string(vault_root.join(template))
+            .map_err(|e| format!("Failed to read template {}: {}", template, e))?,
+        None => "# {{title}}\n".to_string(),
+    };
+    let id = UuidGenerator::new()
+        .generate()
+        .map_err(|e| format!("Failed to generate note ID: {}", e))?;
+    let context = TemplateContext {
+        title: note.title.clone(),
+        date: start,
+        time: Local::now().time(),
+        variables: HashMap::new(),
+    };
+    let content = TemplateEngine::new(&vault_root, &settings.files.templates_folder)
+        .render(&template, &context, &id, Utc::now())
+        .map_err(|e| format!("Failed to render note: {}", e))?
+        .content;
+    // The previous note is looked up before this one exists
+    let previous = adjacent_note(&format.list_notes(&vault_root), start, Direction::Previous)
+        .map(|(_, previous)| previous.clone());
+
+    let edit = FileEdit {
+        path: path.clone(),
+        before: None,
+        after: content,
+    };
+    apply_file_edits(&vault_root, &[edit]).map_err(|e| format!("Failed to create note: {}", e))?;
+    note.created = true;
+    println!("📅 Created {} note {}", period.label(), path);
+
+    let periodic_notes = &settings.periodic_notes;
+    if let Some(previous) = previous.filter(|_| periodic_notes.roll_over_tasks) {
+        note.rolled_over = roll_over_tasks(
+            &mut manager,
+            &previous,
+            &path,
+            &periodic_notes.roll_over_heading,
+        )
+        .await?;
+        println!(
+            "↪️ Rolled {} unfinished tasks over from {}",
+            note.rolled_over, previous
+        );
+    }
+    manager
+        .sync_file_tasks_to_index_async(&vault_root.join(&path))
+        .await
+        .map_err(|e| format!("Failed to sync tasks to index: {}", e))?;
+    Ok(note)
+}
+
+/// The nearest existing note of a period before or after the one `date`
+/// falls in, `None` when there is none that way
+#[tauri::command]
+pub async fn get_adjacent_periodic_note(
+    app: AppHandle,
+    period: Period,
+    date: Option<String>,
+    direction: Direction,
+    identity_manager: State<'_, Arc<Mutex<IdentityManager>>>,
+) -> Result<Option<PeriodicNote>, String> {
+    let start = period.start(parse_day(date.as_deref())?);
+    let vault_root = identity_manager.lock().await.vault_root().to_path_buf();
+    let settings = vault_settings(app, &vault_root).await;
+    let format = PeriodicNoteFormat::from_settings(&settings, period);
+
+    let notes = format.list_notes(&vault_root);
+    Ok(adjacent_note(&notes, start, direction)
+        .map(|(start, path)| PeriodicNote::new(&format, *start, path.clone())))
+}
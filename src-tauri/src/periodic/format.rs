@@ -0,0 +1,173 @@
+//! Date patterns for note names
+//!
+//! Patterns use the tokens of the daily notes plugins users come from:
+//! `YYYY` `YY` year, `GGGG` ISO week year, `Q` quarter, `MMMM` `MMM` `MM`
+//! `M` month, `DD` `D` day, `dddd` `ddd` weekday and `WW` `W` ISO week.
+//! Text in square brackets is kept as is, so `GGGG-[W]WW` gives `2025-W07`.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Year,
+    ShortYear,
+    IsoYear,
+    Quarter,
+    MonthName,
+    MonthShort,
+    Month2,
+    Month,
+    Day2,
+    Day,
+    Weekday,
+    WeekdayShort,
+    Week2,
+    Week,
+}
+
+/// Longest tokens first, so `MMMM` is not read as two `MM`
+const TOKENS: &[(&str, Token)] = &[
+    ("YYYY", Token::Year),
+    ("GGGG", Token::IsoYear),
+    ("MMMM", Token::MonthName),
+    ("dddd", Token::Weekday),
+    ("MMM", Token::MonthShort),
+    ("ddd", Token::WeekdayShort),
+    ("YY", Token::ShortYear),
+    ("MM", Token::Month2),
+    ("DD", Token::Day2),
+    ("WW", Token::Week2),
+    ("M", Token::Month),
+    ("D", Token::Day),
+    ("W", Token::Week),
+    ("Q", Token::Quarter),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Token(Token),
+}
+
+fn tokenize(pattern: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if c == '[' {
+            if let Some(end) = rest.find(']') {
+                literal.push_str(&rest[1..end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        match TOKENS.iter().find(|(text, _)| rest.starts_with(text)) {
+            Some((text, token)) => {
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Token(*token));
+                rest = &rest[text.len()..];
+            }
+            None => {
+                literal.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    parts
+}
+
+/// Format a date with a pattern
+pub fn format_date(date: NaiveDate, pattern: &str) -> String {
+    tokenize(pattern)
+        .into_iter()
+        .map(|part| match part {
+            Part::Literal(text) => text,
+            Part::Token(token) => match token {
+                Token::Year => format!("{:04}", date.year()),
+                Token::ShortYear => format!("{:02}", date.year().rem_euclid(100)),
+                Token::IsoYear => format!("{:04}", date.iso_week().year()),
+                Token::Quarter => ((date.month() - 1) / 3 + 1).to_string(),
+                Token::MonthName => date.format("%B").to_string(),
+                Token::MonthShort => date.format("%b").to_string(),
+                Token::Month2 => format!("{:02}", date.month()),
+                Token::Month => date.month().to_string(),
+                Token::Day2 => format!("{:02}", date.day()),
+                Token::Day => date.day().to_string(),
+                Token::Weekday => date.format("%A").to_string(),
+                Token::WeekdayShort => date.format("%a").to_string(),
+                Token::Week2 => format!("{:02}", date.iso_week().week()),
+                Token::Week => date.iso_week().week().to_string(),
+            },
+        })
+        .collect()
+}
+
+/// Read the date back out of text a pattern made. Fields the pattern does
+/// not have are the first of their period: `YYYY-MM` gives the 1st of the
+/// month and a week gives its Monday.
+pub fn parse_date(text: &str, pattern: &str) -> Option<NaiveDate> {
+    let parts = tokenize(pattern);
+    let mut regex = String::from("^");
+    let mut tokens = Vec::new();
+    for part in &parts {
+        match part {
+            Part::Literal(literal) => regex.push_str(&regex::escape(literal)),
+            Part::Token(token) => {
+                regex.push_str(match token {
+                    Token::Year | Token::IsoYear => r"(\d{4})",
+                    Token::ShortYear | Token::Month2 | Token::Day2 | Token::Week2 => r"(\d{2})",
+                    Token::Month | Token::Day | Token::Week => r"(\d{1,2})",
+                    Token::Quarter => r"([1-4])",
+                    Token::MonthName | Token::MonthShort | Token::Weekday | Token::WeekdayShort => {
+                        r"(\p{L}+)"
+                    }
+                });
+                tokens.push(*token);
+            }
+        }
+    }
+    regex.push('$');
+    let caps = Regex::new(&regex).ok()?.captures(text)?;
+
+    let mut year = None;
+    let mut iso_year = None;
+    let mut month = None;
+    let mut day = None;
+    let mut week = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let value = &caps[i + 1];
+        let number = || value.parse::<u32>().ok();
+        match token {
+            Token::Year => year = Some(value.parse::<i32>().ok()?),
+            Token::ShortYear => year = Some(2000 + value.parse::<i32>().ok()?),
+            Token::IsoYear => iso_year = Some(value.parse::<i32>().ok()?),
+            Token::Quarter => month = Some((number()? - 1) * 3 + 1),
+            Token::MonthName | Token::MonthShort => {
+                let format = if *token == Token::MonthName {
+                    "%B"
+                } else {
+                    "%b"
+                };
+                month = Some((1..=12).find(|&m| {
+                    NaiveDate::from_ymd_opt(2000, m, 1)
+                        .is_some_and(|d| d.format(format).to_string().eq_ignore_ascii_case(value))
+                })?);
+            }
+            Token::Month2 | Token::Month => month = Some(number()?),
+            Token::Day2 | Token::Day => day = Some(number()?),
+            Token::Week2 | Token::Week => week = Some(number()?),
+            Token::Weekday | Token::WeekdayShort => {}
+        }
+    }
+
+    match week {
+        Some(week) => NaiveDate::from_isoywd_opt(iso_year.or(year)?, week, Weekday::Mon),
+        None => NaiveDate::from_ymd_opt(year.or(iso_year)?, month.unwrap_or(1), day.unwrap_or(1)),
+    }
+}
@@ -0,0 +1,217 @@
+//! Periodic notes
+//!
+//! Daily, weekly, monthly and quarterly notes, named from a date pattern
+//! (see [`format`]) in the daily notes folder or a folder of their own. A
+//! new note starts from its template (see [`crate::templates`], with
+//! `{{date}}` the first day of the period) and can take over the unfinished tasks
+//! of the previous note, which keep their UUIDs. Existing notes are found by
+//! reading their names back, so navigating skips periods without a note.
+
+pub mod commands;
+pub mod format;
+
+pub use commands::*;
+pub use format::{format_date, parse_date};
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+use walkdir::WalkDir;
+
+use crate::vault_settings::{PeriodicNotesSettings, VaultSettings};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Daily,
+    Weekly,
+    Monthly,
+    Quarterly,
+}
+
+impl Period {
+    pub const ALL: [Period; 4] = [
+        Period::Daily,
+        Period::Weekly,
+        Period::Monthly,
+        Period::Quarterly,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Period::Daily => "daily",
+            Period::Weekly => "weekly",
+            Period::Monthly => "monthly",
+            Period::Quarterly => "quarterly",
+        }
+    }
+
+    /// First day of the period a date falls in; weeks start on Monday
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Daily => date,
+            Period::Weekly => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            Period::Monthly => date.with_day(1).unwrap(),
+            Period::Quarterly => {
+                NaiveDate::from_ymd_opt(date.year(), (date.month() - 1) / 3 * 3 + 1, 1).unwrap()
+            }
+        }
+    }
+
+    /// Start of the period `count` periods away from the one `start` begins
+    pub fn shift(self, start: NaiveDate, count: i32) -> NaiveDate {
+        let months = |months: u32| Months::new(months * count.unsigned_abs());
+        match self {
+            Period::Daily => start + Duration::days(count.into()),
+            Period::Weekly => start + Duration::weeks(count.into()),
+            Period::Monthly | Period::Quarterly => {
+                let length = if self == Period::Monthly { 1 } else { 3 };
+                let shifted = if count < 0 {
+                    start.checked_sub_months(months(length))
+                } else {
+                    start.checked_add_months(months(length))
+                };
+                shifted.unwrap_or(start)
+            }
+        }
+    }
+
+    /// Last day of the period `start` begins
+    pub fn end(self, start: NaiveDate) -> NaiveDate {
+        self.shift(start, 1) - Duration::days(1)
+    }
+}
+
+/// Where the notes of a period live and what they are called
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodicNoteFormat {
+    pub period: Period,
+    /// Vault-relative folder, empty for the vault root
+    pub folder: String,
+    pub format: String,
+    /// Vault-relative template note, if any
+    pub template: Option<String>,
+}
+
+impl PeriodicNoteFormat {
+    pub fn from_settings(settings: &VaultSettings, period: Period) -> Self {
+        let notes = &settings.periodic_notes;
+        let note = match period {
+            Period::Daily => &notes.daily,
+            Period::Weekly => &notes.weekly,
+            Period::Monthly => &notes.monthly,
+            Period::Quarterly => &notes.quarterly,
+        };
+        let folder = match note.folder.trim() {
+            "" => settings.files.daily_notes_folder.as_str(),
+            folder => folder,
+        };
+        let template = note.template.trim().trim_start_matches('/');
+        Self {
+            period,
+            folder: folder.trim().trim_matches('/').replace('\\', "/"),
+            format: note.format.trim().to_string(),
+            template: (!template.is_empty()).then(|| template.replace('\\', "/")),
+        }
+    }
+
+    /// Vault-relative path of the note for the period starting on `start`
+    pub fn path(&self, start: NaiveDate) -> String {
+        let name = format!("{}.md", format_date(start, &self.format));
+        if self.folder.is_empty() {
+            name
+        } else {
+            format!("{}/{}", self.folder, name)
+        }
+    }
+
+    /// Start of the period a vault-relative path is the note of
+    pub fn start_of(&self, path: &str) -> Option<NaiveDate> {
+        let name = match self.folder.as_str() {
+            "" => path,
+            folder => path.strip_prefix(folder)?.strip_prefix('/')?,
+        };
+        let date = parse_date(name.strip_suffix(".md")?, &self.format)?;
+        let start = self.period.start(date);
+        (self.path(start) == path).then_some(start)
+    }
+
+    /// The period's existing notes, oldest first
+    pub fn list_notes(&self, vault_root: &Path) -> Vec<(NaiveDate, String)> {
+        let mut notes: Vec<(NaiveDate, String)> = WalkDir::new(vault_root.join(&self.folder))
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let relative = entry.path().strip_prefix(vault_root).ok()?;
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                Some((self.start_of(&relative)?, relative))
+            })
+            .collect();
+        notes.sort();
+        notes
+    }
+}
+
+/// Which way to go from a periodic note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Previous,
+    Next,
+}
+
+/// The nearest existing note before or after the period starting on
+/// `start`, from a list made by [`PeriodicNoteFormat::list_notes`]
+pub fn adjacent_note(
+    notes: &[(NaiveDate, String)],
+    start: NaiveDate,
+    direction: Direction,
+) -> Option<&(NaiveDate, String)> {
+    match direction {
+        Direction::Previous => notes.iter().rev().find(|(date, _)| *date < start),
+        Direction::Next => notes.iter().find(|(date, _)| *date > start),
+    }
+}
+
+/// Check that every period's pattern names each period differently and
+/// stays inside its folder
+pub fn validate_settings(settings: &PeriodicNotesSettings) -> Result<(), String> {
+    let samples = ["2024-12-30", "2025-01-01", "2025-02-28", "2025-11-17"];
+    let vault = VaultSettings {
+        periodic_notes: settings.clone(),
+        ..VaultSettings::default()
+    };
+    for period in Period::ALL {
+        let format = PeriodicNoteFormat::from_settings(&vault, period);
+        if format.format.is_empty() {
+            return Err(format!("The {} note format is empty", period.label()));
+        }
+        for sample in samples {
+            let start = period.start(NaiveDate::parse_from_str(sample, "%Y-%m-%d").unwrap());
+            let path = format.path(start);
+            let inside_folder = Path::new(&path)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+            if !inside_folder {
+                return Err(format!(
+                    "The {} note format {} leaves its folder",
+                    period.label(),
+                    format.format
+                ));
+            }
+            if format.start_of(&path) != Some(start) {
+                return Err(format!(
+                    "The {} note format {} does not tell {} notes apart",
+                    period.label(),
+                    format.format,
+                    period.label()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,169 @@
+use super::*;
+use crate::vault_settings::PeriodicNoteSettings;
+use std::fs;
+use tempfile::TempDir;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_format_date_tokens() {
+    let day = date("2025-02-07");
+    assert_eq!(format_date(day, "YYYY-MM-DD"), "2025-02-07");
+    assert_eq!(format_date(day, "dddd, MMMM D YY"), "Friday, February 7 25");
+    assert_eq!(format_date(day, "ddd MMM M/D"), "Fri Feb 2/7");
+    assert_eq!(format_date(day, "YYYY/[Q]Q/[Week] WW"), "2025/Q1/Week 06");
+
+    // ISO weeks belong to the year their Thursday is in
+    assert_eq!(format_date(date("2024-12-30"), "GGGG-[W]WW"), "2025-W01");
+    assert_eq!(format_date(date("2024-12-30"), "YYYY"), "2024");
+}
+
+#[test]
+fn test_parse_date_reads_patterns_back() {
+    assert_eq!(
+        parse_date("2025-02-07", "YYYY-MM-DD"),
+        Some(date("2025-02-07"))
+    );
+    assert_eq!(
+        parse_date("2025-W01", "GGGG-[W]WW"),
+        Some(date("2024-12-30"))
+    );
+    assert_eq!(parse_date("2025-Q3", "YYYY-[Q]Q"), Some(date("2025-07-01")));
+    assert_eq!(
+        parse_date("Friday, February 7 25", "dddd, MMMM D YY"),
+        Some(date("2025-02-07"))
+    );
+    assert_eq!(parse_date("2025-02", "YYYY-MM"), Some(date("2025-02-01")));
+
+    assert_eq!(parse_date("2025-02-30", "YYYY-MM-DD"), None);
+    assert_eq!(parse_date("Meeting notes", "YYYY-MM-DD"), None);
+    assert_eq!(parse_date("2025-02-07 copy", "YYYY-MM-DD"), None);
+}
+
+#[test]
+fn test_period_start_shift_and_end() {
+    let day = date("2025-05-15");
+    assert_eq!(Period::Daily.start(day), day);
+    assert_eq!(Period::Weekly.start(day), date("2025-05-12"));
+    assert_eq!(Period::Monthly.start(day), date("2025-05-01"));
+    assert_eq!(Period::Quarterly.start(day), date("2025-04-01"));
+
+    assert_eq!(
+        Period::Weekly.shift(date("2025-05-12"), -1),
+        date("2025-05-05")
+    );
+    assert_eq!(
+        Period::Monthly.shift(date("2025-01-01"), -1),
+        date("2024-12-01")
+    );
+    assert_eq!(
+        Period::Quarterly.shift(date("2025-10-01"), 1),
+        date("2026-01-01")
+    );
+
+    assert_eq!(Period::Weekly.end(date("2025-05-12")), date("2025-05-18"));
+    assert_eq!(Period::Monthly.end(date("2024-02-01")), date("2024-02-29"));
+    assert_eq!(
+        Period::Quarterly.end(date("2025-10-01")),
+        date("2025-12-31")
+    );
+}
+
+#[test]
+fn test_note_paths_use_daily_notes_folder() {
+    let mut settings = VaultSettings::default();
+    settings.files.daily_notes_folder = "Journal/".to_string();
+    settings.periodic_notes.weekly = PeriodicNoteSettings {
+        folder: "Journal/Weeks".to_string(),
+        format: "GGGG/[W]WW".to_string(),
+        template: "/Templates/Week.md".to_string(),
+    };
+
+    let daily = PeriodicNoteFormat::from_settings(&settings, Period::Daily);
+    assert_eq!(daily.path(date("2025-05-15")), "Journal/2025-05-15.md");
+    assert_eq!(daily.template, None);
+    assert_eq!(
+        daily.start_of("Journal/2025-05-15.md"),
+        Some(date("2025-05-15"))
+    );
+    assert_eq!(daily.start_of("Other/2025-05-15.md"), None);
+
+    let weekly = PeriodicNoteFormat::from_settings(&settings, Period::Weekly);
+    assert_eq!(weekly.path(date("2025-05-12")), "Journal/Weeks/2025/W20.md");
+    assert_eq!(weekly.template.as_deref(), Some("Templates/Week.md"));
+    assert_eq!(
+        weekly.start_of("Journal/Weeks/2025/W20.md"),
+        Some(date("2025-05-12"))
+    );
+}
+
+#[test]
+fn test_list_and_navigate_notes() {
+    let vault = TempDir::new().unwrap();
+    let root = vault.path();
+    for name in [
+        "2025-05-12",
+        "2025-05-09",
+        "2025-05-15",
+        "2025-5-16",
+        "Ideas",
+    ] {
+        write(root, &format!("Daily Notes/{}.md", name), "# Day\n");
+    }
+    write(root, "Daily Notes/2025-05-13.txt", "not a note");
+    write(root, "2025-05-14.md", "outside the folder");
+
+    let format = PeriodicNoteFormat::from_settings(&VaultSettings::default(), Period::Daily);
+    let notes = format.list_notes(root);
+    let paths: Vec<&str> = notes.iter().map(|(_, path)| path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "Daily Notes/2025-05-09.md",
+            "Daily Notes/2025-05-12.md",
+            "Daily Notes/2025-05-15.md"
+        ]
+    );
+
+    // Days without a note are skipped over
+    let previous = adjacent_note(&notes, date("2025-05-12"), Direction::Previous).unwrap();
+    assert_eq!(previous.0, date("2025-05-09"));
+    let next = adjacent_note(&notes, date("2025-05-13"), Direction::Next).unwrap();
+    assert_eq!(next.1, "Daily Notes/2025-05-15.md");
+    assert!(adjacent_note(&notes, date("2025-05-15"), Direction::Next).is_none());
+    assert!(adjacent_note(&notes, date("2025-05-09"), Direction::Previous).is_none());
+}
+
+#[test]
+fn test_validate_settings() {
+    assert!(validate_settings(&PeriodicNotesSettings::default()).is_ok());
+
+    let mut settings = PeriodicNotesSettings::default();
+    settings.daily.format = "YYYY-MM".to_string();
+    assert!(validate_settings(&settings).is_err());
+
+    // The calendar year names the first week of 2025 as week 1 of 2024
+    let mut settings = PeriodicNotesSettings::default();
+    settings.weekly.format = "YYYY-[W]WW".to_string();
+    assert!(validate_settings(&settings).is_err());
+
+    let mut settings = PeriodicNotesSettings::default();
+    settings.weekly.format = "GGGG/[Week] W".to_string();
+    assert!(validate_settings(&settings).is_ok());
+
+    let mut settings = PeriodicNotesSettings::default();
+    settings.monthly.format = "../YYYY-MM".to_string();
+    assert!(validate_settings(&settings).is_err());
+
+    let mut settings = PeriodicNotesSettings::default();
+    settings.quarterly.format = " ".to_string();
+    assert!(validate_settings(&settings).is_err());
+}
@@ -0,0 +1,222 @@
+// Graph API - Read and extend the note link graph from plugins
+// Backlinks, outgoing links and unresolved links need graph:read,
+// neighborhood walks need graph:query and adding links needs graph:write
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::graph::{GraphNeighbor, LinkEdge, LinkGraph, NoteNode};
+use crate::plugin_runtime::permissions::{Capability, Permission, PermissionManager};
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of hops a neighborhood query may walk
+const MAX_QUERY_DEPTH: usize = 5;
+
+/// Permissions for graph operations
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum GraphPermission {
+    Read,
+    Write,
+    Query,
+}
+
+/// Graph API errors
+#[derive(Debug, thiserror::Error)]
+pub enum GraphError {
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Note not found: {0}")]
+    NoteNotFound(String),
+
+    #[error("Invalid link target: {0}")]
+    InvalidTarget(String),
+
+    #[error("IO error: {0}")]
+    IoError(String),
+}
+
+/// Graph API implementation
+pub struct GraphApi {
+    vault_path: PathBuf,
+    permission_manager: Arc<RwLock<PermissionManager>>,
+    graph: Arc<Mutex<Option<LinkGraph>>>,
+}
+
+impl GraphApi {
+    /// Create a new Graph API instance
+    pub fn new(vault_path: PathBuf, permission_manager: Arc<RwLock<PermissionManager>>) -> Self {
+        Self {
+            vault_path,
+            permission_manager,
+            graph: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Grant a permission to a plugin (for testing)
+    #[cfg(test)]
+    pub async fn grant_permission(&self, plugin_id: &str, permission: GraphPermission) {
+        let perm = Permission {
+            capability: Self::capability(&permission),
+            granted: true,
+            granted_at: Some(chrono::Utc::now()),
+            expires_at: None,
+        };
+
+        let manager = self.permission_manager.read().await;
+        manager
+            .grant_permissions(plugin_id, vec![perm])
+            .await
+            .unwrap();
+    }
+
+    fn capability(permission: &GraphPermission) -> Capability {
+        match permission {
+            GraphPermission::Read => Capability::GraphRead,
+            GraphPermission::Write => Capability::GraphWrite,
+            GraphPermission::Query => Capability::GraphQuery,
+        }
+    }
+
+    /// Check if plugin has permission
+    async fn check_permission(
+        &self,
+        plugin_id: &str,
+        permission: GraphPermission,
+    ) -> Result<(), GraphError> {
+        let capability = Self::capability(&permission);
+        let manager = self.permission_manager.read().await;
+        if !manager.has_capability(plugin_id, &capability).await {
+            return Err(GraphError::PermissionDenied(format!(
+                "Plugin {} lacks permission: {:?}",
+                plugin_id, permission
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run `f` against the graph after bringing it up to date with the vault
+    async fn with_graph<T>(
+        &self,
+        f: impl FnOnce(&LinkGraph) -> Result<T, GraphError>,
+    ) -> Result<T, GraphError> {
+        let mut guard = self.graph.lock().await;
+        // Walking the vault blocks, so keep it off the async runtime
+        let cached = guard.take();
+        let vault_path = self.vault_path.clone();
+        let graph = tokio::task::spawn_blocking(move || {
+            let mut graph = cached.unwrap_or_else(|| LinkGraph::open(&vault_path));
+            graph.refresh().map(|_| graph)
+        })
+        .await
+        .map_err(|e| GraphError::IoError(e.to_string()))?
+        .map_err(|e| GraphError::IoError(e.to_string()))?;
+        f(guard.insert(graph))
+    }
+
+    fn find_note<'a>(graph: &'a LinkGraph, note: &str) -> Result<&'a NoteNode, GraphError> {
+        graph
+            .find_note(note)
+            .ok_or_else(|| GraphError::NoteNotFound(note.to_string()))
+    }
+
+    /// Links in other notes that point at `note` (UUID or relative path)
+    pub async fn get_backlinks(
+        &self,
+        plugin_id: &str,
+        note: &str,
+    ) -> Result<Vec<LinkEdge>, GraphError> {
+        self.check_permission(plugin_id, GraphPermission::Read)
+            .await?;
+        self.with_graph(|graph| Ok(graph.backlinks(Self::find_note(graph, note)?)))
+            .await
+    }
+
+    /// Links written in `note`, including embeds and task links
+    pub async fn get_outgoing_links(
+        &self,
+        plugin_id: &str,
+        note: &str,
+    ) -> Result<Vec<LinkEdge>, GraphError> {
+        self.check_permission(plugin_id, GraphPermission::Read)
+            .await?;
+        self.with_graph(|graph| Ok(graph.outgoing(Self::find_note(graph, note)?)))
+            .await
+    }
+
+    /// Links across the vault whose target does not exist
+    pub async fn get_unresolved_links(&self, plugin_id: &str) -> Result<Vec<LinkEdge>, GraphError> {
+        self.check_permission(plugin_id, GraphPermission::Read)
+            .await?;
+        self.with_graph(|graph| Ok(graph.unresolved())).await
+    }
+
+    /// Notes that reference a task with `[[tid:...]]`
+    pub async fn get_task_backlinks(
+        &self,
+        plugin_id: &str,
+        task_id: &str,
+    ) -> Result<Vec<LinkEdge>, GraphError> {
+        self.check_permission(plugin_id, GraphPermission::Read)
+            .await?;
+        self.with_graph(|graph| Ok(graph.task_backlinks(task_id)))
+            .await
+    }
+
+    /// Notes within `depth` hops of `note`, following links in both directions
+    pub async fn get_neighbors(
+        &self,
+        plugin_id: &str,
+        note: &str,
+        depth: usize,
+    ) -> Result<Vec<GraphNeighbor>, GraphError> {
+        self.check_permission(plugin_id, GraphPermission::Query)
+            .await?;
+        let depth = depth.clamp(1, MAX_QUERY_DEPTH);
+        self.with_graph(|graph| Ok(graph.neighbors(Self::find_note(graph, note)?, depth)))
+            .await
+    }
+
+    /// Append a `[[target]]` wikilink to the end of `source`
+    pub async fn add_link(
+        &self,
+        plugin_id: &str,
+        source: &str,
+        target: &str,
+    ) -> Result<(), GraphError> {
+        self.check_permission(plugin_id, GraphPermission::Write)
+            .await?;
+
+        if target.trim().is_empty() || target.contains(['[', ']', '|', '\n']) {
+            return Err(GraphError::InvalidTarget(target.to_string()));
+        }
+
+        let (source_path, target_title) = self
+            .with_graph(|graph| {
+                let source = Self::find_note(graph, source)?;
+                // Unknown targets are allowed and show up as unresolved links
+                let target_title = graph
+                    .find_note(target)
+                    .map(|note| note.title.clone())
+                    .unwrap_or_else(|| target.trim().to_string());
+                Ok((self.vault_path.join(&source.path), target_title))
+            })
+            .await?;
+
+        let mut content = tokio::fs::read_to_string(&source_path)
+            .await
+            .map_err(|e| GraphError::IoError(e.to_string()))?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("[[{}]]\n", target_title));
+
+        tokio::fs::write(&source_path, content)
+            .await
+            .map_err(|e| GraphError::IoError(e.to_string()))
+    }
+}
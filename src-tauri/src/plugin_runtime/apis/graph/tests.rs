@@ -0,0 +1,127 @@
+// Graph API Tests - Link graph access for plugins
+// Tests permission enforcement and graph reads/writes against a temp vault
+
+use super::*;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+#[cfg(test)]
+mod graph_api_tests {
+    use super::*;
+
+    // Helper function to create a test graph API over a small vault
+    async fn create_test_graph() -> (GraphApi, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        std::fs::write(
+            root.join("Hub.md"),
+            "---\nid: 22222222-2222-7222-8222-222222222222\n---\nLinks to [[Spoke]] and [[Nowhere]].\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("Spoke.md"), "Back to [[Hub]].").unwrap();
+        std::fs::write(root.join("Far.md"), "Only knows [[Spoke]].\n").unwrap();
+
+        let permission_manager = Arc::new(RwLock::new(PermissionManager::new()));
+        let graph_api = GraphApi::new(root.to_path_buf(), permission_manager);
+
+        (graph_api, temp_dir)
+    }
+
+    mod permissions {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_read_requires_graph_read() {
+            let (graph, _temp) = create_test_graph().await;
+
+            let result = graph.get_backlinks("test-plugin", "Hub").await;
+            assert!(matches!(result, Err(GraphError::PermissionDenied(_))));
+
+            graph
+                .grant_permission("test-plugin", GraphPermission::Read)
+                .await;
+            assert!(graph.get_backlinks("test-plugin", "Hub").await.is_ok());
+        }
+
+        #[tokio::test]
+        async fn test_read_does_not_grant_query_or_write() {
+            let (graph, _temp) = create_test_graph().await;
+            graph
+                .grant_permission("test-plugin", GraphPermission::Read)
+                .await;
+
+            let neighbors = graph.get_neighbors("test-plugin", "Hub", 1).await;
+            assert!(matches!(neighbors, Err(GraphError::PermissionDenied(_))));
+
+            let added = graph.add_link("test-plugin", "Far", "Hub").await;
+            assert!(matches!(added, Err(GraphError::PermissionDenied(_))));
+        }
+    }
+
+    mod graph_operations {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_backlinks_and_unresolved() {
+            let (graph, _temp) = create_test_graph().await;
+            graph
+                .grant_permission("test-plugin", GraphPermission::Read)
+                .await;
+
+            let backlinks = graph
+                .get_backlinks("test-plugin", "22222222-2222-7222-8222-222222222222")
+                .await
+                .unwrap();
+            assert_eq!(backlinks.len(), 1);
+            assert_eq!(backlinks[0].source_path, "Spoke.md");
+
+            let unresolved = graph.get_unresolved_links("test-plugin").await.unwrap();
+            assert_eq!(unresolved.len(), 1);
+            assert_eq!(unresolved[0].link.target, "Nowhere");
+
+            let missing = graph.get_outgoing_links("test-plugin", "Ghost").await;
+            assert!(matches!(missing, Err(GraphError::NoteNotFound(_))));
+        }
+
+        #[tokio::test]
+        async fn test_neighbors_with_query_permission() {
+            let (graph, _temp) = create_test_graph().await;
+            graph
+                .grant_permission("test-plugin", GraphPermission::Query)
+                .await;
+
+            let one_hop = graph.get_neighbors("test-plugin", "Hub", 1).await.unwrap();
+            assert_eq!(one_hop.len(), 1);
+            assert_eq!(one_hop[0].path, "Spoke.md");
+
+            let two_hops = graph.get_neighbors("test-plugin", "Hub", 2).await.unwrap();
+            assert_eq!(two_hops.len(), 2);
+            assert_eq!(two_hops[1].path, "Far.md");
+            assert_eq!(two_hops[1].distance, 2);
+        }
+
+        #[tokio::test]
+        async fn test_add_link_appends_wikilink() {
+            let (graph, temp) = create_test_graph().await;
+            graph
+                .grant_permission("test-plugin", GraphPermission::Write)
+                .await;
+            graph
+                .grant_permission("test-plugin", GraphPermission::Read)
+                .await;
+
+            graph.add_link("test-plugin", "Spoke", "far").await.unwrap();
+
+            let content = std::fs::read_to_string(temp.path().join("Spoke.md")).unwrap();
+            assert_eq!(content, "Back to [[Hub]].\n[[Far]]\n");
+
+            let backlinks = graph.get_backlinks("test-plugin", "Far").await.unwrap();
+            assert_eq!(backlinks.len(), 1);
+
+            let invalid = graph.add_link("test-plugin", "Spoke", "a]]b").await;
+            assert!(matches!(invalid, Err(GraphError::InvalidTarget(_))));
+        }
+    }
+}
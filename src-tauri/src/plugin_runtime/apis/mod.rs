@@ -1,6 +1,7 @@
 // Plugin APIs Module - Public APIs for plugin development
 // This module provides all the APIs that plugins can use to interact with Vault
 
+pub mod graph;
 pub mod mcp;
 pub mod network;
 pub mod settings;
@@ -8,6 +9,7 @@ pub mod vault;
 pub mod workspace;
 
 // Re-export main API types
+pub use graph::{GraphApi, GraphError};
 pub use mcp::McpApi;
 pub use network::NetworkApi;
 pub use settings::{SettingsApi, SettingsError};
@@ -17,6 +19,7 @@ pub use workspace::{WorkspaceApi, WorkspaceError};
 /// Main API manager that coordinates all plugin APIs
 pub struct ApiManager {
     pub vault: VaultApi,
+    pub graph: GraphApi,
     pub workspace: WorkspaceApi,
     pub settings: SettingsApi,
     pub mcp: McpApi,
@@ -33,7 +36,8 @@ impl ApiManager {
         >,
     ) -> Self {
         Self {
-            vault: VaultApi::new(vault_path, permission_manager.clone()),
+            vault: VaultApi::new(vault_path.clone(), permission_manager.clone()),
+            graph: GraphApi::new(vault_path, permission_manager.clone()),
             workspace: WorkspaceApi::new(permission_manager.clone()),
             settings: SettingsApi::new(settings_path, permission_manager.clone()),
             mcp: McpApi::new(permission_manager.clone()),
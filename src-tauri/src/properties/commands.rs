@@ -0,0 +1,206 @@
+//! Tauri commands for note properties
+//!
+//! Folder schemas are read and written by vault-relative folder path, empty
+//! for the vault root. Single properties of a note can be read and updated
+//! without the editor saving the whole note; only its front matter is
+//! rewritten.
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use tauri::{Emitter, State, Window};
+
+use super::{
+    note_property_errors, schema_for_note, store, PropertyError, PropertySchema,
+    RESERVED_PROPERTIES,
+};
+use crate::graph::commands::window_vault_path;
+use crate::identity::frontmatter::{FrontMatter, FrontMatterParser, FrontMatterWriter};
+use crate::refactored_app_state::RefactoredAppState;
+use crate::vault_agent_commands::validate_and_resolve_path;
+
+/// A note's properties with the schema they are read against
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteProperties {
+    /// Folder the schema comes from, if the note has one
+    pub schema_folder: Option<String>,
+    pub schema: Option<PropertySchema>,
+    /// Front matter values, apart from the keys the app manages
+    pub values: BTreeMap<String, Value>,
+    /// Values that do not fit the schema
+    pub errors: Vec<PropertyError>,
+}
+
+/// Sent to a window when a note it saved has properties that do not fit
+/// their schema, with no errors once they all fit again
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyErrorsEvent {
+    pub file_path: String,
+    pub errors: Vec<PropertyError>,
+}
+
+/// Check a saved note's properties and tell the window about values that do
+/// not fit the schema of its folder
+pub fn report_property_errors(
+    window: &Window,
+    vault_path: &Path,
+    note: &Path,
+    fields: &BTreeMap<String, Value>,
+) {
+    let Some(errors) = note_property_errors(vault_path, note, fields) else {
+        return;
+    };
+    if !errors.is_empty() {
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        eprintln!(
+            "⚠️ Saved {} with invalid properties: {}",
+            note.display(),
+            errors.join("; ")
+        );
+    }
+    let event = PropertyErrorsEvent {
+        file_path: note.to_string_lossy().replace('\\', "/"),
+        errors,
+    };
+    if let Err(e) = window.emit("note-property-errors", event) {
+        eprintln!("⚠️ Failed to report property errors: {}", e);
+    }
+}
+
+fn folder_path(vault_path: &Path, folder: &str) -> Result<PathBuf, String> {
+    let folder = folder.trim().trim_matches('/').replace('\\', "/");
+    let inside_vault = Path::new(&folder)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !inside_vault {
+        return Err(format!("Folder is outside the vault: {}", folder));
+    }
+    Ok(vault_path.join(folder))
+}
+
+fn read_note(path: &Path) -> Result<(Option<FrontMatter>, String), String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read note: {}", e))?;
+    FrontMatterParser::parse(&content).map_err(|e| format!("Failed to parse front matter: {}", e))
+}
+
+fn note_properties(
+    vault_path: &Path,
+    note: &Path,
+    fm: Option<FrontMatter>,
+) -> Result<NoteProperties, String> {
+    let found = schema_for_note(vault_path, note)
+        .map_err(|e| format!("Failed to load property schema: {}", e))?;
+    let mut values = fm.map(|fm| fm.extra_fields).unwrap_or_default();
+    values.retain(|name, _| !RESERVED_PROPERTIES.contains(&name.as_str()));
+    let (schema_folder, schema) = found.unzip();
+    let errors = schema
+        .as_ref()
+        .map(|schema| schema.validate(&values))
+        .unwrap_or_default();
+    Ok(NoteProperties {
+        schema_folder,
+        schema,
+        values,
+        errors,
+    })
+}
+
+/// The schema stored on a folder itself, ignoring those of folders above it
+#[tauri::command]
+pub async fn get_property_schema(
+    folder: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<Option<PropertySchema>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    store::load_schema(&folder_path(&vault_path, &folder)?)
+        .map_err(|e| format!("Failed to load property schema: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_property_schema(
+    folder: String,
+    schema: PropertySchema,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<(), String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    store::save_schema(&folder_path(&vault_path, &folder)?, &schema)
+        .map_err(|e| format!("Failed to save property schema: {}", e))?;
+    println!("🗂️ Saved property schema for folder '{}'", folder);
+    Ok(())
+}
+
+/// Remove a folder's schema. Returns whether it had one.
+#[tauri::command]
+pub async fn delete_property_schema(
+    folder: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<bool, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    store::delete_schema(&folder_path(&vault_path, &folder)?)
+        .map_err(|e| format!("Failed to delete property schema: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_note_properties(
+    file_path: String,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<NoteProperties, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    let note = validate_and_resolve_path(&vault_path, &file_path).map_err(|e| e.to_string())?;
+    let (fm, _) = read_note(&note)?;
+    note_properties(&vault_path, &note, fm)
+}
+
+/// Set one property of a note, or remove it when `value` is null. The value
+/// must fit the note's schema; the body of the note is kept as it is.
+#[tauri::command]
+pub async fn update_note_property(
+    file_path: String,
+    name: String,
+    value: Value,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<NoteProperties, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    let note = validate_and_resolve_path(&vault_path, &file_path).map_err(|e| e.to_string())?;
+    let name = name.trim().to_string();
+    if name.is_empty() || RESERVED_PROPERTIES.contains(&name.as_str()) {
+        return Err(format!("Cannot update property {:?}", name));
+    }
+
+    let schema = schema_for_note(&vault_path, &note)
+        .map_err(|e| format!("Failed to load property schema: {}", e))?;
+    if let Some(property) = schema
+        .as_ref()
+        .and_then(|(_, schema)| schema.property(&name))
+    {
+        property
+            .property_type
+            .check(&value)
+            .map_err(|message| format!("Invalid value for {}: {}", name, message))?;
+    }
+
+    let (fm, body) = read_note(&note)?;
+    let mut fm = fm.unwrap_or_else(FrontMatter::new);
+    if value.is_null() {
+        fm.extra_fields.remove(&name);
+    } else {
+        fm.extra_fields.insert(name.clone(), value);
+    }
+    if fm.id.is_some() {
+        fm.updated_at = Some(Utc::now());
+    }
+    FrontMatterWriter::write_atomic(&note, &fm, &body)
+        .map_err(|e| format!("Failed to write note: {}", e))?;
+
+    note_properties(&vault_path, &note, Some(fm))
+}
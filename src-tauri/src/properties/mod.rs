@@ -0,0 +1,237 @@
+//! Typed note properties
+//!
+//! A folder can give the front matter of its notes a schema, much like the
+//! column schemas of CSV files: each property has a name and a type (text,
+//! number, date, select, multi-select, link to a note or checkbox), so the
+//! notes of the folder can be read as records. The schema is kept in a
+//! `.properties.vault.json` file in the folder (see [`store`]) and applies to
+//! the notes below it, down to a folder with a schema of its own.
+//!
+//! Notes are checked against their schema when saved, and values that do
+//! not fit are reported to the window without stopping the save. Properties
+//! the schema does not know about, and empty values, are left alone.
+
+pub mod commands;
+pub mod store;
+
+pub use commands::*;
+pub use store::{delete_schema, load_schema, save_schema, schema_for_note, schema_path};
+
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// Current schema version, for migrations
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Front matter keys managed elsewhere, which cannot be properties
+pub const RESERVED_PROPERTIES: [&str; 5] =
+    ["id", "created_at", "updated_at", "legacy_ids", "tasks"];
+
+/// Schema of the notes in a folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertySchema {
+    /// Schema version for migrations
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// Property definitions, in display order
+    pub properties: Vec<PropertyDefinition>,
+}
+
+fn default_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+/// A single property of a schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyDefinition {
+    /// Front matter key
+    pub name: String,
+    /// User-friendly display name (optional override)
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    pub property_type: PropertyType,
+}
+
+/// Property type (tagged enum for TypeScript discrimination)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PropertyType {
+    /// Plain text values
+    Text,
+    /// Integer or decimal numbers
+    Number,
+    /// `YYYY-MM-DD` dates, or RFC 3339 date-times
+    Date,
+    /// One of a known list of options
+    Select { options: Vec<String> },
+    /// A list of known options
+    MultiSelect { options: Vec<String> },
+    /// A wikilink to another note, `[[Note]]`
+    Link,
+    /// Boolean true/false values
+    Checkbox,
+}
+
+impl PropertyType {
+    /// Check a value against the type. Empty values fit any type.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        let fits = match (self, value) {
+            (_, Value::Null) => true,
+            (PropertyType::Text, value) => !value.is_array() && !value.is_object(),
+            (PropertyType::Number, value) => value.is_number(),
+            (PropertyType::Date, Value::String(s)) => is_date(s),
+            (PropertyType::Select { options }, Value::String(s)) => options.contains(s),
+            (PropertyType::MultiSelect { options }, Value::Array(items)) => {
+                items.iter().all(|item| {
+                    item.as_str()
+                        .is_some_and(|s| options.iter().any(|o| o == s))
+                })
+            }
+            (PropertyType::Link, Value::String(s)) => link_target(s).is_some(),
+            // Unquoted, `[[Note]]` reads as a list holding a list
+            (PropertyType::Link, Value::Array(outer)) => match outer.as_slice() {
+                [Value::Array(inner)] => match inner.as_slice() {
+                    [Value::String(s)] => link_target(&format!("[[{}]]", s)).is_some(),
+                    _ => false,
+                },
+                _ => false,
+            },
+            (PropertyType::Checkbox, value) => value.is_boolean(),
+            _ => false,
+        };
+        if fits {
+            return Ok(());
+        }
+        Err(match self {
+            PropertyType::Text => "expected text".to_string(),
+            PropertyType::Number => "expected a number".to_string(),
+            PropertyType::Date => "expected a date (YYYY-MM-DD)".to_string(),
+            PropertyType::Select { options } => format!("expected one of {}", options.join(", ")),
+            PropertyType::MultiSelect { options } => {
+                format!("expected a list of {}", options.join(", "))
+            }
+            PropertyType::Link => "expected a link to a note, [[Note]]".to_string(),
+            PropertyType::Checkbox => "expected true or false".to_string(),
+        })
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").is_ok()
+        || DateTime::parse_from_rfc3339(s.trim()).is_ok()
+}
+
+/// The note a `[[Note]]` or `[[Note|alias]]` link points at
+pub fn link_target(s: &str) -> Option<&str> {
+    let inner = s.trim().strip_prefix("[[")?.strip_suffix("]]")?;
+    let target = inner.split('|').next().unwrap_or("").trim();
+    (!target.is_empty() && !target.contains('[') && !target.contains(']')).then_some(target)
+}
+
+/// A property value that does not fit its schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyError {
+    pub property: String,
+    pub message: String,
+}
+
+impl fmt::Display for PropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.property, self.message)
+    }
+}
+
+impl PropertySchema {
+    pub fn property(&self, name: &str) -> Option<&PropertyDefinition> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+
+    /// Check that the schema itself makes sense before it is saved
+    pub fn validate_definitions(&self) -> Result<(), String> {
+        if self.version > SCHEMA_VERSION {
+            return Err(format!(
+                "Schema version {} is newer than this app supports",
+                self.version
+            ));
+        }
+        let mut names = HashSet::new();
+        for property in &self.properties {
+            let name = property.name.trim();
+            if name.is_empty() || name != property.name {
+                return Err(format!("Invalid property name: {:?}", property.name));
+            }
+            if RESERVED_PROPERTIES.contains(&name) {
+                return Err(format!(
+                    "{} is managed by the app and cannot be a property",
+                    name
+                ));
+            }
+            if !names.insert(name) {
+                return Err(format!("Property {} is defined twice", name));
+            }
+            if let PropertyType::Select { options } | PropertyType::MultiSelect { options } =
+                &property.property_type
+            {
+                let mut seen = HashSet::new();
+                if options.is_empty() {
+                    return Err(format!("Property {} has no options", name));
+                }
+                if let Some(option) = options.iter().find(|option| !seen.insert(option.as_str())) {
+                    return Err(format!("Property {} lists {} twice", name, option));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check front matter values against the schema, in schema order
+    pub fn validate(&self, fields: &BTreeMap<String, Value>) -> Vec<PropertyError> {
+        self.properties
+            .iter()
+            .filter_map(|property| {
+                let value = fields.get(&property.name)?;
+                let message = property.property_type.check(value).err()?;
+                Some(PropertyError {
+                    property: property.name.clone(),
+                    message,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Check the front matter of a note being saved against the schema of its
+/// folder, `None` when no schema applies. Problems are reported rather than
+/// refused, so the rest of the note is always saved; a schema that cannot
+/// be read is skipped.
+pub fn note_property_errors(
+    vault_root: &Path,
+    note: &Path,
+    fields: &BTreeMap<String, Value>,
+) -> Option<Vec<PropertyError>> {
+    match schema_for_note(vault_root, note) {
+        Ok(found) => found.map(|(_, schema)| schema.validate(fields)),
+        Err(e) => {
+            eprintln!(
+                "⚠️ Failed to load property schema for {}: {}",
+                note.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
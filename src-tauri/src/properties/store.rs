@@ -0,0 +1,74 @@
+//! Property schema storage
+//!
+//! Handles reading and writing the `.properties.vault.json` schema file of a
+//! folder, and finding the schema that applies to a note.
+
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+use super::PropertySchema;
+
+/// Name of the schema file kept in a folder
+pub const SCHEMA_FILE_NAME: &str = ".properties.vault.json";
+
+/// Returns the path to the schema file of a folder
+pub fn schema_path(folder: &Path) -> PathBuf {
+    folder.join(SCHEMA_FILE_NAME)
+}
+
+/// Loads the schema of a folder, `None` when it has none
+pub fn load_schema(folder: &Path) -> Result<Option<PropertySchema>> {
+    let path = schema_path(folder);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(anyhow!("Failed to read schema file: {}", e)),
+    };
+    let schema = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse schema JSON in {}", path.display()))?;
+    Ok(Some(schema))
+}
+
+/// Saves the schema of a folder using a temp file and rename, so a crash
+/// never leaves half a schema behind
+pub fn save_schema(folder: &Path, schema: &PropertySchema) -> Result<()> {
+    schema.validate_definitions().map_err(|e| anyhow!(e))?;
+    let content = serde_json::to_string_pretty(schema)?;
+
+    std::fs::create_dir_all(folder)?;
+    let mut temp_file = NamedTempFile::new_in(folder)?;
+    temp_file.write_all(content.as_bytes())?;
+    temp_file.as_file().sync_all()?;
+    temp_file.persist(schema_path(folder))?;
+    Ok(())
+}
+
+/// Deletes the schema of a folder. Returns whether there was one.
+pub fn delete_schema(folder: &Path) -> Result<bool> {
+    match std::fs::remove_file(schema_path(folder)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The schema that applies to a note: that of the nearest folder above it,
+/// up to the vault root, that has one. Returns the vault-relative folder
+/// (empty for the root) with the schema.
+pub fn schema_for_note(vault_root: &Path, note: &Path) -> Result<Option<(String, PropertySchema)>> {
+    let note = vault_root.join(note);
+    for folder in note.ancestors().skip(1) {
+        let Ok(relative) = folder.strip_prefix(vault_root) else {
+            break;
+        };
+        if let Some(schema) = load_schema(folder)? {
+            return Ok(Some((
+                relative.to_string_lossy().replace('\\', "/"),
+                schema,
+            )));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,253 @@
+use super::*;
+use crate::identity::frontmatter::FrontMatterParser;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+fn property(name: &str, property_type: PropertyType) -> PropertyDefinition {
+    PropertyDefinition {
+        name: name.to_string(),
+        display_name: None,
+        description: String::new(),
+        property_type,
+    }
+}
+
+fn project_schema() -> PropertySchema {
+    PropertySchema {
+        version: SCHEMA_VERSION,
+        properties: vec![
+            property(
+                "status",
+                PropertyType::Select {
+                    options: vec!["active".into(), "done".into()],
+                },
+            ),
+            property("due", PropertyType::Date),
+            property("budget", PropertyType::Number),
+            property(
+                "labels",
+                PropertyType::MultiSelect {
+                    options: vec!["home".into(), "work".into()],
+                },
+            ),
+            property("owner", PropertyType::Link),
+            property("archived", PropertyType::Checkbox),
+            property("summary", PropertyType::Text),
+        ],
+    }
+}
+
+fn fields(content: &str) -> BTreeMap<String, Value> {
+    let (fm, _) = FrontMatterParser::parse(content).unwrap();
+    fm.unwrap().extra_fields
+}
+
+#[test]
+fn test_property_types_check_values() {
+    let select = PropertyType::Select {
+        options: vec!["a".into(), "b".into()],
+    };
+    assert!(select.check(&json!("a")).is_ok());
+    assert!(select.check(&json!("c")).is_err());
+    assert!(select.check(&Value::Null).is_ok());
+
+    let multi = PropertyType::MultiSelect {
+        options: vec!["a".into(), "b".into()],
+    };
+    assert!(multi.check(&json!(["a", "b"])).is_ok());
+    assert!(multi.check(&json!([])).is_ok());
+    assert!(multi.check(&json!(["a", "c"])).is_err());
+    assert!(multi.check(&json!("a")).is_err());
+
+    assert!(PropertyType::Date.check(&json!("2025-05-15")).is_ok());
+    assert!(PropertyType::Date
+        .check(&json!("2025-05-15T09:30:00+02:00"))
+        .is_ok());
+    assert!(PropertyType::Date.check(&json!("2025-02-30")).is_err());
+    assert!(PropertyType::Date.check(&json!(20250515)).is_err());
+
+    assert!(PropertyType::Number.check(&json!(2.5)).is_ok());
+    assert!(PropertyType::Number.check(&json!("2.5")).is_err());
+    assert!(PropertyType::Checkbox.check(&json!(false)).is_ok());
+    assert!(PropertyType::Checkbox.check(&json!("yes")).is_err());
+    assert!(PropertyType::Text.check(&json!(2025)).is_ok());
+    assert!(PropertyType::Text.check(&json!(["a"])).is_err());
+
+    assert!(PropertyType::Link.check(&json!("[[Sam Lee]]")).is_ok());
+    assert!(PropertyType::Link
+        .check(&json!("[[People/Sam|Sam]]"))
+        .is_ok());
+    assert!(PropertyType::Link.check(&json!("Sam Lee")).is_err());
+    assert!(PropertyType::Link.check(&json!("[[]]")).is_err());
+}
+
+#[test]
+fn test_link_target() {
+    assert_eq!(link_target("[[Note]]"), Some("Note"));
+    assert_eq!(
+        link_target(" [[Folder/Note | alias]] "),
+        Some("Folder/Note")
+    );
+    assert_eq!(link_target("[[a]] and [[b]]"), None);
+    assert_eq!(link_target("Note"), None);
+}
+
+#[test]
+fn test_validate_front_matter_against_schema() {
+    let schema = project_schema();
+    let valid = fields(
+        "---\nid: n1\nstatus: active\ndue: 2025-06-01\nbudget: 1200\nlabels:\n  - work\nowner: \"[[Sam]]\"\narchived: false\nsummary: 42\nextra: [1, 2]\n---\n# Garden\n",
+    );
+    assert_eq!(schema.validate(&valid), vec![]);
+
+    let invalid = fields("---\nstatus: paused\ndue: next week\nbudget: lots\narchived: no\n---\n");
+    let errors = schema.validate(&invalid);
+    let properties: Vec<&str> = errors.iter().map(|e| e.property.as_str()).collect();
+    assert_eq!(properties, vec!["status", "due", "budget", "archived"]);
+    assert_eq!(
+        errors[0].to_string(),
+        "status: expected one of active, done"
+    );
+}
+
+#[test]
+fn test_validate_definitions() {
+    assert!(project_schema().validate_definitions().is_ok());
+
+    let mut schema = project_schema();
+    schema.properties.push(property("due", PropertyType::Text));
+    assert!(schema.validate_definitions().is_err());
+
+    let mut schema = project_schema();
+    schema
+        .properties
+        .push(property("updated_at", PropertyType::Date));
+    assert!(schema.validate_definitions().is_err());
+
+    let mut schema = project_schema();
+    schema
+        .properties
+        .push(property("kind", PropertyType::Select { options: vec![] }));
+    assert!(schema.validate_definitions().is_err());
+
+    let mut schema = project_schema();
+    schema.properties.push(property(
+        "kind",
+        PropertyType::MultiSelect {
+            options: vec!["a".into(), "a".into()],
+        },
+    ));
+    assert!(schema.validate_definitions().is_err());
+
+    let mut schema = project_schema();
+    schema
+        .properties
+        .push(property(" kind", PropertyType::Text));
+    assert!(schema.validate_definitions().is_err());
+}
+
+#[test]
+fn test_schema_save_load_and_delete() {
+    let vault = TempDir::new().unwrap();
+    let folder = vault.path().join("Projects");
+    assert_eq!(load_schema(&folder).unwrap(), None);
+
+    save_schema(&folder, &project_schema()).unwrap();
+    assert!(schema_path(&folder).exists());
+    assert_eq!(load_schema(&folder).unwrap(), Some(project_schema()));
+
+    let json = fs::read_to_string(schema_path(&folder)).unwrap();
+    assert!(json.contains("\"propertyType\""));
+    assert!(json.contains("\"type\": \"multiSelect\""));
+
+    assert!(delete_schema(&folder).unwrap());
+    assert!(!delete_schema(&folder).unwrap());
+    assert_eq!(load_schema(&folder).unwrap(), None);
+
+    let mut invalid = project_schema();
+    invalid.properties.push(property("id", PropertyType::Text));
+    assert!(save_schema(&folder, &invalid).is_err());
+}
+
+#[test]
+fn test_nearest_folder_schema_applies() {
+    let vault = TempDir::new().unwrap();
+    let root = vault.path();
+    save_schema(root, &project_schema()).unwrap();
+    let people = PropertySchema {
+        version: SCHEMA_VERSION,
+        properties: vec![property("born", PropertyType::Date)],
+    };
+    save_schema(&root.join("People"), &people).unwrap();
+
+    let (folder, schema) = schema_for_note(root, Path::new("Notes/Deep/A.md"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(folder, "");
+    assert_eq!(schema, project_schema());
+
+    let (folder, schema) = schema_for_note(root, &root.join("People/Team/Sam.md"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(folder, "People");
+    assert_eq!(schema, people);
+
+    let other = TempDir::new().unwrap();
+    assert_eq!(
+        schema_for_note(other.path(), Path::new("A.md")).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_note_property_errors() {
+    let vault = TempDir::new().unwrap();
+    let root = vault.path();
+    save_schema(&root.join("Projects"), &project_schema()).unwrap();
+    let note = Path::new("Projects/Garden.md");
+
+    assert_eq!(
+        note_property_errors(root, note, &fields("---\nstatus: done\n---\n")),
+        Some(vec![])
+    );
+    let errors =
+        note_property_errors(root, note, &fields("---\nstatus: x\nbudget: y\n---\n")).unwrap();
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "status: expected one of active, done",
+            "budget: expected a number"
+        ]
+    );
+
+    // Notes outside the folder, and unreadable schemas, are not checked
+    let outside = Path::new("Other/Garden.md");
+    assert_eq!(
+        note_property_errors(root, outside, &fields("---\nstatus: x\n---\n")),
+        None
+    );
+    fs::write(schema_path(&root.join("Projects")), "{ not json").unwrap();
+    assert_eq!(
+        note_property_errors(root, note, &fields("---\nstatus: x\n---\n")),
+        None
+    );
+}
+
+#[test]
+fn test_unquoted_links_in_front_matter() {
+    let schema = project_schema();
+    // `owner: [[Sam]]` reads as a list holding a list
+    let unquoted = fields("---\nowner: [[Sam Lee]]\n---\n");
+    assert_eq!(unquoted["owner"], json!([["Sam Lee"]]));
+    assert_eq!(schema.validate(&unquoted), vec![]);
+    assert!(schema
+        .validate(&fields("---\nowner: [[People/Sam|Sam]]\n---\n"))
+        .is_empty());
+
+    for value in ["[[Sam], [Lee]]", "[[]]", "[[1]]", "[Sam]"] {
+        let errors = schema.validate(&fields(&format!("---\nowner: {}\n---\n", value)));
+        assert_eq!(errors.len(), 1, "{}", value);
+    }
+}
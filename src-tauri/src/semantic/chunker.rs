@@ -0,0 +1,202 @@
+//! Heading-based markdown chunking
+//!
+//! Each chunk covers the text between two headings. Front matter is dropped,
+//! headings inside fenced code blocks are ignored, and sections longer than
+//! `MAX_CHUNK_CHARS` are split further on paragraph boundaries.
+
+use crate::identity::frontmatter::FrontMatterParser;
+
+/// Upper bound on the size of a single chunk sent to the embedding model.
+pub const MAX_CHUNK_CHARS: usize = 2000;
+
+/// A section of a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Text of the heading that opens the section, if any
+    pub heading: Option<String>,
+    /// Section body, trimmed
+    pub content: String,
+}
+
+/// Splits a markdown document into heading-delimited chunks.
+pub fn chunk_markdown(content: &str) -> Vec<Chunk> {
+    let body = match FrontMatterParser::parse(content) {
+        Ok((_, body)) => body,
+        Err(_) => content.to_string(),
+    };
+
+    let mut chunks = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut section = String::new();
+    let mut in_fence = false;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            if let Some(text) = heading_text(trimmed) {
+                push_section(&mut chunks, heading.take(), &section);
+                section.clear();
+                heading = Some(text);
+                continue;
+            }
+        }
+
+        section.push_str(line);
+        section.push('\n');
+    }
+    push_section(&mut chunks, heading, &section);
+
+    chunks
+}
+
+/// Returns the heading text if the line is an ATX heading (`# Title`).
+fn heading_text(line: &str) -> Option<String> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') && !rest.starts_with('\t') {
+        return None;
+    }
+
+    let text = rest.trim().trim_end_matches('#').trim();
+    Some(text.to_string())
+}
+
+fn push_section(chunks: &mut Vec<Chunk>, heading: Option<String>, section: &str) {
+    let section = section.trim();
+    if section.is_empty() {
+        // A heading on its own still carries meaning worth matching
+        if let Some(heading) = heading.filter(|h| !h.is_empty()) {
+            chunks.push(Chunk {
+                content: heading.clone(),
+                heading: Some(heading),
+            });
+        }
+        return;
+    }
+
+    if section.chars().count() <= MAX_CHUNK_CHARS {
+        chunks.push(Chunk {
+            heading,
+            content: section.to_string(),
+        });
+        return;
+    }
+
+    let mut current = String::new();
+    // Characters in `current`; the limit is in characters, not bytes
+    let mut current_chars = 0;
+    for paragraph in section.split("\n\n") {
+        let paragraph = paragraph.trim();
+        if paragraph.is_empty() {
+            continue;
+        }
+        let paragraph_chars = paragraph.chars().count();
+
+        if !current.is_empty() && current_chars + paragraph_chars + 2 > MAX_CHUNK_CHARS {
+            chunks.push(Chunk {
+                heading: heading.clone(),
+                content: std::mem::take(&mut current),
+            });
+            current_chars = 0;
+        }
+
+        if paragraph_chars > MAX_CHUNK_CHARS {
+            // A single huge paragraph is cut on character boundaries
+            let chars: Vec<char> = paragraph.chars().collect();
+            for piece in chars.chunks(MAX_CHUNK_CHARS) {
+                chunks.push(Chunk {
+                    heading: heading.clone(),
+                    content: piece.iter().collect(),
+                });
+            }
+            continue;
+        }
+
+        if !current.is_empty() {
+            current.push_str("\n\n");
+            current_chars += 2;
+        }
+        current.push_str(paragraph);
+        current_chars += paragraph_chars;
+    }
+
+    if !current.is_empty() {
+        chunks.push(Chunk {
+            heading,
+            content: current,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splits_on_headings_and_skips_frontmatter() {
+        let content = "---\nid: abc\n---\nIntro text\n\n# First\nAlpha\n\n## Second ##\nBeta\n";
+        let chunks = chunk_markdown(content);
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].heading, None);
+        assert_eq!(chunks[0].content, "Intro text");
+        assert_eq!(chunks[1].heading.as_deref(), Some("First"));
+        assert_eq!(chunks[1].content, "Alpha");
+        assert_eq!(chunks[2].heading.as_deref(), Some("Second"));
+        assert_eq!(chunks[2].content, "Beta");
+    }
+
+    #[test]
+    fn test_ignores_headings_in_code_blocks_and_tags() {
+        let content = "# Script\n```bash\n# not a heading\necho hi\n```\n#tag line\n";
+        let chunks = chunk_markdown(content);
+
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].content.contains("# not a heading"));
+        assert!(chunks[0].content.contains("#tag line"));
+    }
+
+    #[test]
+    fn test_long_sections_are_split() {
+        let paragraph = "word ".repeat(300);
+        let content = format!("# Long\n{}\n\n{}\n\n{}\n", paragraph, paragraph, paragraph);
+        let chunks = chunk_markdown(&content);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks
+            .iter()
+            .all(|c| c.content.chars().count() <= MAX_CHUNK_CHARS));
+        assert!(chunks.iter().all(|c| c.heading.as_deref() == Some("Long")));
+    }
+
+    #[test]
+    fn test_chunk_size_counts_characters() {
+        // 1500 characters but 4500 bytes
+        let paragraph = "語".repeat(1500);
+        let chunks = chunk_markdown(&format!(
+            "# Notes
+{}
+",
+            paragraph
+        ));
+        assert_eq!(chunks.len(), 1);
+
+        let content = format!(
+            "# Notes
+{}
+
+{}
+",
+            paragraph, paragraph
+        );
+        let chunks = chunk_markdown(&content);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|c| c.content == paragraph));
+    }
+}
@@ -0,0 +1,13 @@
+//! Tauri commands for semantic search
+
+use tauri::AppHandle;
+
+use super::embedder::{embedding_settings, openai_compatible_endpoint};
+
+/// OpenAI-compatible endpoint the vault's embeddings come from, passed to the
+/// bundled search server so its hybrid queries are embedded the same way
+#[tauri::command]
+pub async fn get_embedding_endpoint(app: AppHandle) -> Result<String, String> {
+    let settings = embedding_settings(&app).await?;
+    Ok(openai_compatible_endpoint(&settings))
+}
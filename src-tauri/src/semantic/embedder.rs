@@ -0,0 +1,196 @@
+//! Embedding providers
+//!
+//! Only local providers are supported so note content never leaves the
+//! machine. Both Ollama and LM Studio expose the OpenAI-compatible
+//! `/embeddings` endpoint; Ollama's native `/api/embed` endpoint is used when
+//! the configured endpoint is not the `/v1` compatibility layer.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::header::HeaderName;
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::ai_settings_multi::{
+    get_active_ai_provider, get_ai_settings_for_provider, AIProvider, AISettings,
+};
+
+/// Number of chunks sent to the provider per request
+pub const EMBED_BATCH_SIZE: usize = 32;
+
+/// Produces vector embeddings for text
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Identifier of the model; vectors from different models are never compared
+    fn model(&self) -> &str;
+
+    /// Embeds each input, returning one vector per input in the same order
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Embedder backed by a local Ollama or LM Studio server
+pub struct LocalEmbedder {
+    client: reqwest::Client,
+    settings: AISettings,
+    model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbeddingResponse {
+    data: Vec<OpenAIEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbedding {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbeddingResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl LocalEmbedder {
+    pub fn from_settings(settings: AISettings) -> Result<Self> {
+        if !matches!(settings.provider, AIProvider::Ollama | AIProvider::LMStudio) {
+            return Err(anyhow!(
+                "Semantic search requires a local provider (Ollama or LM Studio), got {}",
+                settings.provider.as_str()
+            ));
+        }
+
+        let model = settings
+            .embedding_model
+            .clone()
+            .filter(|m| !m.trim().is_empty())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No embedding model configured for {}",
+                    settings.provider.as_str()
+                )
+            })?;
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            client,
+            settings,
+            model,
+        })
+    }
+
+    fn uses_ollama_native_api(&self) -> bool {
+        self.settings.provider == AIProvider::Ollama && !self.settings.endpoint.contains("/v1")
+    }
+}
+
+#[async_trait]
+impl Embedder for LocalEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let endpoint = self.settings.endpoint.trim_end_matches('/');
+        let url = if self.uses_ollama_native_api() {
+            format!("{}/api/embed", endpoint)
+        } else {
+            format!("{}/embeddings", endpoint)
+        };
+
+        let mut request = self.client.post(&url).json(&serde_json::json!({
+            "model": self.model,
+            "input": inputs,
+        }));
+
+        if let Some(api_key) = &self.settings.api_key {
+            if !api_key.is_empty() {
+                request = request.header("Authorization", format!("Bearer {}", api_key));
+            }
+        }
+
+        // Add any custom headers from settings
+        if let Some(headers) = &self.settings.headers {
+            for kv in headers {
+                if kv.name.is_empty()
+                    || kv.name.eq_ignore_ascii_case("authorization")
+                    || kv.name.eq_ignore_ascii_case("content-type")
+                {
+                    continue;
+                }
+                if let Ok(name) = HeaderName::from_bytes(kv.name.as_bytes()) {
+                    request = request.header(name, kv.value.clone());
+                }
+            }
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach embedding provider at {}: {}", url, e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Embedding request failed ({}): {}", status, body));
+        }
+
+        let vectors = if self.uses_ollama_native_api() {
+            response
+                .json::<OllamaEmbeddingResponse>()
+                .await
+                .map_err(|e| anyhow!("Invalid embedding response: {}", e))?
+                .embeddings
+        } else {
+            let mut data = response
+                .json::<OpenAIEmbeddingResponse>()
+                .await
+                .map_err(|e| anyhow!("Invalid embedding response: {}", e))?
+                .data;
+            data.sort_by_key(|d| d.index);
+            data.into_iter().map(|d| d.embedding).collect()
+        };
+
+        if vectors.len() != inputs.len() {
+            return Err(anyhow!(
+                "Embedding provider returned {} vectors for {} inputs",
+                vectors.len(),
+                inputs.len()
+            ));
+        }
+
+        Ok(vectors)
+    }
+}
+
+/// Base URL of the OpenAI-compatible API for the provider, for clients that
+/// only speak `/embeddings`. Ollama serves it under `/v1`.
+pub fn openai_compatible_endpoint(settings: &AISettings) -> String {
+    let endpoint = settings.endpoint.trim_end_matches('/');
+    if settings.provider == AIProvider::Ollama && !endpoint.contains("/v1") {
+        format!("{}/v1", endpoint)
+    } else {
+        endpoint.to_string()
+    }
+}
+
+/// Picks the provider settings used for embeddings: the active provider when
+/// it is local, otherwise the saved Ollama settings.
+pub async fn embedding_settings(app: &AppHandle) -> Result<AISettings, String> {
+    let active = get_active_ai_provider(app.clone()).await?;
+    let provider = match active {
+        AIProvider::Ollama | AIProvider::LMStudio => active,
+        _ => AIProvider::Ollama,
+    };
+
+    get_ai_settings_for_provider(app.clone(), provider.as_str().to_string()).await
+}
@@ -0,0 +1,29 @@
+//! Local semantic search
+//!
+//! Notes are split into heading-delimited chunks, embedded through a local
+//! OpenAI-compatible provider (Ollama or LM Studio) and stored in a SQLite
+//! database inside the vault. The index is synced in the background as notes
+//! change (see [`sync`]), and only notes that changed since the last sync are
+//! re-embedded; searches only read it.
+
+pub mod chunker;
+pub mod commands;
+pub mod embedder;
+pub mod store;
+pub mod sync;
+
+pub use chunker::{chunk_markdown, Chunk};
+pub use commands::*;
+pub use embedder::{embedding_settings, openai_compatible_endpoint, Embedder, LocalEmbedder};
+pub use store::{SemanticHit, SemanticIndex, SyncStats};
+pub use sync::SemanticIndexState;
+
+use std::path::{Path, PathBuf};
+
+/// Location of the semantic index database for a vault.
+pub fn index_path(vault_root: &Path) -> PathBuf {
+    vault_root.join(".vault").join("semantic.db")
+}
+
+#[cfg(test)]
+mod tests;
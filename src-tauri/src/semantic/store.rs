@@ -0,0 +1,382 @@
+//! SQLite-backed vector store
+//!
+//! Tracks every indexed note with its mtime, size, content hash and the model
+//! its chunks were embedded with. A sync only re-embeds notes whose content
+//! changed or whose vectors came from a different model, and drops notes that
+//! no longer exist. Search is a brute-force cosine scan, which is fast enough
+//! for vault-sized corpora.
+
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+use super::chunker::chunk_markdown;
+use super::embedder::{Embedder, EMBED_BATCH_SIZE};
+
+/// Best-matching chunk of a note for a query
+#[derive(Debug, Clone)]
+pub struct SemanticHit {
+    /// Path relative to the vault root
+    pub path: String,
+    pub title: String,
+    pub heading: Option<String>,
+    pub content: String,
+    pub score: f32,
+}
+
+/// Outcome of an incremental sync
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStats {
+    pub embedded: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+struct NoteRow {
+    mtime: i64,
+    size: i64,
+    hash: String,
+    model: String,
+}
+
+struct PendingNote {
+    path: String,
+    mtime: i64,
+    size: i64,
+    hash: String,
+    content: String,
+}
+
+pub struct SemanticIndex {
+    vault_root: PathBuf,
+    conn: Mutex<Connection>,
+}
+
+impl SemanticIndex {
+    /// Opens (or creates) the index stored inside the vault.
+    pub fn open(vault_root: &Path) -> Result<Self> {
+        Self::open_at(vault_root, &super::index_path(vault_root))
+    }
+
+    /// Opens (or creates) an index for `vault_root` stored at `db_path`.
+    pub fn open_at(vault_root: &Path, db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(db_path)?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE IF NOT EXISTS notes (
+                 path TEXT PRIMARY KEY,
+                 mtime INTEGER NOT NULL,
+                 size INTEGER NOT NULL,
+                 hash TEXT NOT NULL,
+                 model TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS chunks (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 path TEXT NOT NULL REFERENCES notes(path) ON DELETE CASCADE,
+                 heading TEXT,
+                 content TEXT NOT NULL,
+                 embedding BLOB NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_chunks_path ON chunks(path);",
+        )?;
+
+        Ok(Self {
+            vault_root: vault_root.to_path_buf(),
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| anyhow!("Semantic index connection poisoned"))
+    }
+
+    /// Number of notes currently in the index
+    pub fn note_count(&self) -> Result<usize> {
+        let conn = self.lock()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Brings the index up to date with the markdown files in the vault.
+    pub async fn sync(&self, embedder: &dyn Embedder) -> Result<SyncStats> {
+        let mut stats = SyncStats::default();
+        let on_disk = self.scan_vault();
+
+        let pending = {
+            let conn = self.lock()?;
+            let mut known: HashMap<String, NoteRow> = HashMap::new();
+            {
+                let mut stmt = conn.prepare("SELECT path, mtime, size, hash, model FROM notes")?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        NoteRow {
+                            mtime: row.get(1)?,
+                            size: row.get(2)?,
+                            hash: row.get(3)?,
+                            model: row.get(4)?,
+                        },
+                    ))
+                })?;
+                for row in rows {
+                    let (path, note) = row?;
+                    known.insert(path, note);
+                }
+            }
+
+            for path in known.keys().filter(|p| !on_disk.contains_key(*p)) {
+                conn.execute("DELETE FROM notes WHERE path = ?1", params![path])?;
+                stats.removed += 1;
+            }
+
+            let mut pending = Vec::new();
+            for (rel, (full_path, mtime, size)) in &on_disk {
+                let existing = known.get(rel);
+                let same_model = existing.is_some_and(|n| n.model == embedder.model());
+                if same_model && existing.is_some_and(|n| n.mtime == *mtime && n.size == *size) {
+                    stats.unchanged += 1;
+                    continue;
+                }
+
+                let content = match std::fs::read_to_string(full_path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        eprintln!("Skipping {} for semantic index: {}", rel, e);
+                        continue;
+                    }
+                };
+                let hash = hash_content(&content);
+
+                if same_model && existing.is_some_and(|n| n.hash == hash) {
+                    // Touched but not edited; remember the new mtime
+                    conn.execute(
+                        "UPDATE notes SET mtime = ?1, size = ?2 WHERE path = ?3",
+                        params![mtime, size, rel],
+                    )?;
+                    stats.unchanged += 1;
+                    continue;
+                }
+
+                pending.push(PendingNote {
+                    path: rel.clone(),
+                    mtime: *mtime,
+                    size: *size,
+                    hash,
+                    content,
+                });
+            }
+            pending
+        };
+
+        for note in pending {
+            self.embed_note(embedder, note).await?;
+            stats.embedded += 1;
+        }
+
+        Ok(stats)
+    }
+
+    async fn embed_note(&self, embedder: &dyn Embedder, note: PendingNote) -> Result<()> {
+        let title = note_title(&note.path);
+        let chunks = chunk_markdown(&note.content);
+
+        let inputs: Vec<String> = chunks
+            .iter()
+            .map(|chunk| match &chunk.heading {
+                Some(heading) => format!("{} > {}\n\n{}", title, heading, chunk.content),
+                None => format!("{}\n\n{}", title, chunk.content),
+            })
+            .collect();
+
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(EMBED_BATCH_SIZE) {
+            vectors.extend(embedder.embed(batch).await?);
+        }
+
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM chunks WHERE path = ?1", params![note.path])?;
+        tx.execute(
+            "INSERT INTO notes (path, mtime, size, hash, model) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(path) DO UPDATE SET
+                 mtime = excluded.mtime,
+                 size = excluded.size,
+                 hash = excluded.hash,
+                 model = excluded.model",
+            params![
+                note.path,
+                note.mtime,
+                note.size,
+                note.hash,
+                embedder.model()
+            ],
+        )?;
+        for (chunk, vector) in chunks.iter().zip(vectors.iter()) {
+            tx.execute(
+                "INSERT INTO chunks (path, heading, content, embedding) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    note.path,
+                    chunk.heading,
+                    chunk.content,
+                    encode_vector(vector)
+                ],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Returns the notes closest in meaning to `query`, best chunk per note.
+    pub async fn search(
+        &self,
+        embedder: &dyn Embedder,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SemanticHit>> {
+        let query_vector = embedder
+            .embed(&[query.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Embedding provider returned no vector for the query"))?;
+
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT c.path, c.heading, c.content, c.embedding
+             FROM chunks c JOIN notes n ON n.path = c.path
+             WHERE n.model = ?1",
+        )?;
+        let rows = stmt.query_map(params![embedder.model()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Vec<u8>>(3)?,
+            ))
+        })?;
+
+        let mut best: HashMap<String, SemanticHit> = HashMap::new();
+        for row in rows {
+            let (path, heading, content, blob) = row?;
+            let score = cosine_similarity(&query_vector, &decode_vector(&blob));
+            if best.get(&path).is_some_and(|hit| hit.score >= score) {
+                continue;
+            }
+            best.insert(
+                path.clone(),
+                SemanticHit {
+                    title: note_title(&path),
+                    path,
+                    heading,
+                    content,
+                    score,
+                },
+            );
+        }
+
+        let mut hits: Vec<SemanticHit> = best.into_values().collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Markdown files in the vault keyed by relative path, skipping hidden
+    /// folders such as `.vault` and `.git`.
+    fn scan_vault(&self) -> HashMap<String, (PathBuf, i64, i64)> {
+        let mut files = HashMap::new();
+
+        let walker = WalkDir::new(&self.vault_root)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+
+        for entry in walker.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let mtime = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0);
+
+            let rel = path
+                .strip_prefix(&self.vault_root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            files.insert(rel, (path.to_path_buf(), mtime, metadata.len() as i64));
+        }
+
+        files
+    }
+}
+
+fn note_title(rel_path: &str) -> String {
+    Path::new(rel_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Untitled")
+        .to_string()
+}
+
+fn hash_content(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// Cosine similarity; vectors of different dimensions never match.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
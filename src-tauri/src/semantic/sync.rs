@@ -0,0 +1,116 @@
+//! Background syncing of the semantic index
+//!
+//! Embedding notes is slow, so searches never sync the index themselves.
+//! Once a vault has an index, it is brought up to date when the vault is
+//! opened and after notes change, a moment after the last change so a burst
+//! of saves is embedded once. Vaults are left alone until semantic search is
+//! first used in them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::{embedding_settings, index_path, LocalEmbedder, SemanticIndex, SyncStats};
+
+/// How long to wait after a change before syncing
+const SYNC_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncStatus {
+    /// Waiting for changes to settle
+    Scheduled,
+    Running,
+    /// Notes changed while running, so another sync follows
+    Rerun,
+}
+
+/// Syncs in progress, one per vault
+#[derive(Default)]
+pub struct SemanticIndexState {
+    syncs: Mutex<HashMap<PathBuf, SyncStatus>>,
+}
+
+impl SemanticIndexState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn syncs(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, SyncStatus>> {
+        self.syncs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether a sync of `vault_path` is scheduled or running
+    pub fn is_syncing(&self, vault_path: &Path) -> bool {
+        self.syncs().contains_key(vault_path)
+    }
+
+    /// Brings the index of `vault_path` up to date in the background, if the
+    /// vault has one. Called when the vault is opened and when notes change.
+    pub fn refresh(&self, app: &AppHandle, vault_path: &Path) {
+        if index_path(vault_path).exists() {
+            self.schedule_sync(app, vault_path);
+        }
+    }
+
+    /// Syncs the index of `vault_path` in the background, creating it if needed
+    pub fn schedule_sync(&self, app: &AppHandle, vault_path: &Path) {
+        {
+            let mut syncs = self.syncs();
+            match syncs.get_mut(vault_path) {
+                Some(status @ SyncStatus::Running) => {
+                    *status = SyncStatus::Rerun;
+                    return;
+                }
+                Some(_) => return,
+                None => {
+                    syncs.insert(vault_path.to_path_buf(), SyncStatus::Scheduled);
+                }
+            }
+        }
+
+        let app = app.clone();
+        let vault_path = vault_path.to_path_buf();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(SYNC_DELAY).await;
+                let state = app.state::<SemanticIndexState>();
+                state
+                    .syncs()
+                    .insert(vault_path.clone(), SyncStatus::Running);
+
+                match sync_vault(&app, &vault_path).await {
+                    Ok(stats) => println!(
+                        "🧠 Semantic index synced: {} embedded, {} unchanged, {} removed",
+                        stats.embedded, stats.unchanged, stats.removed
+                    ),
+                    Err(e) => eprintln!(
+                        "⚠️ Failed to sync semantic index for {}: {}",
+                        vault_path.display(),
+                        e
+                    ),
+                }
+
+                let mut syncs = state.syncs();
+                if syncs.get(&vault_path) != Some(&SyncStatus::Rerun) {
+                    syncs.remove(&vault_path);
+                    break;
+                }
+                syncs.insert(vault_path.clone(), SyncStatus::Scheduled);
+            }
+        });
+    }
+}
+
+async fn sync_vault(app: &AppHandle, vault_path: &Path) -> Result<SyncStats, String> {
+    let settings = embedding_settings(app).await?;
+    let embedder = LocalEmbedder::from_settings(settings)
+        .map_err(|e| format!("Semantic search unavailable: {}", e))?;
+    let index = SemanticIndex::open(vault_path)
+        .map_err(|e| format!("Failed to open semantic index: {}", e))?;
+    index
+        .sync(&embedder)
+        .await
+        .map_err(|e| format!("Failed to update semantic index: {}", e))
+}
@@ -0,0 +1,193 @@
+use super::*;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
+
+const DIMENSIONS: usize = 64;
+
+/// Deterministic bag-of-words embedder so tests do not need a model server
+struct FakeEmbedder {
+    model: String,
+    calls: AtomicUsize,
+    inputs: AtomicUsize,
+}
+
+impl FakeEmbedder {
+    fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            calls: AtomicUsize::new(0),
+            inputs: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for FakeEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        self.inputs.fetch_add(inputs.len(), Ordering::SeqCst);
+
+        Ok(inputs
+            .iter()
+            .map(|text| {
+                let mut vector = vec![0.0f32; DIMENSIONS];
+                for word in text.split(|c: char| !c.is_alphanumeric()) {
+                    if word.is_empty() {
+                        continue;
+                    }
+                    let bucket = word
+                        .to_lowercase()
+                        .bytes()
+                        .fold(7usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+                    vector[bucket % DIMENSIONS] += 1.0;
+                }
+                vector
+            })
+            .collect())
+    }
+}
+
+fn setup_vault() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("garden.md"),
+        "# Garden\n\n## Tomatoes\nWater tomatoes daily in summer heat.\n\n## Roses\nPrune roses in early spring.\n",
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("work")).unwrap();
+    fs::write(
+        dir.path().join("work/meeting.md"),
+        "---\nid: 123\n---\n# Standup\nDiscussed the quarterly budget and hiring plan.\n",
+    )
+    .unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn test_search_returns_best_matching_chunk() {
+    let vault = setup_vault();
+    let embedder = FakeEmbedder::new("fake");
+    let index = SemanticIndex::open(vault.path()).unwrap();
+
+    let stats = index.sync(&embedder).await.unwrap();
+    assert_eq!(stats.embedded, 2);
+
+    let hits = index
+        .search(&embedder, "when to prune roses", 10)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].path, "garden.md");
+    assert_eq!(hits[0].title, "garden");
+    assert_eq!(hits[0].heading.as_deref(), Some("Roses"));
+    assert!(hits[0].score > hits[1].score);
+
+    let hits = index.search(&embedder, "budget hiring", 1).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].path, "work/meeting.md");
+}
+
+#[tokio::test]
+async fn test_sync_only_embeds_changed_notes() {
+    let vault = setup_vault();
+    let embedder = FakeEmbedder::new("fake");
+
+    {
+        let index = SemanticIndex::open(vault.path()).unwrap();
+        index.sync(&embedder).await.unwrap();
+    }
+
+    // Reopening picks up the persisted state
+    let index = SemanticIndex::open(vault.path()).unwrap();
+    let calls_before = embedder.calls.load(Ordering::SeqCst);
+    let stats = index.sync(&embedder).await.unwrap();
+    assert_eq!(
+        stats,
+        SyncStats {
+            embedded: 0,
+            unchanged: 2,
+            removed: 0
+        }
+    );
+    assert_eq!(embedder.calls.load(Ordering::SeqCst), calls_before);
+
+    fs::write(
+        vault.path().join("garden.md"),
+        "# Garden\n\n## Compost\nTurn the compost pile weekly.\n",
+    )
+    .unwrap();
+    fs::remove_file(vault.path().join("work/meeting.md")).unwrap();
+    fs::write(vault.path().join("new.md"), "Fresh note about compost bins").unwrap();
+
+    let stats = index.sync(&embedder).await.unwrap();
+    assert_eq!(
+        stats,
+        SyncStats {
+            embedded: 2,
+            unchanged: 0,
+            removed: 1
+        }
+    );
+    assert_eq!(index.note_count().unwrap(), 2);
+
+    let hits = index.search(&embedder, "prune roses", 10).await.unwrap();
+    assert!(hits.iter().all(|h| h.heading.as_deref() != Some("Roses")));
+}
+
+#[tokio::test]
+async fn test_model_change_reembeds_everything() {
+    let vault = setup_vault();
+    let index = SemanticIndex::open(vault.path()).unwrap();
+
+    index.sync(&FakeEmbedder::new("model-a")).await.unwrap();
+
+    let other = FakeEmbedder::new("model-b");
+    let stats = index.sync(&other).await.unwrap();
+    assert_eq!(stats.embedded, 2);
+
+    let hits = index.search(&other, "roses", 10).await.unwrap();
+    assert_eq!(hits.len(), 2);
+}
+
+#[tokio::test]
+async fn test_hidden_folders_are_not_indexed() {
+    let vault = setup_vault();
+    fs::create_dir_all(vault.path().join(".trash")).unwrap();
+    fs::write(vault.path().join(".trash/old.md"), "deleted roses").unwrap();
+
+    let embedder = FakeEmbedder::new("fake");
+    let index = SemanticIndex::open(vault.path()).unwrap();
+    index.sync(&embedder).await.unwrap();
+
+    assert!(index_path(vault.path()).exists());
+    assert_eq!(index.note_count().unwrap(), 2);
+}
+
+#[test]
+fn test_openai_compatible_endpoint() {
+    use crate::ai_settings_multi::AIProvider;
+
+    let mut ollama = AIProvider::Ollama.default_settings();
+    assert_eq!(
+        openai_compatible_endpoint(&ollama),
+        "http://localhost:11434/v1"
+    );
+    ollama.endpoint = "http://gpu-box:11434/".to_string();
+    assert_eq!(
+        openai_compatible_endpoint(&ollama),
+        "http://gpu-box:11434/v1"
+    );
+
+    let lm_studio = AIProvider::LMStudio.default_settings();
+    assert_eq!(
+        openai_compatible_endpoint(&lm_studio),
+        "http://localhost:1234/v1"
+    );
+}
@@ -0,0 +1,121 @@
+//! Tag extraction and matching
+//!
+//! Also compiled into the search MCP server, so the tags it indexes follow
+//! the same rules. Keep this file free of other modules of the app.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeSet;
+use yaml_rust::{Yaml, YamlLoader};
+
+lazy_static! {
+    static ref INLINE_TAG_PATTERN: Regex =
+        Regex::new(r"(?:^|[\s(\[,;])#([\p{L}\p{N}_/-]+)").unwrap();
+    static ref INLINE_CODE_PATTERN: Regex = Regex::new(r"`[^`\n]*`").unwrap();
+}
+
+/// Front matter keys that hold tags
+const FRONT_MATTER_KEYS: [&str; 2] = ["tags", "tag"];
+
+/// Tags of a note, normalized, sorted and without duplicates.
+///
+/// Inline tags in code blocks and code spans are ignored. Front matter tags
+/// may be a YAML list or a comma or space separated string.
+pub fn extract_tags(content: &str) -> Vec<String> {
+    let mut tags = BTreeSet::new();
+
+    let (front_matter, body) = split_front_matter(content);
+    if let Some(front_matter) = front_matter {
+        for key in FRONT_MATTER_KEYS {
+            match &front_matter[key] {
+                Yaml::Array(items) => {
+                    for item in items.iter().filter_map(Yaml::as_str) {
+                        tags.extend(normalize_tag(item));
+                    }
+                }
+                Yaml::String(list) => {
+                    for item in list.split(|c: char| c == ',' || c.is_whitespace()) {
+                        tags.extend(normalize_tag(item));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line = INLINE_CODE_PATTERN
+            .replace_all(line, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+        for caps in INLINE_TAG_PATTERN.captures_iter(&line) {
+            tags.extend(normalize_tag(&caps[1]));
+        }
+    }
+
+    tags.into_iter().collect()
+}
+
+/// Parsed front matter and the body after it. A note whose front matter is
+/// not valid YAML is all body.
+fn split_front_matter(content: &str) -> (Option<Yaml>, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (None, content);
+    };
+    let body = rest[end + 4..]
+        .split_once('\n')
+        .map_or("", |(_, body)| body);
+    match YamlLoader::load_from_str(&rest[..end]) {
+        Ok(docs) => (docs.into_iter().next(), body),
+        Err(_) => (None, content),
+    }
+}
+
+/// Lowercases a tag and strips the leading `#` and stray slashes. Returns
+/// `None` for things that are not tags, such as `#123` or an empty string.
+pub fn normalize_tag(raw: &str) -> Option<String> {
+    let tag = raw
+        .trim()
+        .trim_start_matches('#')
+        .trim_matches(['/', '-'])
+        .to_lowercase();
+
+    let valid = !tag.is_empty()
+        && !tag.split('/').any(str::is_empty)
+        && !tag.chars().all(|c| c.is_ascii_digit() || c == '/')
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'));
+    valid.then_some(tag)
+}
+
+/// Whether `tag` matches `query`. `area/*` matches `area` and every tag
+/// nested under it; anything else must match exactly.
+pub fn tag_matches(tag: &str, query: &str) -> bool {
+    match query.strip_suffix("/*") {
+        Some(prefix) => {
+            let Some(prefix) = normalize_tag(prefix) else {
+                return false;
+            };
+            tag == prefix
+                || tag
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }
+        None => normalize_tag(query).is_some_and(|query| tag == query),
+    }
+}
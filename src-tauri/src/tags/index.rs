@@ -0,0 +1,239 @@
+//! In-memory tag index for a vault
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use walkdir::WalkDir;
+
+use super::extract::{extract_tags, tag_matches};
+
+/// A tag and the number of notes carrying it or a tag nested under it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    pub name: String,
+    pub count: usize,
+}
+
+pub struct TagIndex {
+    vault_root: PathBuf,
+    /// Relative note path -> tags
+    notes: HashMap<String, Vec<String>>,
+    /// Tag -> relative paths of the notes carrying it
+    tags: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl TagIndex {
+    /// Reads every note in the vault, skipping hidden folders
+    pub fn build(vault_root: &Path) -> Self {
+        let mut index = Self {
+            vault_root: vault_root.to_path_buf(),
+            notes: HashMap::new(),
+            tags: BTreeMap::new(),
+        };
+
+        for entry in walk(vault_root) {
+            index.update_file(entry.path());
+        }
+        index
+    }
+
+    /// Applies a change to a note or a folder. A folder that was deleted or
+    /// renamed drops the notes under it; a folder that appeared is read.
+    pub fn update_path(&mut self, path: &Path) {
+        if path.extension().and_then(|e| e.to_str()) == Some("md") && !path.is_dir() {
+            self.update_file(path);
+            return;
+        }
+        let Some(folder) = self.relative_folder(path) else {
+            return;
+        };
+        let removed: Vec<String> = self
+            .notes
+            .keys()
+            .filter(|rel| rel.starts_with(&folder))
+            .cloned()
+            .collect();
+        for rel in removed {
+            self.remove(&rel);
+        }
+
+        if path.is_dir() {
+            for entry in walk(path) {
+                self.update_file(entry.path());
+            }
+        }
+    }
+
+    /// Re-reads one file after a change; removes it if it no longer exists
+    pub fn update_file(&mut self, path: &Path) {
+        let Some(rel) = self.relative_path(path) else {
+            return;
+        };
+        self.remove(&rel);
+
+        if !path.is_file() {
+            return;
+        }
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return;
+        };
+        let tags = extract_tags(&content);
+        for tag in &tags {
+            self.tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(rel.clone());
+        }
+        self.notes.insert(rel, tags);
+    }
+
+    fn remove(&mut self, rel: &str) {
+        for tag in self.notes.remove(rel).unwrap_or_default() {
+            if let Some(paths) = self.tags.get_mut(&tag) {
+                paths.remove(rel);
+                if paths.is_empty() {
+                    self.tags.remove(&tag);
+                }
+            }
+        }
+    }
+
+    /// Tags of a note (relative path)
+    pub fn tags_of(&self, rel_path: &str) -> &[String] {
+        self.notes.get(rel_path).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Relative paths of the notes matching a tag query, sorted
+    pub fn search(&self, query: &str) -> Vec<String> {
+        let paths: BTreeSet<&String> = self
+            .tags
+            .iter()
+            .filter(|(tag, _)| tag_matches(tag, query))
+            .flat_map(|(_, paths)| paths)
+            .collect();
+        paths.into_iter().cloned().collect()
+    }
+
+    /// Every tag with its note count, most used first. Parent tags count the
+    /// notes of their nested tags too, and are listed even if never used alone.
+    pub fn counts(&self) -> Vec<TagCount> {
+        let mut notes_by_tag: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (tag, paths) in &self.tags {
+            let ancestors = tag
+                .match_indices('/')
+                .map(|(i, _)| &tag[..i])
+                .chain(std::iter::once(tag.as_str()));
+            for name in ancestors {
+                notes_by_tag
+                    .entry(name)
+                    .or_default()
+                    .extend(paths.iter().map(String::as_str));
+            }
+        }
+
+        let mut counts: Vec<TagCount> = notes_by_tag
+            .into_iter()
+            .map(|(name, notes)| TagCount {
+                name: name.to_string(),
+                count: notes.len(),
+            })
+            .collect();
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+        counts
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<String> {
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            return None;
+        }
+        self.relative(path)
+    }
+
+    /// Relative path of a folder with a trailing `/`
+    fn relative_folder(&self, path: &Path) -> Option<String> {
+        self.relative(path)
+            .filter(|rel| !rel.is_empty())
+            .map(|rel| format!("{}/", rel))
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        let rel = path.strip_prefix(&self.vault_root).ok()?;
+        if rel
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+        {
+            return None;
+        }
+        Some(rel.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+/// Files under `root`, skipping hidden folders
+fn walk(root: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(root)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+}
+
+/// Tag indexes, one per open vault
+#[derive(Default)]
+pub struct TagIndexState {
+    indexes: Mutex<HashMap<PathBuf, TagIndex>>,
+}
+
+impl TagIndexState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f` against the tag index of `vault_path`, building it on first use.
+    pub async fn with_index<T>(
+        &self,
+        vault_path: &Path,
+        f: impl FnOnce(&TagIndex) -> T,
+    ) -> Result<T, String> {
+        let mut indexes = self.indexes.lock().await;
+        if !indexes.contains_key(vault_path) {
+            let root = vault_path.to_path_buf();
+            let index = tokio::task::spawn_blocking(move || TagIndex::build(&root))
+                .await
+                .map_err(|e| format!("Failed to build tag index: {}", e))?;
+            println!(
+                "🏷️ Built tag index for {:?}: {} notes, {} tags",
+                vault_path,
+                index.notes.len(),
+                index.tags.len()
+            );
+            indexes.insert(vault_path.to_path_buf(), index);
+        }
+        Ok(f(&indexes[vault_path]))
+    }
+
+    /// Applies a file watcher event to notes and folders. Vaults that have
+    /// not been queried yet are skipped.
+    pub async fn files_changed(&self, vault_path: &Path, paths: &[PathBuf]) {
+        let mut indexes = self.indexes.lock().await;
+        let Some(mut index) = indexes.remove(vault_path) else {
+            return;
+        };
+        let paths = paths.to_vec();
+        let updated = tokio::task::spawn_blocking(move || {
+            for path in &paths {
+                index.update_path(path);
+            }
+            index
+        })
+        .await;
+        match updated {
+            Ok(index) => {
+                indexes.insert(vault_path.to_path_buf(), index);
+            }
+            // Rebuilt on the next query
+            Err(e) => eprintln!("⚠️ Failed to update tag index: {}", e),
+        }
+    }
+}
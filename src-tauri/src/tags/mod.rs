@@ -0,0 +1,18 @@
+//! Tag index
+//!
+//! One index of the tags used in a vault, shared by the editor's tag search
+//! and the agent tag commands. Tags come from inline `#tag` mentions and from
+//! the `tags:` list in front matter, and may be nested (`#area/sub/leaf`).
+//! Queries match a tag exactly, or a whole subtree with `area/*`.
+//!
+//! The index is built the first time a vault is queried and then kept up to
+//! date from the shared vault file watcher.
+
+pub mod extract;
+pub mod index;
+
+pub use extract::{extract_tags, normalize_tag, tag_matches};
+pub use index::{TagCount, TagIndex, TagIndexState};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,110 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn write(root: &std::path::Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_extract_tags_from_body_and_front_matter() {
+    let content = "---\ntags: [Project/Alpha, meeting]\ntag: draft, review\n---\n\
+                   # Heading is not a tag\n\
+                   Working on #project/beta and #Área (see #42 or #a/) today.\n\
+                   Link http://example.com/#anchor `#code` and x#y\n\
+                   ```\n#fenced\n```\n- item #todo/later,#urgent\n";
+
+    assert_eq!(
+        extract_tags(content),
+        vec![
+            "a",
+            "draft",
+            "meeting",
+            "project/alpha",
+            "project/beta",
+            "review",
+            "todo/later",
+            "urgent",
+            "área"
+        ]
+    );
+
+    let listed = "---\ntags:\n  - '#one'\n  - two/three\n---\nBody\n";
+    assert_eq!(extract_tags(listed), vec!["one", "two/three"]);
+}
+
+#[test]
+fn test_tag_queries() {
+    assert!(tag_matches("project/alpha", "#project/*"));
+    assert!(tag_matches("project", "project/*"));
+    assert!(!tag_matches("projects", "project/*"));
+    assert!(!tag_matches("project/alpha", "project"));
+    assert!(tag_matches("project/alpha", "#Project/Alpha"));
+    assert_eq!(normalize_tag("#123"), None);
+    assert_eq!(normalize_tag("2024/10"), None);
+    assert_eq!(normalize_tag("y2024"), Some("y2024".to_string()));
+}
+
+#[test]
+fn test_index_counts_and_updates() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(root, "a.md", "#area/sub/leaf #solo");
+    write(root, "b.md", "---\ntags: [area]\n---\nText");
+    write(root, "c/d.md", "#area/other");
+    write(root, ".trash/e.md", "#area");
+
+    let mut index = TagIndex::build(root);
+    assert_eq!(index.search("area"), vec!["b.md"]);
+    assert_eq!(index.search("area/*"), vec!["a.md", "b.md", "c/d.md"]);
+    assert_eq!(index.tags_of("c/d.md"), ["area/other".to_string()]);
+
+    let counts = index.counts();
+    assert_eq!(
+        counts[0],
+        TagCount {
+            name: "area".to_string(),
+            count: 3
+        }
+    );
+    assert!(counts.contains(&TagCount {
+        name: "area/sub".to_string(),
+        count: 1
+    }));
+
+    write(root, "a.md", "#solo only");
+    index.update_file(&root.join("a.md"));
+    fs::remove_file(root.join("c/d.md")).unwrap();
+    index.update_file(&root.join("c/d.md"));
+
+    assert_eq!(index.search("area/*"), vec!["b.md"]);
+    assert_eq!(index.search("solo"), vec!["a.md"]);
+    assert!(index.tags_of("c/d.md").is_empty());
+}
+
+#[test]
+fn test_folder_changes_update_notes_under_them() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(root, "Projects/a.md", "#work");
+    write(root, "Projects/Old/b.md", "#work/old");
+    write(root, "Projects.md", "#work");
+
+    let mut index = TagIndex::build(root);
+    assert_eq!(index.search("work/*").len(), 3);
+
+    fs::rename(root.join("Projects"), root.join("Archive")).unwrap();
+    index.update_path(&root.join("Projects"));
+    assert_eq!(index.search("work/*"), vec!["Projects.md"]);
+    index.update_path(&root.join("Archive"));
+    assert_eq!(
+        index.search("work/*"),
+        vec!["Archive/Old/b.md", "Archive/a.md", "Projects.md"]
+    );
+
+    fs::remove_dir_all(root.join("Archive/Old")).unwrap();
+    index.update_path(&root.join("Archive/Old"));
+    assert_eq!(index.search("work/*"), vec!["Archive/a.md", "Projects.md"]);
+}
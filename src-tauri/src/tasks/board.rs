@@ -0,0 +1,225 @@
+//! Kanban boards kept as plain notes
+//!
+//! Every `## Column` heading of a board note is a column, and the top-level
+//! tasks under it are its cards, in order. Tasks nested under a card are its
+//! subtasks. Cards are ordinary tasks, so they carry their task UUIDs and
+//! stay in the task index like any other task.
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::bulk::{Note, HEADING_PATTERN};
+use crate::identity::frontmatter::{TaskFrontMatter, TaskProperties};
+use crate::identity::tasks::{TaskParser, TaskStatus};
+use crate::identity::uuid::UuidGenerator;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Board {
+    /// Text of the note's `#` heading
+    pub title: Option<String>,
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardColumn {
+    pub name: String,
+    /// Status the column stands for, when its name is one (`Todo`, `In
+    /// progress`, `Done`, ...)
+    pub status: Option<TaskStatus>,
+    /// Line of the heading, 1-based
+    pub line_number: usize,
+    pub cards: Vec<BoardCard>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardCard {
+    /// Task UUID, `None` until the task has been given one
+    pub id: Option<String>,
+    pub text: String,
+    pub status: TaskStatus,
+    /// Line of the task, 1-based
+    pub line_number: usize,
+    pub properties: HashMap<String, String>,
+    pub subtasks: usize,
+    pub subtasks_done: usize,
+}
+
+impl Board {
+    fn find_card(&self, card_id: &str) -> Option<&BoardCard> {
+        self.columns
+            .iter()
+            .flat_map(|column| &column.cards)
+            .find(|card| card.id.as_deref() == Some(card_id))
+    }
+
+    fn find_column(&self, name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|column| column.name.eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Status a column name stands for; `To do` and `In progress` count as well
+/// as the status names themselves
+fn column_status(name: &str) -> Option<TaskStatus> {
+    TaskStatus::parse(name).or_else(|| TaskStatus::parse(&name.replace(' ', "")))
+}
+
+/// Number of lines of the note's front matter, fences included
+fn front_matter_len(lines: &[&str]) -> usize {
+    if lines.first().map(|line| line.trim_end()) != Some("---") {
+        return 0;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|line| line.trim_end() == "---")
+        .map_or(0, |end| end + 2)
+}
+
+/// Read a board note
+pub fn parse_board(content: &str) -> Board {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut board = Board::default();
+
+    // Column of each line, by index
+    let mut column_of_line = vec![None; lines.len()];
+    let mut column = None;
+    for (index, line) in lines.iter().enumerate().skip(front_matter_len(&lines)) {
+        if let Some(caps) = HEADING_PATTERN.captures(line) {
+            match caps[1].len() {
+                1 => {
+                    board.title.get_or_insert_with(|| caps[2].to_string());
+                    column = None;
+                }
+                2 => {
+                    board.columns.push(BoardColumn {
+                        name: caps[2].to_string(),
+                        status: column_status(&caps[2]),
+                        line_number: index + 1,
+                        cards: Vec::new(),
+                    });
+                    column = Some(board.columns.len() - 1);
+                }
+                _ => {}
+            }
+        }
+        column_of_line[index] = column;
+    }
+
+    // Card each task belongs to, by line number
+    let mut card_of_line: HashMap<usize, (usize, usize)> = HashMap::new();
+    for task in TaskParser::extract_all_tasks(content) {
+        if let Some(parent) = task.parent_line {
+            if let Some(&(column, index)) = card_of_line.get(&parent) {
+                let card = &mut board.columns[column].cards[index];
+                card.subtasks += 1;
+                if task.status == TaskStatus::Done {
+                    card.subtasks_done += 1;
+                }
+                card_of_line.insert(task.line_number, (column, index));
+            }
+            continue;
+        }
+        let Some(column) = column_of_line[task.line_number - 1] else {
+            continue;
+        };
+        let cards = &mut board.columns[column].cards;
+        card_of_line.insert(task.line_number, (column, cards.len()));
+        cards.push(BoardCard {
+            id: task.id,
+            text: task.content,
+            status: task.status,
+            line_number: task.line_number,
+            properties: task.properties,
+            subtasks: 0,
+            subtasks_done: 0,
+        });
+    }
+
+    board
+}
+
+/// Move a card, with its subtasks, to a column of the board: before the
+/// card at `position` in it, or to the end when there is no such card.
+/// With a `status`, the card's checkbox and front matter entry are updated
+/// too, and a recurring card that gets done leaves its next occurrence where
+/// it was. Returns the new content of the note.
+pub fn move_card(
+    content: &str,
+    card_id: &str,
+    column: &str,
+    position: Option<usize>,
+    status: Option<TaskStatus>,
+    today: NaiveDate,
+) -> Result<String> {
+    let board = parse_board(content);
+    let card = board
+        .find_card(card_id)
+        .ok_or_else(|| anyhow!("Card {} is not on the board", card_id))?;
+    let target = board
+        .find_column(column)
+        .ok_or_else(|| anyhow!("The board has no column {}", column))?;
+
+    let mut note = Note::parse(Some(content.to_string()));
+    let mut index = card.line_number - 1;
+    let line = note.lines[index].clone();
+    let is_recurring = card.properties.contains_key("repeat");
+    if status == Some(TaskStatus::Done) && card.status != TaskStatus::Done && is_recurring {
+        let new_id = UuidGenerator::new().generate()?;
+        if let Some(next) = TaskParser::next_occurrence(&line, &new_id, today) {
+            note.lines.insert(index, next);
+            index += 1;
+        }
+    }
+    let mut block = note.take_block(index);
+    if let Some(status) = status {
+        block[0] = TaskParser::set_task_status(&block[0], status);
+    }
+
+    // Find the column again, as lines have moved
+    let rest = parse_board(&note.content());
+    let column = &rest.columns[target];
+    let cards = &column.cards;
+    let at = match (
+        position.and_then(|position| cards.get(position)),
+        cards.last(),
+    ) {
+        (Some(before), _) => before.line_number - 1,
+        (None, Some(last)) => note.block_end(last.line_number - 1),
+        (None, None) => {
+            // First card of the column, set off from the heading
+            let at = column.line_number;
+            block.insert(0, String::new());
+            if note
+                .lines
+                .get(at)
+                .is_some_and(|line| !line.trim().is_empty())
+            {
+                block.push(String::new());
+            }
+            at
+        }
+    };
+    note.lines.splice(at..at, block);
+
+    if let Some(status) = status.filter(|status| *status != card.status) {
+        note.update_front_matter(|fields| {
+            let mut props = TaskFrontMatter::get_task(fields, card_id)
+                .unwrap_or_else(|| TaskProperties::new(card.text.clone()));
+            props.set_status(status.into());
+            TaskFrontMatter::upsert_task(fields, card_id.to_string(), props);
+            true
+        })?;
+    }
+    Ok(note.content())
+}
+
+#[cfg(test)]
+#[path = "board_test.rs"]
+mod tests;
@@ -0,0 +1,180 @@
+use super::*;
+use crate::identity::frontmatter::{FrontMatterParser, TaskStatus as FrontMatterStatus};
+
+const BOARD: &str = "# Launch
+
+## To do
+
+- [ ] Write copy <!-- tid: 0000-aaaa -->
+  - [x] Outline <!-- tid: 0000-aaab -->
+  - [ ] Draft <!-- tid: 0000-aaac -->
+- [ ] Pick date @due(2025-06-20) <!-- tid: 0000-bbbb -->
+
+## In progress
+
+- [/] Design <!-- tid: 0000-cccc -->
+
+## Done
+
+## Ideas
+
+Things to maybe do.
+";
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 15).unwrap()
+}
+
+fn card_ids(board: &Board, column: usize) -> Vec<&str> {
+    board.columns[column]
+        .cards
+        .iter()
+        .map(|card| card.id.as_deref().unwrap_or(""))
+        .collect()
+}
+
+#[test]
+fn test_parse_board() {
+    let board = parse_board(BOARD);
+    assert_eq!(board.title.as_deref(), Some("Launch"));
+
+    let columns: Vec<(&str, Option<TaskStatus>)> = board
+        .columns
+        .iter()
+        .map(|column| (column.name.as_str(), column.status))
+        .collect();
+    assert_eq!(
+        columns,
+        vec![
+            ("To do", Some(TaskStatus::Todo)),
+            ("In progress", Some(TaskStatus::InProgress)),
+            ("Done", Some(TaskStatus::Done)),
+            ("Ideas", None),
+        ]
+    );
+    assert_eq!(card_ids(&board, 0), vec!["0000-aaaa", "0000-bbbb"]);
+    assert_eq!(card_ids(&board, 1), vec!["0000-cccc"]);
+    assert!(board.columns[2].cards.is_empty());
+
+    let card = &board.columns[0].cards[0];
+    assert_eq!(card.text, "Write copy");
+    assert_eq!(card.line_number, 5);
+    assert_eq!((card.subtasks, card.subtasks_done), (2, 1));
+    assert_eq!(
+        board.columns[0].cards[1]
+            .properties
+            .get("due")
+            .map(String::as_str),
+        Some("2025-06-20")
+    );
+}
+
+#[test]
+fn test_parse_board_ignores_tasks_outside_columns() {
+    let content = "---\ntitle: Board\n---\n# Board\n\n- [ ] Loose <!-- tid: 0000-0001 -->\n\n## Todo\n\n- [ ] Card <!-- tid: 0000-0002 -->\n";
+    let board = parse_board(content);
+    assert_eq!(board.columns.len(), 1);
+    assert_eq!(card_ids(&board, 0), vec!["0000-0002"]);
+}
+
+#[test]
+fn test_move_card_with_subtasks_to_end_of_column() {
+    let content = move_card(BOARD, "0000-aaaa", "in progress", None, None, today()).unwrap();
+    assert_eq!(
+        content,
+        "# Launch
+
+## To do
+
+- [ ] Pick date @due(2025-06-20) <!-- tid: 0000-bbbb -->
+
+## In progress
+
+- [/] Design <!-- tid: 0000-cccc -->
+- [ ] Write copy <!-- tid: 0000-aaaa -->
+  - [x] Outline <!-- tid: 0000-aaab -->
+  - [ ] Draft <!-- tid: 0000-aaac -->
+
+## Done
+
+## Ideas
+
+Things to maybe do.
+"
+    );
+}
+
+#[test]
+fn test_move_card_to_position() {
+    let content = move_card(BOARD, "0000-bbbb", "To do", Some(0), None, today()).unwrap();
+    assert_eq!(
+        card_ids(&parse_board(&content), 0),
+        vec!["0000-bbbb", "0000-aaaa"]
+    );
+
+    let content = move_card(BOARD, "0000-cccc", "To do", Some(1), None, today()).unwrap();
+    let board = parse_board(&content);
+    assert_eq!(
+        card_ids(&board, 0),
+        vec!["0000-aaaa", "0000-cccc", "0000-bbbb"]
+    );
+    assert!(board.columns[1].cards.is_empty());
+    assert!(content.contains("## In progress\n\n## Done"));
+}
+
+#[test]
+fn test_move_card_to_empty_column_updates_status() {
+    let content = move_card(
+        BOARD,
+        "0000-cccc",
+        "Done",
+        None,
+        Some(TaskStatus::Done),
+        today(),
+    )
+    .unwrap();
+
+    let (fm, body) = FrontMatterParser::parse(&content).unwrap();
+    assert!(body.contains(
+        "## Done\n\n- [x] Design <!-- tid: 0000-cccc -->\n\n## Ideas\n\nThings to maybe do.\n"
+    ));
+    let board = parse_board(&content);
+    assert_eq!(card_ids(&board, 2), vec!["0000-cccc"]);
+    assert_eq!(board.columns[2].cards[0].status, TaskStatus::Done);
+
+    let props = TaskFrontMatter::get_task(&fm.unwrap().extra_fields, "0000-cccc").unwrap();
+    assert_eq!(props.status, FrontMatterStatus::Done);
+    assert_eq!(props.text, "Design");
+}
+
+#[test]
+fn test_move_recurring_card_to_done_leaves_next_occurrence() {
+    let content = "## Todo\n\n- [ ] Water plants @due(2025-06-14) @repeat(weekly) <!-- tid: 0000-dddd -->\n\n## Done\n";
+    let moved = move_card(
+        content,
+        "0000-dddd",
+        "done",
+        None,
+        Some(TaskStatus::Done),
+        today(),
+    )
+    .unwrap();
+
+    let board = parse_board(&moved);
+    let next = &board.columns[0].cards;
+    assert_eq!(next.len(), 1);
+    assert_ne!(next[0].id.as_deref(), Some("0000-dddd"));
+    assert_eq!(
+        next[0].properties.get("due").map(String::as_str),
+        Some("2025-06-21")
+    );
+    assert_eq!(card_ids(&board, 1), vec!["0000-dddd"]);
+}
+
+#[test]
+fn test_move_card_errors() {
+    assert!(move_card(BOARD, "0000-ffff", "Done", None, None, today()).is_err());
+    assert!(move_card(BOARD, "0000-aaaa", "Someday", None, None, today()).is_err());
+    // Subtasks move with their card, not on their own
+    assert!(move_card(BOARD, "0000-aaab", "Done", None, None, today()).is_err());
+}
@@ -0,0 +1,901 @@
+//! Bulk edits of the tasks a query matches
+//!
+//! An edit is planned in memory first: every note it touches is read once,
+//! and written once, atomically, when the plan is applied. A dry run returns
+//! the plan without writing, so the preview shows exactly what applying
+//! would do. Applied edits are recorded in an undo journal under
+//! `.vault/task_journal` that holds the notes as they were.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use super::index::TaskRecord;
+use crate::identity::frontmatter::{
+    FrontMatter, FrontMatterParser, FrontMatterWriter, TaskFrontMatter, TaskProperties,
+};
+use crate::identity::tasks::dates::find_task_date;
+use crate::identity::tasks::{
+    format_duration, parse_duration, DateKind, ParsedTask, TaskDate, TaskParser, TaskStatus,
+};
+use crate::identity::uuid::UuidGenerator;
+
+lazy_static! {
+    pub(super) static ref HEADING_PATTERN: Regex =
+        Regex::new(r"^(#{1,6})\s+(.*?)\s*#*\s*$").unwrap();
+}
+
+/// Folder of the monthly notes that archived tasks are moved to
+pub const ARCHIVE_FOLDER: &str = "Archive";
+
+/// Undo journals kept per vault; older ones are dropped
+const MAX_JOURNALS: usize = 20;
+
+/// A change applied to every task a query matches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BulkOperation {
+    /// Set or, without a value, remove a property: `status`, `due`,
+    /// `scheduled`, `start`, `priority`, `project`, `estimate` or `tags`
+    /// (comma separated)
+    SetProperty { key: String, value: Option<String> },
+    /// Move dates by a number of days: all of a task's dates, or the one
+    /// `field` names (`due`, `scheduled` or `start`)
+    Reschedule { days: i64, field: Option<String> },
+    /// Move tasks with their subtasks to the end of a note, or of a section
+    /// of it, creating either when missing. `target` is vault-relative.
+    Move {
+        target: String,
+        section: Option<String>,
+    },
+    /// Mark tasks done, adding the next occurrence of recurring ones
+    Complete,
+    /// Move done and cancelled tasks out of the way, leaving a
+    /// `[[tid:...]]` reference where they were
+    Archive {
+        #[serde(default)]
+        into: ArchiveTarget,
+    },
+}
+
+/// Where archived tasks go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveTarget {
+    /// The note for the month the task was completed in, in `Archive/`
+    #[default]
+    MonthlyNote,
+    /// The `tasks:` front matter of the task's own note
+    FrontMatter,
+}
+
+impl BulkOperation {
+    /// Short description for the undo journal
+    pub fn describe(&self) -> String {
+        match self {
+            BulkOperation::SetProperty {
+                key,
+                value: Some(value),
+            } => format!("Set {} to {}", key, value),
+            BulkOperation::SetProperty { key, value: None } => format!("Remove {}", key),
+            BulkOperation::Reschedule { days, field } => format!(
+                "Reschedule {} by {:+} days",
+                field.as_deref().unwrap_or("dates"),
+                days
+            ),
+            BulkOperation::Move { target, .. } => format!("Move to {}", target),
+            BulkOperation::Complete => "Complete".to_string(),
+            BulkOperation::Archive { .. } => "Archive".to_string(),
+        }
+    }
+}
+
+/// What a bulk edit does to one task
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkChange {
+    pub task_id: String,
+    /// Vault-relative note the task is in
+    pub file_path: String,
+    pub before: String,
+    pub after: String,
+    /// Vault-relative note the task moves to
+    pub moved_to: Option<String>,
+}
+
+/// A matched task the edit leaves alone
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkSkip {
+    pub task_id: String,
+    pub reason: String,
+}
+
+/// A note written by a bulk edit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEdit {
+    /// Vault-relative path
+    pub path: String,
+    /// Content before the edit, `None` for a note the edit creates
+    pub before: Option<String>,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BulkPlan {
+    pub changes: Vec<BulkChange>,
+    pub skipped: Vec<BulkSkip>,
+    pub files: Vec<FileEdit>,
+}
+
+/// A note being edited, kept as lines
+pub(super) struct Note {
+    before: Option<String>,
+    pub(super) lines: Vec<String>,
+    newline: &'static str,
+    trailing_newline: bool,
+}
+
+impl Note {
+    pub(super) fn parse(before: Option<String>) -> Self {
+        let content = before.as_deref().unwrap_or("");
+        Self {
+            lines: content.lines().map(str::to_string).collect(),
+            newline: if content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            },
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+            before,
+        }
+    }
+
+    pub(super) fn content(&self) -> String {
+        let mut content = self.lines.join(self.newline);
+        if self.trailing_newline && !content.is_empty() {
+            content.push_str(self.newline);
+        }
+        content
+    }
+
+    /// Index of the line of a task
+    pub(super) fn find_task(&self, task_id: &str) -> Option<usize> {
+        self.lines.iter().position(|line| {
+            TaskParser::parse_line(line, 0).is_some_and(|task| task.id.as_deref() == Some(task_id))
+        })
+    }
+
+    /// Remove a task line with the lines nested under it, dedented to the
+    /// task's level. Blank lines the block was between are merged.
+    pub(super) fn take_block(&mut self, index: usize) -> Vec<String> {
+        let end = self.block_end(index);
+        let root_indent_len = self.lines[index].len() - self.lines[index].trim_start().len();
+        let block = self
+            .lines
+            .drain(index..end)
+            .map(|line| {
+                let indent_len = line.len() - line.trim_start().len();
+                line[indent_len.min(root_indent_len)..].to_string()
+            })
+            .collect();
+
+        let blank = |line: Option<&String>| line.is_some_and(|line| line.trim().is_empty());
+        if index == self.lines.len() {
+            self.trim_trailing_blank_lines();
+        } else if index > 0 && blank(self.lines.get(index - 1)) && blank(self.lines.get(index)) {
+            self.lines.remove(index);
+        }
+        block
+    }
+
+    /// Index past the last line nested under the line at `index`
+    pub(super) fn block_end(&self, index: usize) -> usize {
+        let root_width = indent_width(&self.lines[index]);
+        (index + 1..self.lines.len())
+            .find(|&i| {
+                self.lines[i].trim().is_empty() || indent_width(&self.lines[i]) <= root_width
+            })
+            .unwrap_or(self.lines.len())
+    }
+
+    /// Append lines to the end of the note, or of a section of it, which
+    /// is added when the note does not have it
+    fn insert_block(&mut self, section: Option<&str>, mut block: Vec<String>) {
+        let heading = section.and_then(|section| {
+            self.lines.iter().position(|line| {
+                HEADING_PATTERN
+                    .captures(line)
+                    .is_some_and(|caps| caps[2].eq_ignore_ascii_case(section.trim()))
+            })
+        });
+        let end = match (section, heading) {
+            (_, Some(heading)) => {
+                let level = HEADING_PATTERN.captures(&self.lines[heading]).unwrap()[1].len();
+                (heading + 1..self.lines.len())
+                    .find(|&i| {
+                        HEADING_PATTERN
+                            .captures(&self.lines[i])
+                            .is_some_and(|caps| caps[1].len() <= level)
+                    })
+                    .unwrap_or(self.lines.len())
+            }
+            (Some(section), None) => {
+                self.trim_trailing_blank_lines();
+                if !self.lines.is_empty() {
+                    self.lines.push(String::new());
+                }
+                self.lines.push(format!("## {}", section.trim()));
+                self.lines.len()
+            }
+            (None, None) => self.lines.len(),
+        };
+
+        // Attach to the last line rather than the blank lines after it, and
+        // keep a blank line between the tasks and anything but a list
+        let mut at = end;
+        while at > 0 && self.lines[at - 1].trim().is_empty() {
+            at -= 1;
+        }
+        if at > 0 && !is_list_line(&self.lines[at - 1]) {
+            block.insert(0, String::new());
+        }
+        if at == end && end < self.lines.len() {
+            block.push(String::new());
+        }
+        self.lines.splice(at..at, block);
+    }
+
+    fn trim_trailing_blank_lines(&mut self) {
+        while self.lines.last().is_some_and(|line| line.trim().is_empty()) {
+            self.lines.pop();
+        }
+    }
+
+    /// Change the task entries in the note's front matter. The note is only
+    /// rewritten when `update` reports a change.
+    pub(super) fn update_front_matter(
+        &mut self,
+        update: impl FnOnce(&mut BTreeMap<String, serde_json::Value>) -> bool,
+    ) -> Result<()> {
+        let (fm, body) = FrontMatterParser::parse(&self.content())?;
+        let mut fm = fm.unwrap_or_else(FrontMatter::new);
+        if !update(&mut fm.extra_fields) {
+            return Ok(());
+        }
+        let before = self.before.take();
+        *self = Note::parse(Some(FrontMatterWriter::write(&fm, &body)?));
+        self.before = before;
+        Ok(())
+    }
+}
+
+fn is_list_line(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("- ")
+        || line.starts_with("* ")
+        || line.starts_with("+ ")
+        || line.split_once(". ").is_some_and(|(number, _)| {
+            !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// An operation with its value checked and normalized
+enum Edit {
+    Status(TaskStatus),
+    Properties(HashMap<String, Option<String>>),
+    Reschedule(Vec<DateKind>, i64),
+    Complete,
+    Move {
+        target: String,
+        section: Option<String>,
+    },
+    Archive(ArchiveTarget),
+}
+
+fn prepare<Tz: TimeZone>(operation: &BulkOperation, now: &DateTime<Tz>) -> Result<Edit>
+where
+    Tz::Offset: Copy,
+{
+    let date_kind = |key: &str| DateKind::ALL.into_iter().find(|kind| kind.key() == key);
+    Ok(match operation {
+        BulkOperation::SetProperty { key, value } => {
+            let key = key.trim().to_lowercase();
+            let value = value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty());
+            if key == "status" {
+                let value = value.ok_or_else(|| anyhow!("A status is required"))?;
+                let status =
+                    TaskStatus::parse(value).ok_or_else(|| anyhow!("Unknown status: {}", value))?;
+                return Ok(Edit::Status(status));
+            }
+            let value = match (key.as_str(), value) {
+                (_, None) => None,
+                ("due" | "scheduled" | "start", Some(value)) => Some(
+                    TaskDate::parse(value, now)
+                        .ok_or_else(|| anyhow!("Not a date: {}", value))?
+                        .to_string(),
+                ),
+                ("priority", Some(value)) => match value.to_lowercase().as_str() {
+                    priority @ ("high" | "medium" | "low") => Some(priority.to_string()),
+                    _ => bail!("Priority must be high, medium or low"),
+                },
+                ("estimate", Some(value)) => Some(format_duration(u64::from(
+                    parse_duration(value).ok_or_else(|| anyhow!("Not a duration: {}", value))?,
+                ))),
+                ("project" | "tags", Some(value)) => Some(value.to_string()),
+                _ => bail!("Unknown task property: {}", key),
+            };
+            Edit::Properties(HashMap::from([(key, value)]))
+        }
+        BulkOperation::Reschedule { days, field } => {
+            let kinds = match field.as_deref().map(str::trim) {
+                None | Some("") => DateKind::ALL.to_vec(),
+                Some(field) => {
+                    vec![date_kind(field).ok_or_else(|| anyhow!("Unknown date: {}", field))?]
+                }
+            };
+            Edit::Reschedule(kinds, *days)
+        }
+        BulkOperation::Move { target, section } => {
+            let target = target.trim().replace('\\', "/");
+            let target = if target.ends_with(".md") {
+                target
+            } else {
+                format!("{}.md", target)
+            };
+            let inside_vault = Path::new(&target)
+                .components()
+                .all(|component| matches!(component, std::path::Component::Normal(_)));
+            if !inside_vault {
+                bail!("Target note must be a relative path inside the vault");
+            }
+            Edit::Move {
+                target,
+                section: section
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|section| !section.is_empty())
+                    .map(str::to_string),
+            }
+        }
+        BulkOperation::Complete => Edit::Complete,
+        BulkOperation::Archive { into } => Edit::Archive(*into),
+    })
+}
+
+/// Vault-relative note that an archived task goes to
+fn archive_note<Tz: TimeZone>(task: &TaskRecord, now: &DateTime<Tz>) -> String {
+    let completed = task
+        .completed_at
+        .map(|at| at.with_timezone(&now.timezone()).date_naive())
+        .unwrap_or_else(|| now.date_naive());
+    format!("{}/Tasks {}.md", ARCHIVE_FOLDER, completed.format("%Y-%m"))
+}
+
+/// A task status to record in a note's front matter
+struct StatusChange {
+    path: String,
+    task_id: String,
+    status: TaskStatus,
+    text: String,
+}
+
+/// Lines taken out of a note to go into another
+struct MovedBlock {
+    source: String,
+    /// `None` when the tasks go into the source note's front matter
+    target: Option<String>,
+    section: Option<String>,
+    lines: Vec<String>,
+    /// Tasks in the lines, with their IDs
+    tasks: Vec<(String, ParsedTask)>,
+    archived: bool,
+}
+
+/// Notes an edit has read, by vault-relative path
+struct Workspace<'a> {
+    vault_root: &'a Path,
+    notes: BTreeMap<String, Note>,
+}
+
+impl Workspace<'_> {
+    fn note(&mut self, path: &str) -> Result<&mut Note> {
+        if !self.notes.contains_key(path) {
+            let before = match std::fs::read_to_string(self.vault_root.join(path)) {
+                Ok(content) => Some(content),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(anyhow!("Failed to read {}: {}", path, e)),
+            };
+            self.notes.insert(path.to_string(), Note::parse(before));
+        }
+        Ok(self.notes.get_mut(path).unwrap())
+    }
+}
+
+/// Plan `operation` on `tasks`, reading their notes from the vault. Tasks
+/// whose notes do not have them any more, or that the operation would not
+/// change, are skipped.
+pub fn plan_bulk_edit<Tz: TimeZone>(
+    tasks: &[TaskRecord],
+    operation: &BulkOperation,
+    vault_root: &Path,
+    now: &DateTime<Tz>,
+) -> Result<BulkPlan>
+where
+    Tz::Offset: Copy,
+{
+    let edit = prepare(operation, now)?;
+    let ids = UuidGenerator::new();
+    let records: HashMap<&str, &TaskRecord> =
+        tasks.iter().map(|task| (task.id.as_str(), task)).collect();
+    let mut plan = BulkPlan::default();
+    let mut workspace = Workspace {
+        vault_root,
+        notes: BTreeMap::new(),
+    };
+    let mut statuses: Vec<StatusChange> = Vec::new();
+    let mut moves: Vec<MovedBlock> = Vec::new();
+    // Tasks on their way to another note, subtasks of moved tasks included
+    let mut moved: HashSet<String> = HashSet::new();
+
+    let mut tasks: Vec<&TaskRecord> = tasks.iter().collect();
+    tasks.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.line_number.cmp(&b.line_number))
+    });
+
+    for task in tasks {
+        if moved.contains(&task.id) {
+            continue;
+        }
+        let skip = |reason: &str| BulkSkip {
+            task_id: task.id.clone(),
+            reason: reason.to_string(),
+        };
+        let Ok(relative) = task.file_path.strip_prefix(vault_root) else {
+            plan.skipped.push(skip("Note is outside the vault"));
+            continue;
+        };
+        let path = relative.to_string_lossy().replace('\\', "/");
+        let note = workspace.note(&path)?;
+        let Some(index) = note.find_task(&task.id) else {
+            plan.skipped.push(skip("Task is no longer in its note"));
+            continue;
+        };
+        let line = note.lines[index].clone();
+
+        let after = match &edit {
+            Edit::Status(status) => TaskParser::set_task_status(&line, *status),
+            Edit::Properties(updates) => TaskParser::update_task_properties(&line, updates),
+            Edit::Reschedule(kinds, days) => {
+                let mut rescheduled = line.clone();
+                for kind in kinds {
+                    let Some(mention) = find_task_date(&rescheduled, *kind, now) else {
+                        continue;
+                    };
+                    if let Some(date) = mention.date {
+                        let moved_date = Duration::try_days(*days)
+                            .and_then(|offset| date.date.checked_add_signed(offset))
+                            .ok_or_else(|| anyhow!("Cannot move {} by {} days", date.date, days))?;
+                        let moved_date = TaskDate {
+                            date: moved_date,
+                            time: date.time,
+                        };
+                        rescheduled.replace_range(
+                            mention.range,
+                            &format!("@{}({})", kind.key(), moved_date),
+                        );
+                    }
+                }
+                rescheduled
+            }
+            Edit::Complete => TaskParser::set_task_status(&line, TaskStatus::Done),
+            Edit::Move { .. } | Edit::Archive(_) => {
+                let (target, section) = match &edit {
+                    Edit::Move { target, section } => (Some(target.clone()), section.clone()),
+                    Edit::Archive(ArchiveTarget::MonthlyNote) => {
+                        (Some(archive_note(task, now)), None)
+                    }
+                    _ => (None, None),
+                };
+                let archived = matches!(edit, Edit::Archive(_));
+                if archived {
+                    let end = note.block_end(index);
+                    let open_subtasks = note.lines[index + 1..end].iter().any(|line| {
+                        TaskParser::parse_line(line, 0).is_some_and(|t| !t.status.is_closed())
+                    });
+                    let reason = if task.archived {
+                        Some("Task is already archived")
+                    } else if !task.status.is_closed() {
+                        Some("Task is not done or cancelled")
+                    } else if open_subtasks {
+                        Some("Task has open subtasks")
+                    } else {
+                        None
+                    };
+                    if let Some(reason) = reason {
+                        plan.skipped.push(skip(reason));
+                        continue;
+                    }
+                }
+
+                // Archived tasks leave a reference behind, so links to them
+                // keep working
+                let reference = archived.then(|| {
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    format!("{}- [[tid:{}]]", indent, task.id)
+                });
+                let lines = match &reference {
+                    Some(reference) => {
+                        note.lines.insert(index, reference.clone());
+                        note.take_block(index + 1)
+                    }
+                    None => note.take_block(index),
+                };
+                let block_tasks: Vec<(String, ParsedTask)> = lines
+                    .iter()
+                    .filter_map(|line| {
+                        let task = TaskParser::parse_line(line, 0)?;
+                        Some((task.id.clone()?, task))
+                    })
+                    .collect();
+                plan.changes.push(BulkChange {
+                    task_id: task.id.clone(),
+                    file_path: path.clone(),
+                    before: line,
+                    after: reference.unwrap_or_else(|| lines[0].clone()),
+                    moved_to: target.clone(),
+                });
+                moved.extend(block_tasks.iter().map(|(id, _)| id.clone()));
+                moves.push(MovedBlock {
+                    source: path,
+                    target,
+                    section,
+                    lines,
+                    tasks: block_tasks,
+                    archived,
+                });
+                continue;
+            }
+        };
+
+        if after == line {
+            let reason = match &edit {
+                Edit::Reschedule(..) => "Task has no date to reschedule",
+                Edit::Complete => "Task is already done",
+                _ => "Task already has this value",
+            };
+            plan.skipped.push(skip(reason));
+            continue;
+        }
+        note.lines[index] = after.clone();
+
+        let status = match &edit {
+            Edit::Status(status) => Some(*status),
+            Edit::Complete => Some(TaskStatus::Done),
+            _ => None,
+        };
+        if let Some(status) = status {
+            let parsed = TaskParser::parse_line(&line, 0);
+            let is_recurring = parsed
+                .as_ref()
+                .is_some_and(|task| task.properties.contains_key("repeat"));
+            if status == TaskStatus::Done && is_recurring {
+                let new_id = ids.generate()?;
+                if let Some(next) = TaskParser::next_occurrence(&line, &new_id, now.date_naive()) {
+                    note.lines.insert(index, next);
+                }
+            }
+            let text = parsed.map_or_else(String::new, |task| task.content);
+            statuses.push(StatusChange {
+                path: path.clone(),
+                task_id: task.id.clone(),
+                status,
+                text,
+            });
+        }
+        plan.changes.push(BulkChange {
+            task_id: task.id.clone(),
+            file_path: path,
+            before: line,
+            after,
+            moved_to: None,
+        });
+    }
+
+    // Archived tasks are marked in their front matter entries, which are
+    // made for tasks without one
+    let archived_at = now.with_timezone(&Utc);
+    let archived_entry = |id: &str, task: &ParsedTask, props: Option<TaskProperties>| {
+        let mut props = props.unwrap_or_else(|| {
+            let mut props = TaskProperties::new(task.content.clone());
+            props.status = task.status.into();
+            props.completed_at = records
+                .get(id)
+                .and_then(|record| record.completed_at)
+                .or(Some(archived_at));
+            props
+        });
+        props.archived_at = Some(archived_at);
+        props
+    };
+
+    // Moved tasks take their front matter entries, and time logs, along
+    for block in moves {
+        let mut entries = Vec::new();
+        workspace
+            .note(&block.source)?
+            .update_front_matter(|fields| {
+                let existing = TaskFrontMatter::extract_tasks(fields).unwrap_or_default();
+                for (id, task) in &block.tasks {
+                    let props = existing.get(id).cloned();
+                    if block.target.is_some() && props.is_some() {
+                        TaskFrontMatter::remove_task(fields, id);
+                    }
+                    let props = match block.archived {
+                        true => Some(archived_entry(id, task, props)),
+                        false => props,
+                    };
+                    entries.extend(props.map(|props| (id.clone(), props)));
+                }
+                if block.target.is_none() {
+                    TaskFrontMatter::batch_update_tasks(fields, std::mem::take(&mut entries));
+                    return true;
+                }
+                block.tasks.iter().any(|(id, _)| existing.contains_key(id))
+            })?;
+        let Some(target) = block.target else {
+            continue;
+        };
+
+        let note = workspace.note(&target)?;
+        if note.before.is_none() && note.lines.is_empty() {
+            let title = Path::new(&target)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            note.lines.push(format!("# {}", title));
+        }
+        note.insert_block(block.section.as_deref(), block.lines);
+        if !entries.is_empty() {
+            note.update_front_matter(|fields| {
+                TaskFrontMatter::batch_update_tasks(fields, entries);
+                true
+            })?;
+        }
+    }
+
+    for change in statuses {
+        workspace
+            .note(&change.path)?
+            .update_front_matter(|fields| {
+                let mut props = TaskFrontMatter::get_task(fields, &change.task_id)
+                    .unwrap_or_else(|| TaskProperties::new(change.text));
+                props.set_status(change.status.into());
+                TaskFrontMatter::upsert_task(fields, change.task_id, props);
+                true
+            })?;
+    }
+
+    plan.files = workspace
+        .notes
+        .into_iter()
+        .filter_map(|(path, note)| {
+            let after = note.content();
+            if note.before.as_deref() == Some(after.as_str()) {
+                return None;
+            }
+            Some(FileEdit {
+                path,
+                before: note.before,
+                after,
+            })
+        })
+        .collect();
+    Ok(plan)
+}
+
+/// Write a note through a temporary file in the same folder
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let parent = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)?;
+    let temp_file = tempfile::NamedTempFile::new_in(parent)?;
+    {
+        let mut file = temp_file.as_file();
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+    }
+    temp_file.persist(path)?;
+    Ok(())
+}
+
+/// Joins a vault-relative path onto the vault root, rejecting anything that
+/// would leave it
+fn note_path(vault_root: &Path, rel: &str) -> Result<PathBuf> {
+    let rel = Path::new(rel);
+    if rel
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("Path is outside the vault: {:?}", rel);
+    }
+    Ok(vault_root.join(rel))
+}
+
+/// Current content of a note, `None` when it does not exist
+fn read_note(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write the notes of a plan. Nothing is written when a note changed since
+/// the plan was made, and when a write fails the notes already written are
+/// put back. Returns the absolute paths written.
+pub fn apply_file_edits(vault_root: &Path, files: &[FileEdit]) -> Result<Vec<PathBuf>> {
+    for file in files {
+        if read_note(&note_path(vault_root, &file.path)?)? != file.before {
+            bail!("{} changed since the edit was planned", file.path);
+        }
+    }
+    let mut written: Vec<PathBuf> = Vec::new();
+    for (index, file) in files.iter().enumerate() {
+        let path = note_path(vault_root, &file.path)?;
+        if let Err(e) = write_atomic(&path, &file.after) {
+            for (file, path) in files[..index].iter().zip(&written) {
+                let restored = match &file.before {
+                    Some(before) => write_atomic(path, before),
+                    None => std::fs::remove_file(path).map_err(Into::into),
+                };
+                if let Err(e) = restored {
+                    eprintln!("⚠️ Failed to restore {}: {}", file.path, e);
+                }
+            }
+            return Err(e.context(format!("Failed to write {}", file.path)));
+        }
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// An applied bulk edit, with what is needed to undo it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkJournal {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub description: String,
+    /// Number of tasks changed
+    pub tasks: usize,
+    pub files: Vec<FileEdit>,
+}
+
+impl BulkJournal {
+    pub fn new(description: String, tasks: usize, files: Vec<FileEdit>) -> Self {
+        let created_at = Utc::now();
+        Self {
+            id: created_at.format("%Y%m%dT%H%M%S%3f").to_string(),
+            created_at,
+            description,
+            tasks,
+            files,
+        }
+    }
+
+    pub fn dir(vault_root: &Path) -> PathBuf {
+        vault_root.join(".vault").join("task_journal")
+    }
+
+    /// Store the journal, dropping the oldest ones past the limit
+    pub fn save(&self, vault_root: &Path) -> Result<()> {
+        let dir = Self::dir(vault_root);
+        std::fs::create_dir_all(&dir)?;
+        write_atomic(
+            &Self::path(vault_root, &self.id)?,
+            &serde_json::to_string_pretty(self)?,
+        )?;
+        for old in Self::list(vault_root)?.into_iter().skip(MAX_JOURNALS) {
+            Self::remove(vault_root, &old.id)?;
+        }
+        Ok(())
+    }
+
+    /// Journals of the vault, newest first
+    pub fn list(vault_root: &Path) -> Result<Vec<Self>> {
+        let dir = Self::dir(vault_root);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut journals = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(serde_json::from_str::<Self>(&json)?))
+            {
+                Ok(journal) => journals.push(journal),
+                Err(e) => eprintln!("⚠️ Skipping unreadable task journal {:?}: {}", path, e),
+            }
+        }
+        journals.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(journals)
+    }
+
+    pub fn load(vault_root: &Path, id: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(Self::path(vault_root, id)?)
+            .map_err(|_| anyhow!("No task journal {}", id))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn remove(vault_root: &Path, id: &str) -> Result<()> {
+        std::fs::remove_file(Self::path(vault_root, id)?)?;
+        Ok(())
+    }
+
+    /// File of a journal. IDs are the timestamps `new` gives them, which
+    /// keeps a caller's ID from naming a file outside the journal folder.
+    fn path(vault_root: &Path, id: &str) -> Result<PathBuf> {
+        let valid = id.len() == 18
+            && id.char_indices().all(|(i, c)| match i {
+                8 => c == 'T',
+                _ => c.is_ascii_digit(),
+            });
+        if !valid {
+            bail!("Invalid task journal ID {:?}", id);
+        }
+        Ok(Self::dir(vault_root).join(format!("{}.json", id)))
+    }
+
+    /// Put the notes back as they were and drop the journal. Nothing is
+    /// restored when a note changed after the edit. Returns the absolute
+    /// paths restored or removed.
+    pub fn undo(&self, vault_root: &Path) -> Result<Vec<PathBuf>> {
+        for file in &self.files {
+            if read_note(&note_path(vault_root, &file.path)?)?.as_deref()
+                != Some(file.after.as_str())
+            {
+                bail!(
+                    "{} changed after the edit, so it cannot be undone",
+                    file.path
+                );
+            }
+        }
+        let mut restored = Vec::new();
+        for file in &self.files {
+            let path = note_path(vault_root, &file.path)?;
+            match &file.before {
+                Some(before) => write_atomic(&path, before)?,
+                None => std::fs::remove_file(&path)?,
+            }
+            restored.push(path);
+        }
+        Self::remove(vault_root, &self.id)?;
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+#[path = "bulk_test.rs"]
+mod tests;
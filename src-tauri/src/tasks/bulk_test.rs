@@ -0,0 +1,564 @@
+use super::*;
+use crate::identity::frontmatter::TaskStatus as FrontMatterStatus;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+const ALPHA: &str = "# Alpha
+
+- [ ] Write report @due(2025-06-20) <!-- tid: 0000-aaaa -->
+  - [ ] Outline <!-- tid: 0000-aaab -->
+- [ ] Call Bob @scheduled(2025-06-18 09:30) <!-- tid: 0000-bbbb -->
+
+## Notes
+
+Some text.
+";
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap()
+}
+
+fn vault() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("Projects")).unwrap();
+    std::fs::write(dir.path().join("Projects/alpha.md"), ALPHA).unwrap();
+    dir
+}
+
+fn task(vault: &TempDir, id: &str, line_number: usize) -> TaskRecord {
+    let at = Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap();
+    TaskRecord {
+        id: id.to_string(),
+        file_path: vault.path().join("Projects/alpha.md"),
+        line_number,
+        status: TaskStatus::Todo,
+        text: String::new(),
+        project: None,
+        due_date: None,
+        due_time: None,
+        scheduled_date: None,
+        scheduled_time: None,
+        start_date: None,
+        start_time: None,
+        priority: None,
+        tags: None,
+        created_at: at,
+        updated_at: at,
+        completed_at: None,
+        properties: HashMap::new(),
+        parent_id: None,
+        blocked_by: Vec::new(),
+        estimate_minutes: None,
+        time_log: Vec::new(),
+        archived: false,
+    }
+}
+
+fn plan_edit(vault: &TempDir, tasks: &[TaskRecord], operation: BulkOperation) -> BulkPlan {
+    plan_bulk_edit(tasks, &operation, vault.path(), &now()).unwrap()
+}
+
+fn read(vault: &TempDir, path: &str) -> String {
+    std::fs::read_to_string(vault.path().join(path)).unwrap()
+}
+
+#[test]
+fn test_set_property_is_planned_without_writing() {
+    let vault = vault();
+    let tasks = [task(&vault, "0000-aaaa", 3), task(&vault, "0000-bbbb", 5)];
+    let plan = plan_edit(
+        &vault,
+        &tasks,
+        BulkOperation::SetProperty {
+            key: "priority".into(),
+            value: Some("High".into()),
+        },
+    );
+
+    assert_eq!(plan.changes.len(), 2);
+    assert_eq!(
+        plan.changes[0].after,
+        "- [ ] Write report @due(2025-06-20) !p1 <!-- tid: 0000-aaaa -->"
+    );
+    assert_eq!(
+        plan.changes[1].after,
+        "- [ ] Call Bob @scheduled(2025-06-18 09:30) !p1 <!-- tid: 0000-bbbb -->"
+    );
+    // One edit for the note, and nothing on disk until it is applied
+    assert_eq!(plan.files.len(), 1);
+    assert_eq!(plan.files[0].path, "Projects/alpha.md");
+    assert_eq!(plan.files[0].before.as_deref(), Some(ALPHA));
+    assert_eq!(read(&vault, "Projects/alpha.md"), ALPHA);
+
+    apply_file_edits(vault.path(), &plan.files).unwrap();
+    assert_eq!(read(&vault, "Projects/alpha.md"), plan.files[0].after);
+
+    // The same edit again changes nothing
+    let again = plan_edit(
+        &vault,
+        &tasks,
+        BulkOperation::SetProperty {
+            key: "priority".into(),
+            value: Some("high".into()),
+        },
+    );
+    assert!(again.changes.is_empty());
+    assert_eq!(again.skipped.len(), 2);
+    assert!(again.files.is_empty());
+}
+
+#[test]
+fn test_invalid_operations_are_rejected() {
+    let vault = vault();
+    let tasks = [task(&vault, "0000-aaaa", 3)];
+    for operation in [
+        BulkOperation::SetProperty {
+            key: "colour".into(),
+            value: Some("red".into()),
+        },
+        BulkOperation::SetProperty {
+            key: "due".into(),
+            value: Some("someday maybe".into()),
+        },
+        BulkOperation::SetProperty {
+            key: "estimate".into(),
+            value: Some("2 days".into()),
+        },
+        BulkOperation::Reschedule {
+            days: 1,
+            field: Some("deadline".into()),
+        },
+        BulkOperation::Reschedule {
+            days: i64::MAX,
+            field: None,
+        },
+        BulkOperation::Reschedule {
+            days: -100_000_000,
+            field: None,
+        },
+        BulkOperation::Move {
+            target: "../outside".into(),
+            section: None,
+        },
+    ] {
+        let result = plan_bulk_edit(&tasks, &operation, vault.path(), &now());
+        assert!(result.is_err(), "{:?} should be rejected", operation);
+    }
+}
+
+#[test]
+fn test_reschedule_keeps_times() {
+    let vault = vault();
+    let tasks = [
+        task(&vault, "0000-aaaa", 3),
+        task(&vault, "0000-aaab", 4),
+        task(&vault, "0000-bbbb", 5),
+    ];
+    let plan = plan_edit(
+        &vault,
+        &tasks,
+        BulkOperation::Reschedule {
+            days: 3,
+            field: None,
+        },
+    );
+
+    let after: Vec<&str> = plan.changes.iter().map(|c| c.after.as_str()).collect();
+    assert_eq!(
+        after,
+        vec![
+            "- [ ] Write report @due(2025-06-23) <!-- tid: 0000-aaaa -->",
+            "- [ ] Call Bob @scheduled(2025-06-21 09:30) <!-- tid: 0000-bbbb -->",
+        ]
+    );
+    assert_eq!(plan.skipped.len(), 1);
+    assert_eq!(plan.skipped[0].task_id, "0000-aaab");
+    assert_eq!(plan.skipped[0].reason, "Task has no date to reschedule");
+}
+
+#[test]
+fn test_complete_adds_next_occurrence_and_front_matter_status() {
+    let vault = TempDir::new().unwrap();
+    std::fs::write(
+        vault.path().join("chores.md"),
+        "- [ ] Water plants @due(2025-06-14) @repeat(weekly) <!-- tid: 0000-cccc -->\n",
+    )
+    .unwrap();
+    let mut record = task(&vault, "0000-cccc", 1);
+    record.file_path = vault.path().join("chores.md");
+
+    let plan = plan_edit(&vault, &[record], BulkOperation::Complete);
+    assert_eq!(plan.changes.len(), 1);
+    assert_eq!(
+        plan.changes[0].after,
+        "- [x] Water plants @due(2025-06-14) @repeat(weekly) <!-- tid: 0000-cccc -->"
+    );
+
+    let (fm, body) = FrontMatterParser::parse(&plan.files[0].after).unwrap();
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("- [ ] Water plants @repeat(weekly) @due(2025-06-21)"));
+    assert!(!lines[0].contains("0000-cccc"));
+    assert_eq!(lines[1], plan.changes[0].after);
+
+    let props = TaskFrontMatter::get_task(&fm.unwrap().extra_fields, "0000-cccc").unwrap();
+    assert_eq!(props.status, FrontMatterStatus::Done);
+    assert!(props.completed_at.is_some());
+}
+
+#[test]
+fn test_move_takes_subtasks_and_front_matter() {
+    let vault = vault();
+    let content = format!(
+        "---\ntasks:\n  0000-aaab:\n    status: todo\n    text: Outline\n    created_at: 2025-06-01T08:00:00+00:00\n    updated_at: 2025-06-01T08:00:00+00:00\n---\n{}",
+        ALPHA
+    );
+    std::fs::write(vault.path().join("Projects/alpha.md"), &content).unwrap();
+    std::fs::write(
+        vault.path().join("Projects/beta.md"),
+        "# Beta\n\n## Next\n\n- [ ] Existing <!-- tid: 0000-dddd -->\n\n## Later\n",
+    )
+    .unwrap();
+
+    // The subtask is matched too, but moves with its parent only once
+    let tasks = [task(&vault, "0000-aaaa", 10), task(&vault, "0000-aaab", 11)];
+    let plan = plan_edit(
+        &vault,
+        &tasks,
+        BulkOperation::Move {
+            target: "Projects/beta".into(),
+            section: Some("next".into()),
+        },
+    );
+
+    assert_eq!(plan.changes.len(), 1);
+    assert_eq!(
+        plan.changes[0].moved_to.as_deref(),
+        Some("Projects/beta.md")
+    );
+    let files: HashMap<&str, &FileEdit> = plan.files.iter().map(|f| (f.path.as_str(), f)).collect();
+
+    let alpha = &files["Projects/alpha.md"].after;
+    assert!(!alpha.contains("tasks:"));
+    assert!(!alpha.contains("Write report"));
+    assert!(!alpha.contains("Outline"));
+    assert!(alpha.contains("Call Bob"));
+
+    let (fm, body) = FrontMatterParser::parse(&files["Projects/beta.md"].after).unwrap();
+    assert_eq!(
+        body,
+        "# Beta\n\n## Next\n\n- [ ] Existing <!-- tid: 0000-dddd -->\n- [ ] Write report @due(2025-06-20) <!-- tid: 0000-aaaa -->\n  - [ ] Outline <!-- tid: 0000-aaab -->\n\n## Later\n"
+    );
+    let props = TaskFrontMatter::get_task(&fm.unwrap().extra_fields, "0000-aaab").unwrap();
+    assert_eq!(props.text, "Outline");
+}
+
+#[test]
+fn test_move_creates_note_and_section() {
+    let vault = vault();
+    let tasks = [task(&vault, "0000-bbbb", 5)];
+    let plan = plan_edit(
+        &vault,
+        &tasks,
+        BulkOperation::Move {
+            target: "Inbox/someday.md".into(),
+            section: Some("Calls".into()),
+        },
+    );
+
+    let created = plan
+        .files
+        .iter()
+        .find(|f| f.path == "Inbox/someday.md")
+        .unwrap();
+    assert_eq!(created.before, None);
+    assert_eq!(
+        created.after,
+        "# someday\n\n## Calls\n\n- [ ] Call Bob @scheduled(2025-06-18 09:30) <!-- tid: 0000-bbbb -->\n"
+    );
+
+    let written = apply_file_edits(vault.path(), &plan.files).unwrap();
+    assert_eq!(written.len(), 2);
+    assert_eq!(read(&vault, "Inbox/someday.md"), created.after);
+}
+
+fn done_task(vault: &TempDir, id: &str, line_number: usize, completed: (u32, u32)) -> TaskRecord {
+    let mut record = task(vault, id, line_number);
+    record.file_path = vault.path().join("done.md");
+    record.status = TaskStatus::Done;
+    record.completed_at = Some(
+        Utc.with_ymd_and_hms(2025, completed.0, completed.1, 10, 0, 0)
+            .unwrap(),
+    );
+    record
+}
+
+#[test]
+fn test_archive_by_completion_month() {
+    let vault = TempDir::new().unwrap();
+    std::fs::write(
+        vault.path().join("done.md"),
+        "# Done\n\n- [x] Ship it <!-- tid: 0000-eeee -->\n  - [x] Tag release <!-- tid: 0000-eeef -->\n- [x] Old one <!-- tid: 0000-ffff -->\n- [ ] Still open <!-- tid: 0000-0000 -->\n",
+    )
+    .unwrap();
+    let mut open = task(&vault, "0000-0000", 6);
+    open.file_path = vault.path().join("done.md");
+    let tasks = [
+        done_task(&vault, "0000-eeee", 3, (5, 30)),
+        done_task(&vault, "0000-ffff", 5, (4, 2)),
+        open,
+    ];
+
+    let plan = plan_edit(
+        &vault,
+        &tasks,
+        BulkOperation::Archive {
+            into: ArchiveTarget::MonthlyNote,
+        },
+    );
+    assert_eq!(plan.skipped.len(), 1);
+    assert_eq!(plan.skipped[0].reason, "Task is not done or cancelled");
+
+    let paths: Vec<&str> = plan.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "Archive/Tasks 2025-04.md",
+            "Archive/Tasks 2025-05.md",
+            "done.md"
+        ]
+    );
+    assert_eq!(
+        plan.files[2].after,
+        "# Done\n\n- [[tid:0000-eeee]]\n- [[tid:0000-ffff]]\n- [ ] Still open <!-- tid: 0000-0000 -->\n"
+    );
+
+    // The archive note marks the task and its subtask as archived, keeping
+    // when they were completed
+    let (fm, body) = FrontMatterParser::parse(&plan.files[1].after).unwrap();
+    assert_eq!(
+        body,
+        "# Tasks 2025-05\n\n- [x] Ship it <!-- tid: 0000-eeee -->\n  - [x] Tag release <!-- tid: 0000-eeef -->\n"
+    );
+    let fields = fm.unwrap().extra_fields;
+    for id in ["0000-eeee", "0000-eeef"] {
+        let props = TaskFrontMatter::get_task(&fields, id).unwrap();
+        assert_eq!(props.archived_at, Some(now()));
+        assert_eq!(props.status, FrontMatterStatus::Done);
+    }
+    assert_eq!(
+        TaskFrontMatter::get_task(&fields, "0000-eeee")
+            .unwrap()
+            .completed_at,
+        Some(Utc.with_ymd_and_hms(2025, 5, 30, 10, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_archive_into_front_matter() {
+    let vault = TempDir::new().unwrap();
+    std::fs::write(
+        vault.path().join("done.md"),
+        "# Done\n\n- [x] Ship it <!-- tid: 0000-eeee -->\n  - [ ] Follow up <!-- tid: 0000-eeef -->\n- [x] Old one <!-- tid: 0000-ffff -->\n",
+    )
+    .unwrap();
+    let mut archived = done_task(&vault, "0000-ffff", 5, (4, 2));
+    archived.archived = true;
+    let tasks = [done_task(&vault, "0000-eeee", 3, (5, 30)), archived];
+
+    let operation: BulkOperation =
+        serde_json::from_str(r#"{"type": "archive", "into": "frontMatter"}"#).unwrap();
+    let plan = plan_edit(&vault, &tasks, operation);
+    let reasons: Vec<&str> = plan.skipped.iter().map(|s| s.reason.as_str()).collect();
+    assert_eq!(
+        reasons,
+        vec!["Task has open subtasks", "Task is already archived"]
+    );
+    assert!(plan.files.is_empty());
+
+    std::fs::write(
+        vault.path().join("done.md"),
+        "# Done\n\n- [x] Ship it <!-- tid: 0000-eeee -->\n",
+    )
+    .unwrap();
+    let plan = plan_edit(
+        &vault,
+        &tasks[..1],
+        BulkOperation::Archive {
+            into: ArchiveTarget::FrontMatter,
+        },
+    );
+    assert_eq!(plan.changes[0].moved_to, None);
+    assert_eq!(plan.files.len(), 1);
+    let (fm, body) = FrontMatterParser::parse(&plan.files[0].after).unwrap();
+    assert_eq!(body, "# Done\n\n- [[tid:0000-eeee]]\n");
+    let props = TaskFrontMatter::get_task(&fm.unwrap().extra_fields, "0000-eeee").unwrap();
+    assert_eq!(props.text, "Ship it");
+    assert_eq!(props.archived_at, Some(now()));
+}
+
+#[test]
+fn test_apply_refuses_changed_notes() {
+    let vault = vault();
+    let tasks = [task(&vault, "0000-aaaa", 3)];
+    let plan = plan_edit(&vault, &tasks, BulkOperation::Complete);
+
+    std::fs::write(
+        vault.path().join("Projects/alpha.md"),
+        "# Edited elsewhere\n",
+    )
+    .unwrap();
+    assert!(apply_file_edits(vault.path(), &plan.files).is_err());
+    assert_eq!(read(&vault, "Projects/alpha.md"), "# Edited elsewhere\n");
+}
+
+#[test]
+fn test_failed_apply_restores_written_notes() {
+    let vault = vault();
+    let alpha = read(&vault, "Projects/alpha.md");
+    let files = [
+        FileEdit {
+            path: "Projects/alpha.md".into(),
+            before: Some(alpha.clone()),
+            after: "# Changed\n".into(),
+        },
+        FileEdit {
+            path: "Inbox/new.md".into(),
+            before: None,
+            after: "# New\n".into(),
+        },
+        // Cannot be written once `Inbox/new.md` is a file
+        FileEdit {
+            path: "Inbox/new.md/child.md".into(),
+            before: None,
+            after: "# Child\n".into(),
+        },
+    ];
+
+    assert!(apply_file_edits(vault.path(), &files).is_err());
+    assert_eq!(read(&vault, "Projects/alpha.md"), alpha);
+    assert!(!vault.path().join("Inbox/new.md").exists());
+}
+
+#[test]
+fn test_journal_undo() {
+    let vault = vault();
+    let tasks = [task(&vault, "0000-bbbb", 5)];
+    let operation = BulkOperation::Move {
+        target: "Inbox/calls.md".into(),
+        section: None,
+    };
+    let plan = plan_edit(&vault, &tasks, operation.clone());
+    apply_file_edits(vault.path(), &plan.files).unwrap();
+
+    let journal = BulkJournal::new(operation.describe(), plan.changes.len(), plan.files);
+    journal.save(vault.path()).unwrap();
+    let journals = BulkJournal::list(vault.path()).unwrap();
+    assert_eq!(journals.len(), 1);
+    assert_eq!(journals[0].description, "Move to Inbox/calls.md");
+    assert_eq!(journals[0].tasks, 1);
+
+    let restored = BulkJournal::load(vault.path(), &journal.id)
+        .unwrap()
+        .undo(vault.path())
+        .unwrap();
+    assert_eq!(restored.len(), 2);
+    assert_eq!(read(&vault, "Projects/alpha.md"), ALPHA);
+    assert!(!vault.path().join("Inbox/calls.md").exists());
+    assert!(BulkJournal::list(vault.path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_journal_undo_refuses_later_edits() {
+    let vault = vault();
+    let tasks = [task(&vault, "0000-aaaa", 3)];
+    let plan = plan_edit(&vault, &tasks, BulkOperation::Complete);
+    apply_file_edits(vault.path(), &plan.files).unwrap();
+    let journal = BulkJournal::new("Complete".into(), 1, plan.files);
+    journal.save(vault.path()).unwrap();
+
+    std::fs::write(vault.path().join("Projects/alpha.md"), "# Edited later\n").unwrap();
+    assert!(journal.undo(vault.path()).is_err());
+    assert_eq!(read(&vault, "Projects/alpha.md"), "# Edited later\n");
+    assert_eq!(BulkJournal::list(vault.path()).unwrap().len(), 1);
+}
+
+#[test]
+fn test_journal_ids_and_paths_stay_inside_the_vault() {
+    let dir = TempDir::new().unwrap();
+    let vault = dir.path().join("vault");
+    std::fs::create_dir_all(vault.join(".vault/task_journal")).unwrap();
+    std::fs::write(dir.path().join("outside.json"), "{}").unwrap();
+    for id in [
+        "../../../outside",
+        "/etc/passwd",
+        "20250615T120000000/..",
+        "",
+    ] {
+        assert!(BulkJournal::load(&vault, id).is_err());
+        assert!(BulkJournal::remove(&vault, id).is_err());
+    }
+    assert!(dir.path().join("outside.json").exists());
+
+    // A tampered journal that would put back a file outside the vault
+    std::fs::write(dir.path().join("outside.md"), "After\n").unwrap();
+    let files = vec![FileEdit {
+        path: "../outside.md".to_string(),
+        before: Some("Before\n".to_string()),
+        after: "After\n".to_string(),
+    }];
+    assert!(BulkJournal::new("Edit".into(), 1, files.clone())
+        .undo(&vault)
+        .is_err());
+    assert!(apply_file_edits(&vault, &files).is_err());
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("outside.md")).unwrap(),
+        "After\n"
+    );
+}
+
+#[test]
+fn test_journals_are_pruned() {
+    let vault = TempDir::new().unwrap();
+    for i in 0..MAX_JOURNALS + 2 {
+        let mut journal = BulkJournal::new(format!("Edit {}", i), 1, Vec::new());
+        journal.id = format!("20250615T1200{:02}000", i);
+        journal.save(vault.path()).unwrap();
+    }
+    let journals = BulkJournal::list(vault.path()).unwrap();
+    assert_eq!(journals.len(), MAX_JOURNALS);
+    assert_eq!(
+        journals[0].description,
+        format!("Edit {}", MAX_JOURNALS + 1)
+    );
+}
+
+#[test]
+fn test_describe_operations() {
+    assert_eq!(
+        BulkOperation::Reschedule {
+            days: -2,
+            field: Some("due".into())
+        }
+        .describe(),
+        "Reschedule due by -2 days"
+    );
+    assert_eq!(
+        BulkOperation::SetProperty {
+            key: "project".into(),
+            value: None
+        }
+        .describe(),
+        "Remove project"
+    );
+    let operation: BulkOperation =
+        serde_json::from_str(r#"{"type": "setProperty", "key": "due", "value": "tomorrow"}"#)
+            .unwrap();
+    assert_eq!(
+        operation,
+        BulkOperation::SetProperty {
+            key: "due".into(),
+            value: Some("tomorrow".into())
+        }
+    );
+}
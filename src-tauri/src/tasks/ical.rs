@@ -0,0 +1,283 @@
+//! iCalendar (RFC 5545) export of dated tasks
+//!
+//! Tasks with a due or scheduled date become `VTODO` or `VEVENT` components
+//! whose `UID` is the task's UUID, so a calendar re-reading an export or the
+//! feed updates the entries it already has instead of adding new ones.
+//! Dates without a time of day are all-day values, and times are written as
+//! floating local times, the way they appear in notes.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::index::TaskRecord;
+use crate::identity::frontmatter::Priority;
+use crate::identity::tasks::TaskStatus;
+
+lazy_static! {
+    // Markup that is kept out of the summary: `@key(...)` properties,
+    // priorities, tags, repeat rules, dependencies and estimates
+    static ref MARKUP_PATTERN: Regex = Regex::new(
+        r"@[A-Za-z_-]+\([^)]*\)|!(?:p[1-5]|high|medium|low)\b|#[A-Za-z0-9][A-Za-z0-9/_-]*|🔁[^@!#<]*|blocked-by::(?:\s*,?\s*\[\[tid:[a-zA-Z0-9-]+\]\])+|(?i:estimate::)(?:\s*\d[\d.]*\s*[A-Za-z]+)+"
+    ).unwrap();
+}
+
+/// Length of a timed `VEVENT`, which tasks only give a start for
+const EVENT_MINUTES: i64 = 30;
+
+/// Longest content line before it is folded, in octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Calendar component a task is exported as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarComponent {
+    /// `VTODO`, shown in task-aware calendars with their due dates
+    #[default]
+    Todo,
+    /// `VEVENT` on the scheduled (or due) day, for calendars that ignore
+    /// `VTODO`
+    Event,
+}
+
+impl CalendarComponent {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "todo" | "vtodo" => Some(CalendarComponent::Todo),
+            "event" | "vevent" => Some(CalendarComponent::Event),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CalendarOptions<'a> {
+    pub component: CalendarComponent,
+    /// Export done and cancelled tasks as well as open ones
+    pub include_closed: bool,
+    /// Calendar name shown by subscribing apps
+    pub name: Option<String>,
+    /// Used to show note paths relative to the vault
+    pub vault_root: Option<&'a Path>,
+}
+
+/// Whether a task carries a date it can be put on a calendar with
+fn has_calendar_date(task: &TaskRecord) -> bool {
+    task.due_date.is_some() || task.scheduled_date.is_some()
+}
+
+/// The tasks that go into a calendar, in note and line order
+pub fn calendar_tasks<'t>(
+    tasks: &'t [TaskRecord],
+    options: &CalendarOptions,
+) -> Vec<&'t TaskRecord> {
+    let mut tasks: Vec<&TaskRecord> = tasks
+        .iter()
+        .filter(|task| has_calendar_date(task))
+        .filter(|task| options.include_closed || !task.status.is_closed())
+        .collect();
+    tasks.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.line_number.cmp(&b.line_number))
+    });
+    tasks
+}
+
+/// Render the dated tasks as an iCalendar document
+pub fn tasks_to_ics(tasks: &[TaskRecord], options: &CalendarOptions) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Vault//Tasks//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    if let Some(name) = &options.name {
+        lines.push(format!("X-WR-CALNAME:{}", escape_text(name)));
+    }
+    for task in calendar_tasks(tasks, options) {
+        match options.component {
+            CalendarComponent::Todo => push_todo(&mut lines, task, options),
+            CalendarComponent::Event => push_event(&mut lines, task, options),
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold_line(&line));
+        ics.push_str("\r\n");
+    }
+    ics
+}
+
+fn push_todo(lines: &mut Vec<String>, task: &TaskRecord, options: &CalendarOptions) {
+    lines.push("BEGIN:VTODO".to_string());
+    push_common(lines, task, options);
+
+    let timed = task.due_time.is_some();
+    if let Some(due) = task.due_date {
+        lines.push(date_property("DUE", due, task.due_time));
+    }
+    // DTSTART has to use the same value type as DUE and not come after it
+    let start = task
+        .start_date
+        .map(|date| (date, task.start_time))
+        .or(task.scheduled_date.map(|date| (date, task.scheduled_time)));
+    if let Some((date, time)) = start {
+        let time = if task.due_date.is_none() {
+            time
+        } else if timed {
+            Some(time.unwrap_or(NaiveTime::MIN))
+        } else {
+            None
+        };
+        let due = task.due_date.map(|due| (due, task.due_time));
+        if due.is_none_or(|due| (date, time) <= due) {
+            lines.push(date_property("DTSTART", date, time));
+        }
+    }
+
+    let status = match task.status {
+        TaskStatus::InProgress => "IN-PROCESS",
+        TaskStatus::Done => "COMPLETED",
+        TaskStatus::Cancelled => "CANCELLED",
+        TaskStatus::Todo | TaskStatus::Blocked | TaskStatus::Deferred => "NEEDS-ACTION",
+    };
+    lines.push(format!("STATUS:{}", status));
+    if task.status == TaskStatus::Done {
+        if let Some(completed_at) = task.completed_at {
+            lines.push(format!("COMPLETED:{}", utc_stamp(completed_at)));
+        }
+    }
+    if let Some(priority) = task.priority {
+        let level = match priority {
+            Priority::High => 1,
+            Priority::Medium => 5,
+            Priority::Low => 9,
+        };
+        lines.push(format!("PRIORITY:{}", level));
+    }
+    if let Some(parent_id) = &task.parent_id {
+        lines.push(format!("RELATED-TO:{}", escape_text(parent_id)));
+    }
+    lines.push("END:VTODO".to_string());
+}
+
+fn push_event(lines: &mut Vec<String>, task: &TaskRecord, options: &CalendarOptions) {
+    let (date, time) = match task.scheduled_date {
+        Some(date) => (date, task.scheduled_time),
+        None => match task.due_date {
+            Some(date) => (date, task.due_time),
+            None => return,
+        },
+    };
+
+    lines.push("BEGIN:VEVENT".to_string());
+    push_common(lines, task, options);
+    lines.push(date_property("DTSTART", date, time));
+    match time {
+        Some(_) => lines.push(format!("DURATION:PT{}M", EVENT_MINUTES)),
+        None => lines.push(date_property("DTEND", date + Duration::days(1), None)),
+    }
+    // All-day entries should not block time in the calendar
+    if time.is_none() {
+        lines.push("TRANSP:TRANSPARENT".to_string());
+    }
+    let status = match task.status {
+        TaskStatus::Cancelled => "CANCELLED",
+        _ => "CONFIRMED",
+    };
+    lines.push(format!("STATUS:{}", status));
+    lines.push("END:VEVENT".to_string());
+}
+
+/// Properties shared by both components
+fn push_common(lines: &mut Vec<String>, task: &TaskRecord, options: &CalendarOptions) {
+    lines.push(format!("UID:{}", escape_text(&task.id)));
+    lines.push(format!("DTSTAMP:{}", utc_stamp(task.updated_at)));
+    lines.push(format!("CREATED:{}", utc_stamp(task.created_at)));
+    lines.push(format!("LAST-MODIFIED:{}", utc_stamp(task.updated_at)));
+    lines.push(format!("SUMMARY:{}", escape_text(&summary(&task.text))));
+
+    let path = options
+        .vault_root
+        .and_then(|root| task.file_path.strip_prefix(root).ok())
+        .unwrap_or(&task.file_path);
+    let mut description = format!(
+        "{}:{}",
+        path.to_string_lossy().replace('\\', "/"),
+        task.line_number
+    );
+    if let Some(project) = &task.project {
+        description.push_str(&format!("\nProject: {}", project));
+    }
+    lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+
+    if let Some(tags) = task.tags.as_ref().filter(|tags| !tags.is_empty()) {
+        let categories: Vec<String> = tags.iter().map(|tag| escape_text(tag)).collect();
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+}
+
+/// Task text without the inline markup its properties are written in
+fn summary(text: &str) -> String {
+    let stripped = MARKUP_PATTERN.replace_all(text, " ");
+    let words: Vec<&str> = stripped.split_whitespace().collect();
+    if words.is_empty() {
+        text.trim().to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+/// A date, or a floating local date-time when there is a time of day
+fn date_property(name: &str, date: NaiveDate, time: Option<NaiveTime>) -> String {
+    match time {
+        Some(time) => format!("{}:{}", name, date.and_time(time).format("%Y%m%dT%H%M%S")),
+        None => format!("{};VALUE=DATE:{}", name, date.format("%Y%m%d")),
+    }
+}
+
+fn utc_stamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line into lines of at most 75 octets, without splitting a
+/// character. Continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut octets = 0;
+    for c in line.chars() {
+        // The leading space of a continuation line counts towards its length
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+#[path = "ical_test.rs"]
+mod tests;
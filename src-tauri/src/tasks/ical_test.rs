@@ -0,0 +1,170 @@
+use super::*;
+use chrono::TimeZone;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M").ok()
+}
+
+fn task(id: &str, text: &str) -> TaskRecord {
+    let at = Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap();
+    TaskRecord {
+        id: id.to_string(),
+        file_path: PathBuf::from("/vault/Projects/alpha.md"),
+        line_number: 3,
+        status: TaskStatus::Todo,
+        text: text.to_string(),
+        project: None,
+        due_date: None,
+        due_time: None,
+        scheduled_date: None,
+        scheduled_time: None,
+        start_date: None,
+        start_time: None,
+        priority: None,
+        tags: None,
+        created_at: at,
+        updated_at: at,
+        completed_at: None,
+        properties: HashMap::new(),
+        parent_id: None,
+        blocked_by: Vec::new(),
+        estimate_minutes: None,
+        time_log: Vec::new(),
+        archived: false,
+    }
+}
+
+fn options(component: CalendarComponent) -> CalendarOptions<'static> {
+    CalendarOptions {
+        component,
+        include_closed: false,
+        name: Some("Work tasks".into()),
+        vault_root: Some(Path::new("/vault")),
+    }
+}
+
+/// Unfolded content lines of a document
+fn lines(ics: &str) -> Vec<String> {
+    ics.replace("\r\n ", "")
+        .split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_export_todos() {
+    let mut report = task(
+        "0000-aaaa",
+        "Write report, draft; final @due(2025-06-13 17:00) @start(2025-06-10) estimate:: 1h 30m !p1 #work",
+    );
+    report.due_date = Some(date("2025-06-13"));
+    report.due_time = time("17:00");
+    report.start_date = Some(date("2025-06-10"));
+    report.priority = Some(Priority::High);
+    report.tags = Some(vec!["work".into()]);
+    report.project = Some("Alpha".into());
+
+    let mut undated = task("0000-bbbb", "Someday");
+    undated.line_number = 4;
+    let mut done = task("0000-cccc", "Shipped @due(2025-06-02)");
+    done.due_date = Some(date("2025-06-02"));
+    done.status = TaskStatus::Done;
+
+    let ics = tasks_to_ics(&[report, undated, done], &options(CalendarComponent::Todo));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(
+        lines(&ics),
+        vec![
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//Vault//Tasks//EN",
+            "CALSCALE:GREGORIAN",
+            "X-WR-CALNAME:Work tasks",
+            "BEGIN:VTODO",
+            "UID:0000-aaaa",
+            "DTSTAMP:20250601T080000Z",
+            "CREATED:20250601T080000Z",
+            "LAST-MODIFIED:20250601T080000Z",
+            "SUMMARY:Write report\\, draft\\; final",
+            "DESCRIPTION:Projects/alpha.md:3\\nProject: Alpha",
+            "CATEGORIES:work",
+            "DUE:20250613T170000",
+            "DTSTART:20250610T000000",
+            "STATUS:NEEDS-ACTION",
+            "PRIORITY:1",
+            "END:VTODO",
+            "END:VCALENDAR",
+        ]
+    );
+}
+
+#[test]
+fn test_export_events() {
+    let mut meeting = task("0000-aaaa", "Prepare demo");
+    meeting.scheduled_date = Some(date("2025-06-12"));
+    meeting.scheduled_time = time("09:30");
+    meeting.due_date = Some(date("2025-06-13"));
+
+    let mut all_day = task("0000-bbbb", "Pay rent");
+    all_day.due_date = Some(date("2025-06-30"));
+    all_day.line_number = 4;
+
+    let mut cancelled = task("0000-cccc", "Old plan");
+    cancelled.due_date = Some(date("2025-06-05"));
+    cancelled.status = TaskStatus::Cancelled;
+    cancelled.line_number = 5;
+
+    let mut options = options(CalendarComponent::Event);
+    options.include_closed = true;
+    let lines = lines(&tasks_to_ics(&[meeting, all_day, cancelled], &options));
+
+    let event = |uid: &str| -> Vec<String> {
+        let start = lines
+            .iter()
+            .position(|line| *line == format!("UID:{}", uid))
+            .unwrap();
+        let end = start
+            + lines[start..]
+                .iter()
+                .position(|l| l == "END:VEVENT")
+                .unwrap();
+        lines[start + 6..end].to_vec()
+    };
+    assert_eq!(
+        event("0000-aaaa"),
+        vec![
+            "DTSTART:20250612T093000",
+            "DURATION:PT30M",
+            "STATUS:CONFIRMED"
+        ]
+    );
+    assert_eq!(
+        event("0000-bbbb"),
+        vec![
+            "DTSTART;VALUE=DATE:20250630",
+            "DTEND;VALUE=DATE:20250701",
+            "TRANSP:TRANSPARENT",
+            "STATUS:CONFIRMED"
+        ]
+    );
+    assert_eq!(event("0000-cccc").last().unwrap(), "STATUS:CANCELLED");
+    assert_eq!(lines.iter().filter(|l| *l == "BEGIN:VEVENT").count(), 3);
+}
+
+#[test]
+fn test_fold_long_lines() {
+    let line = format!("SUMMARY:{}", "é".repeat(60));
+    let folded = fold_line(&line);
+    for part in folded.split("\r\n") {
+        assert!(part.len() <= MAX_LINE_OCTETS, "{} octets", part.len());
+    }
+    assert_eq!(folded.replace("\r\n ", ""), line);
+    assert_eq!(fold_line("SUMMARY:short"), "SUMMARY:short");
+}
@@ -0,0 +1,631 @@
+//! Importers for tasks exported from other apps
+//!
+//! Todoist CSV and JSON exports, TaskPaper documents and Things JSON are read
+//! into [`ImportedTask`]s, which are written out as checkbox tasks in the
+//! inline syntax `TaskParser` understands, one note per project.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, TimeZone};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::identity::frontmatter::Priority;
+use crate::identity::tasks::{Recurrence, TaskDate, TaskIdentity, TaskParser, TaskStatus};
+
+lazy_static! {
+    // Todoist labels written into task content, e.g. `Buy milk @errands`
+    static ref TODOIST_LABEL_PATTERN: Regex = Regex::new(r"(?:^|\s)@([\w-]+)").unwrap();
+    // TaskPaper tags: `@done`, `@due(2025-06-13)`
+    static ref TASKPAPER_TAG_PATTERN: Regex = Regex::new(r"(?:^|\s)@([\w.-]+)(?:\(([^)]*)\))?").unwrap();
+}
+
+/// Note that tasks without a project are written to
+const INBOX_NOTE: &str = "Inbox";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    TodoistCsv,
+    TodoistJson,
+    TaskPaper,
+    ThingsJson,
+}
+
+impl ImportFormat {
+    /// Guess the format of an export from its file name and content
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("csv") => Some(ImportFormat::TodoistCsv),
+            Some("taskpaper") | Some("txt") => Some(ImportFormat::TaskPaper),
+            Some("json") => match serde_json::from_str::<Value>(content).ok()? {
+                Value::Array(items)
+                    if items.iter().all(|item| item.get("attributes").is_some()) =>
+                {
+                    Some(ImportFormat::ThingsJson)
+                }
+                Value::Object(object)
+                    if ["items", "tasks"]
+                        .iter()
+                        .any(|key| object.contains_key(*key)) =>
+                {
+                    Some(ImportFormat::TodoistJson)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Read the tasks of an export. `default_project` is used for formats
+    /// that export one project per file without naming it, and relative
+    /// dates are resolved against `now`.
+    pub fn parse<Tz: TimeZone>(
+        self,
+        content: &str,
+        default_project: Option<&str>,
+        now: &DateTime<Tz>,
+    ) -> Result<Vec<ImportedTask>>
+    where
+        Tz::Offset: Copy,
+    {
+        match self {
+            ImportFormat::TodoistCsv => parse_todoist_csv(content, default_project, now),
+            ImportFormat::TodoistJson => parse_todoist_json(content, now),
+            ImportFormat::TaskPaper => Ok(parse_taskpaper(content, now)),
+            ImportFormat::ThingsJson => parse_things_json(content, now),
+        }
+    }
+}
+
+/// A task read from an export, before it is written to a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedTask {
+    pub text: String,
+    pub status: TaskStatus,
+    /// ISO dates, or the value as written when it could not be read
+    pub due: Option<String>,
+    pub scheduled: Option<String>,
+    pub start: Option<String>,
+    pub repeat: Option<String>,
+    pub priority: Option<Priority>,
+    pub project: Option<String>,
+    /// Heading the task is listed under within its project
+    pub section: Option<String>,
+    pub tags: Vec<String>,
+    /// Nesting below a parent task, 0 for top-level tasks
+    pub depth: usize,
+}
+
+impl ImportedTask {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+            status: TaskStatus::Todo,
+            due: None,
+            scheduled: None,
+            start: None,
+            repeat: None,
+            priority: None,
+            project: None,
+            section: None,
+            tags: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// The task as a markdown line, with `task_id` as its UUID
+    pub fn to_line(&self, task_id: &str) -> String {
+        let mut line = format!(
+            "{}- [{}] {}",
+            "  ".repeat(self.depth),
+            self.status.checkbox(),
+            self.text
+        );
+        if let Some(project) = &self.project {
+            line.push_str(&format!(" @project({})", property_value(project)));
+        }
+        for (key, value) in [
+            ("due", &self.due),
+            ("scheduled", &self.scheduled),
+            ("start", &self.start),
+            ("repeat", &self.repeat),
+        ] {
+            if let Some(value) = value {
+                line.push_str(&format!(" @{}({})", key, property_value(value)));
+            }
+        }
+        if let Some(priority) = self.priority {
+            let priority = match priority {
+                Priority::High => "!high",
+                Priority::Medium => "!medium",
+                Priority::Low => "!low",
+            };
+            line.push(' ');
+            line.push_str(priority);
+        }
+        for tag in &self.tags {
+            let tag = tag_name(tag);
+            if !tag.is_empty() {
+                line.push_str(&format!(" #{}", tag));
+            }
+        }
+        TaskParser::add_tid_to_line(&line, task_id)
+    }
+}
+
+/// A note of imported tasks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedNote {
+    /// Project name, used as the note's title and file name
+    pub title: String,
+    /// Task lines, under section headings where the export had them
+    pub body: String,
+    pub task_count: usize,
+}
+
+/// Group imported tasks into one note per project, giving every task a new
+/// UUID
+pub fn render_notes(
+    tasks: &[ImportedTask],
+    identity: &mut TaskIdentity,
+) -> Result<Vec<ImportedNote>> {
+    let mut notes: Vec<ImportedNote> = Vec::new();
+    let mut sections: Vec<Option<String>> = Vec::new();
+
+    for task in tasks {
+        let title = task.project.as_deref().unwrap_or(INBOX_NOTE);
+        let index = match notes.iter().position(|note| note.title == title) {
+            Some(index) => index,
+            None => {
+                notes.push(ImportedNote {
+                    title: title.to_string(),
+                    body: String::new(),
+                    task_count: 0,
+                });
+                sections.push(None);
+                notes.len() - 1
+            }
+        };
+
+        let note = &mut notes[index];
+        if task.section.is_some() && task.section != sections[index] {
+            if !note.body.is_empty() {
+                note.body.push('\n');
+            }
+            note.body.push_str(&format!(
+                "## {}\n\n",
+                task.section.as_deref().unwrap_or_default()
+            ));
+            sections[index] = task.section.clone();
+        }
+        note.body.push_str(&task.to_line(&identity.generate_id()?));
+        note.body.push('\n');
+        note.task_count += 1;
+    }
+
+    Ok(notes)
+}
+
+/// A value inside `@key(...)`, which cannot hold a closing parenthesis
+fn property_value(value: &str) -> String {
+    value.replace(['(', ')'], "").trim().to_string()
+}
+
+/// A tag in the characters `#tag` allows, with spaces turned into dashes
+fn tag_name(tag: &str) -> String {
+    let tag: String = tag
+        .trim()
+        .trim_start_matches(['#', '@'])
+        .chars()
+        .map(|c| if c.is_whitespace() { '-' } else { c })
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-'))
+        .collect();
+    tag.trim_start_matches(['/', '_', '-']).to_string()
+}
+
+/// An ISO date or date-time, the value as written when it is not a date, or
+/// `None` when it is empty
+fn import_date<Tz: TimeZone>(value: &str, now: &DateTime<Tz>) -> Option<String>
+where
+    Tz::Offset: Copy,
+{
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    // Fixed times are exported in UTC and written in local time
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        let local = at.with_timezone(&Local).naive_local();
+        return Some(
+            TaskDate {
+                date: local.date(),
+                time: Some(local.time()),
+            }
+            .to_string(),
+        );
+    }
+    Some(
+        TaskDate::parse(value, now)
+            .map(|date| date.to_string())
+            .unwrap_or_else(|| value.to_string()),
+    )
+}
+
+/// Todoist priorities run from 1 (p1, most urgent) to 4 (none) in CSV
+/// exports, and the other way round in the API
+fn todoist_priority(level: i64, api: bool) -> Option<Priority> {
+    let p = if api { 5 - level } else { level };
+    match p {
+        1 => Some(Priority::High),
+        2 => Some(Priority::Medium),
+        3 => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+/// Split Todoist labels (`@label`) out of task content
+fn split_todoist_labels(content: &str) -> (String, Vec<String>) {
+    let tags = TODOIST_LABEL_PATTERN
+        .captures_iter(content)
+        .map(|captures| captures[1].to_string())
+        .collect();
+    let text = TODOIST_LABEL_PATTERN
+        .replace_all(content, "")
+        .trim()
+        .to_string();
+    (text, tags)
+}
+
+/// Todoist's CSV project export: one row per task, section or comment, with
+/// `INDENT` giving the nesting
+fn parse_todoist_csv<Tz: TimeZone>(
+    content: &str,
+    project: Option<&str>,
+    now: &DateTime<Tz>,
+) -> Result<Vec<ImportedTask>>
+where
+    Tz::Offset: Copy,
+{
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .context("Failed to read CSV header")?
+        .iter()
+        .map(|header| header.trim().to_uppercase())
+        .collect();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+    let (Some(kind), Some(text)) = (column("TYPE"), column("CONTENT")) else {
+        return Err(anyhow!(
+            "Not a Todoist CSV export: missing TYPE or CONTENT column"
+        ));
+    };
+    let (priority, indent, date) = (column("PRIORITY"), column("INDENT"), column("DATE"));
+
+    let mut tasks = Vec::new();
+    let mut section = None;
+    for record in reader.records() {
+        let record = record.context("Failed to read CSV row")?;
+        let field = |index: Option<usize>| index.and_then(|index| record.get(index)).unwrap_or("");
+        match field(Some(kind)).trim() {
+            "section" => section = Some(field(Some(text)).trim().to_string()),
+            "task" => {
+                let (content, tags) = split_todoist_labels(field(Some(text)));
+                let mut task = ImportedTask::new(&content);
+                task.tags = tags;
+                task.project = project.map(str::to_string);
+                task.section = section.clone();
+                task.priority = field(priority)
+                    .trim()
+                    .parse()
+                    .ok()
+                    .and_then(|level| todoist_priority(level, false));
+                task.depth = field(indent)
+                    .trim()
+                    .parse::<usize>()
+                    .map_or(0, |indent| indent.saturating_sub(1));
+                // Recurring dates are written as the rule, e.g. `every monday`
+                let date = field(date).trim();
+                if Recurrence::parse(date).is_some() {
+                    task.repeat = Some(date.to_string());
+                } else {
+                    task.due = import_date(date, now);
+                }
+                tasks.push(task);
+            }
+            // Comments and view metadata
+            _ => {}
+        }
+    }
+    Ok(tasks)
+}
+
+/// An id that Todoist writes as either a string or a number
+fn json_id(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn json_str<'v>(value: &'v Value, key: &str) -> Option<&'v str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn json_bool(value: &Value, key: &str) -> bool {
+    match value.get(key) {
+        Some(Value::Bool(flag)) => *flag,
+        Some(Value::Number(flag)) => flag.as_i64() == Some(1),
+        _ => false,
+    }
+}
+
+/// Todoist JSON from the API or a backup: `items` (or `tasks`) with their
+/// `projects` and `sections`
+fn parse_todoist_json<Tz: TimeZone>(content: &str, now: &DateTime<Tz>) -> Result<Vec<ImportedTask>>
+where
+    Tz::Offset: Copy,
+{
+    let data: Value = serde_json::from_str(content).context("Failed to parse Todoist JSON")?;
+    let items = data
+        .get("items")
+        .or_else(|| data.get("tasks"))
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("Not a Todoist JSON export: no items or tasks"))?;
+
+    let names = |key: &str| -> HashMap<String, String> {
+        data.get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                Some((
+                    json_id(entry.get("id"))?,
+                    json_str(entry, "name")?.to_string(),
+                ))
+            })
+            .collect()
+    };
+    let projects = names("projects");
+    let sections = names("sections");
+
+    // Children are listed after their parent, in export order
+    let ids: Vec<Option<String>> = items.iter().map(|item| json_id(item.get("id"))).collect();
+    let mut children: HashMap<Option<String>, Vec<usize>> = HashMap::new();
+    for (index, item) in items.iter().enumerate() {
+        let parent =
+            json_id(item.get("parent_id")).filter(|parent| ids.contains(&Some(parent.clone())));
+        children.entry(parent).or_default().push(index);
+    }
+    let mut order = Vec::new();
+    let mut stack: Vec<(usize, usize)> = children
+        .get(&None)
+        .into_iter()
+        .flatten()
+        .rev()
+        .map(|index| (*index, 0))
+        .collect();
+    while let Some((index, depth)) = stack.pop() {
+        order.push((index, depth));
+        if let Some(id) = &ids[index] {
+            for child in children.get(&Some(id.clone())).into_iter().flatten().rev() {
+                stack.push((*child, depth + 1));
+            }
+        }
+    }
+
+    let mut tasks = Vec::new();
+    for (index, depth) in order {
+        let item = &items[index];
+        let content = json_str(item, "content").unwrap_or_default();
+        let mut task = ImportedTask::new(content);
+        task.depth = depth;
+        if json_bool(item, "checked")
+            || json_bool(item, "is_completed")
+            || json_bool(item, "completed")
+        {
+            task.status = TaskStatus::Done;
+        }
+        task.project = json_id(item.get("project_id")).and_then(|id| projects.get(&id).cloned());
+        task.section = json_id(item.get("section_id")).and_then(|id| sections.get(&id).cloned());
+        task.priority = item
+            .get("priority")
+            .and_then(Value::as_i64)
+            .and_then(|level| todoist_priority(level, true));
+        task.tags = item
+            .get("labels")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|label| label.as_str().map(str::to_string))
+            .collect();
+        if let Some(due) = item.get("due").filter(|due| due.is_object()) {
+            task.due = json_str(due, "date").and_then(|date| import_date(date, now));
+            // Kept as written even when it is not a rule the parser reads,
+            // so the recurrence is still visible in the note
+            if json_bool(due, "is_recurring") {
+                task.repeat = json_str(due, "string").map(str::to_string);
+            }
+        }
+        tasks.push(task);
+    }
+    Ok(tasks)
+}
+
+/// A TaskPaper document: `Project:` lines, `- task @tag(value)` lines and
+/// notes, nested with tabs
+fn parse_taskpaper<Tz: TimeZone>(content: &str, now: &DateTime<Tz>) -> Vec<ImportedTask>
+where
+    Tz::Offset: Copy,
+{
+    let mut tasks = Vec::new();
+    // Enclosing projects and tasks, with their indentation
+    let mut projects: Vec<(usize, String)> = Vec::new();
+    let mut parents: Vec<usize> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum::<usize>()
+            / 4;
+        while projects.last().is_some_and(|(depth, _)| *depth >= indent) {
+            projects.pop();
+        }
+        while parents.last().is_some_and(|depth| *depth >= indent) {
+            parents.pop();
+        }
+
+        let Some(text) = trimmed.strip_prefix("- ") else {
+            // A project is a line ending in a colon, before any tags
+            let name = TASKPAPER_TAG_PATTERN.replace_all(trimmed, "");
+            if let Some(name) = name.trim().strip_suffix(':') {
+                projects.push((indent, name.trim().to_string()));
+                parents.clear();
+            }
+            continue;
+        };
+
+        let mut task = ImportedTask::new(&TASKPAPER_TAG_PATTERN.replace_all(text, ""));
+        task.project = projects.last().map(|(_, name)| name.clone());
+        task.depth = parents.len();
+        for captures in TASKPAPER_TAG_PATTERN.captures_iter(text) {
+            let value = captures.get(2).map_or("", |value| value.as_str());
+            match captures[1].to_lowercase().as_str() {
+                "done" => task.status = TaskStatus::Done,
+                "due" => task.due = import_date(value, now),
+                "today" => task.scheduled = import_date("today", now),
+                "start" | "defer" => task.start = import_date(value, now),
+                "priority" => {
+                    task.priority = match value.trim().to_lowercase().as_str() {
+                        "1" | "high" => Some(Priority::High),
+                        "2" | "3" | "medium" => Some(Priority::Medium),
+                        "4" | "5" | "low" => Some(Priority::Low),
+                        _ => None,
+                    }
+                }
+                _ => task.tags.push(captures[1].to_string()),
+            }
+        }
+        tasks.push(task);
+        parents.push(indent);
+    }
+    tasks
+}
+
+/// Things JSON, as used by its `things:///json` import: to-dos and projects
+/// of to-dos and headings, with checklist items
+fn parse_things_json<Tz: TimeZone>(content: &str, now: &DateTime<Tz>) -> Result<Vec<ImportedTask>>
+where
+    Tz::Offset: Copy,
+{
+    let data: Value = serde_json::from_str(content).context("Failed to parse Things JSON")?;
+    let items = data
+        .as_array()
+        .ok_or_else(|| anyhow!("Not a Things JSON export: expected a list of items"))?;
+
+    let mut tasks = Vec::new();
+    for item in items {
+        let attributes = item.get("attributes").unwrap_or(&Value::Null);
+        match json_str(item, "type") {
+            Some("to-do") => push_things_todo(&mut tasks, attributes, None, None, now),
+            Some("project") => {
+                let project = json_str(attributes, "title").map(str::to_string);
+                let mut section = None;
+                let children = attributes.get("items").and_then(Value::as_array);
+                for child in children.into_iter().flatten() {
+                    let child_attributes = child.get("attributes").unwrap_or(&Value::Null);
+                    match json_str(child, "type") {
+                        Some("heading") => {
+                            section = json_str(child_attributes, "title").map(str::to_string)
+                        }
+                        Some("to-do") => push_things_todo(
+                            &mut tasks,
+                            child_attributes,
+                            project.clone(),
+                            section.clone(),
+                            now,
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(tasks)
+}
+
+fn push_things_todo<Tz: TimeZone>(
+    tasks: &mut Vec<ImportedTask>,
+    attributes: &Value,
+    project: Option<String>,
+    section: Option<String>,
+    now: &DateTime<Tz>,
+) where
+    Tz::Offset: Copy,
+{
+    let mut task = ImportedTask::new(json_str(attributes, "title").unwrap_or_default());
+    if json_bool(attributes, "canceled") {
+        task.status = TaskStatus::Cancelled;
+    } else if json_bool(attributes, "completed") {
+        task.status = TaskStatus::Done;
+    }
+    // `when` is the day the to-do is planned for, `2025-06-13@18:00` with a
+    // reminder, while `anytime` and `someday` have no date
+    task.scheduled = match json_str(attributes, "when").map(|when| when.to_lowercase()) {
+        Some(when) if when == "evening" => import_date("today", now),
+        Some(when) if when == "anytime" || when == "someday" => None,
+        Some(when) => import_date(&when.replace('@', " "), now),
+        None => None,
+    };
+    task.due = json_str(attributes, "deadline").and_then(|deadline| import_date(deadline, now));
+    task.tags = attributes
+        .get("tags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|tag| tag.as_str().map(str::to_string))
+        .collect();
+    task.project = project;
+    task.section = section;
+
+    let checklist: Vec<ImportedTask> = attributes
+        .get("checklist-items")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.get("attributes"))
+        .map(|item| {
+            let mut subtask = ImportedTask::new(json_str(item, "title").unwrap_or_default());
+            if json_bool(item, "completed") {
+                subtask.status = TaskStatus::Done;
+            }
+            subtask.project = task.project.clone();
+            subtask.section = task.section.clone();
+            subtask.depth = 1;
+            subtask
+        })
+        .collect();
+
+    tasks.push(task);
+    tasks.extend(checklist);
+}
+
+#[cfg(test)]
+#[path = "import_test.rs"]
+mod tests;
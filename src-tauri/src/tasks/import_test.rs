@@ -0,0 +1,219 @@
+use super::*;
+use chrono::FixedOffset;
+
+// Wednesday 2025-06-11, 09:30 in UTC+2
+fn now() -> DateTime<FixedOffset> {
+    FixedOffset::east_opt(2 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2025, 6, 11, 9, 30, 0)
+        .unwrap()
+}
+
+fn texts(tasks: &[ImportedTask]) -> Vec<(&str, usize)> {
+    tasks
+        .iter()
+        .map(|task| (task.text.as_str(), task.depth))
+        .collect()
+}
+
+#[test]
+fn test_detect_format() {
+    let detect = |name: &str, content: &str| ImportFormat::detect(Path::new(name), content);
+    assert_eq!(detect("Work.csv", ""), Some(ImportFormat::TodoistCsv));
+    assert_eq!(detect("todo.taskpaper", ""), Some(ImportFormat::TaskPaper));
+    assert_eq!(
+        detect("backup.json", r#"{"projects": [], "items": []}"#),
+        Some(ImportFormat::TodoistJson)
+    );
+    assert_eq!(
+        detect("things.json", r#"[{"type": "to-do", "attributes": {}}]"#),
+        Some(ImportFormat::ThingsJson)
+    );
+    assert_eq!(detect("other.json", r#"{"notes": []}"#), None);
+    assert_eq!(detect("notes.md", ""), None);
+}
+
+#[test]
+fn test_parse_todoist_csv() {
+    let csv =
+        "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE,DATE_LANG,TIMEZONE\n\
+               task,Write spec @writing,,1,1,Sam (1),,2025-06-20,en,Europe/Berlin\n\
+               task,Review draft,,4,2,Sam (1),,tomorrow,en,Europe/Berlin\n\
+               note,Looks good,,,,Sam (1),,,en,\n\
+               ,,,,,,,,,\n\
+               section,Later,,,,,,,,\n\
+               task,Water plants,,3,1,Sam (1),,every monday,en,Europe/Berlin\n";
+    let tasks = ImportFormat::TodoistCsv
+        .parse(csv, Some("Launch"), &now())
+        .unwrap();
+
+    assert_eq!(
+        texts(&tasks),
+        vec![("Write spec", 0), ("Review draft", 1), ("Water plants", 0)]
+    );
+    assert_eq!(tasks[0].tags, vec!["writing"]);
+    assert_eq!(tasks[0].priority, Some(Priority::High));
+    assert_eq!(tasks[0].due.as_deref(), Some("2025-06-20"));
+    assert_eq!(tasks[0].project.as_deref(), Some("Launch"));
+    assert_eq!(tasks[1].priority, None);
+    assert_eq!(tasks[1].due.as_deref(), Some("2025-06-12"));
+    assert_eq!(tasks[2].section.as_deref(), Some("Later"));
+    assert_eq!(tasks[2].repeat.as_deref(), Some("every monday"));
+    assert_eq!(tasks[2].due, None);
+
+    assert!(ImportFormat::TodoistCsv
+        .parse("Name,Value\nx,y\n", None, &now())
+        .is_err());
+}
+
+#[test]
+fn test_parse_todoist_json() {
+    let json = r#"{
+        "projects": [{"id": "p1", "name": "Home"}],
+        "sections": [{"id": 7, "name": "Garden", "project_id": "p1"}],
+        "items": [
+            {"id": "3", "content": "Prune roses", "project_id": "p1", "parent_id": "1",
+             "priority": 1, "checked": true},
+            {"id": "1", "content": "Garden  work", "project_id": "p1", "section_id": 7,
+             "priority": 4, "labels": ["outside"],
+             "due": {"date": "2025-06-14T10:00:00", "string": "every saturday 10am", "is_recurring": true}},
+            {"id": "2", "content": "Call plumber", "project_id": "p9", "priority": 2,
+             "due": {"date": "2025-06-12", "string": "Jun 12", "is_recurring": false}}
+        ]
+    }"#;
+    let tasks = ImportFormat::TodoistJson.parse(json, None, &now()).unwrap();
+
+    assert_eq!(
+        texts(&tasks),
+        vec![("Garden work", 0), ("Prune roses", 1), ("Call plumber", 0)]
+    );
+    let garden = &tasks[0];
+    assert_eq!(garden.project.as_deref(), Some("Home"));
+    assert_eq!(garden.section.as_deref(), Some("Garden"));
+    assert_eq!(garden.priority, Some(Priority::High));
+    assert_eq!(garden.tags, vec!["outside"]);
+    assert_eq!(garden.due.as_deref(), Some("2025-06-14 10:00"));
+    assert_eq!(garden.repeat.as_deref(), Some("every saturday 10am"));
+    assert_eq!(tasks[1].status, TaskStatus::Done);
+    assert_eq!(tasks[2].project, None);
+    assert_eq!(tasks[2].priority, Some(Priority::Low));
+    assert_eq!(tasks[2].repeat, None);
+}
+
+#[test]
+fn test_parse_taskpaper() {
+    let document = "Launch:\n\
+                    \t- Write spec @due(friday) @priority(1) @flagged\n\
+                    \t\t- Outline @done(2025-06-01)\n\
+                    \tSome notes about the launch\n\
+                    \t- Book venue @start(2025-06-15) @today\n\
+                    Errands: @home\n\
+                    \t- Buy milk\n\
+                    - Loose task\n";
+    let tasks = ImportFormat::TaskPaper
+        .parse(document, None, &now())
+        .unwrap();
+
+    assert_eq!(
+        texts(&tasks),
+        vec![
+            ("Write spec", 0),
+            ("Outline", 1),
+            ("Book venue", 0),
+            ("Buy milk", 0),
+            ("Loose task", 0),
+        ]
+    );
+    let spec = &tasks[0];
+    assert_eq!(spec.project.as_deref(), Some("Launch"));
+    assert_eq!(spec.due.as_deref(), Some("2025-06-13"));
+    assert_eq!(spec.priority, Some(Priority::High));
+    assert_eq!(spec.tags, vec!["flagged"]);
+    assert_eq!(tasks[1].status, TaskStatus::Done);
+    assert_eq!(tasks[2].start.as_deref(), Some("2025-06-15"));
+    assert_eq!(tasks[2].scheduled.as_deref(), Some("2025-06-11"));
+    assert_eq!(tasks[3].project.as_deref(), Some("Errands"));
+    assert_eq!(tasks[4].project, None);
+}
+
+#[test]
+fn test_parse_things_json() {
+    let json = r#"[
+        {"type": "to-do", "attributes": {"title": "Pay rent", "when": "evening", "deadline": "2025-06-30"}},
+        {"type": "project", "attributes": {"title": "Move", "items": [
+            {"type": "heading", "attributes": {"title": "Packing"}},
+            {"type": "to-do", "attributes": {"title": "Buy boxes", "when": "2025-06-13@18:00",
+             "tags": ["Errand", "Quick win"],
+             "checklist-items": [
+                {"type": "checklist-item", "attributes": {"title": "Tape", "completed": true}}
+             ]}},
+            {"type": "to-do", "attributes": {"title": "Hire van", "canceled": true, "when": "someday"}}
+        ]}}
+    ]"#;
+    let tasks = ImportFormat::ThingsJson.parse(json, None, &now()).unwrap();
+
+    assert_eq!(
+        texts(&tasks),
+        vec![
+            ("Pay rent", 0),
+            ("Buy boxes", 0),
+            ("Tape", 1),
+            ("Hire van", 0)
+        ]
+    );
+    assert_eq!(tasks[0].scheduled.as_deref(), Some("2025-06-11"));
+    assert_eq!(tasks[0].due.as_deref(), Some("2025-06-30"));
+    assert_eq!(tasks[1].project.as_deref(), Some("Move"));
+    assert_eq!(tasks[1].section.as_deref(), Some("Packing"));
+    assert_eq!(tasks[1].scheduled.as_deref(), Some("2025-06-13 18:00"));
+    assert_eq!(tasks[2].status, TaskStatus::Done);
+    assert_eq!(tasks[2].section.as_deref(), Some("Packing"));
+    assert_eq!(tasks[3].status, TaskStatus::Cancelled);
+    assert_eq!(tasks[3].scheduled, None);
+}
+
+#[test]
+fn test_render_notes_round_trip() {
+    let mut spec = ImportedTask::new("Write spec");
+    spec.project = Some("Launch (Q3)".into());
+    spec.section = Some("Docs".into());
+    spec.due = Some("2025-06-20 15:00".into());
+    spec.priority = Some(Priority::Medium);
+    spec.tags = vec!["Quick win".into(), "@writing".into()];
+    let mut outline = ImportedTask::new("Outline");
+    outline.project = spec.project.clone();
+    outline.section = spec.section.clone();
+    outline.depth = 1;
+    outline.status = TaskStatus::Done;
+    let milk = ImportedTask::new("Buy milk");
+
+    let mut identity = TaskIdentity::new();
+    let notes = render_notes(&[spec, milk, outline], &mut identity).unwrap();
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes[0].title, "Launch (Q3)");
+    assert_eq!(notes[0].task_count, 2);
+    assert_eq!(notes[1].title, "Inbox");
+
+    let body: Vec<&str> = notes[0].body.lines().collect();
+    assert_eq!(body[0], "## Docs");
+    assert_eq!(body[1], "");
+
+    let parsed = TaskParser::extract_all_tasks(&notes[0].body);
+    assert_eq!(parsed.len(), 2);
+    let spec = &parsed[0];
+    assert!(spec.id.is_some());
+    assert_eq!(
+        spec.properties.get("project"),
+        Some(&"Launch Q3".to_string())
+    );
+    assert_eq!(spec.properties.get("due"), Some(&"2025-06-20".to_string()));
+    assert_eq!(spec.properties.get("due_time"), Some(&"15:00".to_string()));
+    assert_eq!(spec.properties.get("priority"), Some(&"medium".to_string()));
+    assert_eq!(
+        spec.properties.get("tags"),
+        Some(&"Quick-win,writing".to_string())
+    );
+    assert_eq!(parsed[1].status, TaskStatus::Done);
+    assert_eq!(parsed[1].parent_line, Some(spec.line_number));
+    assert_ne!(parsed[0].id, parsed[1].id);
+}
@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -8,8 +8,8 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::identity::frontmatter::Priority;
-use crate::identity::tasks::TaskStatus;
+use crate::identity::frontmatter::{Priority, TimeEntry};
+use crate::identity::tasks::{Recurrence, TaskStatus};
 
 /// A complete task record stored in the index
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,23 +22,100 @@ pub struct TaskRecord {
     pub text: String,
     pub project: Option<String>,
     pub due_date: Option<NaiveDate>,
+    /// Time of day the task is due, in local time
+    #[serde(default)]
+    pub due_time: Option<NaiveTime>,
+    /// Day the task is planned to be worked on
+    #[serde(default)]
+    pub scheduled_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub scheduled_time: Option<NaiveTime>,
+    /// Day before which the task is not actionable
+    #[serde(default)]
+    pub start_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub start_time: Option<NaiveTime>,
     pub priority: Option<Priority>,
     pub tags: Option<Vec<String>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub properties: HashMap<String, String>,
+    /// Task this one is nested under in its checklist
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Tasks that must be closed before this one, from `blocked-by::`
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+    /// Estimated effort in minutes, from `estimate:: 2h`
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    /// Timer log kept in the note's front matter
+    #[serde(default)]
+    pub time_log: Vec<TimeEntry>,
+    /// Completed and moved to an archive note, or into front matter
+    #[serde(default)]
+    pub archived: bool,
 }
 
 /// Statistics about the task index
 #[derive(Debug, Clone, Serialize)]
 pub struct IndexStats {
     pub total_tasks: usize,
+    /// Tasks that are not done or cancelled
     pub open_tasks: usize,
     pub done_tasks: usize,
+    pub in_progress_tasks: usize,
+    pub cancelled_tasks: usize,
+    pub blocked_tasks: usize,
+    pub deferred_tasks: usize,
     pub files_with_tasks: usize,
     pub projects: usize,
     pub tasks_with_due_dates: usize,
+    /// Time logged on tasks over all time
+    pub time: TimeStats,
+}
+
+/// Logged and estimated time of a group of tasks
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TimeTotals {
+    pub logged_minutes: u64,
+    /// Estimates of the tasks that time was logged on
+    pub estimated_minutes: u64,
+    /// Tasks with time logged
+    pub tasks: usize,
+}
+
+impl TimeTotals {
+    fn add(&mut self, logged_minutes: u64, estimated_minutes: u64) {
+        self.logged_minutes += logged_minutes;
+        self.estimated_minutes += estimated_minutes;
+        self.tasks += 1;
+    }
+}
+
+/// Time logged on tasks between two days, inclusive
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TimeStats {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub total: TimeTotals,
+    pub by_project: BTreeMap<String, TimeTotals>,
+    pub by_tag: BTreeMap<String, TimeTotals>,
+    /// Logged minutes per day
+    pub by_date: BTreeMap<NaiveDate, u64>,
+    /// Tasks whose timer is running, whenever it was started
+    pub running: Vec<String>,
+}
+
+/// Completion of a task's subtasks, counted over all descendants
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskProgress {
+    /// Descendants that count towards progress, cancelled ones excluded
+    pub total: usize,
+    pub done: usize,
+    /// Done share of `total`, 0-100, or 0 without subtasks
+    pub percent: f64,
 }
 
 /// Cache statistics
@@ -59,6 +136,10 @@ pub struct TaskQuery {
     priority: Option<Priority>,
     has_due_date: Option<bool>,
     tags: Option<Vec<String>>,
+    recurring: Option<bool>,
+    repeat: Option<String>,
+    include_archived: bool,
+    completed_before: Option<DateTime<Utc>>,
 }
 
 impl TaskQuery {
@@ -91,13 +172,52 @@ impl TaskQuery {
         self
     }
 
+    pub fn with_recurring(mut self, recurring: bool) -> Self {
+        self.recurring = Some(recurring);
+        self
+    }
+
+    /// Match tasks with this repeat rule, in any syntax the parser accepts
+    /// (`weekly`, `every week`, `FREQ=WEEKLY`). An unparseable rule matches
+    /// nothing.
+    pub fn with_repeat(mut self, rule: &str) -> Self {
+        self.repeat = Some(
+            Recurrence::parse(rule)
+                .map(|recurrence| recurrence.to_string())
+                .unwrap_or_default(),
+        );
+        self
+    }
+
+    /// Archived tasks are left out of queries unless asked for
+    pub fn with_archived(mut self, include: bool) -> Self {
+        self.include_archived = include;
+        self
+    }
+
+    /// Match done or cancelled tasks completed before the cutoff
+    pub fn with_completed_before(mut self, cutoff: DateTime<Utc>) -> Self {
+        self.completed_before = Some(cutoff);
+        self
+    }
+
     fn matches(&self, task: &TaskRecord) -> bool {
+        if task.archived && !self.include_archived {
+            return false;
+        }
+
         if let Some(status) = &self.status {
             if task.status != *status {
                 return false;
             }
         }
 
+        if let Some(cutoff) = self.completed_before {
+            if !task.status.is_closed() || !matches!(task.completed_at, Some(at) if at < cutoff) {
+                return false;
+            }
+        }
+
         if let Some(project) = &self.project {
             if task.project.as_ref() != Some(project) {
                 return false;
@@ -119,6 +239,18 @@ impl TaskQuery {
             }
         }
 
+        if let Some(recurring) = self.recurring {
+            if task.properties.contains_key("repeat") != recurring {
+                return false;
+            }
+        }
+
+        if let Some(repeat) = &self.repeat {
+            if task.properties.get("repeat") != Some(repeat) {
+                return false;
+            }
+        }
+
         if let Some(query_tags) = &self.tags {
             if let Some(task_tags) = &task.tags {
                 for tag in query_tags {
@@ -145,7 +277,10 @@ struct IndexInner {
     by_status: HashMap<TaskStatus, HashSet<String>>,
     by_project: HashMap<String, HashSet<String>>,
     by_due_date: BTreeMap<NaiveDate, HashSet<String>>,
+    by_scheduled: BTreeMap<NaiveDate, HashSet<String>>,
     by_priority: HashMap<Priority, HashSet<String>>,
+    by_parent: HashMap<String, HashSet<String>>,
+    by_blocker: HashMap<String, HashSet<String>>,
 
     // LRU cache for frequently accessed tasks
     cache: LruCache<String, TaskRecord>,
@@ -164,7 +299,10 @@ impl IndexInner {
             by_status: HashMap::new(),
             by_project: HashMap::new(),
             by_due_date: BTreeMap::new(),
+            by_scheduled: BTreeMap::new(),
             by_priority: HashMap::new(),
+            by_parent: HashMap::new(),
+            by_blocker: HashMap::new(),
             cache: LruCache::new(NonZeroUsize::new(cache_size).unwrap()),
             cache_hits: 0,
             cache_misses: 0,
@@ -172,6 +310,19 @@ impl IndexInner {
         }
     }
 
+    fn clear(&mut self) {
+        self.tasks.clear();
+        self.by_file.clear();
+        self.by_status.clear();
+        self.by_project.clear();
+        self.by_due_date.clear();
+        self.by_scheduled.clear();
+        self.by_priority.clear();
+        self.by_parent.clear();
+        self.by_blocker.clear();
+        self.cache.clear();
+    }
+
     fn add_to_indices(&mut self, task: &TaskRecord) {
         let id = &task.id;
 
@@ -203,6 +354,14 @@ impl IndexInner {
                 .insert(id.clone());
         }
 
+        // Update by_scheduled index
+        if let Some(scheduled) = task.scheduled_date {
+            self.by_scheduled
+                .entry(scheduled)
+                .or_default()
+                .insert(id.clone());
+        }
+
         // Update by_priority index
         if let Some(priority) = &task.priority {
             self.by_priority
@@ -210,6 +369,22 @@ impl IndexInner {
                 .or_insert_with(HashSet::new)
                 .insert(id.clone());
         }
+
+        // Update by_parent index
+        if let Some(parent_id) = &task.parent_id {
+            self.by_parent
+                .entry(parent_id.clone())
+                .or_default()
+                .insert(id.clone());
+        }
+
+        // Update by_blocker index
+        for blocker_id in &task.blocked_by {
+            self.by_blocker
+                .entry(blocker_id.clone())
+                .or_default()
+                .insert(id.clone());
+        }
     }
 
     fn remove_from_indices(&mut self, task: &TaskRecord) {
@@ -251,6 +426,16 @@ impl IndexInner {
             }
         }
 
+        // Remove from by_scheduled index
+        if let Some(scheduled) = task.scheduled_date {
+            if let Some(date_tasks) = self.by_scheduled.get_mut(&scheduled) {
+                date_tasks.remove(id);
+                if date_tasks.is_empty() {
+                    self.by_scheduled.remove(&scheduled);
+                }
+            }
+        }
+
         // Remove from by_priority index
         if let Some(priority) = &task.priority {
             if let Some(priority_tasks) = self.by_priority.get_mut(priority) {
@@ -260,6 +445,115 @@ impl IndexInner {
                 }
             }
         }
+
+        // Remove from by_parent index
+        if let Some(parent_id) = &task.parent_id {
+            if let Some(children) = self.by_parent.get_mut(parent_id) {
+                children.remove(id);
+                if children.is_empty() {
+                    self.by_parent.remove(parent_id);
+                }
+            }
+        }
+
+        // Remove from by_blocker index
+        for blocker_id in &task.blocked_by {
+            if let Some(dependents) = self.by_blocker.get_mut(blocker_id) {
+                dependents.remove(id);
+                if dependents.is_empty() {
+                    self.by_blocker.remove(blocker_id);
+                }
+            }
+        }
+    }
+
+    /// Tasks with the given IDs that are in the index, sorted by file and line
+    fn collect_sorted<'a>(&self, ids: impl IntoIterator<Item = &'a String>) -> Vec<TaskRecord> {
+        let mut tasks: Vec<TaskRecord> = ids
+            .into_iter()
+            .filter_map(|id| self.tasks.get(id).cloned())
+            .collect();
+        tasks.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then(a.line_number.cmp(&b.line_number))
+        });
+        tasks
+    }
+
+    /// Time logged from `from` to `to`, in days of the time zone of `now`.
+    /// Entries count towards the day they started on, and running timers
+    /// count up to `now`.
+    fn time_stats<Tz: TimeZone>(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        now: &DateTime<Tz>,
+    ) -> TimeStats {
+        let now_utc = now.with_timezone(&Utc);
+        let mut stats = TimeStats {
+            from,
+            to,
+            ..TimeStats::default()
+        };
+
+        for task in self.tasks.values() {
+            if task.time_log.iter().any(|entry| entry.end.is_none()) {
+                stats.running.push(task.id.clone());
+            }
+
+            let mut worked = false;
+            let mut logged = 0;
+            for entry in &task.time_log {
+                let day = entry.start.with_timezone(&now.timezone()).date_naive();
+                if from.is_some_and(|from| day < from) || to.is_some_and(|to| day > to) {
+                    continue;
+                }
+                let minutes = entry.minutes_at(now_utc);
+                *stats.by_date.entry(day).or_default() += minutes;
+                logged += minutes;
+                worked = true;
+            }
+            if !worked {
+                continue;
+            }
+
+            let estimate = u64::from(task.estimate_minutes.unwrap_or(0));
+            stats.total.add(logged, estimate);
+            if let Some(project) = &task.project {
+                stats
+                    .by_project
+                    .entry(project.clone())
+                    .or_default()
+                    .add(logged, estimate);
+            }
+            for tag in task.tags.iter().flatten() {
+                stats
+                    .by_tag
+                    .entry(tag.clone())
+                    .or_default()
+                    .add(logged, estimate);
+            }
+        }
+
+        stats.running.sort();
+        stats
+    }
+
+    /// IDs of every task nested under `task_id`, at any depth
+    fn descendant_ids(&self, task_id: &str) -> Vec<String> {
+        let mut descendants = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = vec![task_id.to_string()];
+        while let Some(id) = pending.pop() {
+            for child in self.by_parent.get(&id).into_iter().flatten() {
+                if seen.insert(child.clone()) {
+                    descendants.push(child.clone());
+                    pending.push(child.clone());
+                }
+            }
+        }
+        descendants
     }
 }
 
@@ -297,30 +591,52 @@ impl TaskIndex {
     pub async fn get_stats(&self) -> IndexStats {
         let inner = self.inner.read().await;
 
-        let open_tasks = inner
-            .by_status
-            .get(&TaskStatus::Todo)
-            .map(|s| s.len())
-            .unwrap_or(0);
+        let count = |status: TaskStatus| inner.by_status.get(&status).map(|s| s.len()).unwrap_or(0);
 
-        let done_tasks = inner
-            .by_status
-            .get(&TaskStatus::Done)
-            .map(|s| s.len())
-            .unwrap_or(0);
+        let open_tasks = TaskStatus::ALL
+            .iter()
+            .filter(|status| !status.is_closed())
+            .map(|status| count(*status))
+            .sum();
 
         let tasks_with_due_dates = inner.by_due_date.values().map(|s| s.len()).sum();
 
         IndexStats {
             total_tasks: inner.tasks.len(),
             open_tasks,
-            done_tasks,
+            done_tasks: count(TaskStatus::Done),
+            in_progress_tasks: count(TaskStatus::InProgress),
+            cancelled_tasks: count(TaskStatus::Cancelled),
+            blocked_tasks: count(TaskStatus::Blocked),
+            deferred_tasks: count(TaskStatus::Deferred),
             files_with_tasks: inner.by_file.len(),
             projects: inner.by_project.len(),
             tasks_with_due_dates,
+            time: inner.time_stats(None, None, &Local::now()),
         }
     }
 
+    /// Time logged on tasks from `from` to `to`, inclusive, in local days
+    pub async fn get_time_stats(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> TimeStats {
+        self.get_time_stats_at(from, to, &Local::now()).await
+    }
+
+    /// Time logged from `from` to `to`, in days of the time zone of `now`,
+    /// with running timers counted up to `now`
+    pub async fn get_time_stats_at<Tz: TimeZone>(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        now: &DateTime<Tz>,
+    ) -> TimeStats {
+        let inner = self.inner.read().await;
+        inner.time_stats(from, to, now)
+    }
+
     /// Get cache statistics
     pub async fn get_cache_stats(&self) -> CacheStats {
         let inner = self.inner.read().await;
@@ -605,24 +921,83 @@ impl TaskIndex {
         results
     }
 
-    /// Get all tasks due today
+    /// Get the tasks for today in local time
     pub async fn query_today(&self) -> Vec<TaskRecord> {
-        let today = Utc::now().date_naive();
-        self.get_tasks_by_due_date(today).await
+        self.query_today_on(Local::now().date_naive()).await
     }
 
-    /// Get all overdue tasks (incomplete tasks with due date < today)
+    /// Get the tasks for a day: those due that day, and those scheduled for
+    /// it or scheduled earlier and still open, once their start date is
+    /// reached. Tasks with a time of day come first, in time order.
+    pub async fn query_today_on(&self, today: NaiveDate) -> Vec<TaskRecord> {
+        let inner = self.inner.read().await;
+
+        let mut ids: HashSet<&String> = inner
+            .by_due_date
+            .get(&today)
+            .map(|ids| ids.iter().collect())
+            .unwrap_or_default();
+
+        for (date, scheduled_ids) in inner.by_scheduled.range(..=today) {
+            for id in scheduled_ids {
+                let Some(task) = inner.tasks.get(id) else {
+                    continue;
+                };
+                let carried_over = *date < today && !task.status.is_closed();
+                let started = task.start_date.is_none_or(|start| start <= today);
+                if (*date == today || carried_over) && started {
+                    ids.insert(id);
+                }
+            }
+        }
+
+        let mut results: Vec<TaskRecord> = ids
+            .into_iter()
+            .filter_map(|id| inner.tasks.get(id).cloned())
+            .collect();
+
+        // Time of day for the day's plan: when it is due, or scheduled
+        let time_on = |task: &TaskRecord| {
+            let due = task.due_time.filter(|_| task.due_date == Some(today));
+            let scheduled = task
+                .scheduled_time
+                .filter(|_| task.scheduled_date == Some(today));
+            due.into_iter().chain(scheduled).min()
+        };
+        results.sort_by(|a, b| {
+            let (time_a, time_b) = (time_on(a), time_on(b));
+            time_b
+                .is_some()
+                .cmp(&time_a.is_some())
+                .then(time_a.cmp(&time_b))
+                .then(a.file_path.cmp(&b.file_path))
+                .then(a.line_number.cmp(&b.line_number))
+        });
+
+        results
+    }
+
+    /// Get all overdue tasks in local time
     pub async fn query_overdue(&self) -> Vec<TaskRecord> {
+        self.query_overdue_at(Local::now().naive_local()).await
+    }
+
+    /// Get all overdue tasks: incomplete tasks due before `now`'s day, or due
+    /// earlier that day at a time of day that has passed. Tasks scheduled in
+    /// the past are carried over to today instead of being overdue.
+    pub async fn query_overdue_at(&self, now: NaiveDateTime) -> Vec<TaskRecord> {
         let inner = self.inner.read().await;
-        let today = Utc::now().date_naive();
+        let today = now.date();
 
         let mut results = Vec::new();
 
-        for (date, ids) in inner.by_due_date.range(..today) {
+        for (date, ids) in inner.by_due_date.range(..=today) {
             for id in ids {
                 if let Some(task) = inner.tasks.get(id) {
+                    let past_due =
+                        *date < today || task.due_time.is_some_and(|time| time < now.time());
                     // Only include incomplete tasks
-                    if task.status == TaskStatus::Todo {
+                    if past_due && !task.status.is_closed() {
                         results.push(task.clone());
                     }
                 }
@@ -679,6 +1054,163 @@ impl TaskIndex {
         results
     }
 
+    /// Get the tasks nested directly under a task
+    pub async fn get_children(&self, task_id: &str) -> Vec<TaskRecord> {
+        let inner = self.inner.read().await;
+        inner.collect_sorted(inner.by_parent.get(task_id).into_iter().flatten())
+    }
+
+    /// Get every task nested under a task, at any depth
+    pub async fn get_descendants(&self, task_id: &str) -> Vec<TaskRecord> {
+        let inner = self.inner.read().await;
+        inner.collect_sorted(&inner.descendant_ids(task_id))
+    }
+
+    /// Get the tasks a task is blocked by. Blockers that are not in the
+    /// index are left out.
+    pub async fn get_blockers(&self, task_id: &str) -> Vec<TaskRecord> {
+        let inner = self.inner.read().await;
+        inner
+            .tasks
+            .get(task_id)
+            .map(|task| inner.collect_sorted(&task.blocked_by))
+            .unwrap_or_default()
+    }
+
+    /// Get the tasks that are blocked by a task
+    pub async fn get_dependents(&self, task_id: &str) -> Vec<TaskRecord> {
+        let inner = self.inner.read().await;
+        inner.collect_sorted(inner.by_blocker.get(task_id).into_iter().flatten())
+    }
+
+    /// Roll up the completion of a task's subtasks
+    pub async fn get_progress(&self, task_id: &str) -> Result<TaskProgress> {
+        let inner = self.inner.read().await;
+        if !inner.tasks.contains_key(task_id) {
+            return Err(anyhow!("Task {} not found", task_id));
+        }
+
+        let statuses: Vec<TaskStatus> = inner
+            .descendant_ids(task_id)
+            .iter()
+            .filter_map(|id| inner.tasks.get(id))
+            .map(|task| task.status)
+            .filter(|status| *status != TaskStatus::Cancelled)
+            .collect();
+        let total = statuses.len();
+        let done = statuses
+            .iter()
+            .filter(|status| **status == TaskStatus::Done)
+            .count();
+        let percent = if total > 0 {
+            done as f64 * 100.0 / total as f64
+        } else {
+            0.0
+        };
+
+        Ok(TaskProgress {
+            total,
+            done,
+            percent,
+        })
+    }
+
+    /// Find cycles in `blocked-by` dependencies. Each cycle lists its task
+    /// IDs sorted, and cycles are sorted by their first ID.
+    pub async fn find_dependency_cycles(&self) -> Vec<Vec<String>> {
+        let inner = self.inner.read().await;
+
+        // Tarjan's strongly connected components over blocker edges
+        struct Search<'a> {
+            inner: &'a IndexInner,
+            next_index: usize,
+            index: HashMap<&'a str, usize>,
+            low_link: HashMap<&'a str, usize>,
+            stack: Vec<&'a str>,
+            on_stack: HashSet<&'a str>,
+            cycles: Vec<Vec<String>>,
+        }
+
+        impl<'a> Search<'a> {
+            fn visit(&mut self, id: &'a str) {
+                self.index.insert(id, self.next_index);
+                self.low_link.insert(id, self.next_index);
+                self.next_index += 1;
+                self.stack.push(id);
+                self.on_stack.insert(id);
+
+                let blockers = self
+                    .inner
+                    .tasks
+                    .get(id)
+                    .map(|task| task.blocked_by.as_slice())
+                    .unwrap_or_default();
+                for blocker in blockers {
+                    let Some((blocker, _)) = self.inner.tasks.get_key_value(blocker) else {
+                        continue;
+                    };
+                    let blocker = blocker.as_str();
+                    if !self.index.contains_key(blocker) {
+                        self.visit(blocker);
+                        let low = self.low_link[id].min(self.low_link[blocker]);
+                        self.low_link.insert(id, low);
+                    } else if self.on_stack.contains(blocker) {
+                        let low = self.low_link[id].min(self.index[blocker]);
+                        self.low_link.insert(id, low);
+                    }
+                }
+
+                if self.low_link[id] == self.index[id] {
+                    let mut component = Vec::new();
+                    while let Some(member) = self.stack.pop() {
+                        self.on_stack.remove(member);
+                        component.push(member.to_string());
+                        if member == id {
+                            break;
+                        }
+                    }
+                    let blocks_itself = component.len() == 1
+                        && self
+                            .inner
+                            .tasks
+                            .get(id)
+                            .is_some_and(|task| task.blocked_by.iter().any(|b| b == id));
+                    if component.len() > 1 || blocks_itself {
+                        component.sort();
+                        self.cycles.push(component);
+                    }
+                }
+            }
+        }
+
+        let mut search = Search {
+            inner: &inner,
+            next_index: 0,
+            index: HashMap::new(),
+            low_link: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            cycles: Vec::new(),
+        };
+
+        let mut ids: Vec<&str> = inner
+            .tasks
+            .values()
+            .filter(|task| !task.blocked_by.is_empty())
+            .map(|task| task.id.as_str())
+            .collect();
+        ids.sort();
+        for id in ids {
+            if !search.index.contains_key(id) {
+                search.visit(id);
+            }
+        }
+
+        let mut cycles = search.cycles;
+        cycles.sort();
+        cycles
+    }
+
     /// Execute a compound query
     pub async fn query(&self, query: TaskQuery) -> Vec<TaskRecord> {
         let inner = self.inner.read().await;
@@ -763,6 +1295,28 @@ impl TaskIndex {
             }
         }
 
+        for ids in inner.by_scheduled.values() {
+            for id in ids {
+                if !inner.tasks.contains_key(id) {
+                    return Err(anyhow!(
+                        "Inconsistency: task {} in by_scheduled index but not in primary storage",
+                        id
+                    ));
+                }
+            }
+        }
+
+        for ids in inner.by_parent.values().chain(inner.by_blocker.values()) {
+            for id in ids {
+                if !inner.tasks.contains_key(id) {
+                    return Err(anyhow!(
+                        "Inconsistency: task {} in dependency indices but not in primary storage",
+                        id
+                    ));
+                }
+            }
+        }
+
         // Check that all tasks in primary storage are properly indexed
         for (id, task) in &inner.tasks {
             // Check file index
@@ -823,6 +1377,20 @@ impl TaskIndex {
                 }
             }
 
+            // Check scheduled index if applicable
+            if let Some(scheduled) = task.scheduled_date {
+                if !inner
+                    .by_scheduled
+                    .get(&scheduled)
+                    .is_some_and(|date_tasks| date_tasks.contains(id))
+                {
+                    return Err(anyhow!(
+                        "Inconsistency: task {} not in by_scheduled index",
+                        id
+                    ));
+                }
+            }
+
             // Check priority index if applicable
             if let Some(priority) = &task.priority {
                 if let Some(priority_tasks) = inner.by_priority.get(priority) {
@@ -844,6 +1412,13 @@ impl TaskIndex {
         Ok(())
     }
 
+    /// Remove every task from the index
+    pub async fn clear(&self) {
+        let mut inner = self.inner.write().await;
+        inner.clear();
+        inner.version += 1;
+    }
+
     /// Serialize the index to bytes for persistence
     pub async fn serialize(&self) -> Result<Vec<u8>> {
         let inner = self.inner.read().await;
@@ -875,13 +1450,7 @@ impl TaskIndex {
         let mut inner = self.inner.write().await;
 
         // Clear existing data
-        inner.tasks.clear();
-        inner.by_file.clear();
-        inner.by_status.clear();
-        inner.by_project.clear();
-        inner.by_due_date.clear();
-        inner.by_priority.clear();
-        inner.cache.clear();
+        inner.clear();
 
         // Restore tasks and rebuild indices
         for (id, task) in snapshot.tasks {
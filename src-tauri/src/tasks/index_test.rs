@@ -1,7 +1,7 @@
 use super::*;
 use crate::identity::frontmatter::Priority;
 use crate::identity::tasks::TaskStatus;
-use chrono::{NaiveDate, Utc};
+use chrono::{NaiveDate, NaiveTime, Utc};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -25,16 +25,26 @@ fn create_test_task(
         text: format!("Test task {}", id),
         project: project.map(String::from),
         due_date: due,
+        due_time: None,
+        scheduled_date: None,
+        scheduled_time: None,
+        start_date: None,
+        start_time: None,
         priority,
         tags: Some(vec!["test".to_string()]),
         created_at: now,
         updated_at: now,
-        completed_at: if status == TaskStatus::Done {
+        completed_at: if status.is_closed() {
             Some(now)
         } else {
             None
         },
         properties: HashMap::new(),
+        parent_id: None,
+        blocked_by: Vec::new(),
+        estimate_minutes: None,
+        time_log: Vec::new(),
+        archived: false,
     }
 }
 
@@ -331,7 +341,7 @@ async fn test_query_by_date_range() {
 async fn test_query_today_tasks() {
     let index = TaskIndex::new();
 
-    let today = Utc::now().date_naive();
+    let today = chrono::Local::now().date_naive();
     let tomorrow = today + chrono::Duration::days(1);
     let yesterday = today - chrono::Duration::days(1);
 
@@ -373,7 +383,7 @@ async fn test_query_today_tasks() {
 async fn test_query_overdue_tasks() {
     let index = TaskIndex::new();
 
-    let today = Utc::now().date_naive();
+    let today = chrono::Local::now().date_naive();
     let yesterday = today - chrono::Duration::days(1);
     let last_week = today - chrono::Duration::days(7);
     let tomorrow = today + chrono::Duration::days(1);
@@ -422,6 +432,124 @@ async fn test_query_overdue_tasks() {
     assert!(overdue.iter().any(|t| t.id == "task-002"));
 }
 
+#[tokio::test]
+async fn test_query_today_with_scheduled_and_start_dates() {
+    let index = TaskIndex::new();
+    let today = NaiveDate::from_ymd_opt(2025, 6, 11).unwrap();
+    let yesterday = today.pred_opt().unwrap();
+    let tomorrow = today.succ_opt().unwrap();
+    let at = |h| NaiveTime::from_hms_opt(h, 0, 0);
+
+    let mut due = create_test_task(
+        "due",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        Some(today),
+        None,
+    );
+    due.due_time = at(17);
+    let mut scheduled = create_test_task(
+        "scheduled",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        None,
+        None,
+    );
+    scheduled.scheduled_date = Some(today);
+    scheduled.scheduled_time = at(9);
+    let mut carried =
+        create_test_task("carried", "/notes/b.md", TaskStatus::Todo, None, None, None);
+    carried.scheduled_date = Some(yesterday);
+    let mut finished = create_test_task(
+        "finished",
+        "/notes/b.md",
+        TaskStatus::Done,
+        None,
+        None,
+        None,
+    );
+    finished.scheduled_date = Some(yesterday);
+    let mut not_started = create_test_task(
+        "not-started",
+        "/notes/b.md",
+        TaskStatus::Todo,
+        None,
+        None,
+        None,
+    );
+    not_started.scheduled_date = Some(yesterday);
+    not_started.start_date = Some(tomorrow);
+    let mut later = create_test_task("later", "/notes/b.md", TaskStatus::Todo, None, None, None);
+    later.scheduled_date = Some(tomorrow);
+
+    for task in [due, scheduled, carried, finished, not_started, later] {
+        index.insert_task(task).await.unwrap();
+    }
+
+    let ids: Vec<String> = index
+        .query_today_on(today)
+        .await
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    assert_eq!(ids, vec!["scheduled", "due", "carried"]);
+
+    // Scheduled tasks are planned, not late
+    let overdue = index
+        .query_overdue_at(today.and_hms_opt(12, 0, 0).unwrap())
+        .await;
+    assert!(overdue.is_empty());
+    index.verify_consistency().await.unwrap();
+
+    index.remove_task("carried").await.unwrap();
+    index.verify_consistency().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_query_overdue_respects_time_of_day() {
+    let index = TaskIndex::new();
+    let today = NaiveDate::from_ymd_opt(2025, 6, 11).unwrap();
+
+    let mut morning = create_test_task(
+        "morning",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        Some(today),
+        None,
+    );
+    morning.due_time = NaiveTime::from_hms_opt(9, 0, 0);
+    let mut evening = create_test_task(
+        "evening",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        Some(today),
+        None,
+    );
+    evening.due_time = NaiveTime::from_hms_opt(18, 0, 0);
+    let all_day = create_test_task(
+        "all-day",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        Some(today),
+        None,
+    );
+
+    for task in [morning, evening, all_day] {
+        index.insert_task(task).await.unwrap();
+    }
+
+    let overdue = index
+        .query_overdue_at(today.and_hms_opt(12, 0, 0).unwrap())
+        .await;
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue[0].id, "morning");
+}
+
 #[tokio::test]
 async fn test_sorted_retrieval_by_due_date() {
     let index = TaskIndex::new();
@@ -840,3 +968,355 @@ async fn test_update_file_tasks_incremental() {
     assert!(index.get_task("task-002").await.is_err()); // Removed
     assert!(index.get_task("task-003").await.is_ok()); // Added
 }
+
+#[tokio::test]
+async fn test_query_recurring_tasks() {
+    let index = TaskIndex::new();
+
+    let mut weekly = create_test_task(
+        "task-001",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        None,
+        None,
+    );
+    weekly
+        .properties
+        .insert("repeat".to_string(), "FREQ=WEEKLY;BYDAY=MO".to_string());
+    let mut monthly = create_test_task(
+        "task-002",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        None,
+        None,
+    );
+    monthly
+        .properties
+        .insert("repeat".to_string(), "FREQ=MONTHLY".to_string());
+    let once = create_test_task(
+        "task-003",
+        "/notes/a.md",
+        TaskStatus::Todo,
+        None,
+        None,
+        None,
+    );
+
+    index.insert_task(weekly).await.unwrap();
+    index.insert_task(monthly).await.unwrap();
+    index.insert_task(once).await.unwrap();
+
+    let recurring = index.query(TaskQuery::new().with_recurring(true)).await;
+    assert_eq!(recurring.len(), 2);
+
+    let one_off = index.query(TaskQuery::new().with_recurring(false)).await;
+    assert_eq!(one_off.len(), 1);
+    assert_eq!(one_off[0].id, "task-003");
+
+    let mondays = index
+        .query(TaskQuery::new().with_repeat("every week on Monday"))
+        .await;
+    assert_eq!(mondays.len(), 1);
+    assert_eq!(mondays[0].id, "task-001");
+
+    let invalid = index.query(TaskQuery::new().with_repeat("sometimes")).await;
+    assert!(invalid.is_empty());
+}
+
+#[tokio::test]
+async fn test_query_archived_tasks() {
+    let index = TaskIndex::new();
+
+    let open = create_test_task(
+        "task-001",
+        "/notes/daily.md",
+        TaskStatus::Todo,
+        None,
+        None,
+        None,
+    );
+    let mut archived = create_test_task(
+        "task-002",
+        "/notes/Archive/Tasks 2025-05.md",
+        TaskStatus::Done,
+        None,
+        None,
+        None,
+    );
+    archived.archived = true;
+    index.insert_task(open).await.unwrap();
+    index.insert_task(archived).await.unwrap();
+
+    let tasks = index.query(TaskQuery::new()).await;
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, "task-001");
+
+    let tasks = index
+        .query(
+            TaskQuery::new()
+                .with_status(TaskStatus::Done)
+                .with_archived(true),
+        )
+        .await;
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, "task-002");
+
+    // Archived tasks can still be looked up by ID
+    assert!(index.get_task("task-002").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_query_completed_before() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let note = dir.path().join("done.md");
+    std::fs::write(
+        &note,
+        r#"---
+tasks:
+  0000-0001:
+    status: done
+    text: Shipped
+    completed_at: "2025-05-20T09:00:00Z"
+  0000-0002:
+    status: cancelled
+    text: Dropped
+    completed_at: "2025-05-25T09:00:00Z"
+  0000-0003:
+    status: done
+    text: Shipped late
+    completed_at: "2025-06-02T09:00:00Z"
+  0000-0004:
+    status: todo
+    text: Reopened
+    completed_at: "2025-05-01T09:00:00Z"
+---
+- [x] Shipped <!-- tid: 0000-0001 -->
+- [-] Dropped <!-- tid: 0000-0002 -->
+- [x] Shipped late <!-- tid: 0000-0003 -->
+- [ ] Reopened <!-- tid: 0000-0004 -->
+"#,
+    )
+    .unwrap();
+
+    // Indexed the way the app does, so cancelled tasks carry their date too
+    let manager = crate::identity::IdentityManager::new(dir.path().to_path_buf());
+    manager.sync_file_tasks_to_index_async(&note).await.unwrap();
+
+    let cutoff = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let mut ids: Vec<String> = manager
+        .task_index()
+        .query(TaskQuery::new().with_completed_before(cutoff))
+        .await
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["0000-0001", "0000-0002"]);
+}
+
+#[tokio::test]
+async fn test_lifecycle_states_in_stats_and_queries() {
+    let index = TaskIndex::new();
+    let yesterday = Utc::now().date_naive() - chrono::Duration::days(1);
+
+    let statuses = [
+        TaskStatus::Todo,
+        TaskStatus::InProgress,
+        TaskStatus::Blocked,
+        TaskStatus::Deferred,
+        TaskStatus::Done,
+        TaskStatus::Cancelled,
+    ];
+    for (i, status) in statuses.iter().enumerate() {
+        let task = create_test_task(
+            &format!("task-{:03}", i),
+            "/notes/states.md",
+            *status,
+            None,
+            Some(yesterday),
+            None,
+        );
+        index.insert_task(task).await.unwrap();
+    }
+
+    let stats = index.get_stats().await;
+    assert_eq!(stats.open_tasks, 4);
+    assert_eq!(stats.done_tasks, 1);
+    assert_eq!(stats.in_progress_tasks, 1);
+    assert_eq!(stats.cancelled_tasks, 1);
+    assert_eq!(stats.blocked_tasks, 1);
+    assert_eq!(stats.deferred_tasks, 1);
+
+    let blocked = index.get_tasks_by_status(TaskStatus::Blocked).await;
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0].id, "task-002");
+
+    // Done and cancelled tasks are never overdue
+    assert_eq!(index.query_overdue().await.len(), 4);
+    index.verify_consistency().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_task_hierarchy_and_progress() {
+    let index = TaskIndex::new();
+
+    let statuses = [
+        ("parent", None, TaskStatus::InProgress),
+        ("child-1", Some("parent"), TaskStatus::Done),
+        ("child-2", Some("parent"), TaskStatus::Todo),
+        ("grandchild-1", Some("child-2"), TaskStatus::Done),
+        ("grandchild-2", Some("child-2"), TaskStatus::Cancelled),
+    ];
+    for (line, (id, parent, status)) in statuses.iter().enumerate() {
+        let mut task = create_test_task(id, "/notes/plan.md", *status, None, None, None);
+        task.line_number = line + 1;
+        task.parent_id = parent.map(String::from);
+        index.insert_task(task).await.unwrap();
+    }
+
+    let children: Vec<String> = index
+        .get_children("parent")
+        .await
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    assert_eq!(children, vec!["child-1", "child-2"]);
+    assert_eq!(index.get_descendants("parent").await.len(), 4);
+
+    // Cancelled subtasks don't count
+    let progress = index.get_progress("parent").await.unwrap();
+    assert_eq!((progress.total, progress.done), (3, 2));
+    assert!((progress.percent - 66.666).abs() < 0.01);
+
+    let progress = index.get_progress("child-1").await.unwrap();
+    assert_eq!((progress.total, progress.percent), (0, 0.0));
+    assert!(index.get_progress("missing").await.is_err());
+
+    // Removing a child drops it from the hierarchy
+    index.remove_task("child-1").await.unwrap();
+    assert_eq!(index.get_children("parent").await.len(), 1);
+    index.verify_consistency().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_task_dependencies_and_cycles() {
+    let index = TaskIndex::new();
+
+    let edges = [
+        ("design", vec![]),
+        ("build", vec!["design"]),
+        ("test", vec!["build", "external"]),
+        ("a", vec!["b"]),
+        ("b", vec!["c"]),
+        ("c", vec!["a"]),
+        ("self", vec!["self"]),
+    ];
+    for (id, blocked_by) in &edges {
+        let mut task = create_test_task(id, "/notes/deps.md", TaskStatus::Todo, None, None, None);
+        task.blocked_by = blocked_by.iter().map(|b| b.to_string()).collect();
+        index.insert_task(task).await.unwrap();
+    }
+
+    // Blockers outside the index are left out
+    let blockers: Vec<String> = index
+        .get_blockers("test")
+        .await
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    assert_eq!(blockers, vec!["build"]);
+
+    let dependents: Vec<String> = index
+        .get_dependents("design")
+        .await
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    assert_eq!(dependents, vec!["build"]);
+
+    assert_eq!(
+        index.find_dependency_cycles().await,
+        vec![vec!["a", "b", "c"], vec!["self"]]
+    );
+
+    // Breaking the cycle clears it
+    let mut c = index.get_task("c").await.unwrap();
+    c.blocked_by.clear();
+    index.update_task(c).await.unwrap();
+    assert_eq!(index.find_dependency_cycles().await, vec![vec!["self"]]);
+    assert!(index.get_dependents("a").await.is_empty());
+    index.verify_consistency().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_time_stats_by_project_tag_and_date() {
+    use crate::identity::frontmatter::TimeEntry;
+    use chrono::{FixedOffset, TimeZone};
+
+    // 23:30 UTC on the 10th is already the 11th in UTC+2
+    let at = |d: u32, h: u32, m: u32| Utc.with_ymd_and_hms(2025, 6, d, h, m, 0).unwrap();
+    let entry = |start, minutes| TimeEntry {
+        start,
+        end: Some(start + chrono::Duration::minutes(minutes)),
+    };
+    let day = |d: u32| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+    let now = FixedOffset::east_opt(2 * 3600)
+        .unwrap()
+        .from_utc_datetime(&at(12, 10, 0).naive_utc());
+
+    let index = TaskIndex::new();
+    let mut report = create_test_task(
+        "report",
+        "/a.md",
+        TaskStatus::Todo,
+        Some("Acme"),
+        None,
+        None,
+    );
+    report.estimate_minutes = Some(120);
+    report.time_log = vec![entry(at(10, 23, 30), 45), entry(at(12, 8, 0), 60)];
+    let mut call = create_test_task("call", "/a.md", TaskStatus::Done, Some("Acme"), None, None);
+    call.tags = Some(vec!["meeting".to_string()]);
+    call.time_log = vec![entry(at(9, 9, 0), 30)];
+    let mut running =
+        create_test_task("running", "/b.md", TaskStatus::InProgress, None, None, None);
+    running.time_log = vec![TimeEntry {
+        start: at(12, 9, 20),
+        end: None,
+    }];
+    let idle = create_test_task("idle", "/b.md", TaskStatus::Todo, Some("Other"), None, None);
+    for task in [report, call, running, idle] {
+        index.insert_task(task).await.unwrap();
+    }
+
+    let stats = index.get_time_stats_at(None, None, &now).await;
+    assert_eq!(stats.total.logged_minutes, 45 + 60 + 30 + 40);
+    assert_eq!(stats.total.tasks, 3);
+    assert_eq!(
+        stats.by_project["Acme"],
+        TimeTotals {
+            logged_minutes: 135,
+            estimated_minutes: 120,
+            tasks: 2
+        }
+    );
+    assert!(!stats.by_project.contains_key("Other"));
+    assert_eq!(stats.by_tag["meeting"].logged_minutes, 30);
+    assert_eq!(stats.by_tag["test"].logged_minutes, 145);
+    assert_eq!(
+        stats.by_date.into_iter().collect::<Vec<_>>(),
+        vec![(day(9), 30), (day(11), 45), (day(12), 100)]
+    );
+    assert_eq!(stats.running, vec!["running"]);
+
+    let stats = index
+        .get_time_stats_at(Some(day(10)), Some(day(11)), &now)
+        .await;
+    assert_eq!(stats.total.logged_minutes, 45);
+    assert_eq!(stats.by_project["Acme"].tasks, 1);
+    assert_eq!(stats.running, vec!["running"]);
+
+    assert_eq!(index.get_stats().await.time.total.tasks, 3);
+}
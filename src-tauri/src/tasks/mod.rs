@@ -3,11 +3,30 @@
 #![allow(unused_imports)]
 #![allow(unused_variables)]
 
+mod board;
+mod bulk;
+mod ical;
+mod import;
 mod index;
 mod migration;
+mod query_lang;
+mod snapshot;
+mod timesheet;
 
 #[cfg(test)]
 mod migration_test;
 
-pub use index::{IndexStats, TaskIndex, TaskQuery, TaskRecord};
+pub use board::{move_card, parse_board, Board, BoardCard, BoardColumn};
+pub use bulk::{
+    apply_file_edits, plan_bulk_edit, ArchiveTarget, BulkChange, BulkJournal, BulkOperation,
+    BulkPlan, BulkSkip, FileEdit, ARCHIVE_FOLDER,
+};
+pub use ical::{calendar_tasks, tasks_to_ics, CalendarComponent, CalendarOptions};
+pub use import::{render_notes, ImportFormat, ImportedNote, ImportedTask};
+pub use index::{
+    IndexStats, TaskIndex, TaskProgress, TaskQuery, TaskRecord, TimeStats, TimeTotals,
+};
 pub use migration::{TaskMigrationConfig, TaskMigrationManager, TaskMigrationReport};
+pub use query_lang::{extract_task_query_blocks, QueryContext, TaskQueryBlock, TaskSearch};
+pub use snapshot::{snapshot_path, FileFingerprint, TaskIndexSnapshot, WarmStartStats};
+pub use timesheet::time_summary_csv;
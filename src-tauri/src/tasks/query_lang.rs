@@ -0,0 +1,834 @@
+//! Textual task queries
+//!
+//! A query is a list of filters separated by whitespace. Filters are ANDed
+//! together unless joined with `OR`, `NOT` or a leading `-` negates one, and
+//! parentheses group them:
+//!
+//! - `word` / `"exact phrase"`: text that appears in the task
+//! - `status:todo`, `is:open`, `is:closed`, `is:recurring`, `is:subtask`,
+//!   `is:archived` (archived tasks are only searched when a query names them)
+//! - `project:alpha`, `priority:high`, `tag:work` (or a nested tag below it)
+//! - `path:Projects/`: the note's vault-relative path starts with the prefix
+//! - `repeat:weekly`: the repeat rule, in any syntax the parser accepts
+//! - `has:due`, `has:scheduled`, `has:start`, `has:priority`, `has:project`,
+//!   `has:tags`, `has:blockers`
+//! - `due<=+7d`, `due:today`, `scheduled:today`, `start<=today`,
+//!   `created>=2025-01-01`, `updated<-2w`, `completed:yesterday`, and
+//!   `due:none` / `due:any`
+//! - `sort:priority,-due` orders the results (`-` for descending) and
+//!   `limit:50` caps them
+//!
+//! Dates are `YYYY-MM-DD`, `today`, `tomorrow`, `yesterday` or an offset
+//! from today in days, weeks or months (`+3d`, `-2w`, `+1m`). The same syntax
+//! is used in fenced ```` ```tasks ```` blocks in notes.
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Months, NaiveDate};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::path::Path;
+
+use super::index::TaskRecord;
+use crate::identity::frontmatter::Priority;
+use crate::identity::tasks::{Recurrence, TaskStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Before,
+    OnOrBefore,
+    On,
+    OnOrAfter,
+    After,
+}
+
+impl Comparison {
+    fn holds(self, value: NaiveDate, target: NaiveDate) -> bool {
+        match self {
+            Comparison::Before => value < target,
+            Comparison::OnOrBefore => value <= target,
+            Comparison::On => value == target,
+            Comparison::OnOrAfter => value >= target,
+            Comparison::After => value > target,
+        }
+    }
+}
+
+/// Which date of a task a date filter looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Due,
+    Scheduled,
+    Start,
+    Created,
+    Updated,
+    Completed,
+}
+
+/// Furthest a relative date may reach, about 10,000 years
+const MAX_OFFSET_DAYS: i64 = 3_652_500;
+
+/// A date as written in a query, resolved against today when evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryDate {
+    Absolute(NaiveDate),
+    Days(i64),
+    Months(i32),
+}
+
+impl QueryDate {
+    fn resolve(self, today: NaiveDate) -> NaiveDate {
+        match self {
+            QueryDate::Absolute(date) => date,
+            QueryDate::Days(days) => Duration::try_days(days)
+                .and_then(|offset| today.checked_add_signed(offset))
+                .unwrap_or(if days < 0 {
+                    NaiveDate::MIN
+                } else {
+                    NaiveDate::MAX
+                }),
+            QueryDate::Months(months) if months >= 0 => today
+                .checked_add_months(Months::new(months as u32))
+                .unwrap_or(today),
+            QueryDate::Months(months) => today
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+                .unwrap_or(today),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Text(String),
+    Status(TaskStatus),
+    Open,
+    Closed,
+    Recurring,
+    Subtask,
+    Archived,
+    Project(String),
+    Priority(Priority),
+    Tag(String),
+    Path(String),
+    Repeat(String),
+    Has(Property),
+    Date(DateField, Comparison, QueryDate),
+    NoDate(DateField),
+}
+
+/// Properties `has:` can test for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    Due,
+    Scheduled,
+    Start,
+    Priority,
+    Project,
+    Tags,
+    Blockers,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Filter(Filter),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Due,
+    Scheduled,
+    Start,
+    Priority,
+    Status,
+    Project,
+    Path,
+    Created,
+    Updated,
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// What a query is evaluated relative to
+pub struct QueryContext<'a> {
+    /// Vault root, for matching `path:` against vault-relative paths
+    pub vault_root: Option<&'a Path>,
+    pub today: NaiveDate,
+}
+
+/// A parsed textual task query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSearch {
+    /// `None` when the query only sorts or limits
+    pub filter: Option<Expr>,
+    pub sort: Vec<SortKey>,
+    pub limit: Option<usize>,
+}
+
+/// Keys that take a value rather than a comparison
+const FILTER_KEYS: [&str; 10] = [
+    "sort", "limit", "status", "is", "project", "priority", "tag", "path", "repeat", "has",
+];
+
+/// Deepest nesting of parentheses and `NOT`s a query may use
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Or,
+    And,
+    Not,
+    Term { text: String, quoted: bool },
+}
+
+impl TaskSearch {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut search = Self {
+            filter: None,
+            sort: Vec::new(),
+            limit: None,
+        };
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+            search: &mut search,
+            depth: 0,
+            nesting: 0,
+        };
+        let filter = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(anyhow!("Unexpected {} in task query", describe(token)));
+        }
+        search.filter = filter;
+        Ok(search)
+    }
+
+    /// Whether the query filters on `is:archived`, so archived tasks have to
+    /// be searched too
+    pub fn includes_archived(&self) -> bool {
+        fn mentions(expr: &Expr) -> bool {
+            match expr {
+                Expr::Filter(filter) => *filter == Filter::Archived,
+                Expr::Not(expr) => mentions(expr),
+                Expr::And(exprs) | Expr::Or(exprs) => exprs.iter().any(mentions),
+            }
+        }
+        self.filter.as_ref().is_some_and(mentions)
+    }
+
+    /// Whether a task satisfies the query's filters
+    pub fn matches(&self, task: &TaskRecord, context: &QueryContext) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|filter| expr_holds(filter, task, context))
+    }
+
+    /// Filters, sorts and limits `tasks`. Without a `sort:` tasks are
+    /// ordered by note and line.
+    pub fn apply(&self, tasks: Vec<TaskRecord>, context: &QueryContext) -> Vec<TaskRecord> {
+        let mut results: Vec<TaskRecord> = tasks
+            .into_iter()
+            .filter(|task| self.matches(task, context))
+            .collect();
+
+        results.sort_by(|a, b| {
+            self.sort
+                .iter()
+                .map(|key| compare(a, b, *key))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| {
+                    a.file_path
+                        .cmp(&b.file_path)
+                        .then(a.line_number.cmp(&b.line_number))
+                })
+        });
+
+        if let Some(limit) = self.limit {
+            results.truncate(limit);
+        }
+        results
+    }
+}
+
+/// A fenced ```` ```tasks ```` block in a note
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskQueryBlock {
+    /// 1-based line of the opening fence
+    pub line: usize,
+    pub query: String,
+}
+
+/// The ```` ```tasks ```` blocks in a note, in document order. An
+/// unterminated block runs to the end of the note.
+pub fn extract_task_query_blocks(content: &str) -> Vec<TaskQueryBlock> {
+    enum State<'a> {
+        Text,
+        /// Inside another code block, closed by this fence
+        Code(&'a str),
+        Tasks(&'a str, TaskQueryBlock),
+    }
+
+    let mut blocks = Vec::new();
+    let mut state = State::Text;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        let fence_len = trimmed
+            .find(|c| c != '`' && c != '~')
+            .unwrap_or(trimmed.len());
+        let (fence, info) = trimmed.split_at(fence_len);
+        let is_fence = fence_len >= 3;
+
+        state = match state {
+            State::Text if is_fence && info.trim().eq_ignore_ascii_case("tasks") => State::Tasks(
+                fence,
+                TaskQueryBlock {
+                    line: index + 1,
+                    query: String::new(),
+                },
+            ),
+            State::Text if is_fence => State::Code(fence),
+            State::Text => State::Text,
+            State::Code(open) if is_fence && info.is_empty() && fence.starts_with(open) => {
+                State::Text
+            }
+            State::Code(open) => State::Code(open),
+            State::Tasks(open, block) if is_fence && info.is_empty() && fence.starts_with(open) => {
+                blocks.push(block);
+                State::Text
+            }
+            State::Tasks(open, mut block) => {
+                if !block.query.is_empty() {
+                    block.query.push('\n');
+                }
+                block.query.push_str(line);
+                State::Tasks(open, block)
+            }
+        };
+    }
+
+    if let State::Tasks(_, block) = state {
+        blocks.push(block);
+    }
+    blocks
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    search: &'a mut TaskSearch,
+    /// Parentheses the parser is inside
+    depth: usize,
+    /// Parentheses and `NOT`s the parser is inside
+    nesting: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Runs `parse` one level deeper, failing past `MAX_DEPTH`
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.nesting >= MAX_DEPTH {
+            return Err(anyhow!(
+                "Task query is nested more than {} levels deep",
+                MAX_DEPTH
+            ));
+        }
+        self.nesting += 1;
+        let result = parse(self);
+        self.nesting -= 1;
+        result
+    }
+
+    /// `a OR b OR c`
+    fn parse_or(&mut self) -> Result<Option<Expr>> {
+        let mut alternatives = Vec::new();
+        let mut after_or = false;
+        loop {
+            match self.parse_and()? {
+                Some(expr) => alternatives.push(expr),
+                None if after_or || matches!(self.peek(), Some(Token::Or)) => {
+                    return Err(anyhow!("OR needs a filter on both sides"));
+                }
+                None => {}
+            }
+            if !matches!(self.peek(), Some(Token::Or)) {
+                break;
+            }
+            self.next();
+            after_or = true;
+        }
+        Ok(match alternatives.len() {
+            0 => None,
+            1 => alternatives.pop(),
+            _ => Some(Expr::Or(alternatives)),
+        })
+    }
+
+    /// `a b AND c`
+    fn parse_and(&mut self) -> Result<Option<Expr>> {
+        let mut terms = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::Or) | Some(Token::Close) => break,
+                Some(Token::And) => {
+                    self.next();
+                    let ends = matches!(self.peek(), None | Some(Token::Or) | Some(Token::Close));
+                    if terms.is_empty() || ends {
+                        return Err(anyhow!("AND needs a filter on both sides"));
+                    }
+                }
+                _ => {
+                    if let Some(expr) = self.parse_unary()? {
+                        terms.push(expr);
+                    }
+                }
+            }
+        }
+        Ok(match terms.len() {
+            0 => None,
+            1 => terms.pop(),
+            _ => Some(Expr::And(terms)),
+        })
+    }
+
+    /// `NOT a`, `-a`, `(a OR b)` or a single filter. Returns `None` for
+    /// `sort:` and `limit:`, which are recorded on the query instead.
+    fn parse_unary(&mut self) -> Result<Option<Expr>> {
+        match self.next() {
+            Some(Token::Not) => match self.nested(Self::parse_unary)? {
+                Some(expr) => Ok(Some(Expr::Not(Box::new(expr)))),
+                None => Err(anyhow!("NOT needs a filter after it")),
+            },
+            Some(Token::Open) => {
+                self.depth += 1;
+                let expr = self.nested(Self::parse_or)?;
+                if self.next() != Some(Token::Close) {
+                    return Err(anyhow!("Missing closing parenthesis in task query"));
+                }
+                self.depth -= 1;
+                expr.map(Some)
+                    .ok_or_else(|| anyhow!("Empty parentheses in task query"))
+            }
+            Some(Token::Term { text, quoted }) => {
+                if quoted {
+                    return Ok(Some(Expr::Filter(Filter::Text(text.to_lowercase()))));
+                }
+                if let Some(negated) = text.strip_prefix('-').filter(|rest| !rest.is_empty()) {
+                    let expr = self
+                        .parse_term(negated)?
+                        .ok_or_else(|| anyhow!("{} cannot be negated", negated))?;
+                    return Ok(Some(Expr::Not(Box::new(expr))));
+                }
+                self.parse_term(&text)
+            }
+            Some(token) => Err(anyhow!("Unexpected {} in task query", describe(&token))),
+            None => Ok(None),
+        }
+    }
+
+    fn parse_term(&mut self, text: &str) -> Result<Option<Expr>> {
+        let Some((key, cmp, value)) = split_filter(text) else {
+            return Ok(Some(Expr::Filter(Filter::Text(text.to_lowercase()))));
+        };
+        let key = key.to_lowercase();
+        if date_field(&key).is_none() && cmp != Comparison::On {
+            if FILTER_KEYS.contains(&key.as_str()) {
+                return Err(anyhow!("{} cannot be compared", key));
+            }
+            return Ok(Some(Expr::Filter(Filter::Text(text.to_lowercase()))));
+        }
+
+        let filter = match key.as_str() {
+            "sort" | "limit" if self.depth > 0 => {
+                return Err(anyhow!("{}: cannot be used inside parentheses", key));
+            }
+            "sort" => {
+                self.search.sort = parse_sort(value)?;
+                return Ok(None);
+            }
+            "limit" => {
+                let limit = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid limit: {}", value))?;
+                self.search.limit = Some(limit);
+                return Ok(None);
+            }
+            "status" => Filter::Status(
+                TaskStatus::parse(value).ok_or_else(|| anyhow!("Unknown status: {}", value))?,
+            ),
+            "is" => match value.to_lowercase().as_str() {
+                "open" => Filter::Open,
+                "closed" => Filter::Closed,
+                "recurring" => Filter::Recurring,
+                "subtask" => Filter::Subtask,
+                "archived" => Filter::Archived,
+                other => Filter::Status(
+                    TaskStatus::parse(other).ok_or_else(|| anyhow!("Unknown is:{}", value))?,
+                ),
+            },
+            "project" => Filter::Project(value.to_lowercase()),
+            "priority" => Filter::Priority(match value.to_lowercase().as_str() {
+                "high" => Priority::High,
+                "medium" => Priority::Medium,
+                "low" => Priority::Low,
+                _ => return Err(anyhow!("Unknown priority: {}", value)),
+            }),
+            "tag" => Filter::Tag(value.trim_start_matches('#').to_lowercase()),
+            "path" => Filter::Path(value.trim_start_matches('/').to_lowercase()),
+            "repeat" => Filter::Repeat(
+                Recurrence::parse(value)
+                    .ok_or_else(|| anyhow!("Invalid repeat rule: {}", value))?
+                    .to_string(),
+            ),
+            "has" => Filter::Has(match value.to_lowercase().as_str() {
+                "due" => Property::Due,
+                "scheduled" => Property::Scheduled,
+                "start" => Property::Start,
+                "priority" => Property::Priority,
+                "project" => Property::Project,
+                "tags" | "tag" => Property::Tags,
+                "blockers" | "blocked-by" => Property::Blockers,
+                _ => return Err(anyhow!("Unknown has:{}", value)),
+            }),
+            _ => {
+                let Some(field) = date_field(&key) else {
+                    // Not a filter key, so plain text such as `re:` or a time
+                    return Ok(Some(Expr::Filter(Filter::Text(text.to_lowercase()))));
+                };
+                match value.to_lowercase().as_str() {
+                    "none" if cmp == Comparison::On => Filter::NoDate(field),
+                    "any" if cmp == Comparison::On => {
+                        return Ok(Some(Expr::Not(Box::new(Expr::Filter(Filter::NoDate(
+                            field,
+                        ))))));
+                    }
+                    _ => Filter::Date(field, cmp, parse_date(value)?),
+                }
+            }
+        };
+        Ok(Some(Expr::Filter(filter)))
+    }
+}
+
+/// Splits `key:value`, `key<=value`, `key:>=value` and similar
+fn split_filter(text: &str) -> Option<(&str, Comparison, &str)> {
+    let split = text.find([':', '<', '>', '='])?;
+    let (key, rest) = text.split_at(split);
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    let (cmp, value) = if let Some(value) = rest.strip_prefix(">=") {
+        (Comparison::OnOrAfter, value)
+    } else if let Some(value) = rest.strip_prefix("<=") {
+        (Comparison::OnOrBefore, value)
+    } else if let Some(value) = rest.strip_prefix('>') {
+        (Comparison::After, value)
+    } else if let Some(value) = rest.strip_prefix('<') {
+        (Comparison::Before, value)
+    } else {
+        (Comparison::On, rest.strip_prefix('=').unwrap_or(rest))
+    };
+    if value.is_empty() {
+        return None;
+    }
+    Some((key, cmp, value))
+}
+
+fn date_field(key: &str) -> Option<DateField> {
+    match key {
+        "due" => Some(DateField::Due),
+        "scheduled" => Some(DateField::Scheduled),
+        "start" => Some(DateField::Start),
+        "created" => Some(DateField::Created),
+        "updated" => Some(DateField::Updated),
+        "completed" | "done" => Some(DateField::Completed),
+        _ => None,
+    }
+}
+
+fn parse_date(value: &str) -> Result<QueryDate> {
+    let lower = value.to_lowercase();
+    match lower.as_str() {
+        "today" => return Ok(QueryDate::Days(0)),
+        "tomorrow" => return Ok(QueryDate::Days(1)),
+        "yesterday" => return Ok(QueryDate::Days(-1)),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(QueryDate::Absolute(date));
+    }
+
+    // +3d, -2w, +1m
+    let invalid = || {
+        anyhow!(
+            "Invalid date: {} (expected YYYY-MM-DD, today or +Nd)",
+            value
+        )
+    };
+    let (sign, rest) = match lower.as_bytes().first() {
+        Some(b'+') => (1, &lower[1..]),
+        Some(b'-') => (-1, &lower[1..]),
+        _ => return Err(invalid()),
+    };
+    let unit = rest.chars().last().ok_or_else(invalid)?;
+    let amount: i64 = rest
+        .strip_suffix(unit)
+        .unwrap_or(rest)
+        .parse()
+        .map_err(|_| invalid())?;
+    let too_far = || anyhow!("Invalid date: {} is too far away", value);
+    let days = |per_unit: i64| {
+        amount
+            .checked_mul(sign * per_unit)
+            .filter(|days| days.abs() <= MAX_OFFSET_DAYS)
+            .map(QueryDate::Days)
+            .ok_or_else(too_far)
+    };
+    match unit {
+        'd' => days(1),
+        'w' => days(7),
+        'm' => i32::try_from(sign * amount)
+            .ok()
+            .filter(|months| months.unsigned_abs() <= MAX_OFFSET_DAYS as u32 / 30)
+            .map(QueryDate::Months)
+            .ok_or_else(too_far),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_sort(value: &str) -> Result<Vec<SortKey>> {
+    value
+        .split(',')
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            let (descending, name) = match field.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, field),
+            };
+            let field = match name.to_lowercase().as_str() {
+                "due" => SortField::Due,
+                "scheduled" => SortField::Scheduled,
+                "start" => SortField::Start,
+                "priority" => SortField::Priority,
+                "status" => SortField::Status,
+                "project" => SortField::Project,
+                "path" | "file" => SortField::Path,
+                "created" => SortField::Created,
+                "updated" => SortField::Updated,
+                "text" => SortField::Text,
+                _ => return Err(anyhow!("Cannot sort by {}", name)),
+            };
+            Ok(SortKey { field, descending })
+        })
+        .collect()
+}
+
+/// Splits the query into words, quoted phrases, parentheses and the
+/// `AND`/`OR`/`NOT` keywords
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            continue;
+        }
+
+        // A term that starts with a quote is a phrase; quotes inside a
+        // term (`project:"Home office"`) only keep the value together
+        let quoted = c == '"';
+        let mut text = String::new();
+        let mut in_quotes = false;
+        while let Some(&c) = chars.peek() {
+            if c == '"' {
+                in_quotes = !in_quotes;
+                chars.next();
+                continue;
+            }
+            if !in_quotes && (c.is_whitespace() || c == '(' || c == ')') {
+                break;
+            }
+            text.push(c);
+            chars.next();
+        }
+        if in_quotes {
+            return Err(anyhow!("Unterminated quote in task query"));
+        }
+
+        tokens.push(match text.as_str() {
+            "OR" if !quoted => Token::Or,
+            "AND" if !quoted => Token::And,
+            "NOT" if !quoted => Token::Not,
+            _ => Token::Term { text, quoted },
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Open => "(".to_string(),
+        Token::Close => ")".to_string(),
+        Token::Or => "OR".to_string(),
+        Token::And => "AND".to_string(),
+        Token::Not => "NOT".to_string(),
+        Token::Term { text, .. } => text.clone(),
+    }
+}
+
+fn expr_holds(expr: &Expr, task: &TaskRecord, context: &QueryContext) -> bool {
+    match expr {
+        Expr::Filter(filter) => filter_holds(filter, task, context),
+        Expr::Not(expr) => !expr_holds(expr, task, context),
+        Expr::And(exprs) => exprs.iter().all(|expr| expr_holds(expr, task, context)),
+        Expr::Or(exprs) => exprs.iter().any(|expr| expr_holds(expr, task, context)),
+    }
+}
+
+fn filter_holds(filter: &Filter, task: &TaskRecord, context: &QueryContext) -> bool {
+    match filter {
+        Filter::Text(text) => task.text.to_lowercase().contains(text.as_str()),
+        Filter::Status(status) => task.status == *status,
+        Filter::Open => !task.status.is_closed(),
+        Filter::Closed => task.status.is_closed(),
+        Filter::Recurring => task.properties.contains_key("repeat"),
+        Filter::Subtask => task.parent_id.is_some(),
+        Filter::Archived => task.archived,
+        Filter::Project(project) => task
+            .project
+            .as_ref()
+            .is_some_and(|p| p.to_lowercase() == *project),
+        Filter::Priority(priority) => task.priority == Some(*priority),
+        Filter::Tag(tag) => task.tags.iter().flatten().any(|t| {
+            let t = t.to_lowercase();
+            t == *tag || t.starts_with(&format!("{}/", tag))
+        }),
+        Filter::Path(prefix) => {
+            let path = context
+                .vault_root
+                .and_then(|root| task.file_path.strip_prefix(root).ok())
+                .unwrap_or(&task.file_path);
+            path.to_string_lossy()
+                .replace('\\', "/")
+                .to_lowercase()
+                .starts_with(prefix.as_str())
+        }
+        Filter::Repeat(rule) => task.properties.get("repeat") == Some(rule),
+        Filter::Has(property) => match property {
+            Property::Due => task.due_date.is_some(),
+            Property::Scheduled => task.scheduled_date.is_some(),
+            Property::Start => task.start_date.is_some(),
+            Property::Priority => task.priority.is_some(),
+            Property::Project => task.project.is_some(),
+            Property::Tags => task.tags.as_ref().is_some_and(|tags| !tags.is_empty()),
+            Property::Blockers => !task.blocked_by.is_empty(),
+        },
+        Filter::Date(field, cmp, date) => task_date(task, *field)
+            .is_some_and(|value| cmp.holds(value, date.resolve(context.today))),
+        Filter::NoDate(field) => task_date(task, *field).is_none(),
+    }
+}
+
+fn task_date(task: &TaskRecord, field: DateField) -> Option<NaiveDate> {
+    match field {
+        DateField::Due => task.due_date,
+        DateField::Scheduled => task.scheduled_date,
+        DateField::Start => task.start_date,
+        DateField::Created => Some(task.created_at.date_naive()),
+        DateField::Updated => Some(task.updated_at.date_naive()),
+        DateField::Completed => task.completed_at.map(|at| at.date_naive()),
+    }
+}
+
+/// Order of two tasks by a sort key. Tasks missing the value sort last in
+/// either direction.
+fn compare(a: &TaskRecord, b: &TaskRecord, key: SortKey) -> Ordering {
+    fn missing_last<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+    let priority_rank = |task: &TaskRecord| {
+        task.priority.map(|priority| match priority {
+            Priority::High => 0,
+            Priority::Medium => 1,
+            Priority::Low => 2,
+        })
+    };
+    let status_rank = |task: &TaskRecord| {
+        TaskStatus::ALL
+            .iter()
+            .position(|status| *status == task.status)
+    };
+
+    let descending = key.descending;
+    let ordering = match key.field {
+        SortField::Due => {
+            return missing_last(
+                a.due_date.map(|date| (date, a.due_time)),
+                b.due_date.map(|date| (date, b.due_time)),
+                descending,
+            )
+        }
+        SortField::Scheduled => {
+            return missing_last(
+                a.scheduled_date.map(|date| (date, a.scheduled_time)),
+                b.scheduled_date.map(|date| (date, b.scheduled_time)),
+                descending,
+            )
+        }
+        SortField::Start => return missing_last(a.start_date, b.start_date, descending),
+        SortField::Priority => return missing_last(priority_rank(a), priority_rank(b), descending),
+        SortField::Project => {
+            return missing_last(
+                a.project.as_ref().map(|p| p.to_lowercase()),
+                b.project.as_ref().map(|p| p.to_lowercase()),
+                descending,
+            )
+        }
+        SortField::Status => status_rank(a).cmp(&status_rank(b)),
+        SortField::Path => a.file_path.cmp(&b.file_path),
+        SortField::Created => a.created_at.cmp(&b.created_at),
+        SortField::Updated => a.updated_at.cmp(&b.updated_at),
+        SortField::Text => a.text.to_lowercase().cmp(&b.text.to_lowercase()),
+    };
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+#[cfg(test)]
+#[path = "query_lang_test.rs"]
+mod tests;
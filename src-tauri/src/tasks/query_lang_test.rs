@@ -0,0 +1,296 @@
+use super::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn task(id: &str, file: &str, text: &str) -> TaskRecord {
+    let now = Utc::now();
+    TaskRecord {
+        id: id.to_string(),
+        file_path: PathBuf::from("/vault").join(file),
+        line_number: 1,
+        status: TaskStatus::Todo,
+        text: text.to_string(),
+        project: None,
+        due_date: None,
+        due_time: None,
+        scheduled_date: None,
+        scheduled_time: None,
+        start_date: None,
+        start_time: None,
+        priority: None,
+        tags: None,
+        created_at: now,
+        updated_at: now,
+        completed_at: None,
+        properties: HashMap::new(),
+        parent_id: None,
+        blocked_by: Vec::new(),
+        estimate_minutes: None,
+        time_log: Vec::new(),
+        archived: false,
+    }
+}
+
+fn ids(tasks: &[TaskRecord]) -> Vec<&str> {
+    tasks.iter().map(|t| t.id.as_str()).collect()
+}
+
+fn filter(f: Filter) -> Expr {
+    Expr::Filter(f)
+}
+
+#[test]
+fn test_parse_example_query() {
+    let search = TaskSearch::parse(
+        "status:todo due<=+7d (tag:work OR project:alpha) path:Projects/ sort:priority,-due limit:50",
+    )
+    .unwrap();
+
+    assert_eq!(
+        search.filter,
+        Some(Expr::And(vec![
+            filter(Filter::Status(TaskStatus::Todo)),
+            filter(Filter::Date(
+                DateField::Due,
+                Comparison::OnOrBefore,
+                QueryDate::Days(7)
+            )),
+            Expr::Or(vec![
+                filter(Filter::Tag("work".into())),
+                filter(Filter::Project("alpha".into())),
+            ]),
+            filter(Filter::Path("projects/".into())),
+        ]))
+    );
+    assert_eq!(
+        search.sort,
+        vec![
+            SortKey {
+                field: SortField::Priority,
+                descending: false
+            },
+            SortKey {
+                field: SortField::Due,
+                descending: true
+            },
+        ]
+    );
+    assert_eq!(search.limit, Some(50));
+}
+
+#[test]
+fn test_parse_negation_phrases_and_text() {
+    let search =
+        TaskSearch::parse(r#"NOT is:closed -tag:someday "call back" project:"Home office" 10:30"#)
+            .unwrap();
+    assert_eq!(
+        search.filter,
+        Some(Expr::And(vec![
+            Expr::Not(Box::new(filter(Filter::Closed))),
+            Expr::Not(Box::new(filter(Filter::Tag("someday".into())))),
+            filter(Filter::Text("call back".into())),
+            filter(Filter::Project("home office".into())),
+            filter(Filter::Text("10:30".into())),
+        ]))
+    );
+
+    let search = TaskSearch::parse("sort:due").unwrap();
+    assert_eq!(search.filter, None);
+}
+
+#[test]
+fn test_parse_errors() {
+    for query in [
+        "status:someday",
+        "due<=soon",
+        "priority>high",
+        "(tag:work",
+        "tag:work)",
+        "()",
+        "tag:work OR",
+        "OR tag:work",
+        "AND tag:work",
+        "NOT",
+        "(sort:due)",
+        "limit:many",
+        "sort:colour",
+        "\"unterminated",
+    ] {
+        assert!(TaskSearch::parse(query).is_err(), "{} should fail", query);
+    }
+
+    // Deep nesting is refused instead of overflowing the stack
+    let nested = format!("{}tag:work{}", "(".repeat(100_000), ")".repeat(100_000));
+    assert!(TaskSearch::parse(&nested).is_err());
+    assert!(TaskSearch::parse(&format!("{}tag:work", "NOT ".repeat(100_000))).is_err());
+    assert!(TaskSearch::parse(&format!("{}tag:work{}", "(".repeat(8), ")".repeat(8))).is_ok());
+}
+
+#[test]
+fn test_evaluate_filters_sort_and_limit() {
+    let today = date("2025-06-10");
+    let context = QueryContext {
+        vault_root: Some(Path::new("/vault")),
+        today,
+    };
+
+    let mut report = task("report", "Projects/alpha.md", "Write report");
+    report.project = Some("Alpha".into());
+    report.due_date = Some(date("2025-06-12"));
+    report.priority = Some(Priority::Low);
+
+    let mut review = task("review", "Projects/beta.md", "Review PR");
+    review.tags = Some(vec!["work/code".into()]);
+    review.due_date = Some(date("2025-06-11"));
+    review.priority = Some(Priority::High);
+
+    let mut later = task("later", "Projects/beta.md", "Plan offsite");
+    later.tags = Some(vec!["work".into()]);
+    later.due_date = Some(date("2025-07-01"));
+    later.line_number = 2;
+
+    let mut personal = task("personal", "Home/list.md", "Buy milk");
+    personal.tags = Some(vec!["work".into()]);
+    personal.due_date = Some(date("2025-06-10"));
+
+    let mut done = task("done", "Projects/alpha.md", "Old work");
+    done.status = TaskStatus::Done;
+    done.project = Some("alpha".into());
+    done.line_number = 3;
+
+    let tasks = vec![
+        report.clone(),
+        review.clone(),
+        later.clone(),
+        personal.clone(),
+        done.clone(),
+    ];
+
+    let search = TaskSearch::parse(
+        "status:todo due<=+7d (tag:work OR project:alpha) path:Projects/ sort:priority,due",
+    )
+    .unwrap();
+    assert_eq!(
+        ids(&search.apply(tasks.clone(), &context)),
+        vec!["review", "report"]
+    );
+
+    let search = TaskSearch::parse("due:none").unwrap();
+    assert_eq!(ids(&search.apply(tasks.clone(), &context)), vec!["done"]);
+
+    let search = TaskSearch::parse("due:today OR due>2025-06-30").unwrap();
+    assert_eq!(
+        ids(&search.apply(tasks.clone(), &context)),
+        vec!["personal", "later"]
+    );
+
+    // Without a sort, results are in note and line order
+    let search = TaskSearch::parse("is:open -\"buy milk\" limit:2").unwrap();
+    assert_eq!(
+        ids(&search.apply(tasks.clone(), &context)),
+        vec!["report", "review"]
+    );
+
+    // Tasks without the sorted value stay last in either direction
+    let search = TaskSearch::parse("sort:-due").unwrap();
+    assert_eq!(
+        ids(&search.apply(tasks, &context)),
+        vec!["later", "report", "review", "personal", "done"]
+    );
+}
+
+#[test]
+fn test_archived_filter() {
+    let context = QueryContext {
+        vault_root: None,
+        today: date("2025-06-10"),
+    };
+    let mut archived = task("archived", "Archive/Tasks 2025-05.md", "Old chore");
+    archived.status = TaskStatus::Done;
+    archived.archived = true;
+    let tasks = vec![task("open", "a.md", "New chore"), archived];
+
+    let search = TaskSearch::parse("is:archived").unwrap();
+    assert!(search.includes_archived());
+    assert_eq!(
+        ids(&search.apply(tasks.clone(), &context)),
+        vec!["archived"]
+    );
+
+    let search = TaskSearch::parse("chore -is:archived").unwrap();
+    assert!(search.includes_archived());
+    assert_eq!(ids(&search.apply(tasks, &context)), vec!["open"]);
+
+    assert!(!TaskSearch::parse("is:closed").unwrap().includes_archived());
+}
+
+#[test]
+fn test_relative_dates() {
+    let today = date("2025-01-31");
+    assert_eq!(
+        parse_date("+1m").unwrap().resolve(today),
+        date("2025-02-28")
+    );
+    assert_eq!(
+        parse_date("-2w").unwrap().resolve(today),
+        date("2025-01-17")
+    );
+    assert_eq!(
+        parse_date("tomorrow").unwrap().resolve(today),
+        date("2025-02-01")
+    );
+    assert!(parse_date("+1y").is_err());
+
+    // Multibyte units and amounts past the calendar are errors, not panics
+    assert!(parse_date("+3é").is_err());
+    assert!(parse_date("+é").is_err());
+    for value in [
+        "+100000000d",
+        "-100000000d",
+        "+9223372036854775807w",
+        "+3000000000m",
+    ] {
+        assert!(parse_date(value).is_err(), "{}", value);
+    }
+    assert!(TaskSearch::parse("due<=+100000000d").is_err());
+    assert_eq!(
+        parse_date("+520w").unwrap().resolve(today),
+        date("2035-01-19")
+    );
+    assert_eq!(QueryDate::Days(i64::MAX).resolve(today), NaiveDate::MAX);
+}
+
+#[test]
+fn test_extract_task_query_blocks() {
+    let content = "# Plan\n\
+                   ```tasks\n\
+                   status:todo\n\
+                   sort:due\n\
+                   ```\n\
+                   ````markdown\n\
+                   ```tasks\n\
+                   not a query\n\
+                   ```\n\
+                   ````\n\
+                   ~~~ tasks\n\
+                   tag:work";
+
+    assert_eq!(
+        extract_task_query_blocks(content),
+        vec![
+            TaskQueryBlock {
+                line: 2,
+                query: "status:todo\nsort:due".into(),
+            },
+            TaskQueryBlock {
+                line: 11,
+                query: "tag:work".into(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,147 @@
+//! Persisted task index snapshots
+//!
+//! A vault's task index is saved under the app data directory together with
+//! a manifest of the mtime, size and content hash of every markdown file at
+//! the time it was parsed. When the vault is opened again only files whose
+//! fingerprint changed are re-parsed, and tasks from files that disappeared
+//! are dropped, instead of rescanning the whole vault.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::index::TaskIndex;
+use crate::vault_id::generate_vault_id_hash;
+
+/// Bump when the snapshot layout or the way tasks are parsed changes, so
+/// older snapshots are rebuilt instead of restored
+const SNAPSHOT_FORMAT: u32 = 7;
+
+/// Where the task index snapshot for a vault is kept
+pub fn snapshot_path(app_data_dir: &Path, vault_root: &Path) -> PathBuf {
+    app_data_dir
+        .join("task_index")
+        .join(format!("{}.bin", generate_vault_id_hash(vault_root)))
+}
+
+/// State of a file when its tasks were last parsed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFingerprint {
+    /// Modification time in milliseconds since the epoch
+    pub mtime: u64,
+    pub size: u64,
+    /// SHA-256 of the content, used when only the mtime changed
+    pub hash: String,
+}
+
+impl FileFingerprint {
+    pub fn new(metadata: &Metadata, content: &str) -> Self {
+        Self {
+            mtime: modified_millis(metadata),
+            size: metadata.len(),
+            hash: hash_content(content),
+        }
+    }
+
+    /// Whether the file looks untouched without reading it
+    pub fn matches_metadata(&self, metadata: &Metadata) -> bool {
+        self.mtime == modified_millis(metadata) && self.size == metadata.len()
+    }
+}
+
+/// Counts from bringing a restored index up to date with the vault
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WarmStartStats {
+    /// Whether a usable snapshot was found
+    pub restored: bool,
+    pub unchanged: usize,
+    pub reparsed: usize,
+    pub removed: usize,
+}
+
+/// A serialized task index plus the file manifest it was built from
+#[derive(Serialize, Deserialize)]
+pub struct TaskIndexSnapshot {
+    format: u32,
+    pub vault_root: PathBuf,
+    /// Fingerprints keyed by the same paths the index stores for each task
+    pub files: HashMap<PathBuf, FileFingerprint>,
+    index: Vec<u8>,
+}
+
+impl TaskIndexSnapshot {
+    /// Snapshot of `index` built from the files in `files`
+    pub async fn capture(
+        vault_root: &Path,
+        files: HashMap<PathBuf, FileFingerprint>,
+        index: &TaskIndex,
+    ) -> Result<Self> {
+        Ok(Self {
+            format: SNAPSHOT_FORMAT,
+            vault_root: vault_root.to_path_buf(),
+            files,
+            index: index.serialize().await?,
+        })
+    }
+
+    /// Loads the snapshot at `path`. Returns `None` when there is none yet,
+    /// and an error when it is unreadable or belongs to another vault.
+    pub fn load(path: &Path, vault_root: &Path) -> Result<Option<Self>> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let snapshot: Self = bincode::deserialize(&data)?;
+        if snapshot.format != SNAPSHOT_FORMAT {
+            return Err(anyhow!(
+                "Snapshot format {} is not supported",
+                snapshot.format
+            ));
+        }
+        if snapshot.vault_root != vault_root {
+            return Err(anyhow!(
+                "Snapshot belongs to {}",
+                snapshot.vault_root.display()
+            ));
+        }
+        Ok(Some(snapshot))
+    }
+
+    /// Writes the snapshot through a temporary file so a crash never leaves
+    /// a truncated one behind
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("bin.tmp");
+        std::fs::write(&temp_path, bincode::serialize(self)?)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Replaces the contents of `index` with the snapshot's tasks
+    pub async fn restore(&self, index: &TaskIndex) -> Result<()> {
+        index.deserialize(&self.index).await
+    }
+}
+
+fn modified_millis(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn hash_content(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
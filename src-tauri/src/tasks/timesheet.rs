@@ -0,0 +1,62 @@
+//! Per-project summaries of logged time, as CSV for billing
+
+use anyhow::{anyhow, Result};
+
+use super::index::{TimeStats, TimeTotals};
+
+/// Hours with two decimals, the way invoices show them
+fn hours(minutes: u64) -> String {
+    format!("{:.2}", minutes as f64 / 60.0)
+}
+
+/// One row per project, then time on tasks without a project and the
+/// total. Each task counts towards one project, so the rows add up to the
+/// total.
+pub fn time_summary_csv(stats: &TimeStats) -> Result<String> {
+    let date = |date: Option<chrono::NaiveDate>| {
+        date.map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+    let (from, to) = (date(stats.from), date(stats.to));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "Project",
+        "From",
+        "To",
+        "Tasks",
+        "Logged (h)",
+        "Estimated (h)",
+    ])?;
+    let mut write_row = |project: &str, totals: &TimeTotals| {
+        writer.write_record([
+            project,
+            &from,
+            &to,
+            &totals.tasks.to_string(),
+            &hours(totals.logged_minutes),
+            &hours(totals.estimated_minutes),
+        ])
+    };
+
+    let mut unassigned = stats.total.clone();
+    for (project, totals) in &stats.by_project {
+        write_row(project, totals)?;
+        unassigned.logged_minutes -= totals.logged_minutes;
+        unassigned.estimated_minutes -= totals.estimated_minutes;
+        unassigned.tasks -= totals.tasks;
+    }
+    if unassigned.tasks > 0 {
+        write_row("", &unassigned)?;
+    }
+    write_row("Total", &stats.total)?;
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| anyhow!("Failed to write CSV: {}", e))?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+#[path = "timesheet_test.rs"]
+mod tests;
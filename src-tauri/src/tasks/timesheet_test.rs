@@ -0,0 +1,44 @@
+use super::*;
+use chrono::NaiveDate;
+
+fn totals(logged_minutes: u64, estimated_minutes: u64, tasks: usize) -> TimeTotals {
+    TimeTotals {
+        logged_minutes,
+        estimated_minutes,
+        tasks,
+    }
+}
+
+#[test]
+fn test_time_summary_csv() {
+    let mut stats = TimeStats {
+        from: NaiveDate::from_ymd_opt(2025, 6, 1),
+        to: NaiveDate::from_ymd_opt(2025, 6, 30),
+        total: totals(200, 240, 4),
+        ..TimeStats::default()
+    };
+    stats
+        .by_project
+        .insert("Acme, Inc".into(), totals(135, 120, 2));
+    stats.by_project.insert("Globex".into(), totals(45, 120, 1));
+
+    let csv = time_summary_csv(&stats).unwrap();
+    assert_eq!(
+        csv.lines().collect::<Vec<_>>(),
+        vec![
+            "Project,From,To,Tasks,Logged (h),Estimated (h)",
+            "\"Acme, Inc\",2025-06-01,2025-06-30,2,2.25,2.00",
+            "Globex,2025-06-01,2025-06-30,1,0.75,2.00",
+            ",2025-06-01,2025-06-30,1,0.33,0.00",
+            "Total,2025-06-01,2025-06-30,4,3.33,4.00",
+        ]
+    );
+
+    // Without a range or unassigned time there are only the project rows
+    stats.from = None;
+    stats.to = None;
+    stats.total = totals(180, 240, 3);
+    let csv = time_summary_csv(&stats).unwrap();
+    assert_eq!(csv.lines().count(), 4);
+    assert!(csv.ends_with("Total,,,3,3.00,4.00\n"));
+}
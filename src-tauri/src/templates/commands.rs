@@ -0,0 +1,84 @@
+//! Tauri commands for note templates
+//!
+//! The templates folder comes from the vault settings. Notes are made from
+//! templates by `create_new_file` and `create_note_from_wikilink`; these
+//! commands let the frontend offer the templates and ask for their prompts.
+
+use chrono::{Local, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, State, Window};
+
+use super::engine::{RenderedNote, TemplateContext, TemplateEngine, TemplateInfo, TemplatePrompt};
+use crate::graph::commands::window_vault_path;
+use crate::refactored_app_state::RefactoredAppState;
+use crate::vault_settings::get_vault_settings;
+
+/// The template engine of a vault, over the templates folder in its settings
+pub async fn vault_template_engine(app: AppHandle, vault_path: &Path) -> TemplateEngine {
+    let folder = match get_vault_settings(app, vault_path.to_string_lossy().to_string()).await {
+        Ok(settings) => settings.files.templates_folder,
+        Err(e) => {
+            eprintln!(
+                "⚠️ Failed to load vault settings, using default templates folder: {}",
+                e
+            );
+            "Templates".to_string()
+        }
+    };
+    TemplateEngine::new(vault_path, &folder)
+}
+
+/// Render a new note titled `title` from a template in the templates folder
+pub async fn render_note_template(
+    app: AppHandle,
+    vault_path: &Path,
+    template: &str,
+    title: &str,
+    variables: HashMap<String, String>,
+    id: &str,
+) -> Result<RenderedNote, String> {
+    let engine = vault_template_engine(app, vault_path).await;
+    let source = engine
+        .load(template)
+        .map_err(|e| format!("Failed to load template: {}", e))?;
+    let now = Local::now();
+    let context = TemplateContext {
+        title: title.to_string(),
+        date: now.date_naive(),
+        time: now.time(),
+        variables,
+    };
+    engine
+        .render(&source, &context, id, now.with_timezone(&Utc))
+        .map_err(|e| format!("Failed to render template {}: {}", template, e))
+}
+
+/// Templates in the vault's templates folder
+#[tauri::command]
+pub async fn list_templates(
+    app: AppHandle,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<Vec<TemplateInfo>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    Ok(vault_template_engine(app, &vault_path).await.list())
+}
+
+/// Values to ask the user for before making a note from a template
+#[tauri::command]
+pub async fn get_template_prompts(
+    template: String,
+    app: AppHandle,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+) -> Result<Vec<TemplatePrompt>, String> {
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+    let engine = vault_template_engine(app, &vault_path).await;
+    let source = engine
+        .load(&template)
+        .map_err(|e| format!("Failed to load template: {}", e))?;
+    engine
+        .prompts(&source)
+        .map_err(|e| format!("Failed to read template prompts: {}", e))
+}
@@ -0,0 +1,312 @@
+//! Rendering templates into new notes
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::identity::frontmatter::{FrontMatter, FrontMatterParser, FrontMatterWriter};
+use crate::periodic::format_date;
+
+lazy_static! {
+    static ref TAG_PATTERN: Regex = Regex::new(r"\{\{\s*(\w+)\s*(?::([^}]*))?\}\}").unwrap();
+    static ref TASK_ID_PATTERN: Regex = Regex::new(r"\s*<!-- tid:\s*[a-zA-Z0-9-]+\s*-->").unwrap();
+    static ref PLACEHOLDER_PATTERN: Regex = Regex::new(r"__template_tag_(\d+)__").unwrap();
+}
+
+/// How deep includes may nest before a template is taken to include itself
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Marks the cursor while a note is put together
+const CURSOR_MARKER: &str = "\u{0}cursor\u{0}";
+
+/// A template in the templates folder
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateInfo {
+    /// Path inside the templates folder, without `.md`
+    pub name: String,
+    /// Vault-relative path
+    pub path: String,
+}
+
+/// A value the user is asked for, from `{{prompt:Name}}` or
+/// `{{prompt:Name|default}}`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePrompt {
+    pub name: String,
+    pub default: Option<String>,
+}
+
+/// What a template is filled in with
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    pub title: String,
+    /// `{{date}}`, today for most notes and the first day of the period for
+    /// periodic notes
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+    /// Answers to prompts, and values for any other `{{name}}`
+    pub variables: HashMap<String, String>,
+}
+
+/// A new note made from a template
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedNote {
+    pub content: String,
+    /// Where `{{cursor}}` was, in characters from the start of `content`
+    pub cursor: Option<usize>,
+}
+
+/// Templates of a vault, kept as notes in its templates folder
+pub struct TemplateEngine {
+    vault_root: PathBuf,
+    /// Vault-relative
+    folder: String,
+}
+
+impl TemplateEngine {
+    pub fn new(vault_root: &Path, folder: &str) -> Self {
+        Self {
+            vault_root: vault_root.to_path_buf(),
+            folder: folder.trim().trim_matches('/').replace('\\', "/"),
+        }
+    }
+
+    /// Templates in the folder, by name
+    pub fn list(&self) -> Vec<TemplateInfo> {
+        let folder = self.vault_root.join(&self.folder);
+        let mut templates: Vec<TemplateInfo> = WalkDir::new(&folder)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.file_type().is_file()
+                    && entry.path().extension().and_then(|ext| ext.to_str()) == Some("md")
+            })
+            .filter_map(|entry| {
+                let name = entry.path().strip_prefix(&folder).ok()?.with_extension("");
+                let path = entry.path().strip_prefix(&self.vault_root).ok()?;
+                Some(TemplateInfo {
+                    name: name.to_string_lossy().replace('\\', "/"),
+                    path: path.to_string_lossy().replace('\\', "/"),
+                })
+            })
+            .collect();
+        templates.sort_by_key(|template| template.name.to_lowercase());
+        templates
+    }
+
+    /// Read a template by its name in the templates folder
+    pub fn load(&self, name: &str) -> Result<String> {
+        let name = name.trim().trim_start_matches('/').replace('\\', "/");
+        let inside_folder = !name.is_empty()
+            && Path::new(&name)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !inside_folder {
+            bail!(
+                "Template names are paths inside the templates folder: {}",
+                name
+            );
+        }
+        let file = if name.ends_with(".md") {
+            name.clone()
+        } else {
+            format!("{}.md", name)
+        };
+        std::fs::read_to_string(self.vault_root.join(&self.folder).join(file))
+            .map_err(|e| anyhow!("Failed to read template {}: {}", name, e))
+    }
+
+    /// The prompts of a template and the templates it includes, each once
+    pub fn prompts(&self, source: &str) -> Result<Vec<TemplatePrompt>> {
+        let mut prompts = Vec::new();
+        self.collect_prompts(source, 0, &mut prompts)?;
+        Ok(prompts)
+    }
+
+    fn collect_prompts(
+        &self,
+        source: &str,
+        depth: usize,
+        prompts: &mut Vec<TemplatePrompt>,
+    ) -> Result<()> {
+        for caps in TAG_PATTERN.captures_iter(source) {
+            let argument = caps.get(2).map_or("", |m| m.as_str());
+            match &caps[1] {
+                "prompt" => {
+                    let prompt = parse_prompt(argument);
+                    if !prompts.iter().any(|p| p.name == prompt.name) {
+                        prompts.push(prompt);
+                    }
+                }
+                "include" => {
+                    if depth >= MAX_INCLUDE_DEPTH {
+                        bail!("Templates include each other in a loop");
+                    }
+                    self.collect_prompts(&self.load(argument)?, depth + 1, prompts)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Render a template into a new note with the given ID. The front
+    /// matter of included templates is merged under the template's own, and
+    /// task IDs are dropped so the note's tasks get IDs of their own.
+    pub fn render(
+        &self,
+        source: &str,
+        context: &TemplateContext,
+        id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<RenderedNote> {
+        let (fm, body) = self.expand(source, context, 0)?;
+        let mut fm = fm.unwrap_or_else(FrontMatter::new);
+        fm.id = Some(id.to_string());
+        fm.created_at = Some(now);
+        fm.updated_at = Some(now);
+        fm.legacy_ids = None;
+        fm.extra_fields.remove("tasks");
+
+        let body = TASK_ID_PATTERN.replace_all(&body, "");
+        let content = FrontMatterWriter::write(&fm, &body)?;
+        let cursor = content
+            .find(CURSOR_MARKER)
+            .map(|at| content[..at].chars().count());
+        Ok(RenderedNote {
+            content: content.replace(CURSOR_MARKER, ""),
+            cursor,
+        })
+    }
+
+    /// Fill in a template's tags, returning its merged front matter and body
+    fn expand(
+        &self,
+        source: &str,
+        context: &TemplateContext,
+        depth: usize,
+    ) -> Result<(Option<FrontMatter>, String)> {
+        // Tags stand in as placeholders while the front matter is read, and
+        // values go into its parsed strings, so a title like `Q3: plan` or
+        // an answer like `yes` stays text
+        let mut values = Vec::new();
+        let held = TAG_PATTERN.replace_all(source, |caps: &Captures| {
+            values.push(fill_tag(caps, context).unwrap_or_else(|| caps[0].to_string()));
+            format!("__template_tag_{}__", values.len() - 1)
+        });
+        let (mut fm, body) = FrontMatterParser::parse(&held)?;
+        let fill = |text: &str| {
+            PLACEHOLDER_PATTERN
+                .replace_all(text, |caps: &Captures| {
+                    caps[1]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| values.get(index))
+                        .cloned()
+                        .unwrap_or_else(|| caps[0].to_string())
+                })
+                .into_owned()
+        };
+        if let Some(fm) = fm.as_mut() {
+            for value in fm.extra_fields.values_mut() {
+                fill_value(value, &fill);
+            }
+        }
+        let body = fill(&body);
+
+        let mut error = None;
+        let body = TAG_PATTERN.replace_all(&body, |caps: &Captures| {
+            if &caps[1] != "include" || error.is_some() {
+                return caps[0].to_string();
+            }
+            if depth >= MAX_INCLUDE_DEPTH {
+                error = Some(anyhow!("Templates include each other in a loop"));
+                return String::new();
+            }
+            let included = caps
+                .get(2)
+                .map_or(Ok(String::new()), |name| self.load(name.as_str()))
+                .and_then(|source| self.expand(&source, context, depth + 1));
+            match included {
+                Ok((included_fm, included_body)) => {
+                    fm = merge_front_matter(included_fm, fm.take());
+                    included_body.trim_end_matches('\n').to_string()
+                }
+                Err(e) => {
+                    error = Some(e);
+                    String::new()
+                }
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        Ok((fm, body.into_owned()))
+    }
+}
+
+fn parse_prompt(argument: &str) -> TemplatePrompt {
+    let (name, default) = match argument.split_once('|') {
+        Some((name, default)) => (name, Some(default.trim().to_string())),
+        None => (argument, None),
+    };
+    TemplatePrompt {
+        name: name.trim().to_string(),
+        default,
+    }
+}
+
+/// The text for a tag, `None` for tags left as they are: includes and the
+/// cursor are handled later, and unknown names stay for other tools
+fn fill_tag(caps: &Captures, context: &TemplateContext) -> Option<String> {
+    let argument = caps.get(2).map(|m| m.as_str().trim());
+    Some(match &caps[1] {
+        "title" => context.title.clone(),
+        "date" => format_date(context.date, argument.unwrap_or("YYYY-MM-DD")),
+        "time" => context.time.format("%H:%M").to_string(),
+        "cursor" => CURSOR_MARKER.to_string(),
+        "include" => return None,
+        "prompt" => {
+            let prompt = parse_prompt(argument.unwrap_or(""));
+            context
+                .variables
+                .get(&prompt.name)
+                .cloned()
+                .or(prompt.default)
+                .unwrap_or_default()
+        }
+        name => context.variables.get(name)?.clone(),
+    })
+}
+
+/// Fill the placeholders in the strings of a front matter value
+fn fill_value(value: &mut Value, fill: &impl Fn(&str) -> String) {
+    match value {
+        Value::String(text) => *text = fill(text),
+        Value::Array(items) => items.iter_mut().for_each(|item| fill_value(item, fill)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| fill_value(field, fill)),
+        _ => {}
+    }
+}
+
+/// Front matter of an included template with the including one's on top
+fn merge_front_matter(base: Option<FrontMatter>, over: Option<FrontMatter>) -> Option<FrontMatter> {
+    match (base, over) {
+        (Some(mut base), Some(over)) => {
+            base.extra_fields.extend(over.extra_fields);
+            base.other.extend(over.other);
+            Some(base)
+        }
+        (base, over) => over.or(base),
+    }
+}
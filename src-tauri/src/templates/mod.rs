@@ -0,0 +1,18 @@
+//! Note templates
+//!
+//! Templates are notes in the vault's templates folder. `{{title}}`,
+//! `{{date}}`, `{{date:YYYY-MM-DD}}` (see [`crate::periodic::format`]) and
+//! `{{time}}` are filled in, `{{cursor}}` marks where the editor puts the
+//! cursor, `{{include:Name}}` pulls in another template and
+//! `{{prompt:Name|default}}` asks the user for a value first. A template's
+//! front matter is merged into the new note's, which always gets a fresh
+//! UUID.
+
+pub mod commands;
+pub mod engine;
+
+pub use commands::*;
+pub use engine::{RenderedNote, TemplateContext, TemplateEngine, TemplateInfo, TemplatePrompt};
+
+#[cfg(test)]
+mod tests;
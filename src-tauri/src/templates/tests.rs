@@ -0,0 +1,223 @@
+use super::*;
+use crate::identity::frontmatter::FrontMatterParser;
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn context(title: &str, variables: &[(&str, &str)]) -> TemplateContext {
+    TemplateContext {
+        title: title.to_string(),
+        date: NaiveDate::from_ymd_opt(2025, 5, 15).unwrap(),
+        time: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+        variables: variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+    }
+}
+
+fn render(engine: &TemplateEngine, name: &str, context: &TemplateContext) -> RenderedNote {
+    let source = engine.load(name).unwrap();
+    engine
+        .render(
+            &source,
+            context,
+            "note-id",
+            Utc.with_ymd_and_hms(2025, 5, 15, 7, 30, 0).unwrap(),
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_render_variables_and_cursor() {
+    let vault = TempDir::new().unwrap();
+    write(
+        vault.path(),
+        "Templates/Meeting.md",
+        "# {{title}}\n\n{{date:dddd D MMMM}} at {{time}}, with {{prompt:Who}} about {{ prompt: Topic | planning }}\n\n{{cursor}}\n\n{{unknown}} stays\n",
+    );
+    let engine = TemplateEngine::new(vault.path(), "Templates/");
+
+    let note = render(&engine, "Meeting", &context("Kickoff", &[("Who", "Sam")]));
+    let (fm, body) = FrontMatterParser::parse(&note.content).unwrap();
+    let fm = fm.unwrap();
+    assert_eq!(fm.id.as_deref(), Some("note-id"));
+    assert_eq!(
+        fm.created_at,
+        Some(Utc.with_ymd_and_hms(2025, 5, 15, 7, 30, 0).unwrap())
+    );
+    assert_eq!(
+        body,
+        "# Kickoff\n\nThursday 15 May at 09:30, with Sam about planning\n\n\n\n{{unknown}} stays\n"
+    );
+
+    let cursor = note.cursor.unwrap();
+    let before: String = note.content.chars().take(cursor).collect();
+    assert!(before.ends_with("about planning\n\n"));
+}
+
+#[test]
+fn test_front_matter_is_merged_with_a_fresh_id() {
+    let vault = TempDir::new().unwrap();
+    write(
+        vault.path(),
+        "Templates/Header.md",
+        "---\nid: header-id\ntype: note\nstatus: draft\n---\n> Made on {{date}}\n",
+    );
+    write(
+        vault.path(),
+        "Templates/Project.md",
+        "---\nid: template-id\nstatus: active\ndue: \"{{prompt:Due}}\"\ntasks:\n  abc-123:\n    text: Plan\n---\n{{include:Header}}\n\n# {{title}}\n\n- [ ] Plan <!-- tid: abc-123 -->\n",
+    );
+    let engine = TemplateEngine::new(vault.path(), "Templates");
+
+    let note = render(
+        &engine,
+        "Project",
+        &context("Garden", &[("Due", "2025-06-01")]),
+    );
+    let (fm, body) = FrontMatterParser::parse(&note.content).unwrap();
+    let fm = fm.unwrap();
+    assert_eq!(fm.id.as_deref(), Some("note-id"));
+    assert_eq!(fm.extra_fields["type"], "note");
+    assert_eq!(fm.extra_fields["status"], "active");
+    assert_eq!(fm.extra_fields["due"], "2025-06-01");
+    assert!(!fm.extra_fields.contains_key("tasks"));
+    assert_eq!(body, "> Made on 2025-05-15\n\n# Garden\n\n- [ ] Plan\n");
+    assert_eq!(note.cursor, None);
+}
+
+#[test]
+fn test_values_in_front_matter_stay_text() {
+    let vault = TempDir::new().unwrap();
+    write(
+        vault.path(),
+        "Templates/Review.md",
+        "---\ntitle: {{title}}\napproved: {{prompt:Approved}}\ntags: [review, \"{{prompt:Tag}}\"]\nnote: \"{{prompt:Note}} on {{date}}\"\n---\n# {{title}}\n",
+    );
+    let engine = TemplateEngine::new(vault.path(), "Templates");
+
+    let note = render(
+        &engine,
+        "Review",
+        &context(
+            "Q3: plan",
+            &[("Approved", "yes"), ("Tag", "#x"), ("Note", "[a")],
+        ),
+    );
+    let (fm, body) = FrontMatterParser::parse(&note.content).unwrap();
+    let fm = fm.unwrap();
+    assert_eq!(fm.extra_fields["title"], "Q3: plan");
+    assert_eq!(fm.extra_fields["approved"], "yes");
+    assert_eq!(fm.extra_fields["tags"], serde_json::json!(["review", "#x"]));
+    assert_eq!(fm.extra_fields["note"], "[a on 2025-05-15");
+    assert_eq!(body, "# Q3: plan\n");
+}
+
+#[test]
+fn test_prompts_include_included_templates() {
+    let vault = TempDir::new().unwrap();
+    write(
+        vault.path(),
+        "Templates/Parts/Owner.md",
+        "Owner: {{prompt:Owner|me}}, {{prompt:Project}}\n",
+    );
+    write(
+        vault.path(),
+        "Templates/Project.md",
+        "# {{prompt:Project}}\n{{include:Parts/Owner}}\n",
+    );
+    let engine = TemplateEngine::new(vault.path(), "Templates");
+
+    let prompts = engine.prompts(&engine.load("Project.md").unwrap()).unwrap();
+    assert_eq!(
+        prompts,
+        vec![
+            TemplatePrompt {
+                name: "Project".into(),
+                default: None
+            },
+            TemplatePrompt {
+                name: "Owner".into(),
+                default: Some("me".into())
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_include_loops_and_paths_outside_the_folder_fail() {
+    let vault = TempDir::new().unwrap();
+    write(vault.path(), "Templates/A.md", "a {{include:B}}");
+    write(vault.path(), "Templates/B.md", "b {{include:A}}");
+    write(vault.path(), "Secret.md", "secret");
+    let engine = TemplateEngine::new(vault.path(), "Templates");
+
+    let source = engine.load("A").unwrap();
+    let now = Utc::now();
+    assert!(engine
+        .render(&source, &context("Loop", &[]), "id", now)
+        .is_err());
+    assert!(engine.prompts(&source).is_err());
+
+    assert!(engine.load("../Secret").is_err());
+    assert!(engine.load("").is_err());
+    assert!(engine
+        .render("{{include:../Secret}}", &context("Out", &[]), "id", now)
+        .is_err());
+}
+
+#[test]
+fn test_list_templates() {
+    let vault = TempDir::new().unwrap();
+    write(vault.path(), "Templates/meeting.md", "");
+    write(vault.path(), "Templates/Daily/Morning.md", "");
+    write(vault.path(), "Templates/image.png", "");
+    write(vault.path(), "Notes/Other.md", "");
+    let engine = TemplateEngine::new(vault.path(), "Templates");
+
+    let templates = engine.list();
+    assert_eq!(
+        templates,
+        vec![
+            TemplateInfo {
+                name: "Daily/Morning".into(),
+                path: "Templates/Daily/Morning.md".into()
+            },
+            TemplateInfo {
+                name: "meeting".into(),
+                path: "Templates/meeting.md".into()
+            },
+        ]
+    );
+
+    assert!(TemplateEngine::new(vault.path(), "Missing")
+        .list()
+        .is_empty());
+}
+
+#[test]
+fn test_render_source_without_front_matter() {
+    let engine = TemplateEngine::new(Path::new("/vault"), "Templates");
+    let mut context = context("2025-W20", &[]);
+    context.date = NaiveDate::from_ymd_opt(2025, 5, 12).unwrap();
+
+    let note = engine
+        .render(
+            "# {{title}}\n\nWeek of {{date:MMMM D}}\n",
+            &context,
+            "note-id",
+            Utc::now(),
+        )
+        .unwrap();
+    let (fm, body) = FrontMatterParser::parse(&note.content).unwrap();
+    assert_eq!(fm.unwrap().id.as_deref(), Some("note-id"));
+    assert_eq!(body, "# 2025-W20\n\nWeek of May 12\n");
+}
@@ -3,12 +3,14 @@
 // SECURITY: All path operations are validated in Rust before any file I/O.
 // The frontend should NOT perform path validation - rely on this module.
 
+use crate::graph::commands::window_vault_path;
+use crate::graph::{self, LinkGraphState, UnlinkedMention};
 use crate::refactored_app_state::{extract_window_id, RefactoredAppState};
-use regex::Regex;
+use crate::semantic;
+use crate::tags::TagIndexState;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use tauri::{State, Window};
+use tauri::{AppHandle, State, Window};
 
 /// Maximum number of tool calls allowed per turn in agent loop
 /// Note: This constant is enforced in the frontend (ClaudeAgentSDK.js)
@@ -41,6 +43,15 @@ pub struct TagInfo {
 pub struct NoteInfo {
     pub path: String,
     pub title: String,
+    /// Heading of the matching section (semantic search only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    /// Text of the matching section (semantic search only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Similarity to the query, higher is better (semantic search only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
 }
 
 /// Path validation errors
@@ -521,184 +532,214 @@ pub async fn agent_append_to_note(
     }
 }
 
-/// List all tags in the vault
+/// List all tags in the vault with the number of notes using each one.
+/// Nested tags also count towards their parents (`area` includes `area/sub`).
 #[tauri::command]
 pub async fn agent_list_tags(
     limit: Option<usize>,
     window: Window,
     refactored_state: State<'_, RefactoredAppState>,
+    tag_state: State<'_, TagIndexState>,
 ) -> Result<Vec<TagInfo>, String> {
     println!("🏷️ agent_list_tags called");
 
-    let window_id = extract_window_id(&window);
     let limit = limit.unwrap_or(50);
-
-    match refactored_state.get_window_state(&window_id).await {
-        Some(window_state) => {
-            let vault_lock = window_state.vault.lock().await;
-            match &*vault_lock {
-                Some(vault) => {
-                    let files = vault
-                        .list_markdown_files()
-                        .map_err(|e| format!("Failed to list files: {}", e))?;
-
-                    let mut tag_counts: HashMap<String, usize> = HashMap::new();
-
-                    // Regex for finding tags: #tag-name (but not ## headers or # at end of URL)
-                    let tag_regex =
-                        Regex::new(r"(?:^|\s)#([a-zA-Z][a-zA-Z0-9_-]*)(?:\s|$|[.,!?;:])")
-                            .map_err(|e| format!("Regex error: {}", e))?;
-
-                    for file_path in files {
-                        if file_path.is_dir() {
-                            continue;
-                        }
-
-                        // Only process .md files
-                        if file_path.extension().and_then(|e| e.to_str()) != Some("md") {
-                            continue;
-                        }
-
-                        // Read file content
-                        if let Ok(content) = std::fs::read_to_string(&file_path) {
-                            for cap in tag_regex.captures_iter(&content) {
-                                if let Some(tag) = cap.get(1) {
-                                    let tag_name = tag.as_str().to_string();
-                                    *tag_counts.entry(tag_name).or_insert(0) += 1;
-                                }
-                            }
-                        }
-                    }
-
-                    // Convert to sorted list
-                    let mut tags: Vec<TagInfo> = tag_counts
-                        .into_iter()
-                        .map(|(name, count)| TagInfo { name, count })
-                        .collect();
-
-                    // Sort by count descending, then by name
-                    tags.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
-
-                    // Apply limit
-                    tags.truncate(limit);
-
-                    println!("✅ agent_list_tags found {} tags", tags.len());
-                    Ok(tags)
-                }
-                None => Err("No vault opened".to_string()),
-            }
-        }
-        None => Err("Window not found".to_string()),
-    }
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+
+    let tags: Vec<TagInfo> = tag_state
+        .with_index(&vault_path, |index| index.counts())
+        .await?
+        .into_iter()
+        .take(limit)
+        .map(|tag| TagInfo {
+            name: tag.name,
+            count: tag.count,
+        })
+        .collect();
+
+    println!("✅ agent_list_tags found {} tags", tags.len());
+    Ok(tags)
 }
 
-/// Find notes by tag
+/// Find notes by tag. `area/*` also finds notes with tags nested under `area`.
 #[tauri::command]
 pub async fn agent_notes_by_tag(
     tag: String,
     limit: Option<usize>,
     window: Window,
     refactored_state: State<'_, RefactoredAppState>,
+    tag_state: State<'_, TagIndexState>,
 ) -> Result<Vec<NoteInfo>, String> {
     println!("🏷️ agent_notes_by_tag called for tag: {}", tag);
 
-    let window_id = extract_window_id(&window);
     let limit = limit.unwrap_or(20);
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+
+    let results: Vec<NoteInfo> = tag_state
+        .with_index(&vault_path, |index| index.search(&tag))
+        .await?
+        .into_iter()
+        .take(limit)
+        .map(|path| NoteInfo {
+            // Title from the file name
+            title: Path::new(&path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Untitled")
+                .to_string(),
+            path,
+            heading: None,
+            snippet: None,
+            score: None,
+        })
+        .collect();
+
+    println!("✅ agent_notes_by_tag found {} notes", results.len());
+    Ok(results)
+}
 
-    // Normalize tag (remove leading # if present)
-    let tag_normalized = if tag.starts_with('#') {
-        &tag[1..]
-    } else {
-        &tag
-    };
+/// Semantic search over the vault using local embeddings (Ollama or LM Studio).
+/// The index is kept up to date in the background; the first search in a
+/// vault starts building it.
+#[tauri::command]
+pub async fn agent_semantic_search(
+    query: String,
+    limit: Option<usize>,
+    app: AppHandle,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    semantic_state: State<'_, semantic::SemanticIndexState>,
+) -> Result<Vec<NoteInfo>, String> {
+    println!("🧠 agent_semantic_search called for query: {}", query);
 
-    match refactored_state.get_window_state(&window_id).await {
-        Some(window_state) => {
-            let vault_lock = window_state.vault.lock().await;
-            match &*vault_lock {
-                Some(vault) => {
-                    let files = vault
-                        .list_markdown_files()
-                        .map_err(|e| format!("Failed to list files: {}", e))?;
-
-                    let mut results: Vec<NoteInfo> = Vec::new();
-
-                    // Pattern to find specific tag
-                    let pattern = format!(
-                        r"(?:^|\s)#{}(?:\s|$|[.,!?;:])",
-                        regex::escape(tag_normalized)
-                    );
-                    let tag_regex =
-                        Regex::new(&pattern).map_err(|e| format!("Regex error: {}", e))?;
-
-                    for file_path in files {
-                        if file_path.is_dir() {
-                            continue;
-                        }
+    let limit = limit.unwrap_or(10);
 
-                        // Only process .md files
-                        if file_path.extension().and_then(|e| e.to_str()) != Some("md") {
-                            continue;
-                        }
+    if query.trim().is_empty() {
+        return Err("Query cannot be empty".to_string());
+    }
 
-                        // Read file content
-                        if let Ok(content) = std::fs::read_to_string(&file_path) {
-                            if tag_regex.is_match(&content) {
-                                // Get relative path from vault
-                                let relative_path = file_path
-                                    .strip_prefix(vault.path())
-                                    .unwrap_or(&file_path)
-                                    .to_string_lossy()
-                                    .to_string();
-
-                                // Extract title from filename or first heading
-                                let title = file_path
-                                    .file_stem()
-                                    .and_then(|s| s.to_str())
-                                    .unwrap_or("Untitled")
-                                    .to_string();
-
-                                results.push(NoteInfo {
-                                    path: relative_path,
-                                    title,
-                                });
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
 
-                                if results.len() >= limit {
-                                    break;
-                                }
-                            }
-                        }
-                    }
+    let settings = semantic::embedding_settings(&app).await?;
+    let embedder = semantic::LocalEmbedder::from_settings(settings)
+        .map_err(|e| format!("Semantic search unavailable: {}", e))?;
 
-                    println!("✅ agent_notes_by_tag found {} notes", results.len());
-                    Ok(results)
-                }
-                None => Err("No vault opened".to_string()),
-            }
-        }
-        None => Err("Window not found".to_string()),
+    if !semantic::index_path(&vault_path).exists() {
+        semantic_state.schedule_sync(&app, &vault_path);
+    }
+    let index = semantic::SemanticIndex::open(&vault_path)
+        .map_err(|e| format!("Failed to open semantic index: {}", e))?;
+    let note_count = index
+        .note_count()
+        .map_err(|e| format!("Failed to read semantic index: {}", e))?;
+    if note_count == 0 && semantic_state.is_syncing(&vault_path) {
+        return Err(
+            "The semantic index for this vault is being built; try again shortly".to_string(),
+        );
     }
+
+    let hits = index
+        .search(&embedder, &query, limit)
+        .await
+        .map_err(|e| format!("Semantic search failed: {}", e))?;
+
+    let results: Vec<NoteInfo> = hits
+        .into_iter()
+        .map(|hit| NoteInfo {
+            path: hit.path,
+            title: hit.title,
+            heading: hit.heading,
+            snippet: Some(hit.content),
+            score: Some(hit.score),
+        })
+        .collect();
+
+    println!("✅ agent_semantic_search found {} notes", results.len());
+    Ok(results)
 }
 
-/// Semantic search (placeholder - requires premium/embeddings)
+/// Find plain-text mentions of a note (its title or aliases) in other notes
+/// that are not linked yet. Code, existing links, URLs and tags are skipped.
 #[tauri::command]
-pub async fn agent_semantic_search(
-    query: String,
+pub async fn agent_find_unlinked_mentions(
+    note: String,
     limit: Option<usize>,
-    _window: Window,
-    _refactored_state: State<'_, RefactoredAppState>,
-) -> Result<Vec<NoteInfo>, String> {
-    println!("🧠 agent_semantic_search called for query: {}", query);
-    let _limit = limit.unwrap_or(10);
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<Vec<UnlinkedMention>, String> {
+    println!("🔗 agent_find_unlinked_mentions called for note: {}", note);
 
-    // For now, return an error indicating this requires premium
-    // In a full implementation, this would:
-    // 1. Check premium status
-    // 2. Generate embeddings for the query
-    // 3. Search against pre-computed embeddings
-    // 4. Return ranked results
+    let limit = limit.unwrap_or(50);
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+
+    let mut mentions = graph_state
+        .with_graph(vault_path, |graph| {
+            let node = graph
+                .find_note(&note)
+                .ok_or_else(|| format!("Note not found: {}", note))?;
+            graph::find_unlinked_mentions(graph, node)
+                .map_err(|e| format!("Failed to find mentions: {}", e))
+        })
+        .await?;
+    mentions.truncate(limit);
+
+    println!(
+        "✅ agent_find_unlinked_mentions found {} mentions",
+        mentions.len()
+    );
+    Ok(mentions)
+}
 
-    Err("Semantic search requires premium subscription. Please upgrade or use search_notes instead.".to_string())
+/// Turn unlinked mentions of a note into `[[links]]`, either everywhere or
+/// only in the notes listed in `paths`
+#[tauri::command]
+pub async fn agent_link_mentions(
+    note: String,
+    paths: Option<Vec<String>>,
+    window: Window,
+    refactored_state: State<'_, RefactoredAppState>,
+    graph_state: State<'_, LinkGraphState>,
+) -> Result<VaultOperationResult, String> {
+    println!("🔗 agent_link_mentions called for note: {}", note);
+
+    let vault_path = window_vault_path(&window, &refactored_state).await?;
+
+    let result = graph_state
+        .with_graph(vault_path, |graph| {
+            let node = graph
+                .find_note(&note)
+                .ok_or_else(|| format!("Note not found: {}", note))?;
+            let mentions: Vec<UnlinkedMention> = graph::find_unlinked_mentions(graph, node)
+                .map_err(|e| format!("Failed to find mentions: {}", e))?
+                .into_iter()
+                .filter(|m| paths.as_ref().is_none_or(|paths| paths.contains(&m.path)))
+                .collect();
+            let linked = graph::link_mentions(graph, node, &mentions)
+                .map_err(|e| format!("Failed to link mentions: {}", e))?;
+            Ok((node.path.clone(), linked))
+        })
+        .await;
+
+    match result {
+        Ok((path, linked)) => {
+            println!("✅ agent_link_mentions linked {} mentions", linked);
+            Ok(VaultOperationResult {
+                success: true,
+                message: format!("Linked {} mentions of {}", linked, note),
+                path: Some(path),
+                content: None,
+                length: Some(linked),
+            })
+        }
+        Err(e) => Ok(VaultOperationResult {
+            success: false,
+            message: e,
+            path: None,
+            content: None,
+            length: None,
+        }),
+    }
 }
 
 // ============================================================================
@@ -14,6 +14,10 @@ pub struct VaultSettings {
     pub vault_path: String,
     pub editor: EditorSettings,
     pub files: FileSettings,
+    #[serde(default)]
+    pub tasks: TaskSettings,
+    #[serde(default)]
+    pub periodic_notes: PeriodicNotesSettings,
     pub last_modified: chrono::DateTime<chrono::Utc>,
 }
 
@@ -35,12 +39,102 @@ pub struct FileSettings {
     pub image_naming_pattern: String,
     #[serde(default = "default_daily_notes_folder")]
     pub daily_notes_folder: String,
+    #[serde(default = "default_templates_folder")]
+    pub templates_folder: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskSettings {
+    /// Statuses a task moves through when toggled, e.g. `["todo", "in_progress", "done"]`
+    #[serde(default = "default_status_cycle")]
+    pub status_cycle: Vec<String>,
+    #[serde(default)]
+    pub calendar_feed: CalendarFeedSettings,
+}
+
+/// Loopback iCalendar feed of the vault's tasks
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CalendarFeedSettings {
+    /// Serve the feed whenever the vault is open
+    #[serde(default)]
+    pub enabled: bool,
+    /// Port on 127.0.0.1, kept so subscriptions survive restarts
+    #[serde(default)]
+    pub port: u16,
+    /// Secret path segment of the feed URL
+    #[serde(default)]
+    pub token: String,
+}
+
+/// Daily, weekly, monthly and quarterly notes
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PeriodicNotesSettings {
+    #[serde(default = "default_daily_note")]
+    pub daily: PeriodicNoteSettings,
+    #[serde(default = "default_weekly_note")]
+    pub weekly: PeriodicNoteSettings,
+    #[serde(default = "default_monthly_note")]
+    pub monthly: PeriodicNoteSettings,
+    #[serde(default = "default_quarterly_note")]
+    pub quarterly: PeriodicNoteSettings,
+    /// Move unfinished tasks from the previous note into a new one
+    #[serde(default)]
+    pub roll_over_tasks: bool,
+    /// Heading rolled-over tasks go under, the end of the note when empty
+    #[serde(default)]
+    pub roll_over_heading: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PeriodicNoteSettings {
+    /// Folder of the notes, the daily notes folder when empty
+    #[serde(default)]
+    pub folder: String,
+    /// Filename pattern such as `YYYY-MM-DD` or `GGGG-[W]WW`, may contain `/`
+    pub format: String,
+    /// Vault-relative note new notes are made from
+    #[serde(default)]
+    pub template: String,
+}
+
+impl PeriodicNoteSettings {
+    fn with_format(format: &str) -> Self {
+        PeriodicNoteSettings {
+            folder: String::new(),
+            format: format.to_string(),
+            template: String::new(),
+        }
+    }
+}
+
+fn default_daily_note() -> PeriodicNoteSettings {
+    PeriodicNoteSettings::with_format("YYYY-MM-DD")
+}
+
+fn default_weekly_note() -> PeriodicNoteSettings {
+    PeriodicNoteSettings::with_format("GGGG-[W]WW")
+}
+
+fn default_monthly_note() -> PeriodicNoteSettings {
+    PeriodicNoteSettings::with_format("YYYY-MM")
+}
+
+fn default_quarterly_note() -> PeriodicNoteSettings {
+    PeriodicNoteSettings::with_format("YYYY-[Q]Q")
+}
+
+fn default_status_cycle() -> Vec<String> {
+    vec!["todo".to_string(), "done".to_string()]
 }
 
 fn default_daily_notes_folder() -> String {
     "Daily Notes".to_string()
 }
 
+fn default_templates_folder() -> String {
+    "Templates".to_string()
+}
+
 fn default_font_color() -> String {
     "#1f2937".to_string() // slate-800 default editor text
 }
@@ -51,11 +145,35 @@ impl Default for VaultSettings {
             vault_path: String::new(),
             editor: EditorSettings::default(),
             files: FileSettings::default(),
+            tasks: TaskSettings::default(),
+            periodic_notes: PeriodicNotesSettings::default(),
             last_modified: chrono::Utc::now(),
         }
     }
 }
 
+impl Default for TaskSettings {
+    fn default() -> Self {
+        TaskSettings {
+            status_cycle: default_status_cycle(),
+            calendar_feed: CalendarFeedSettings::default(),
+        }
+    }
+}
+
+impl Default for PeriodicNotesSettings {
+    fn default() -> Self {
+        PeriodicNotesSettings {
+            daily: default_daily_note(),
+            weekly: default_weekly_note(),
+            monthly: default_monthly_note(),
+            quarterly: default_quarterly_note(),
+            roll_over_tasks: false,
+            roll_over_heading: String::new(),
+        }
+    }
+}
+
 impl Default for EditorSettings {
     fn default() -> Self {
         EditorSettings {
@@ -76,6 +194,7 @@ impl Default for FileSettings {
             image_location: normalize_image_location("Files/"),
             image_naming_pattern: "Pasted image {timestamp}".to_string(),
             daily_notes_folder: "Daily Notes".to_string(),
+            templates_folder: default_templates_folder(),
         }
     }
 }
@@ -186,6 +305,13 @@ pub struct VaultSettingsInput {
     pub vault_path: String,
     pub editor: EditorSettings,
     pub files: FileSettings,
+    /// Left out by callers that only edit the other sections, in which case
+    /// the stored task settings are kept
+    #[serde(default)]
+    pub tasks: Option<TaskSettings>,
+    /// Kept from the stored settings when left out, like `tasks`
+    #[serde(default)]
+    pub periodic_notes: Option<PeriodicNotesSettings>,
 }
 
 #[tauri::command]
@@ -195,20 +321,44 @@ pub async fn save_vault_settings(
 ) -> Result<(), String> {
     println!("Saving vault settings for: {}", settings.vault_path);
     settings.files.image_location = normalize_image_location(&settings.files.image_location);
+    if let Some(tasks) = &settings.tasks {
+        crate::identity::tasks::TaskStatusCycle::parse(&tasks.status_cycle)?;
+    }
+    if let Some(periodic_notes) = &settings.periodic_notes {
+        crate::periodic::validate_settings(periodic_notes)?;
+    }
+
+    let store_name = get_store_filename(&settings.vault_path);
+    let store = app
+        .store(&store_name)
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+
+    let stored = || {
+        store
+            .get("settings")
+            .and_then(|value| serde_json::from_value::<VaultSettings>(value.clone()).ok())
+    };
+    let tasks = match settings.tasks {
+        Some(tasks) => tasks,
+        None => stored().map(|stored| stored.tasks).unwrap_or_default(),
+    };
+    let periodic_notes = match settings.periodic_notes {
+        Some(periodic_notes) => periodic_notes,
+        None => stored()
+            .map(|stored| stored.periodic_notes)
+            .unwrap_or_default(),
+    };
 
     // Create VaultSettings with current timestamp
     let vault_settings = VaultSettings {
         vault_path: settings.vault_path.clone(),
         editor: settings.editor,
         files: settings.files,
+        tasks,
+        periodic_notes,
         last_modified: chrono::Utc::now(),
     };
 
-    let store_name = get_store_filename(&settings.vault_path);
-    let store = app
-        .store(&store_name)
-        .map_err(|e| format!("Failed to access store: {}", e))?;
-
     let value = serde_json::to_value(&vault_settings).map_err(|e| e.to_string())?;
     store.set("settings", value);
 
@@ -235,6 +385,8 @@ pub async fn reset_vault_settings(
         vault_path: vault_path.clone(),
         editor: settings.editor.clone(),
         files: settings.files.clone(),
+        tasks: Some(settings.tasks.clone()),
+        periodic_notes: Some(settings.periodic_notes.clone()),
     };
 
     // Save the default settings
@@ -321,6 +473,26 @@ mod tests {
         assert_eq!(defaults.editor.font_color, "#1f2937");
         assert!(!defaults.editor.line_numbers);
     }
+
+    #[test]
+    fn settings_saved_before_task_settings_get_default_cycle() {
+        let mut value = serde_json::to_value(VaultSettings::default()).unwrap();
+        value.as_object_mut().unwrap().remove("tasks");
+
+        let settings: VaultSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.tasks.status_cycle, vec!["todo", "done"]);
+    }
+
+    #[test]
+    fn settings_saved_before_periodic_notes_get_default_formats() {
+        let mut value = serde_json::to_value(VaultSettings::default()).unwrap();
+        value.as_object_mut().unwrap().remove("periodic_notes");
+
+        let settings: VaultSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.periodic_notes.daily.format, "YYYY-MM-DD");
+        assert_eq!(settings.periodic_notes.weekly.format, "GGGG-[W]WW");
+        assert!(!settings.periodic_notes.roll_over_tasks);
+    }
 }
 
 // Get all vault settings (for debugging/admin purposes)
@@ -13,6 +13,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::AppHandle;
 use tauri::Emitter;
+use tauri::Manager;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
@@ -671,9 +672,26 @@ impl SharedFileWatcherRegistry {
         // Filter relevant events
         match file_event.event.kind {
             EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                // The link graph also tracks attachments and folders
+                if let Some(graph_state) = app_handle.try_state::<crate::graph::LinkGraphState>() {
+                    graph_state.files_changed(&file_event.vault_path, &file_event.event.paths);
+                }
+                // Folder events too, so notes under a moved or deleted folder go
+                if let Some(tag_state) = app_handle.try_state::<crate::tags::TagIndexState>() {
+                    tag_state
+                        .files_changed(&file_event.vault_path, &file_event.event.paths)
+                        .await;
+                }
+
                 // Only process markdown files
                 for path in &file_event.event.paths {
                     if path.extension().and_then(|s| s.to_str()) == Some("md") {
+                        if let Some(semantic_state) =
+                            app_handle.try_state::<crate::semantic::SemanticIndexState>()
+                        {
+                            semantic_state.refresh(app_handle, &file_event.vault_path);
+                        }
+
                         // Emit to all windows - Tauri will handle filtering by window
                         let event_data = serde_json::json!({
                             "vaultPath": file_event.vault_path.to_string_lossy(),
@@ -232,7 +232,7 @@ export class ClaudeAgentSDK {
       },
       {
         name: "mcp__vault__semantic_search",
-        description: "Search notes using semantic/meaning-based search (requires premium).",
+        description: "Search notes by meaning using local embeddings (Ollama or LM Studio). Returns the best-matching section of each note.",
         input_schema: {
           type: "object",
           properties: {
@@ -242,6 +242,44 @@ export class ClaudeAgentSDK {
           required: ["query"]
         }
       },
+      {
+        name: "mcp__vault__get_backlinks",
+        description: "List links from other notes (wikilinks, embeds, markdown links and [[tid:...]] task references) that point at a note.",
+        input_schema: {
+          type: "object",
+          properties: {
+            note: { type: "string", description: "Note UUID (front matter id), vault-relative path, or note name" }
+          },
+          required: ["note"]
+        }
+      },
+      {
+        name: "mcp__vault__get_outgoing_links",
+        description: "List the links written in a note with the note or file each one resolves to.",
+        input_schema: {
+          type: "object",
+          properties: {
+            note: { type: "string", description: "Note UUID (front matter id), vault-relative path, or note name" }
+          },
+          required: ["note"]
+        }
+      },
+      {
+        name: "mcp__vault__get_unresolved_links",
+        description: "List links across the vault whose target note or file does not exist.",
+        input_schema: {
+          type: "object",
+          properties: {}
+        }
+      },
+      {
+        name: "mcp__vault__check_vault_health",
+        description: "Report vault problems: broken wikilinks, links to deleted tasks or note UUIDs, missing images and attachments, unused files in the image folder, duplicate note UUIDs and orphaned .meta.json sidecars. Each issue includes the fix the app would apply.",
+        input_schema: {
+          type: "object",
+          properties: {}
+        }
+      },
       {
         name: "mcp__vault__write_note",
         description: "Create a new note in the vault with the specified content.",
@@ -321,6 +359,10 @@ export class ClaudeAgentSDK {
       "mcp__vault__list_tags": this.handleListTags.bind(this),
       "mcp__vault__notes_by_tag": this.handleNotesByTag.bind(this),
       "mcp__vault__semantic_search": this.handleSemanticSearch.bind(this),
+      "mcp__vault__get_backlinks": this.handleGetBacklinks.bind(this),
+      "mcp__vault__get_outgoing_links": this.handleGetOutgoingLinks.bind(this),
+      "mcp__vault__get_unresolved_links": this.handleGetUnresolvedLinks.bind(this),
+      "mcp__vault__check_vault_health": this.handleCheckVaultHealth.bind(this),
       "mcp__vault__write_note": this.handleWriteNote.bind(this),
       "mcp__vault__update_note": this.handleUpdateNote.bind(this),
       "mcp__vault__append_to_note": this.handleAppendToNote.bind(this),
@@ -450,11 +492,65 @@ export class ClaudeAgentSDK {
       return JSON.stringify({ results, query: args.query });
     } catch (error) {
       console.error('semantic_search error:', error);
-      // This will typically fail with "requires premium" message from Rust
+      // Typically fails when no local embedding provider is reachable
       return JSON.stringify({ error: error.message || 'Semantic search failed', query: args.query });
     }
   }
 
+  async handleGetBacklinks(args) {
+    console.log('get_backlinks called:', args);
+    try {
+      if (!args.note) {
+        return JSON.stringify({ error: "Note is required" });
+      }
+      // Answered from the app's link graph, which the file watcher keeps current
+      const backlinks = await invoke('get_backlinks', { note: args.note });
+      console.log('get_backlinks found', backlinks.length, 'links');
+      return JSON.stringify({ note: args.note, backlinks });
+    } catch (error) {
+      console.error('get_backlinks error:', error);
+      return JSON.stringify({ error: error.message || error || 'Failed to get backlinks', note: args.note });
+    }
+  }
+
+  async handleGetOutgoingLinks(args) {
+    console.log('get_outgoing_links called:', args);
+    try {
+      if (!args.note) {
+        return JSON.stringify({ error: "Note is required" });
+      }
+      const links = await invoke('get_outgoing_links', { note: args.note });
+      console.log('get_outgoing_links found', links.length, 'links');
+      return JSON.stringify({ note: args.note, links });
+    } catch (error) {
+      console.error('get_outgoing_links error:', error);
+      return JSON.stringify({ error: error.message || error || 'Failed to get outgoing links', note: args.note });
+    }
+  }
+
+  async handleGetUnresolvedLinks() {
+    console.log('get_unresolved_links called');
+    try {
+      const links = await invoke('get_unresolved_links');
+      console.log('get_unresolved_links found', links.length, 'links');
+      return JSON.stringify({ links, total: links.length });
+    } catch (error) {
+      console.error('get_unresolved_links error:', error);
+      return JSON.stringify({ error: error.message || error || 'Failed to get unresolved links' });
+    }
+  }
+
+  async handleCheckVaultHealth() {
+    console.log('check_vault_health called');
+    try {
+      const report = await invoke('get_vault_health');
+      return JSON.stringify(report);
+    } catch (error) {
+      console.error('check_vault_health error:', error);
+      return JSON.stringify({ error: error.message || error || 'Failed to check vault health' });
+    }
+  }
+
   async handleWriteNote(args) {
     console.log('write_note called:', args);
     try {
@@ -38,7 +38,7 @@ export const bundledServers = [
     transport: {
       type: 'stdio',
       command: '${BUNDLE_PATH}/mcp-search-server',
-      args: ['--line-transport', '--index-path', '${VAULT_PATH}/.vault/search'],
+      args: ['--line-transport', '--vault-path', '${VAULT_PATH}', '--app-data-path', '${APP_DATA_PATH}'],
       env: {},
       working_dir: null // Will be set to vault path at runtime
     },
@@ -76,12 +76,43 @@ function normalizeBundlePath(bundlePath) {
   return bundlePath;
 }
 
+/**
+ * Get the embedding endpoint the app's semantic index uses, so hybrid search
+ * embeds queries with the same provider
+ * @returns {Promise<string|null>}
+ */
+async function getEmbeddingEndpoint() {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    return await invoke('get_embedding_endpoint');
+  } catch (e) {
+    console.warn('Could not get embedding endpoint from Tauri:', e);
+    return null;
+  }
+}
+
+/**
+ * Get the app's data directory, where the search server keeps its index
+ * @returns {Promise<string|null>}
+ */
+async function getAppDataPath() {
+  try {
+    const { appDataDir } = await import('@tauri-apps/api/path');
+    return await appDataDir();
+  } catch (e) {
+    console.warn('Could not get app data path from Tauri:', e);
+    return null;
+  }
+}
+
 /**
  * Get bundled servers with variables substituted
  * @param {string} vaultPath - Path to the vault
  * @param {string} bundlePath - Path to the app bundle resources (optional, fetched from Tauri)
+ * @param {string|null} embeddingEndpoint - Endpoint for hybrid search embeddings (optional, fetched from Tauri)
+ * @param {string|null} appDataPath - App data directory for the search index (optional, fetched from Tauri)
  */
-export async function getBundledServers(vaultPath, bundlePath = null) {
+export async function getBundledServers(vaultPath, bundlePath = null, embeddingEndpoint = undefined, appDataPath = undefined) {
   // Get bundle path from Tauri if not provided
   if (!bundlePath) {
     try {
@@ -108,6 +139,13 @@ export async function getBundledServers(vaultPath, bundlePath = null) {
 
   console.log(`[bundledServers] Using bundlePath: ${bundlePath}, vaultPath: ${vaultPath}`);
 
+  if (embeddingEndpoint === undefined) {
+    embeddingEndpoint = await getEmbeddingEndpoint();
+  }
+  if (appDataPath === undefined) {
+    appDataPath = await getAppDataPath();
+  }
+
   return bundledServers.map(server => {
     // Deep clone the server config
     const config = JSON.parse(JSON.stringify(server));
@@ -119,15 +157,29 @@ export async function getBundledServers(vaultPath, bundlePath = null) {
         .replace(/\$\{VAULT_PATH\}/g, vaultPath);
     }
 
+    // Without an app data path the search server falls back to its own default
+    if (config.transport.args && !appDataPath) {
+      const index = config.transport.args.indexOf('--app-data-path');
+      if (index !== -1) {
+        config.transport.args.splice(index, 2);
+      }
+    }
+
     // Replace variables in args
     if (config.transport.args) {
       config.transport.args = config.transport.args.map(arg =>
         arg
           .replace(/\$\{BUNDLE_PATH\}/g, bundlePath)
           .replace(/\$\{VAULT_PATH\}/g, vaultPath)
+          .replace(/\$\{APP_DATA_PATH\}/g, appDataPath)
       );
     }
 
+    // The search server embeds hybrid queries itself and defaults to Ollama
+    if (config.id === 'vault-search' && embeddingEndpoint) {
+      config.transport.args.push('--embedding-endpoint', embeddingEndpoint);
+    }
+
     // Set working_dir to vault path for all Rust servers
     config.transport.working_dir = vaultPath;
 
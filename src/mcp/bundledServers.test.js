@@ -75,6 +75,41 @@ describe('bundledServers', () => {
       expect(search.transport.working_dir).toBe(mockVaultPath);
     });
 
+    test('passes the embedding endpoint to the search server', async () => {
+      const endpoint = 'http://localhost:1234/v1';
+      const servers = await getBundledServers(mockVaultPath, mockBundlePath, endpoint);
+
+      const search = servers.find(s => s.id === 'vault-search');
+      expect(search.transport.args.slice(-2)).toEqual(['--embedding-endpoint', endpoint]);
+
+      const filesystem = servers.find(s => s.id === 'vault-filesystem');
+      expect(filesystem.transport.args).not.toContain('--embedding-endpoint');
+    });
+
+    test('leaves the embedding endpoint to the search server when none is configured', async () => {
+      const servers = await getBundledServers(mockVaultPath, mockBundlePath, null);
+
+      const search = servers.find(s => s.id === 'vault-search');
+      expect(search.transport.args).not.toContain('--embedding-endpoint');
+    });
+
+    test('keeps the search index in the app data directory', async () => {
+      const appData = '/Users/test/Library/Application Support/vault';
+      const servers = await getBundledServers(mockVaultPath, mockBundlePath, null, appData);
+
+      const search = servers.find(s => s.id === 'vault-search');
+      expect(search.transport.args).toEqual([
+        '--line-transport', '--vault-path', mockVaultPath, '--app-data-path', appData
+      ]);
+    });
+
+    test('leaves the index location to the search server without an app data directory', async () => {
+      const servers = await getBundledServers(mockVaultPath, mockBundlePath, null, null);
+
+      const search = servers.find(s => s.id === 'vault-search');
+      expect(search.transport.args).toEqual(['--line-transport', '--vault-path', mockVaultPath]);
+    });
+
     test('does not mutate original bundledServers array', async () => {
       const originalFilesystem = bundledServers.find(s => s.id === 'vault-filesystem');
       const originalCommand = originalFilesystem.transport.command;
@@ -43,7 +43,8 @@ export class UserSettingsPanel {
             files: {
                 imageLocation: 'Files/',
                 imageNamingPattern: 'Pasted image {timestamp}',
-                dailyNotesFolder: 'Daily Notes'
+                dailyNotesFolder: 'Daily Notes',
+                templatesFolder: 'Templates'
             },
             isDirty: false,
             isSaving: false,
@@ -123,7 +124,8 @@ export class UserSettingsPanel {
                 ...this.state.files,
                 imageLocation: normalizeImageLocation(settings.files.image_location || this.state.files.imageLocation),
                 imageNamingPattern: settings.files.image_naming_pattern || this.state.files.imageNamingPattern,
-                dailyNotesFolder: settings.files.daily_notes_folder || this.state.files.dailyNotesFolder
+                dailyNotesFolder: settings.files.daily_notes_folder || this.state.files.dailyNotesFolder,
+                templatesFolder: settings.files.templates_folder || this.state.files.templatesFolder
             };
             this.state.isDirty = false;
         } catch (error) {
@@ -157,7 +159,8 @@ export class UserSettingsPanel {
                 files: {
                     image_location: this.state.files.imageLocation,
                     image_naming_pattern: this.state.files.imageNamingPattern,
-                    daily_notes_folder: this.state.files.dailyNotesFolder
+                    daily_notes_folder: this.state.files.dailyNotesFolder,
+                    templates_folder: this.state.files.templatesFolder
                 }
             };
             
@@ -209,7 +212,8 @@ export class UserSettingsPanel {
                 ...this.state.files,
                 imageLocation: normalizeImageLocation(settings.files.image_location || this.state.files.imageLocation),
                 imageNamingPattern: settings.files.image_naming_pattern || this.state.files.imageNamingPattern,
-                dailyNotesFolder: settings.files.daily_notes_folder || this.state.files.dailyNotesFolder
+                dailyNotesFolder: settings.files.daily_notes_folder || this.state.files.dailyNotesFolder,
+                templatesFolder: settings.files.templates_folder || this.state.files.templatesFolder
             };
             this.state.isDirty = false;
             
@@ -587,6 +591,16 @@ export class UserSettingsPanel {
                                        class="settings-input">
                                 <p class="form-help">Folder where daily notes are created. Default: Daily Notes</p>
                             </div>
+                            
+                            <div class="form-group">
+                                <label>Templates Folder:</label>
+                                <input type="text" 
+                                       value="${this.state.files.templatesFolder}"
+                                       placeholder="Templates"
+                                       onchange="userSettingsPanel.updateFileSetting('templatesFolder', this.value)"
+                                       class="settings-input">
+                                <p class="form-help">Folder note templates are read from. Default: Templates</p>
+                            </div>
                         </div>
                     </div>
                 </div>